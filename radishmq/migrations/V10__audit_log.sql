@@ -0,0 +1,26 @@
+create table if not exists audit_log (
+                       id uuid not null,
+                       occurred_at timestamptz not null,
+                       namespace varchar(64) not null,
+                       actor varchar(255),
+                       address varchar(64),
+                       action varchar(32) not null,
+                       resource_type varchar(32) not null,
+                       resource varchar(255) not null,
+                       before jsonb,
+                       after jsonb,
+                       constraint audit_log_pkey primary key (id)
+);
+
+create index if not exists audit_log_namespace_occurred_at_idx on audit_log (namespace, occurred_at);
+
+-- entries are only ever appended, nobody may rewrite who did what
+create or replace function audit_log_append_only() returns trigger as $$
+begin
+    raise exception 'the audit log is append-only';
+end;
+$$ language plpgsql;
+
+create trigger audit_log_append_only
+    before update or delete on audit_log
+    for each row execute function audit_log_append_only();
@@ -0,0 +1,17 @@
+create table if not exists topic_data_key (
+                       namespace varchar(64) not null,
+                       topic_type varchar(32) not null,
+                       topic_name varchar(255) not null,
+                       master_key_id varchar(64) not null,
+                       wrapped_key bytea not null,
+                       constraint topic_data_key_pkey primary key (namespace, topic_type, topic_name)
+);
+
+-- with encryption the name columns hold a blind index of the identity, the sealed_* columns the ciphertext
+alter table message_topic_publisher add column if not exists sealed_name bytea;
+alter table message_topic_subscriber add column if not exists sealed_name bytea;
+alter table message_topic_subscriber add column if not exists sealed_filter bytea;
+alter table task_topic_publisher add column if not exists sealed_name bytea;
+alter table task_topic_subscriber add column if not exists sealed_name bytea;
+alter table retained_message add column if not exists sealed_data bytea;
+alter table retained_message add column if not exists sealed_headers bytea;
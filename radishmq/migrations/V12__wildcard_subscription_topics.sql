@@ -0,0 +1,19 @@
+alter table message_topic_wildcard_subscriber drop constraint message_topic_wildcard_subscriber_subscriber_name_key;
+alter table message_topic_wildcard_subscriber add constraint message_topic_wildcard_subscriber_unique unique (subscriber_name, pattern);
+
+create table if not exists message_topic_wildcard_subscriber_topic (
+                            subscriber_name varchar(255) not null,
+                            pattern varchar(255) not null,
+                            topic_name varchar(255) not null,
+                            constraint message_topic_wildcard_subscriber_topic_pkey primary key (subscriber_name, pattern, topic_name)
+);
+
+-- existing wildcard subscriptions own every subscription on a default namespace topic their pattern matches,
+-- as they removed all of them before
+insert into message_topic_wildcard_subscriber_topic (subscriber_name, pattern, topic_name)
+select w.subscriber_name, w.pattern, t.topic_name
+from message_topic_wildcard_subscriber w
+         join message_topic_subscriber s on s.subscriber_name = w.subscriber_name
+         join message_topic t on t.id = s.message_topic_id and t.namespace = 'default'
+where t.topic_name ~ ('^' || replace(replace(replace(replace(replace(w.pattern, '.', '\.'), '*', '[^.]+'), '\.#', '(\..+)?'), '#\.', '(.+\.)?'), '#', '.+') || '$')
+on conflict do nothing;
@@ -0,0 +1,14 @@
+-- the key that seals topic names and keys their blind index, there is at most one
+create table if not exists topic_name_key (
+                       id boolean not null default true,
+                       master_key_id varchar(64) not null,
+                       wrapped_key bytea not null,
+                       constraint topic_name_key_pkey primary key (id),
+                       constraint topic_name_key_single check (id)
+);
+
+-- with encryption the topic_name columns hold a blind index of the topic, the sealed_name columns the ciphertext
+alter table message_topic add column if not exists sealed_name bytea;
+alter table task_topic add column if not exists sealed_name bytea;
+alter table retained_message add column if not exists sealed_name bytea;
+alter table topic_data_key add column if not exists sealed_name bytea;
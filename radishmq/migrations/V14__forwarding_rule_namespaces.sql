@@ -0,0 +1,3 @@
+alter table forwarding_rule add column namespace varchar(64) not null default 'default';
+alter table forwarding_rule drop constraint forwarding_rule_rule_name_key;
+alter table forwarding_rule add constraint forwarding_rule_unique unique (namespace, rule_name);
@@ -0,0 +1,4 @@
+-- with encryption these columns hold a blind index of the topic they reference, the sealed columns the ciphertext
+alter table message_topic_wildcard_subscriber_topic add column if not exists sealed_topic_name bytea;
+alter table exchange_binding add column if not exists sealed_destination_name bytea;
+alter table forwarding_rule add column if not exists sealed_destination_name bytea;
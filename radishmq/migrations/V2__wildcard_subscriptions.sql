@@ -0,0 +1,6 @@
+create table if not exists message_topic_wildcard_subscriber (
+                            id uuid default uuid_generate_v4(),
+                            subscriber_name varchar(255) not null unique,
+                            pattern varchar(255) not null,
+                            constraint message_topic_wildcard_subscriber_pkey primary key (id)
+);
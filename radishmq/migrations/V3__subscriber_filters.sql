@@ -0,0 +1,3 @@
+alter table message_topic_subscriber add column if not exists filter text;
+
+alter table message_topic_wildcard_subscriber add column if not exists filter text;
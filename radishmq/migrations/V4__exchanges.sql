@@ -0,0 +1,26 @@
+create table if not exists exchange (
+                       id uuid default uuid_generate_v4(),
+                       exchange_name varchar(255) not null unique,
+                       exchange_type varchar(16) not null,
+                       constraint exchange_pkey primary key (id)
+);
+
+create table if not exists exchange_binding (
+                            id uuid not null,
+                            exchange_id uuid not null,
+                            destination_type varchar(16) not null,
+                            destination_name varchar(255) not null,
+                            routing_key varchar(255),
+                            arguments text not null default '{}',
+                            header_match varchar(8) not null default 'all',
+                            constraint exchange_binding_pkey primary key (id),
+                            constraint exchange_binding_exchange_id_fkey foreign key (exchange_id) references exchange(id) on delete cascade
+);
+
+create table if not exists exchange_publisher (
+                            id uuid default uuid_generate_v4(),
+                            exchange_id uuid not null,
+                            publisher_name varchar(255) not null,
+                            constraint exchange_publisher_pkey primary key (id),
+                            constraint exchange_publisher_exchange_id_fkey foreign key (exchange_id) references exchange(id) on delete cascade
+);
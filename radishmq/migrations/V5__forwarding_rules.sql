@@ -0,0 +1,9 @@
+create table if not exists forwarding_rule (
+                       id uuid default uuid_generate_v4(),
+                       rule_name varchar(255) not null unique,
+                       source varchar(255) not null,
+                       destination_type varchar(16) not null,
+                       destination_name varchar(255) not null,
+                       header_rewrite text not null default '{}',
+                       constraint forwarding_rule_pkey primary key (id)
+);
@@ -0,0 +1,6 @@
+create table if not exists retained_message (
+                       topic_name varchar(255) not null,
+                       data text not null,
+                       headers text not null default '{}',
+                       constraint retained_message_pkey primary key (topic_name)
+);
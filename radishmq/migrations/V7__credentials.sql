@@ -0,0 +1,7 @@
+create table if not exists credential (
+                       id uuid default uuid_generate_v4(),
+                       credential_name varchar(255) not null unique,
+                       secret_hash varchar(64) not null unique,
+                       principals text not null default '[]',
+                       constraint credential_pkey primary key (id)
+);
@@ -0,0 +1,8 @@
+create table if not exists acl_grant (
+                       id uuid not null,
+                       principal varchar(255) not null,
+                       role varchar(32) not null,
+                       pattern varchar(255) not null,
+                       constraint acl_grant_pkey primary key (id),
+                       constraint acl_grant_unique unique (principal, role, pattern)
+);
@@ -0,0 +1,15 @@
+alter table message_topic add column namespace varchar(64) not null default 'default';
+alter table message_topic drop constraint message_topic_topic_name_key;
+alter table message_topic add constraint message_topic_unique unique (namespace, topic_name);
+
+alter table task_topic add column namespace varchar(64) not null default 'default';
+alter table task_topic drop constraint task_topic_topic_name_key;
+alter table task_topic add constraint task_topic_unique unique (namespace, topic_name);
+
+alter table credential add column namespace varchar(64) not null default 'default';
+alter table credential drop constraint credential_credential_name_key;
+alter table credential add constraint credential_unique unique (namespace, credential_name);
+
+alter table acl_grant add column namespace varchar(64) not null default 'default';
+alter table acl_grant drop constraint acl_grant_unique;
+alter table acl_grant add constraint acl_grant_unique unique (namespace, principal, role, pattern);
@@ -0,0 +1,31 @@
+use crate::auth::identity::Identity;
+use crate::auth::jwt::{jwt_settings_from_env, JwtSettings};
+use crate::auth::{auth_mode_from_env, AuthMode};
+use crate::service::credential_service;
+
+/// Verifies the API keys or JWT bearer tokens of listeners outside of the HTTP API, with the same
+/// rules as the HTTP authentication middleware.
+pub enum Authenticator {
+    ApiKey,
+    Jwt(JwtSettings),
+}
+
+impl Authenticator {
+    /// Reads `AUTH_MODE`, `None` if authentication is disabled.
+    pub fn from_env() -> Option<Self> {
+        auth_mode_from_env().map(|mode| match mode {
+            AuthMode::ApiKey => Self::ApiKey,
+            AuthMode::Jwt => Self::Jwt(jwt_settings_from_env()),
+        })
+    }
+
+    pub fn authenticate(&self, credential: &str) -> Result<Identity, String> {
+        match self {
+            Self::ApiKey => credential_service::authenticate(credential.trim())
+                .ok_or("a valid API key is required".to_string()),
+            Self::Jwt(jwt_settings) => jwt_settings
+                .verify(credential.trim())
+                .map_err(|e| format!("the bearer token is not valid: {}", e)),
+        }
+    }
+}
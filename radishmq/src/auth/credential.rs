@@ -0,0 +1,44 @@
+use crate::model::credential_model::CredentialModel;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+const SECRET_LENGTH: usize = 32;
+
+/// An API key, only the SHA-256 hash of its secret is kept.
+#[derive(Debug, Clone)]
+pub struct Credential {
+    pub namespace: String,
+    pub name: String,
+    pub secret_hash: String,
+    pub principals: Vec<String>,
+}
+
+impl Credential {
+    pub fn new(
+        namespace: String,
+        name: String,
+        secret_hash: String,
+        principals: Vec<String>,
+    ) -> Self {
+        Self {
+            namespace,
+            name,
+            secret_hash,
+            principals,
+        }
+    }
+
+    pub fn to_model(&self) -> CredentialModel {
+        CredentialModel::new(self.name.clone(), self.principals.clone())
+    }
+}
+
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_LENGTH];
+    rand::thread_rng().fill_bytes(&mut secret);
+    hex::encode(secret)
+}
+
+pub fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
@@ -0,0 +1,49 @@
+use crate::auth::permission::Action;
+use crate::model::acl_model::{GrantModel, Role};
+use crate::utils::topic_pattern::TopicPattern;
+use uuid::Uuid;
+
+/// A role of a principal on the topics of a namespace matching the pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grant {
+    pub id: Uuid,
+    pub namespace: String,
+    pub principal: String,
+    pub role: Role,
+    pub pattern: TopicPattern,
+}
+
+impl Grant {
+    pub fn new(
+        id: Uuid,
+        namespace: String,
+        principal: String,
+        role: Role,
+        pattern: TopicPattern,
+    ) -> Self {
+        Self {
+            id,
+            namespace,
+            principal,
+            role,
+            pattern,
+        }
+    }
+
+    pub fn allows(&self, action: Action, topic_name: &str) -> bool {
+        self.role.allows(action) && self.pattern.matches(topic_name)
+    }
+
+    pub fn allows_pattern(&self, action: Action, pattern: &TopicPattern) -> bool {
+        self.role.allows(action) && self.pattern.covers(pattern)
+    }
+
+    pub fn to_model(&self) -> GrantModel {
+        GrantModel::new(
+            self.id,
+            self.principal.clone(),
+            self.role,
+            self.pattern.as_str().to_string(),
+        )
+    }
+}
@@ -0,0 +1,100 @@
+use crate::auth::permission::{Action, Permission};
+use crate::auth::ADMIN_PRINCIPAL;
+use crate::utils::namespace::{self, DEFAULT_NAMESPACE};
+use crate::utils::topic_pattern::TopicPattern;
+use std::future::Future;
+
+tokio::task_local! {
+    static IDENTITY: Identity;
+}
+
+/// The authenticated caller of a request, the namespace it belongs to, the principals it may act
+/// as and the permissions its token grants on top of the ACL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub name: String,
+    pub namespace: String,
+    pub principals: Vec<String>,
+    pub permissions: Vec<Permission>,
+}
+
+impl Identity {
+    pub fn new(name: String, principals: Vec<String>) -> Self {
+        Self {
+            name,
+            namespace: DEFAULT_NAMESPACE.to_string(),
+            principals,
+            permissions: Vec::new(),
+        }
+    }
+
+    pub fn with_namespace(mut self, namespace: String) -> Self {
+        self.namespace = namespace;
+        self
+    }
+
+    pub fn with_permissions(mut self, permissions: Vec<Permission>) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    pub fn with_principal(mut self, principal: String) -> Self {
+        self.principals.push(principal);
+        self
+    }
+
+    pub fn acts_as(&self, principal: &str) -> bool {
+        self.principals.iter().any(|p| p == principal)
+    }
+
+    /// Callers stay in their own namespace, only the admin of the default namespace enters every
+    /// namespace.
+    pub fn may_enter(&self, namespace: &str) -> bool {
+        self.namespace == namespace || self.enters_every_namespace()
+    }
+
+    pub fn enters_every_namespace(&self) -> bool {
+        self.namespace == DEFAULT_NAMESPACE && self.acts_as(ADMIN_PRINCIPAL)
+    }
+
+    pub fn may(&self, action: Action, topic_name: &str) -> bool {
+        self.permissions
+            .iter()
+            .any(|permission| permission.allows(action, topic_name))
+    }
+
+    pub fn may_pattern(&self, action: Action, pattern: &TopicPattern) -> bool {
+        self.permissions
+            .iter()
+            .any(|permission| permission.allows_pattern(action, pattern))
+    }
+}
+
+/// Runs `f` on behalf of `identity`, the services see it through `current_identity`.
+pub async fn scope<F: Future>(identity: Identity, f: F) -> F::Output {
+    IDENTITY.scope(identity, f).await
+}
+
+/// Runs `f` on behalf of `identity` inside the namespace it belongs to, for listeners whose
+/// requests do not name a namespace. Without an identity `f` runs unauthenticated.
+pub async fn on_behalf_of<F: Future>(identity: Option<Identity>, f: F) -> F::Output {
+    match identity {
+        Some(identity) => namespace::scope(identity.namespace.clone(), scope(identity, f)).await,
+        None => f.await,
+    }
+}
+
+/// Runs `f` on behalf of the current identity that may also act as `principal`, for identifiers a
+/// listener derives from one the identity acts as, like the subscriber names of MQTT subscriptions.
+pub async fn with_principal<F: Future>(principal: String, f: F) -> F::Output {
+    match current_identity() {
+        Some(identity) => scope(identity.with_principal(principal), f).await,
+        None => f.await,
+    }
+}
+
+/// The identity of the running request, `None` if authentication is disabled or the call does
+/// not come from an authenticated listener.
+pub fn current_identity() -> Option<Identity> {
+    IDENTITY.try_with(|identity| identity.clone()).ok()
+}
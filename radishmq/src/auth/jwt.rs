@@ -0,0 +1,186 @@
+use crate::auth::identity::Identity;
+use crate::auth::permission::Permission;
+use crate::utils::namespace::{is_valid_namespace, DEFAULT_NAMESPACE};
+use jsonwebtoken::jwk::{AlgorithmParameters, EllipticCurve, JwkSet, PublicKeyUse};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use log::warn;
+use serde_json::{Map, Value};
+use std::env;
+use std::fs;
+use std::str::FromStr;
+
+const RSA_ALGORITHMS: [Algorithm; 6] = [
+    Algorithm::RS256,
+    Algorithm::RS384,
+    Algorithm::RS512,
+    Algorithm::PS256,
+    Algorithm::PS384,
+    Algorithm::PS512,
+];
+
+/// A public key tokens can be signed with, `kid` is only known for keys of a JWKS.
+struct VerificationKey {
+    kid: Option<String>,
+    algorithms: Vec<Algorithm>,
+    key: DecodingKey,
+}
+
+pub struct JwtSettings {
+    keys: Vec<VerificationKey>,
+    audience: String,
+    issuer: String,
+    principal_claim: String,
+    namespace_claim: String,
+    permissions_claim: String,
+}
+
+/// Reads the keys and the expected claims of `AUTH_MODE=jwt`, the keys come either from the JWKS
+/// file `AUTH_JWT_JWKS` or the PEM file `AUTH_JWT_PUBLIC_KEY`.
+pub fn jwt_settings_from_env() -> JwtSettings {
+    let keys = match (env::var("AUTH_JWT_JWKS"), env::var("AUTH_JWT_PUBLIC_KEY")) {
+        (Ok(path), Err(_)) => load_jwks(&path),
+        (Err(_), Ok(path)) => vec![load_public_key(&path)],
+        _ => panic!("AUTH_MODE jwt requires either AUTH_JWT_JWKS or AUTH_JWT_PUBLIC_KEY"),
+    };
+    if keys.is_empty() {
+        panic!("AUTH_JWT_JWKS contains no usable signing key");
+    }
+
+    JwtSettings {
+        keys,
+        audience: env::var("AUTH_JWT_AUDIENCE").expect("AUTH_JWT_AUDIENCE must be set"),
+        issuer: env::var("AUTH_JWT_ISSUER").expect("AUTH_JWT_ISSUER must be set"),
+        principal_claim: env::var("AUTH_JWT_PRINCIPAL_CLAIM").unwrap_or("sub".to_string()),
+        namespace_claim: env::var("AUTH_JWT_NAMESPACE_CLAIM").unwrap_or("namespace".to_string()),
+        permissions_claim: env::var("AUTH_JWT_PERMISSIONS_CLAIM")
+            .unwrap_or("permissions".to_string()),
+    }
+}
+
+fn load_jwks(path: &str) -> Vec<VerificationKey> {
+    let content = fs::read_to_string(path).expect("AUTH_JWT_JWKS must be a readable file");
+    let jwks: JwkSet = serde_json::from_str(&content).expect("AUTH_JWT_JWKS must be a JWKS");
+
+    jwks.keys
+        .iter()
+        .filter(|jwk| jwk.common.public_key_use != Some(PublicKeyUse::Encryption))
+        .filter_map(|jwk| {
+            let algorithms = match jwk.common.key_algorithm {
+                Some(algorithm) => vec![Algorithm::from_str(&algorithm.to_string()).ok()?],
+                None => match &jwk.algorithm {
+                    AlgorithmParameters::RSA(_) => RSA_ALGORITHMS.to_vec(),
+                    AlgorithmParameters::EllipticCurve(params) => match params.curve {
+                        EllipticCurve::P256 => vec![Algorithm::ES256],
+                        EllipticCurve::P384 => vec![Algorithm::ES384],
+                        _ => return None,
+                    },
+                    AlgorithmParameters::OctetKeyPair(_) => vec![Algorithm::EdDSA],
+                    // shared secrets would let every holder issue tokens
+                    AlgorithmParameters::OctetKey(_) => return None,
+                },
+            };
+
+            Some(VerificationKey {
+                kid: jwk.common.key_id.clone(),
+                algorithms,
+                key: DecodingKey::from_jwk(jwk).ok()?,
+            })
+        })
+        .collect()
+}
+
+fn load_public_key(path: &str) -> VerificationKey {
+    let pem = fs::read(path).expect("AUTH_JWT_PUBLIC_KEY must be a readable file");
+    let algorithm = env::var("AUTH_JWT_ALGORITHM")
+        .map(|algorithm| {
+            Algorithm::from_str(&algorithm).expect("AUTH_JWT_ALGORITHM must be a JWS algorithm")
+        })
+        .unwrap_or(Algorithm::RS256);
+
+    let key = match algorithm {
+        Algorithm::ES256 | Algorithm::ES384 => DecodingKey::from_ec_pem(&pem),
+        Algorithm::EdDSA => DecodingKey::from_ed_pem(&pem),
+        algorithm if RSA_ALGORITHMS.contains(&algorithm) => DecodingKey::from_rsa_pem(&pem),
+        _ => panic!("AUTH_JWT_ALGORITHM must be an asymmetric algorithm"),
+    };
+
+    VerificationKey {
+        kid: None,
+        algorithms: vec![algorithm],
+        key: key.expect("AUTH_JWT_PUBLIC_KEY must be a PEM public key of AUTH_JWT_ALGORITHM"),
+    }
+}
+
+impl JwtSettings {
+    /// Verifies the signature, expiry, audience and issuer of a token and maps its claims to an
+    /// identity.
+    pub fn verify(&self, token: &str) -> Result<Identity, String> {
+        let header = decode_header(token).map_err(|e| e.to_string())?;
+        let key = self
+            .keys
+            .iter()
+            .find(|key| match (&key.kid, &header.kid) {
+                (Some(key_id), Some(kid)) => key_id == kid,
+                (Some(_), None) => self.keys.len() == 1,
+                // the static public key has no id, it verifies every token
+                (None, _) => true,
+            })
+            .ok_or("no key matches the token".to_string())?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.algorithms = key.algorithms.clone();
+        validation.set_audience(&[&self.audience]);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_required_spec_claims(&["exp", "aud", "iss"]);
+
+        let claims = decode::<Map<String, Value>>(token, &key.key, &validation)
+            .map_err(|e| e.to_string())?
+            .claims;
+
+        let principal = claims
+            .get(&self.principal_claim)
+            .and_then(Value::as_str)
+            .ok_or(format!("the token has no '{}' claim", self.principal_claim))?;
+        // tokens without a namespace belong to the default namespace
+        let namespace = match claims.get(&self.namespace_claim) {
+            Some(value) => value
+                .as_str()
+                .filter(|namespace| is_valid_namespace(namespace))
+                .ok_or(format!(
+                    "the '{}' claim is not a valid namespace",
+                    self.namespace_claim
+                ))?,
+            None => DEFAULT_NAMESPACE,
+        };
+
+        Ok(
+            Identity::new(principal.to_string(), vec![principal.to_string()])
+                .with_namespace(namespace.to_string())
+                .with_permissions(self.permissions(&claims)),
+        )
+    }
+
+    /// Reads the permissions claim, either a list of strings or a space-separated string like
+    /// an OAuth scope.
+    fn permissions(&self, claims: &Map<String, Value>) -> Vec<Permission> {
+        let values: Vec<&str> = match claims.get(&self.permissions_claim) {
+            Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).collect(),
+            Some(Value::String(values)) => values.split_whitespace().collect(),
+            _ => Vec::new(),
+        };
+
+        values
+            .into_iter()
+            .filter_map(|value| {
+                let permission = Permission::parse(value);
+                if permission.is_none() {
+                    warn!(
+                        "JwtSettings::permissions ignored the invalid permission '{}'",
+                        value
+                    );
+                }
+                permission
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,26 @@
+pub mod authenticator;
+pub mod credential;
+pub mod grant;
+pub mod identity;
+pub mod jwt;
+pub mod permission;
+
+use std::env;
+
+/// The principal that may manage credentials.
+pub const ADMIN_PRINCIPAL: &str = "admin";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthMode {
+    ApiKey,
+    Jwt,
+}
+
+/// Reads `AUTH_MODE`, authentication is disabled if it is not set.
+pub fn auth_mode_from_env() -> Option<AuthMode> {
+    env::var("AUTH_MODE").ok().map(|mode| match mode.as_str() {
+        "api_key" => AuthMode::ApiKey,
+        "jwt" => AuthMode::Jwt,
+        _ => panic!("AUTH_MODE must be 'api_key' or 'jwt'"),
+    })
+}
@@ -0,0 +1,58 @@
+use crate::utils::topic_pattern::TopicPattern;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Read,
+    Publish,
+    Subscribe,
+    Manage,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Read => write!(f, "read"),
+            Action::Publish => write!(f, "publish"),
+            Action::Subscribe => write!(f, "subscribe"),
+            Action::Manage => write!(f, "manage"),
+        }
+    }
+}
+
+/// A permission like `publish:orders.*` or `subscribe:audit`, the topic part is a topic pattern.
+/// Every permission allows reading the topics it matches, `manage` allows every action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permission {
+    pub action: Action,
+    pub pattern: TopicPattern,
+}
+
+impl Permission {
+    pub fn parse(permission: &str) -> Option<Self> {
+        let (action, pattern) = permission.split_once(':')?;
+        let action = match action {
+            "publish" => Action::Publish,
+            "subscribe" => Action::Subscribe,
+            "manage" => Action::Manage,
+            _ => return None,
+        };
+
+        Some(Self {
+            action,
+            pattern: TopicPattern::parse(pattern)?,
+        })
+    }
+
+    pub fn allows(&self, action: Action, topic_name: &str) -> bool {
+        self.allows_action(action) && self.pattern.matches(topic_name)
+    }
+
+    pub fn allows_pattern(&self, action: Action, pattern: &TopicPattern) -> bool {
+        self.allows_action(action) && self.pattern.covers(pattern)
+    }
+
+    fn allows_action(&self, action: Action) -> bool {
+        self.action == action || self.action == Action::Manage || action == Action::Read
+    }
+}
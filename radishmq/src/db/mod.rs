@@ -1,2 +1,3 @@
 pub mod migration;
 pub mod pool;
+pub mod restore;
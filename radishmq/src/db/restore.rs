@@ -0,0 +1,789 @@
+use crate::auth::credential::Credential;
+use crate::auth::grant::Grant;
+use crate::encryption::data_key::DataKeyId;
+use crate::encryption::{self, WrappedDataKey};
+use crate::event_queue::worker::ThreadData;
+use crate::model::acl_model::Role;
+use crate::model::destination_model::{DestinationType, TopicDestination};
+use crate::model::entity::retained_message_entities::RetainedMessageEntity;
+use crate::model::exchange_model::{ExchangeType, HeaderMatch};
+use crate::model::message_topic_model::Message;
+use crate::model::retained_message_model::RetainedMessageModel;
+use crate::repository::{
+    acl_repository, credential_repository, data_key_repository, exchange_repository,
+    forwarding_rule_repository, message_topic_repository, retained_message_repository,
+    task_topic_repository, wildcard_subscription_repository,
+};
+use crate::topic::exchange::{Binding, Exchange};
+use crate::topic::forwarding_rule::ForwardingRule;
+use crate::topic::message_topic::MessageTopic;
+use crate::topic::task_topic::TaskTopic;
+use crate::topic::wildcard_subscription::WildcardSubscription;
+use crate::utils::namespace::DEFAULT_NAMESPACE;
+use crate::utils::topic_pattern::TopicPattern;
+use crate::STATE;
+use log::warn;
+use sqlx::{Pool, Postgres};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// messages are not persisted, so topics come back empty. Message topics keep their index and every
+// subscriber its offset, the messages it had not fetched before the restart are lost
+pub async fn restore_state(db_connection_pool: &Pool<Postgres>) {
+    println!("Restoring state from DB...");
+
+    if encryption::is_enabled() {
+        restore_topic_name_key(db_connection_pool).await;
+        seal_plaintext_topic_names(db_connection_pool).await;
+    }
+    restore_data_keys(db_connection_pool).await;
+    restore_message_topics(db_connection_pool).await;
+    restore_task_topics(db_connection_pool).await;
+    restore_wildcard_subscriptions(db_connection_pool).await;
+    restore_exchanges(db_connection_pool).await;
+    restore_forwarding_rules(db_connection_pool).await;
+    restore_retained_messages(db_connection_pool).await;
+    restore_credentials(db_connection_pool).await;
+    restore_acl_grants(db_connection_pool).await;
+
+    if encryption::is_enabled() {
+        seal_plaintext_rows(db_connection_pool).await;
+    }
+
+    println!("State restored!");
+}
+
+// sealed rows cannot be read without their data key, so a missing master key stops the broker
+async fn restore_data_keys(db_connection_pool: &Pool<Postgres>) {
+    let entities = data_key_repository::get_all_data_keys(db_connection_pool).await;
+
+    let mut rewrapped_keys = 0;
+    for entity in entities {
+        let Some(topic_name) =
+            encryption::open_topic_name(&entity.topic_name, entity.sealed_name.as_deref())
+        else {
+            warn!(
+                "Restore::restore_data_keys skipped a data key whose topic name cannot be unsealed"
+            );
+            continue;
+        };
+        let Ok(destination_type) = entity.topic_type.parse::<DestinationType>() else {
+            warn!(
+                "Restore::restore_data_keys skipped the data key of '{}' because of an unknown topic type",
+                topic_name
+            );
+            continue;
+        };
+        let wrapped = WrappedDataKey {
+            id: DataKeyId::new(destination_type, entity.namespace, topic_name),
+            master_key_id: entity.master_key_id,
+            wrapped_key: entity.wrapped_key,
+        };
+
+        match encryption::restore_data_key(&wrapped) {
+            Ok(Some(rewrapped)) => {
+                data_key_repository::rewrap_data_key(db_connection_pool, rewrapped).await;
+                rewrapped_keys += 1;
+            }
+            Ok(None) => {}
+            Err(e) => panic!("Data keys could not be restored: {}", e),
+        }
+    }
+
+    if rewrapped_keys > 0 {
+        println!(
+            "Rewrapped {} data keys with the active master key",
+            rewrapped_keys
+        );
+    }
+}
+
+async fn restore_message_topics(db_connection_pool: &Pool<Postgres>) {
+    let entities = message_topic_repository::get_all_message_topics(db_connection_pool).await;
+    let subscribers =
+        message_topic_repository::get_all_message_topic_subscribers(db_connection_pool).await;
+    let publishers =
+        message_topic_repository::get_all_message_topic_publishers(db_connection_pool).await;
+
+    if let Ok(mut topics) = STATE.message_topics.lock() {
+        for mut entity in entities {
+            let Some(topic_name) =
+                encryption::open_topic_name(&entity.topic_name, entity.sealed_name.as_deref())
+            else {
+                warn!(
+                    "Restore::restore_message_topics skipped a topic whose name cannot be unsealed"
+                );
+                continue;
+            };
+            entity.topic_name = topic_name;
+            let mut topic = MessageTopic::restore(
+                entity.namespace.clone(),
+                entity.topic_name.clone(),
+                entity.data_index.max(0) as usize,
+            );
+            let key_id = DataKeyId::new(
+                DestinationType::MessageTopic,
+                entity.namespace.clone(),
+                entity.topic_name.clone(),
+            );
+
+            for publisher in publishers
+                .iter()
+                .filter(|publisher| publisher.message_topic_id == entity.id)
+            {
+                match encryption::open_identity(
+                    &key_id,
+                    &publisher.publisher_name,
+                    publisher.sealed_name.as_deref(),
+                ) {
+                    Some(publisher_name) => topic.add_publisher(publisher_name),
+                    None => warn!(
+                        "Restore::restore_message_topics skipped a publisher of '{}' that cannot be unsealed",
+                        entity.topic_name
+                    ),
+                }
+            }
+            for subscriber in subscribers
+                .iter()
+                .filter(|subscriber| subscriber.message_topic_id == entity.id)
+            {
+                let Some(subscriber_name) = encryption::open_identity(
+                    &key_id,
+                    &subscriber.subscriber_name,
+                    subscriber.sealed_name.as_deref(),
+                ) else {
+                    warn!(
+                        "Restore::restore_message_topics skipped a subscriber of '{}' that cannot be unsealed",
+                        entity.topic_name
+                    );
+                    continue;
+                };
+                let filter = match subscriber.sealed_filter.as_deref() {
+                    Some(sealed_filter) => encryption::open(&key_id, sealed_filter),
+                    None => subscriber.filter.clone(),
+                };
+                let filter = filter.and_then(|filter| serde_json::from_str(&filter).ok());
+                topic.restore_subscriber(
+                    subscriber_name,
+                    filter,
+                    subscriber.subscriber_index.max(0) as usize,
+                );
+            }
+
+            topics.push(topic);
+        }
+    } else {
+        warn!("Restore::restore_message_topics tried to lock a poisoned mutex");
+    }
+}
+
+async fn restore_task_topics(db_connection_pool: &Pool<Postgres>) {
+    let entities = task_topic_repository::get_all_task_topics(db_connection_pool).await;
+    let subscribers =
+        task_topic_repository::get_all_task_topic_subscribers(db_connection_pool).await;
+    let publishers = task_topic_repository::get_all_task_topic_publishers(db_connection_pool).await;
+
+    if let Ok(mut topics) = STATE.task_topics.lock() {
+        for mut entity in entities {
+            let Some(topic_name) =
+                encryption::open_topic_name(&entity.topic_name, entity.sealed_name.as_deref())
+            else {
+                warn!("Restore::restore_task_topics skipped a topic whose name cannot be unsealed");
+                continue;
+            };
+            entity.topic_name = topic_name;
+            let mut topic = TaskTopic::new(entity.namespace.clone(), entity.topic_name.clone());
+            let key_id = DataKeyId::new(
+                DestinationType::TaskTopic,
+                entity.namespace.clone(),
+                entity.topic_name.clone(),
+            );
+
+            for publisher in publishers
+                .iter()
+                .filter(|publisher| publisher.task_topic_id == entity.id)
+            {
+                match encryption::open_identity(
+                    &key_id,
+                    &publisher.publisher_name,
+                    publisher.sealed_name.as_deref(),
+                ) {
+                    Some(publisher_name) => topic.add_publisher(publisher_name),
+                    None => warn!(
+                        "Restore::restore_task_topics skipped a publisher of '{}' that cannot be unsealed",
+                        entity.topic_name
+                    ),
+                }
+            }
+            for subscriber in subscribers
+                .iter()
+                .filter(|subscriber| subscriber.task_topic_id == entity.id)
+            {
+                match encryption::open_identity(
+                    &key_id,
+                    &subscriber.subscriber_name,
+                    subscriber.sealed_name.as_deref(),
+                ) {
+                    Some(subscriber_name) => topic.add_subscriber(subscriber_name),
+                    None => warn!(
+                        "Restore::restore_task_topics skipped a subscriber of '{}' that cannot be unsealed",
+                        entity.topic_name
+                    ),
+                }
+            }
+
+            topics.push(topic);
+        }
+    } else {
+        warn!("Restore::restore_task_topics tried to lock a poisoned mutex");
+    }
+}
+
+async fn restore_wildcard_subscriptions(db_connection_pool: &Pool<Postgres>) {
+    let entities =
+        wildcard_subscription_repository::get_all_wildcard_subscriptions(db_connection_pool).await;
+    let topics =
+        wildcard_subscription_repository::get_all_wildcard_subscription_topics(db_connection_pool)
+            .await;
+
+    if let Ok(mut subscriptions) = STATE.wildcard_subscriptions.lock() {
+        for entity in entities {
+            let Some(pattern) = TopicPattern::parse(&entity.pattern) else {
+                warn!(
+                    "Restore::restore_wildcard_subscriptions skipped '{}' because of an invalid pattern",
+                    entity.subscriber_name
+                );
+                continue;
+            };
+            let filter = entity
+                .filter
+                .as_deref()
+                .and_then(|filter| serde_json::from_str(filter).ok());
+
+            let owned_topics = topics
+                .iter()
+                .filter(|topic| {
+                    topic.subscriber_name == entity.subscriber_name
+                        && topic.pattern == entity.pattern
+                })
+                .filter_map(|topic| {
+                    let topic_name = encryption::open_topic_name(
+                        &topic.topic_name,
+                        topic.sealed_topic_name.as_deref(),
+                    );
+                    if topic_name.is_none() {
+                        warn!(
+                            "Restore::restore_wildcard_subscriptions skipped a topic of '{}' whose name cannot be unsealed",
+                            entity.subscriber_name
+                        );
+                    }
+                    topic_name
+                })
+                .collect();
+
+            subscriptions.push(WildcardSubscription::new(
+                entity.subscriber_name,
+                pattern,
+                filter,
+                owned_topics,
+            ));
+        }
+    } else {
+        warn!("Restore::restore_wildcard_subscriptions tried to lock a poisoned mutex");
+    }
+}
+
+async fn restore_exchanges(db_connection_pool: &Pool<Postgres>) {
+    let entities = exchange_repository::get_all_exchanges(db_connection_pool).await;
+    let bindings = exchange_repository::get_all_exchange_bindings(db_connection_pool).await;
+    let publishers = exchange_repository::get_all_exchange_publishers(db_connection_pool).await;
+
+    if let Ok(mut exchanges) = STATE.exchanges.lock() {
+        for entity in entities {
+            let Ok(exchange_type) = entity.exchange_type.parse::<ExchangeType>() else {
+                warn!(
+                    "Restore::restore_exchanges skipped '{}' because of an unknown exchange type",
+                    entity.exchange_name
+                );
+                continue;
+            };
+            let mut exchange = Exchange::new(entity.exchange_name.clone(), exchange_type);
+
+            for binding in bindings
+                .iter()
+                .filter(|binding| binding.exchange_id == entity.id)
+            {
+                let Ok(destination_type) = binding.destination_type.parse::<DestinationType>()
+                else {
+                    warn!("Restore::restore_exchanges skipped a binding with an unknown destination type");
+                    continue;
+                };
+                let Some(destination_name) = encryption::open_topic_name(
+                    &binding.destination_name,
+                    binding.sealed_destination_name.as_deref(),
+                ) else {
+                    warn!("Restore::restore_exchanges skipped a binding whose destination cannot be unsealed");
+                    continue;
+                };
+                exchange.add_binding(Binding::new(
+                    binding.id,
+                    TopicDestination::new(destination_type, destination_name),
+                    binding.routing_key.clone(),
+                    serde_json::from_str(&binding.arguments).unwrap_or_default(),
+                    binding
+                        .header_match
+                        .parse::<HeaderMatch>()
+                        .unwrap_or_default(),
+                ));
+            }
+            for publisher in publishers
+                .iter()
+                .filter(|publisher| publisher.exchange_id == entity.id)
+            {
+                exchange.add_publisher(publisher.publisher_name.clone());
+            }
+
+            exchanges.push(exchange);
+        }
+    } else {
+        warn!("Restore::restore_exchanges tried to lock a poisoned mutex");
+    }
+}
+
+async fn restore_forwarding_rules(db_connection_pool: &Pool<Postgres>) {
+    let entities = forwarding_rule_repository::get_all_forwarding_rules(db_connection_pool).await;
+
+    if let Ok(mut rules) = STATE.forwarding_rules.lock() {
+        for entity in entities {
+            let (Some(source), Ok(destination_type), Some(destination_name)) = (
+                TopicPattern::parse(&entity.source),
+                entity.destination_type.parse::<DestinationType>(),
+                encryption::open_topic_name(
+                    &entity.destination_name,
+                    entity.sealed_destination_name.as_deref(),
+                ),
+            ) else {
+                warn!(
+                    "Restore::restore_forwarding_rules skipped '{}' because it could not be parsed",
+                    entity.rule_name
+                );
+                continue;
+            };
+
+            rules.push(ForwardingRule::new(
+                entity.namespace,
+                entity.rule_name,
+                source,
+                TopicDestination::new(destination_type, destination_name),
+                serde_json::from_str(&entity.header_rewrite).unwrap_or_default(),
+            ));
+        }
+    } else {
+        warn!("Restore::restore_forwarding_rules tried to lock a poisoned mutex");
+    }
+}
+
+async fn restore_retained_messages(db_connection_pool: &Pool<Postgres>) {
+    let entities = retained_message_repository::get_all_retained_messages(db_connection_pool).await;
+
+    if let Ok(mut retained_messages) = STATE.retained_messages.lock() {
+        for mut entity in entities {
+            let Some(topic_name) =
+                encryption::open_topic_name(&entity.topic_name, entity.sealed_name.as_deref())
+            else {
+                warn!("Restore::restore_retained_messages skipped a message whose topic name cannot be unsealed");
+                continue;
+            };
+            entity.topic_name = topic_name;
+            let key_id = DataKeyId::for_retained_message(entity.topic_name.clone());
+            let (data, headers) = match (entity.sealed_data, entity.sealed_headers) {
+                (Some(sealed_data), Some(sealed_headers)) => (
+                    encryption::open(&key_id, &sealed_data),
+                    encryption::open(&key_id, &sealed_headers),
+                ),
+                _ => (Some(entity.data), Some(entity.headers)),
+            };
+            let (Some(data), Some(headers)) = (data, headers) else {
+                warn!(
+                    "Restore::restore_retained_messages skipped '{}' because it cannot be unsealed",
+                    entity.topic_name
+                );
+                continue;
+            };
+
+            retained_messages.insert(
+                entity.topic_name,
+                Message::new(data, serde_json::from_str(&headers).unwrap_or_default()),
+            );
+        }
+    } else {
+        warn!("Restore::restore_retained_messages tried to lock a poisoned mutex");
+    }
+}
+
+async fn restore_credentials(db_connection_pool: &Pool<Postgres>) {
+    let entities = credential_repository::get_all_credentials(db_connection_pool).await;
+
+    if let Ok(mut credentials) = STATE.credentials.lock() {
+        for entity in entities {
+            credentials.push(Credential::new(
+                entity.namespace,
+                entity.credential_name,
+                entity.secret_hash,
+                serde_json::from_str(&entity.principals).unwrap_or_default(),
+            ));
+        }
+    } else {
+        warn!("Restore::restore_credentials tried to lock a poisoned mutex");
+    }
+}
+
+async fn restore_acl_grants(db_connection_pool: &Pool<Postgres>) {
+    let entities = acl_repository::get_all_grants(db_connection_pool).await;
+
+    if let Ok(mut grants) = STATE.acl_grants.lock() {
+        for entity in entities {
+            let (Ok(role), Some(pattern)) = (
+                entity.role.parse::<Role>(),
+                TopicPattern::parse(&entity.pattern),
+            ) else {
+                warn!(
+                    "Restore::restore_acl_grants skipped the invalid grant '{}'",
+                    entity.id
+                );
+                continue;
+            };
+            grants.push(Grant::new(
+                entity.id,
+                entity.namespace,
+                entity.principal,
+                role,
+                pattern,
+            ));
+        }
+    } else {
+        warn!("Restore::restore_acl_grants tried to lock a poisoned mutex");
+    }
+}
+
+// the topic name key seals the names every other row is found by, so it is restored first
+async fn restore_topic_name_key(db_connection_pool: &Pool<Postgres>) {
+    if let Err(e) = data_key_repository::ensure_topic_name_key(db_connection_pool).await {
+        panic!("The topic name key could not be restored: {}", e);
+    }
+}
+
+/// Seals the topic names written before encryption was enabled, before any topic is looked up.
+async fn seal_plaintext_topic_names(db_connection_pool: &Pool<Postgres>) {
+    let mut sealed_names = 0;
+    for topic in message_topic_repository::get_all_message_topics(db_connection_pool)
+        .await
+        .into_iter()
+        .filter(|topic| topic.sealed_name.is_none())
+    {
+        let key_id = DataKeyId::new(
+            DestinationType::MessageTopic,
+            topic.namespace,
+            topic.topic_name,
+        );
+        message_topic_repository::seal_message_topic_name(
+            db_connection_pool,
+            topic.id,
+            encryption::topic_name_lookup(&key_id),
+            encryption::seal_topic_name(&key_id),
+        )
+        .await;
+        sealed_names += 1;
+    }
+    for topic in task_topic_repository::get_all_task_topics(db_connection_pool)
+        .await
+        .into_iter()
+        .filter(|topic| topic.sealed_name.is_none())
+    {
+        let key_id = DataKeyId::new(
+            DestinationType::TaskTopic,
+            topic.namespace,
+            topic.topic_name,
+        );
+        task_topic_repository::seal_task_topic_name(
+            db_connection_pool,
+            topic.id,
+            encryption::topic_name_lookup(&key_id),
+            encryption::seal_topic_name(&key_id),
+        )
+        .await;
+        sealed_names += 1;
+    }
+    for message in retained_message_repository::get_all_retained_messages(db_connection_pool)
+        .await
+        .into_iter()
+        .filter(|message| message.sealed_name.is_none())
+    {
+        let key_id = DataKeyId::for_retained_message(message.topic_name.clone());
+        retained_message_repository::seal_retained_message_name(
+            db_connection_pool,
+            message.topic_name,
+            encryption::topic_name_lookup(&key_id),
+            encryption::seal_topic_name(&key_id),
+        )
+        .await;
+        sealed_names += 1;
+    }
+    for data_key in data_key_repository::get_all_data_keys(db_connection_pool)
+        .await
+        .into_iter()
+        .filter(|data_key| data_key.sealed_name.is_none())
+    {
+        let Ok(destination_type) = data_key.topic_type.parse::<DestinationType>() else {
+            continue;
+        };
+        let key_id = DataKeyId::new(
+            destination_type,
+            data_key.namespace,
+            data_key.topic_name.clone(),
+        );
+        data_key_repository::seal_data_key_name(db_connection_pool, &key_id, data_key.topic_name)
+            .await;
+        sealed_names += 1;
+    }
+
+    for topic in
+        wildcard_subscription_repository::get_all_wildcard_subscription_topics(db_connection_pool)
+            .await
+            .into_iter()
+            .filter(|topic| topic.sealed_topic_name.is_none())
+    {
+        let key_id = DataKeyId::new(
+            DestinationType::MessageTopic,
+            DEFAULT_NAMESPACE.to_string(),
+            topic.topic_name.clone(),
+        );
+        wildcard_subscription_repository::seal_wildcard_subscription_topic_name(
+            db_connection_pool,
+            topic,
+            encryption::topic_name_lookup(&key_id),
+            encryption::seal_topic_name(&key_id),
+        )
+        .await;
+        sealed_names += 1;
+    }
+    for binding in exchange_repository::get_all_exchange_bindings(db_connection_pool)
+        .await
+        .into_iter()
+        .filter(|binding| binding.sealed_destination_name.is_none())
+    {
+        let Ok(destination_type) = binding.destination_type.parse::<DestinationType>() else {
+            continue;
+        };
+        let key_id = DataKeyId::new(
+            destination_type,
+            DEFAULT_NAMESPACE.to_string(),
+            binding.destination_name,
+        );
+        exchange_repository::seal_exchange_binding_destination(
+            db_connection_pool,
+            binding.id,
+            encryption::topic_name_lookup(&key_id),
+            encryption::seal_topic_name(&key_id),
+        )
+        .await;
+        sealed_names += 1;
+    }
+    for rule in forwarding_rule_repository::get_all_forwarding_rules(db_connection_pool)
+        .await
+        .into_iter()
+        .filter(|rule| rule.sealed_destination_name.is_none())
+    {
+        let Ok(destination_type) = rule.destination_type.parse::<DestinationType>() else {
+            continue;
+        };
+        let key_id = DataKeyId::new(destination_type, rule.namespace, rule.destination_name);
+        forwarding_rule_repository::seal_forwarding_rule_destination(
+            db_connection_pool,
+            rule.id,
+            encryption::topic_name_lookup(&key_id),
+            encryption::seal_topic_name(&key_id),
+        )
+        .await;
+        sealed_names += 1;
+    }
+
+    if sealed_names > 0 {
+        println!(
+            "Sealed {} topic names written before encryption was enabled",
+            sealed_names
+        );
+    }
+}
+
+/// Seals the rows written before encryption was enabled, every topic gets its data key first.
+async fn seal_plaintext_rows(db_connection_pool: &Pool<Postgres>) {
+    let message_topics = message_topic_repository::get_all_message_topics(db_connection_pool).await;
+    let task_topics = task_topic_repository::get_all_task_topics(db_connection_pool).await;
+    let retained_messages =
+        retained_message_repository::get_all_retained_messages(db_connection_pool).await;
+
+    let message_topic_keys: HashMap<Uuid, DataKeyId> = message_topics
+        .into_iter()
+        .filter_map(|topic| {
+            let topic_name =
+                encryption::open_topic_name(&topic.topic_name, topic.sealed_name.as_deref())?;
+            let key_id = DataKeyId::new(DestinationType::MessageTopic, topic.namespace, topic_name);
+            Some((topic.id, key_id))
+        })
+        .collect();
+    let task_topic_keys: HashMap<Uuid, DataKeyId> = task_topics
+        .into_iter()
+        .filter_map(|topic| {
+            let topic_name =
+                encryption::open_topic_name(&topic.topic_name, topic.sealed_name.as_deref())?;
+            let key_id = DataKeyId::new(DestinationType::TaskTopic, topic.namespace, topic_name);
+            Some((topic.id, key_id))
+        })
+        .collect();
+    let retained_messages: Vec<RetainedMessageEntity> = retained_messages
+        .into_iter()
+        .filter_map(|mut message| {
+            message.topic_name =
+                encryption::open_topic_name(&message.topic_name, message.sealed_name.as_deref())?;
+            Some(message)
+        })
+        .collect();
+    let retained_message_keys = retained_messages
+        .iter()
+        .map(|message| DataKeyId::for_retained_message(message.topic_name.clone()));
+
+    for key_id in message_topic_keys
+        .values()
+        .chain(task_topic_keys.values())
+        .cloned()
+        .chain(retained_message_keys)
+    {
+        if let Err(e) = data_key_repository::ensure_data_key(db_connection_pool, &key_id).await {
+            panic!("Data keys could not be stored: {}", e);
+        }
+    }
+
+    let mut sealed_rows = 0;
+    for publisher in message_topic_repository::get_all_message_topic_publishers(db_connection_pool)
+        .await
+        .into_iter()
+        .filter(|publisher| publisher.sealed_name.is_none())
+    {
+        let Some(key_id) = message_topic_keys.get(&publisher.message_topic_id) else {
+            continue;
+        };
+        let Ok((publisher_name, sealed_name)) =
+            encryption::seal_identity(key_id, &publisher.publisher_name)
+        else {
+            continue;
+        };
+        message_topic_repository::seal_message_topic_publisher(
+            db_connection_pool,
+            publisher.id,
+            publisher_name,
+            sealed_name,
+        )
+        .await;
+        sealed_rows += 1;
+    }
+    for subscriber in
+        message_topic_repository::get_all_message_topic_subscribers(db_connection_pool)
+            .await
+            .into_iter()
+            .filter(|subscriber| subscriber.sealed_name.is_none())
+    {
+        let Some(key_id) = message_topic_keys.get(&subscriber.message_topic_id) else {
+            continue;
+        };
+        let Ok((subscriber_name, sealed_name)) =
+            encryption::seal_identity(key_id, &subscriber.subscriber_name)
+        else {
+            continue;
+        };
+        let Ok(sealed_filter) = subscriber
+            .filter
+            .as_deref()
+            .map(|filter| encryption::seal(key_id, filter))
+            .transpose()
+        else {
+            continue;
+        };
+        let sealed_filter = sealed_filter.flatten();
+        message_topic_repository::seal_message_topic_subscriber(
+            db_connection_pool,
+            subscriber.id,
+            subscriber_name,
+            sealed_name,
+            sealed_filter,
+        )
+        .await;
+        sealed_rows += 1;
+    }
+    for publisher in task_topic_repository::get_all_task_topic_publishers(db_connection_pool)
+        .await
+        .into_iter()
+        .filter(|publisher| publisher.sealed_name.is_none())
+    {
+        let Some(key_id) = task_topic_keys.get(&publisher.task_topic_id) else {
+            continue;
+        };
+        let Ok((publisher_name, sealed_name)) =
+            encryption::seal_identity(key_id, &publisher.publisher_name)
+        else {
+            continue;
+        };
+        task_topic_repository::seal_task_topic_publisher(
+            db_connection_pool,
+            publisher.id,
+            publisher_name,
+            sealed_name,
+        )
+        .await;
+        sealed_rows += 1;
+    }
+    for subscriber in task_topic_repository::get_all_task_topic_subscribers(db_connection_pool)
+        .await
+        .into_iter()
+        .filter(|subscriber| subscriber.sealed_name.is_none())
+    {
+        let Some(key_id) = task_topic_keys.get(&subscriber.task_topic_id) else {
+            continue;
+        };
+        let Ok((subscriber_name, sealed_name)) =
+            encryption::seal_identity(key_id, &subscriber.subscriber_name)
+        else {
+            continue;
+        };
+        task_topic_repository::seal_task_topic_subscriber(
+            db_connection_pool,
+            subscriber.id,
+            subscriber_name,
+            sealed_name,
+        )
+        .await;
+        sealed_rows += 1;
+    }
+    for message in retained_messages
+        .into_iter()
+        .filter(|message| message.sealed_data.is_none())
+    {
+        let model = RetainedMessageModel::new(
+            message.topic_name,
+            message.data,
+            serde_json::from_str(&message.headers).unwrap_or_default(),
+        );
+        let thread_data = ThreadData {
+            db_connection_pool: db_connection_pool.clone(),
+        };
+        retained_message_repository::set_retained_message(thread_data, model).await;
+        sealed_rows += 1;
+    }
+
+    if sealed_rows > 0 {
+        println!(
+            "Sealed {} rows written before encryption was enabled",
+            sealed_rows
+        );
+    }
+}
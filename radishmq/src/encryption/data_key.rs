@@ -0,0 +1,148 @@
+use crate::encryption::{decrypt, encrypt};
+use crate::model::destination_model::DestinationType;
+use crate::utils::namespace::DEFAULT_NAMESPACE;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use aes_gcm::{Aes256Gcm, KeyInit};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub const KEY_LENGTH: usize = 32;
+
+const BLIND_INDEX_CONTEXT: &[u8] = b"radish-mq blind index";
+
+/// The topic a data key belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DataKeyId {
+    pub destination_type: DestinationType,
+    pub namespace: String,
+    pub topic_name: String,
+}
+
+impl DataKeyId {
+    pub fn new(destination_type: DestinationType, namespace: String, topic_name: String) -> Self {
+        Self {
+            destination_type,
+            namespace,
+            topic_name,
+        }
+    }
+
+    /// Retained messages only exist in the default namespace and are sealed with the key of their
+    /// message topic.
+    pub fn for_retained_message(topic_name: String) -> Self {
+        Self::new(
+            DestinationType::MessageTopic,
+            DEFAULT_NAMESPACE.to_string(),
+            topic_name,
+        )
+    }
+
+    /// Binds a wrapped key to its topic, so it cannot be moved to another one.
+    pub fn context(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.destination_type.as_str(),
+            self.namespace,
+            self.topic_name
+        )
+    }
+}
+
+/// The AES-256-GCM key of a topic, it is only persisted wrapped by a master key.
+#[derive(Clone)]
+pub struct DataKey {
+    key: [u8; KEY_LENGTH],
+}
+
+impl DataKey {
+    pub fn generate() -> Self {
+        let mut key = [0u8; KEY_LENGTH];
+        OsRng.fill_bytes(&mut key);
+        Self { key }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(Self {
+            key: bytes.try_into().ok()?,
+        })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn seal(&self, plaintext: &[u8]) -> Vec<u8> {
+        encrypt(&self.cipher(), plaintext, &[])
+    }
+
+    pub fn open(&self, sealed: &[u8]) -> Option<Vec<u8>> {
+        decrypt(&self.cipher(), sealed, &[])
+    }
+
+    /// A keyed hash of `value` that is stored instead of it where the database has to find or
+    /// compare values, sealed values differ on every write.
+    pub fn blind_index(&self, value: &str) -> String {
+        let mut index_key = self.mac();
+        index_key.update(BLIND_INDEX_CONTEXT);
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&index_key.finalize().into_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(value.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(&self.key.into())
+    }
+
+    fn mac(&self) -> Hmac<Sha256> {
+        <Hmac<Sha256> as Mac>::new_from_slice(&self.key).expect("HMAC accepts keys of any length")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_and_open_round_trip() {
+        let key = DataKey::generate();
+        for plaintext in ["", "hello", &"x".repeat(4096)] {
+            let sealed = key.seal(plaintext.as_bytes());
+            assert_ne!(sealed, plaintext.as_bytes());
+            assert_eq!(key.open(&sealed).as_deref(), Some(plaintext.as_bytes()));
+        }
+    }
+
+    #[test]
+    fn sealing_twice_uses_a_fresh_nonce() {
+        let key = DataKey::generate();
+        assert_ne!(key.seal(b"hello"), key.seal(b"hello"));
+    }
+
+    #[test]
+    fn tampered_or_foreign_ciphertexts_do_not_open() {
+        let key = DataKey::generate();
+        let mut sealed = key.seal(b"hello");
+        assert!(DataKey::generate().open(&sealed).is_none());
+        assert!(key.open(&sealed[..sealed.len() - 1]).is_none());
+        assert!(key.open(&sealed[..4]).is_none());
+
+        let last = sealed.len() - 1;
+        sealed[last] ^= 1;
+        assert!(key.open(&sealed).is_none());
+    }
+
+    #[test]
+    fn blind_indexes_are_stable_per_key() {
+        let key = DataKey::generate();
+        let restored = DataKey::from_bytes(key.as_bytes()).unwrap();
+        assert_eq!(key.blind_index("alice"), restored.blind_index("alice"));
+        assert_ne!(key.blind_index("alice"), key.blind_index("bob"));
+        assert_ne!(
+            key.blind_index("alice"),
+            DataKey::generate().blind_index("alice")
+        );
+        assert!(DataKey::from_bytes(&[0u8; 16]).is_none());
+    }
+}
@@ -0,0 +1,157 @@
+use crate::encryption::data_key::{DataKey, KEY_LENGTH};
+use crate::encryption::{decrypt, encrypt};
+use aes_gcm::{Aes256Gcm, KeyInit};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use std::fs;
+
+const MAX_KEY_ID_LENGTH: usize = 64;
+
+/// A master key of the keyfile, it only wraps the data keys of the topics and the topic name key.
+pub struct MasterKey {
+    pub id: String,
+    cipher: Aes256Gcm,
+}
+
+impl MasterKey {
+    /// Wraps a key bound to `context`, so it cannot be moved to another topic.
+    pub fn wrap(&self, context: &str, data_key: &DataKey) -> Vec<u8> {
+        encrypt(&self.cipher, data_key.as_bytes(), context.as_bytes())
+    }
+
+    pub fn unwrap(&self, context: &str, wrapped_key: &[u8]) -> Option<DataKey> {
+        decrypt(&self.cipher, wrapped_key, context.as_bytes())
+            .and_then(|key| DataKey::from_bytes(&key))
+    }
+}
+
+/// The master keys of the keyfile, the last one wraps new data keys and the older ones are kept
+/// to unwrap the data keys they wrapped before a rotation.
+pub struct Keyring {
+    keys: Vec<MasterKey>,
+}
+
+impl Keyring {
+    /// Reads a keyfile with one `<id> <base64 key>` line per 256 bit master key, empty lines and
+    /// lines starting with `#` are skipped.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut keys: Vec<MasterKey> = Vec::new();
+
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (id, key) = line
+                .split_once(char::is_whitespace)
+                .ok_or(format!("'{}' is not an '<id> <key>' line", line))?;
+            if id.len() > MAX_KEY_ID_LENGTH
+                || !id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            {
+                return Err(format!("'{}' is not a valid key id", id));
+            }
+            if keys.iter().any(|master_key| master_key.id == id) {
+                return Err(format!("the key id '{}' is used twice", id));
+            }
+            let key = BASE64_STANDARD
+                .decode(key.trim())
+                .ok()
+                .filter(|key| key.len() == KEY_LENGTH)
+                .ok_or(format!(
+                    "the key '{}' is not {} base64 bytes",
+                    id, KEY_LENGTH
+                ))?;
+
+            keys.push(MasterKey {
+                id: id.to_string(),
+                cipher: Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string())?,
+            });
+        }
+
+        if keys.is_empty() {
+            return Err("the keyfile has no keys".to_string());
+        }
+        Ok(Self { keys })
+    }
+
+    pub fn active(&self) -> &MasterKey {
+        // load guarantees at least one key
+        &self.keys[self.keys.len() - 1]
+    }
+
+    pub fn get(&self, id: &str) -> Option<&MasterKey> {
+        self.keys.iter().find(|master_key| master_key.id == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn keyfile(name: &str, content: &str) -> String {
+        let path = env::temp_dir().join(format!("radish-mq-{}-{}", name, std::process::id()));
+        fs::write(&path, content).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn key_line(id: &str, byte: u8) -> String {
+        format!("{} {}\n", id, BASE64_STANDARD.encode([byte; KEY_LENGTH]))
+    }
+
+    #[test]
+    fn wrapped_keys_only_unwrap_in_their_context() {
+        let path = keyfile("wrap", &(key_line("k1", 1) + &key_line("k2", 2)));
+        let keyring = Keyring::load(&path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        let data_key = DataKey::generate();
+        let wrapped = keyring
+            .active()
+            .wrap("message_topic:default:orders", &data_key);
+        let unwrapped = keyring
+            .active()
+            .unwrap("message_topic:default:orders", &wrapped)
+            .unwrap();
+        assert_eq!(unwrapped.as_bytes(), data_key.as_bytes());
+
+        assert!(keyring
+            .active()
+            .unwrap("message_topic:default:billing", &wrapped)
+            .is_none());
+        assert!(keyring
+            .get("k1")
+            .unwrap()
+            .unwrap("message_topic:default:orders", &wrapped)
+            .is_none());
+    }
+
+    #[test]
+    fn the_last_key_of_the_keyfile_is_active() {
+        let content = format!("# rotated\n\n{}{}", key_line("k1", 1), key_line("k2", 2));
+        let path = keyfile("active", &content);
+        let keyring = Keyring::load(&path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(keyring.active().id, "k2");
+        assert!(keyring.get("k1").is_some());
+        assert!(keyring.get("k3").is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_keyfiles() {
+        let short_key = format!("k1 {}\n", BASE64_STANDARD.encode([1u8; 16]));
+        for (name, content) in [
+            ("empty", "# no keys\n".to_string()),
+            ("no-key", "k1\n".to_string()),
+            ("short-key", short_key),
+            ("bad-id", key_line("k/1", 1)),
+            ("duplicate-id", key_line("k1", 1) + &key_line("k1", 2)),
+        ] {
+            let path = keyfile(name, &content);
+            assert!(Keyring::load(&path).is_err(), "{}", name);
+            fs::remove_file(path).unwrap();
+        }
+    }
+}
@@ -0,0 +1,282 @@
+pub mod data_key;
+pub mod keyring;
+
+use crate::encryption::data_key::{DataKey, DataKeyId};
+use crate::encryption::keyring::Keyring;
+use crate::STATE;
+use aes_gcm::aead::{Aead, AeadCore, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use lazy_static::lazy_static;
+use log::warn;
+use std::env;
+
+const NONCE_LENGTH: usize = 12;
+
+// binds the wrapped topic name key, data keys are bound to their topic
+const TOPIC_NAME_KEY_CONTEXT: &str = "topic-names";
+
+lazy_static! {
+    static ref KEYRING: Option<Keyring> = keyring_from_env();
+}
+
+/// Encryption at rest is enabled by `ENCRYPTION_KEYFILE`, a keyfile that cannot be read stops the
+/// broker instead of persisting plaintext.
+fn keyring_from_env() -> Option<Keyring> {
+    let path = env::var("ENCRYPTION_KEYFILE").ok()?;
+    match Keyring::load(&path) {
+        Ok(keyring) => Some(keyring),
+        Err(e) => panic!("ENCRYPTION_KEYFILE '{}' is not valid: {}", path, e),
+    }
+}
+
+/// A data key as it is persisted, wrapped by the master key `master_key_id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrappedDataKey {
+    pub id: DataKeyId,
+    pub master_key_id: String,
+    pub wrapped_key: Vec<u8>,
+}
+
+/// The topic name key as it is persisted, wrapped by the master key `master_key_id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrappedTopicNameKey {
+    pub master_key_id: String,
+    pub wrapped_key: Vec<u8>,
+}
+
+pub fn is_enabled() -> bool {
+    KEYRING.is_some()
+}
+
+/// Generates a data key for a topic, wrapped by the active master key. It is only used once
+/// `restore_data_key` installs it after it was stored, `None` if encryption is disabled.
+pub fn generate_data_key(id: DataKeyId) -> Option<WrappedDataKey> {
+    let master_key = KEYRING.as_ref()?.active();
+    let wrapped_key = master_key.wrap(&id.context(), &DataKey::generate());
+
+    Some(WrappedDataKey {
+        id,
+        master_key_id: master_key.id.clone(),
+        wrapped_key,
+    })
+}
+
+pub fn has_data_key(id: &DataKeyId) -> bool {
+    data_key(id).is_some()
+}
+
+/// Drops the data key of a deleted topic, data sealed with it cannot be opened anymore.
+pub fn forget_data_key(id: &DataKeyId) {
+    if let Ok(mut data_keys) = STATE.data_keys.lock() {
+        data_keys.remove(id);
+    } else {
+        warn!("Encryption::forget_data_key tried to lock a poisoned mutex");
+    }
+}
+
+/// Unwraps a persisted data key. A key wrapped by an older master key is returned wrapped by the
+/// active one, so it can be rewritten and the old master key removed from the keyfile.
+pub fn restore_data_key(wrapped: &WrappedDataKey) -> Result<Option<WrappedDataKey>, String> {
+    let (data_key, rewrapped) = unwrap_key(
+        &wrapped.master_key_id,
+        &wrapped.id.context(),
+        &wrapped.wrapped_key,
+    )?;
+
+    let mut data_keys = STATE
+        .data_keys
+        .lock()
+        .map_err(|_| "the data keys are unavailable".to_string())?;
+    data_keys.insert(wrapped.id.clone(), data_key);
+    Ok(
+        rewrapped.map(|(master_key_id, wrapped_key)| WrappedDataKey {
+            id: wrapped.id.clone(),
+            master_key_id,
+            wrapped_key,
+        }),
+    )
+}
+
+/// Generates the topic name key on the first start with encryption, it is only used once
+/// `restore_topic_name_key` installs it after it was stored.
+pub fn generate_topic_name_key() -> Option<WrappedTopicNameKey> {
+    let master_key = KEYRING.as_ref()?.active();
+    let wrapped_key = master_key.wrap(TOPIC_NAME_KEY_CONTEXT, &DataKey::generate());
+
+    Some(WrappedTopicNameKey {
+        master_key_id: master_key.id.clone(),
+        wrapped_key,
+    })
+}
+
+/// Unwraps the stored topic name key, rewrapped by the active master key like a data key.
+pub fn restore_topic_name_key(
+    wrapped: &WrappedTopicNameKey,
+) -> Result<Option<WrappedTopicNameKey>, String> {
+    let (topic_name_key, rewrapped) = unwrap_key(
+        &wrapped.master_key_id,
+        TOPIC_NAME_KEY_CONTEXT,
+        &wrapped.wrapped_key,
+    )?;
+
+    let mut key = STATE
+        .topic_name_key
+        .lock()
+        .map_err(|_| "the topic name key is unavailable".to_string())?;
+    *key = Some(topic_name_key);
+    Ok(
+        rewrapped.map(|(master_key_id, wrapped_key)| WrappedTopicNameKey {
+            master_key_id,
+            wrapped_key,
+        }),
+    )
+}
+
+// the id of the active master key and a key wrapped by it
+type Rewrapped = (String, Vec<u8>);
+
+// the unwrapped key and, if an older master key wrapped it, the key wrapped by the active one
+fn unwrap_key(
+    master_key_id: &str,
+    context: &str,
+    wrapped_key: &[u8],
+) -> Result<(DataKey, Option<Rewrapped>), String> {
+    let keyring = KEYRING
+        .as_ref()
+        .ok_or("data keys exist but ENCRYPTION_KEYFILE is not set".to_string())?;
+    let master_key = keyring.get(master_key_id).ok_or(format!(
+        "the master key '{}' is missing in the keyfile",
+        master_key_id
+    ))?;
+    let key = master_key
+        .unwrap(context, wrapped_key)
+        .ok_or(format!("the key of '{}' cannot be unwrapped", context))?;
+
+    let active = keyring.active();
+    let rewrapped =
+        (active.id != master_key.id).then(|| (active.id.clone(), active.wrap(context, &key)));
+    Ok((key, rewrapped))
+}
+
+fn topic_name_key() -> Option<DataKey> {
+    KEYRING.as_ref()?;
+    if let Ok(key) = STATE.topic_name_key.lock() {
+        key.clone()
+    } else {
+        warn!("Encryption::topic_name_key tried to lock a poisoned mutex");
+        None
+    }
+}
+
+/// The stored value of a topic name, with encryption a blind index of the namespace, type and
+/// name of the topic. The topic name key is restored before anything is persisted.
+pub fn topic_name_lookup(id: &DataKeyId) -> String {
+    match topic_name_key() {
+        Some(key) => key.blind_index(&id.context()),
+        None => id.topic_name.clone(),
+    }
+}
+
+/// The sealed form of a topic name, `None` if encryption is disabled.
+pub fn seal_topic_name(id: &DataKeyId) -> Option<Vec<u8>> {
+    topic_name_key().map(|key| key.seal(id.topic_name.as_bytes()))
+}
+
+/// Reads back a stored topic name, rows written without encryption hold it in plaintext.
+pub fn open_topic_name(stored: &str, sealed: Option<&[u8]>) -> Option<String> {
+    match sealed {
+        Some(sealed) => String::from_utf8(topic_name_key()?.open(sealed)?).ok(),
+        None => Some(stored.to_string()),
+    }
+}
+
+fn data_key(id: &DataKeyId) -> Option<DataKey> {
+    KEYRING.as_ref()?;
+    if let Ok(data_keys) = STATE.data_keys.lock() {
+        data_keys.get(id).cloned()
+    } else {
+        warn!("Encryption::data_key tried to lock a poisoned mutex");
+        None
+    }
+}
+
+/// The value to store for a client identity and its sealed form, the identity itself if
+/// encryption is disabled. The data key of the topic has to be stored first.
+pub fn seal_identity(id: &DataKeyId, identity: &str) -> Result<(String, Option<Vec<u8>>), String> {
+    match sealing_key(id)? {
+        Some(data_key) => Ok((
+            data_key.blind_index(identity),
+            Some(data_key.seal(identity.as_bytes())),
+        )),
+        None => Ok((identity.to_string(), None)),
+    }
+}
+
+/// The stored value of a client identity, to find its row. Without a data key nothing of the
+/// topic was sealed, so the identity is stored in plaintext.
+pub fn identity_lookup(id: &DataKeyId, identity: &str) -> String {
+    match data_key(id) {
+        Some(data_key) => data_key.blind_index(identity),
+        None => identity.to_string(),
+    }
+}
+
+/// Reads back a stored client identity, rows written without encryption hold it in plaintext.
+pub fn open_identity(id: &DataKeyId, stored: &str, sealed: Option<&[u8]>) -> Option<String> {
+    match sealed {
+        Some(sealed) => open(id, sealed),
+        None => Some(stored.to_string()),
+    }
+}
+
+/// Seals a value with the data key of its topic, `None` if encryption is disabled. The data key of
+/// the topic has to be stored first.
+pub fn seal(id: &DataKeyId, plaintext: &str) -> Result<Option<Vec<u8>>, String> {
+    Ok(sealing_key(id)?.map(|data_key| data_key.seal(plaintext.as_bytes())))
+}
+
+// a topic without a stored data key is refused instead of persisting its data in plaintext
+fn sealing_key(id: &DataKeyId) -> Result<Option<DataKey>, String> {
+    if !is_enabled() {
+        return Ok(None);
+    }
+    data_key(id)
+        .map(Some)
+        .ok_or(format!("the data key of '{}' is not stored", id.context()))
+}
+
+pub fn open(id: &DataKeyId, sealed: &[u8]) -> Option<String> {
+    let plaintext = data_key(id)?.open(sealed)?;
+    String::from_utf8(plaintext).ok()
+}
+
+/// AES-GCM with a random nonce, which is prepended to the ciphertext.
+fn encrypt(cipher: &Aes256Gcm, plaintext: &[u8], aad: &[u8]) -> Vec<u8> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .expect("AES-GCM encrypts any plaintext that fits into memory");
+    [nonce.as_slice(), &ciphertext].concat()
+}
+
+fn decrypt(cipher: &Aes256Gcm, sealed: &[u8], aad: &[u8]) -> Option<Vec<u8>> {
+    if sealed.len() < NONCE_LENGTH {
+        return None;
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+    cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .ok()
+}
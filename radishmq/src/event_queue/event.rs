@@ -1,14 +1,30 @@
+use crate::auth::credential::Credential;
+use crate::auth::grant::Grant;
 use crate::event_queue::worker::ThreadData;
+use crate::model::audit_model::AuditEntryModel;
+use crate::model::exchange_model::{
+    AddPublisherToExchange, BindingModel, CreateExchange, RemovePublisherFromExchange,
+};
+use crate::model::forwarding_rule_model::CreateForwardingRule;
 use crate::model::message_topic_model::{
     AddPublisherToMessageTopic, AddSubscriberToMessageTopic, CreateMessageTopic,
     PublishToMessageTopic, RemovePublisherFromMessageTopic, RemoveSubscriberFromMessageTopic,
 };
+use crate::model::retained_message_model::RetainedMessageModel;
 use crate::model::task_topic_model::{
     AddPublisherToTaskTopic, AddSubscriberToTaskTopic, CreateTaskTopic, PublishToTaskTopic,
     RemovePublisherFromTaskTopic, RemoveSubscriberFromTaskTopic,
 };
-use crate::repository::{message_topic_repository, task_topic_repository};
+use crate::model::wildcard_subscription_model::CreateWildcardSubscription;
+use crate::repository::{
+    acl_repository, audit_repository, credential_repository, exchange_repository,
+    forwarding_rule_repository, message_topic_repository, retained_message_repository,
+    task_topic_repository, wildcard_subscription_repository,
+};
 use crate::utils::types::TopicType;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+use uuid::Uuid;
 
 #[derive(Clone)]
 #[allow(clippy::enum_variant_names)]
@@ -29,6 +45,24 @@ pub enum TopicEvent {
     RemoveSubscriberTaskTopic(EventRemoveSubscriberFromTaskTopicData),
     AddPublisherTaskTopic(EventAddPublisherToTaskTopicData),
     RemovePublisherTaskTopic(EventRemovePublisherFromTaskTopicData),
+    CreateWildcardSubscription(EventCreateWildcardSubscriptionData),
+    DeleteWildcardSubscription(EventDeleteWildcardSubscriptionData),
+    AddTopicToWildcardSubscription(EventAddTopicToWildcardSubscriptionData),
+    CreateExchange(EventCreateExchangeData),
+    DeleteExchange(EventDeleteExchangeData),
+    AddBindingExchange(EventAddBindingToExchangeData),
+    RemoveBindingExchange(EventRemoveBindingFromExchangeData),
+    AddPublisherExchange(EventAddPublisherToExchangeData),
+    RemovePublisherExchange(EventRemovePublisherFromExchangeData),
+    CreateForwardingRule(EventCreateForwardingRuleData),
+    DeleteForwardingRule(EventDeleteForwardingRuleData),
+    SetRetainedMessage(EventSetRetainedMessageData),
+    DeleteRetainedMessage(EventDeleteRetainedMessageData),
+    CreateCredential(EventCreateCredentialData),
+    DeleteCredential(EventDeleteCredentialData),
+    CreateAclGrant(EventCreateAclGrantData),
+    DeleteAclGrant(EventDeleteAclGrantData),
+    AppendAuditEntry(EventAppendAuditEntryData),
 }
 
 impl TopicEvent {
@@ -50,75 +84,155 @@ impl TopicEvent {
             Self::RemoveSubscriberTaskTopic(data) => data.handle(thread_data).await,
             Self::AddPublisherTaskTopic(data) => data.handle(thread_data).await,
             Self::RemovePublisherTaskTopic(data) => data.handle(thread_data).await,
+            Self::CreateWildcardSubscription(data) => data.handle(thread_data).await,
+            Self::DeleteWildcardSubscription(data) => data.handle(thread_data).await,
+            Self::AddTopicToWildcardSubscription(data) => data.handle(thread_data).await,
+            Self::CreateExchange(data) => data.handle(thread_data).await,
+            Self::DeleteExchange(data) => data.handle(thread_data).await,
+            Self::AddBindingExchange(data) => data.handle(thread_data).await,
+            Self::RemoveBindingExchange(data) => data.handle(thread_data).await,
+            Self::AddPublisherExchange(data) => data.handle(thread_data).await,
+            Self::RemovePublisherExchange(data) => data.handle(thread_data).await,
+            Self::CreateForwardingRule(data) => data.handle(thread_data).await,
+            Self::DeleteForwardingRule(data) => data.handle(thread_data).await,
+            Self::SetRetainedMessage(data) => data.handle(thread_data).await,
+            Self::DeleteRetainedMessage(data) => data.handle(thread_data).await,
+            Self::CreateCredential(data) => data.handle(thread_data).await,
+            Self::DeleteCredential(data) => data.handle(thread_data).await,
+            Self::CreateAclGrant(data) => data.handle(thread_data).await,
+            Self::DeleteAclGrant(data) => data.handle(thread_data).await,
+            Self::AppendAuditEntry(data) => data.handle(thread_data).await,
         }
     }
 }
 
 #[derive(Clone)]
 pub struct EventCreateMessageTopicData {
+    pub namespace: String,
     pub data: CreateMessageTopic,
 }
 
 impl EventCreateMessageTopicData {
-    pub fn new(data: CreateMessageTopic) -> Self {
-        Self { data }
+    pub fn new(namespace: String, data: CreateMessageTopic) -> Self {
+        Self { namespace, data }
     }
 
     pub async fn handle(&self, thread_data: ThreadData) {
-        message_topic_repository::create_message_topic(thread_data, self.data.clone()).await;
+        message_topic_repository::create_message_topic(
+            thread_data,
+            self.namespace.clone(),
+            self.data.clone(),
+        )
+        .await;
     }
 }
 
 #[derive(Clone)]
 pub struct EventDeleteMessageTopicData {
+    pub namespace: String,
     pub topic_name: String,
 }
 
 impl EventDeleteMessageTopicData {
-    pub fn new(topic_name: String) -> Self {
-        Self { topic_name }
+    pub fn new(namespace: String, topic_name: String) -> Self {
+        Self {
+            namespace,
+            topic_name,
+        }
     }
 
     pub async fn handle(&self, thread_data: ThreadData) {
-        message_topic_repository::delete_message_topic(thread_data, self.topic_name.clone()).await;
+        message_topic_repository::delete_message_topic(
+            thread_data,
+            self.namespace.clone(),
+            self.topic_name.clone(),
+        )
+        .await;
+    }
+}
+
+/// Tells the publisher whether the event it is attached to has been written to the DB.
+#[derive(Clone)]
+pub struct PersistenceAck {
+    sender: Arc<Mutex<Option<oneshot::Sender<bool>>>>,
+}
+
+impl PersistenceAck {
+    pub fn new() -> (Self, oneshot::Receiver<bool>) {
+        let (sender, receiver) = oneshot::channel();
+        let ack = Self {
+            sender: Arc::new(Mutex::new(Some(sender))),
+        };
+        (ack, receiver)
+    }
+
+    pub fn acknowledge(&self, persisted: bool) {
+        if let Ok(mut sender) = self.sender.lock() {
+            if let Some(sender) = sender.take() {
+                let _ = sender.send(persisted);
+            }
+        }
     }
 }
 
 #[derive(Clone)]
 pub struct EventPublishToMessageTopicData {
+    pub namespace: String,
     pub topic_name: String,
     pub data: PublishToMessageTopic<TopicType>,
+    pub ack: Option<PersistenceAck>,
 }
 
 impl EventPublishToMessageTopicData {
-    pub fn new(topic_name: String, data: PublishToMessageTopic<TopicType>) -> Self {
-        Self { topic_name, data }
+    pub fn new(
+        namespace: String,
+        topic_name: String,
+        data: PublishToMessageTopic<TopicType>,
+        ack: Option<PersistenceAck>,
+    ) -> Self {
+        Self {
+            namespace,
+            topic_name,
+            data,
+            ack,
+        }
     }
 
     pub async fn handle(&self, thread_data: ThreadData) {
-        message_topic_repository::publish_to_message_topic(
+        let persisted = message_topic_repository::publish_to_message_topic(
             thread_data,
+            self.namespace.clone(),
             self.topic_name.clone(),
             self.data.clone(),
         )
         .await;
+
+        if let Some(ack) = &self.ack {
+            ack.acknowledge(persisted);
+        }
     }
 }
 
 #[derive(Clone)]
 pub struct EventAddPublisherToMessageTopicData {
+    pub namespace: String,
     pub topic_name: String,
     pub data: AddPublisherToMessageTopic,
 }
 
 impl EventAddPublisherToMessageTopicData {
-    pub fn new(topic_name: String, data: AddPublisherToMessageTopic) -> Self {
-        Self { topic_name, data }
+    pub fn new(namespace: String, topic_name: String, data: AddPublisherToMessageTopic) -> Self {
+        Self {
+            namespace,
+            topic_name,
+            data,
+        }
     }
 
     pub async fn handle(&self, thread_data: ThreadData) {
         message_topic_repository::add_publisher_to_message_topic(
             thread_data,
+            self.namespace.clone(),
             self.topic_name.clone(),
             self.data.clone(),
         )
@@ -128,18 +242,28 @@ impl EventAddPublisherToMessageTopicData {
 
 #[derive(Clone)]
 pub struct EventRemovePublisherFromMessageTopicData {
+    pub namespace: String,
     pub topic_name: String,
     pub data: RemovePublisherFromMessageTopic,
 }
 
 impl EventRemovePublisherFromMessageTopicData {
-    pub fn new(topic_name: String, data: RemovePublisherFromMessageTopic) -> Self {
-        Self { topic_name, data }
+    pub fn new(
+        namespace: String,
+        topic_name: String,
+        data: RemovePublisherFromMessageTopic,
+    ) -> Self {
+        Self {
+            namespace,
+            topic_name,
+            data,
+        }
     }
 
     pub async fn handle(&self, thread_data: ThreadData) {
         message_topic_repository::remove_publisher_from_message_topic(
             thread_data,
+            self.namespace.clone(),
             self.topic_name.clone(),
             self.data.clone(),
         )
@@ -149,18 +273,24 @@ impl EventRemovePublisherFromMessageTopicData {
 
 #[derive(Clone)]
 pub struct EventAddSubscriberToMessageTopicData {
+    pub namespace: String,
     pub topic_name: String,
     pub data: AddSubscriberToMessageTopic,
 }
 
 impl EventAddSubscriberToMessageTopicData {
-    pub fn new(topic_name: String, data: AddSubscriberToMessageTopic) -> Self {
-        Self { topic_name, data }
+    pub fn new(namespace: String, topic_name: String, data: AddSubscriberToMessageTopic) -> Self {
+        Self {
+            namespace,
+            topic_name,
+            data,
+        }
     }
 
     pub async fn handle(&self, thread_data: ThreadData) {
         message_topic_repository::add_subscriber_to_message_topic(
             thread_data,
+            self.namespace.clone(),
             self.topic_name.clone(),
             self.data.clone(),
         )
@@ -170,18 +300,28 @@ impl EventAddSubscriberToMessageTopicData {
 
 #[derive(Clone)]
 pub struct EventRemoveSubscriberFromMessageTopicData {
+    pub namespace: String,
     pub topic_name: String,
     pub data: RemoveSubscriberFromMessageTopic,
 }
 
 impl EventRemoveSubscriberFromMessageTopicData {
-    pub fn new(topic_name: String, data: RemoveSubscriberFromMessageTopic) -> Self {
-        Self { topic_name, data }
+    pub fn new(
+        namespace: String,
+        topic_name: String,
+        data: RemoveSubscriberFromMessageTopic,
+    ) -> Self {
+        Self {
+            namespace,
+            topic_name,
+            data,
+        }
     }
 
     pub async fn handle(&self, thread_data: ThreadData) {
         message_topic_repository::remove_subscriber_from_message_topic(
             thread_data,
+            self.namespace.clone(),
             self.topic_name.clone(),
             self.data.clone(),
         )
@@ -191,14 +331,21 @@ impl EventRemoveSubscriberFromMessageTopicData {
 
 #[derive(Clone)]
 pub struct EventFetchDataFromMessageTopicData {
+    pub namespace: String,
     pub topic_name: String,
     pub subscriber_name: String,
     pub subscriber_index: usize,
 }
 
 impl EventFetchDataFromMessageTopicData {
-    pub fn new(topic_name: String, subscriber_name: String, subscriber_index: usize) -> Self {
+    pub fn new(
+        namespace: String,
+        topic_name: String,
+        subscriber_name: String,
+        subscriber_index: usize,
+    ) -> Self {
         Self {
+            namespace,
             topic_name,
             subscriber_name,
             subscriber_index,
@@ -208,6 +355,7 @@ impl EventFetchDataFromMessageTopicData {
     pub async fn handle(&self, thread_data: ThreadData) {
         message_topic_repository::fetch_data_from_message_topic(
             thread_data,
+            self.namespace.clone(),
             self.topic_name.clone(),
             self.subscriber_name.clone(),
             self.subscriber_index,
@@ -218,13 +366,15 @@ impl EventFetchDataFromMessageTopicData {
 
 #[derive(Clone)]
 pub struct EventResetIndexOfMessageTopicData {
+    pub namespace: String,
     pub topic_name: String,
     pub index_subtrahend: usize,
 }
 
 impl EventResetIndexOfMessageTopicData {
-    pub fn new(topic_name: String, index_subtrahend: usize) -> Self {
+    pub fn new(namespace: String, topic_name: String, index_subtrahend: usize) -> Self {
         Self {
+            namespace,
             topic_name,
             index_subtrahend,
         }
@@ -233,6 +383,7 @@ impl EventResetIndexOfMessageTopicData {
     pub async fn handle(&self, thread_data: ThreadData) {
         message_topic_repository::reset_index_of_message_topic(
             thread_data,
+            self.namespace.clone(),
             self.topic_name.clone(),
             self.index_subtrahend,
         )
@@ -242,48 +393,69 @@ impl EventResetIndexOfMessageTopicData {
 
 #[derive(Clone)]
 pub struct EventCreateTaskTopicData {
+    pub namespace: String,
     pub data: CreateTaskTopic,
 }
 
 impl EventCreateTaskTopicData {
-    pub fn new(data: CreateTaskTopic) -> Self {
-        Self { data }
+    pub fn new(namespace: String, data: CreateTaskTopic) -> Self {
+        Self { namespace, data }
     }
 
     pub async fn handle(&self, thread_data: ThreadData) {
-        task_topic_repository::create_task_topic(thread_data, self.data.clone()).await;
+        task_topic_repository::create_task_topic(
+            thread_data,
+            self.namespace.clone(),
+            self.data.clone(),
+        )
+        .await;
     }
 }
 
 #[derive(Clone)]
 pub struct EventDeleteTaskTopicData {
+    pub namespace: String,
     pub topic_name: String,
 }
 
 impl EventDeleteTaskTopicData {
-    pub fn new(topic_name: String) -> Self {
-        Self { topic_name }
+    pub fn new(namespace: String, topic_name: String) -> Self {
+        Self {
+            namespace,
+            topic_name,
+        }
     }
 
     pub async fn handle(&self, thread_data: ThreadData) {
-        task_topic_repository::delete_task_topic(thread_data, self.topic_name.clone()).await;
+        task_topic_repository::delete_task_topic(
+            thread_data,
+            self.namespace.clone(),
+            self.topic_name.clone(),
+        )
+        .await;
     }
 }
 
 #[derive(Clone)]
 pub struct EventPublishToTaskTopicData {
+    pub namespace: String,
     pub topic_name: String,
     pub data: PublishToTaskTopic<TopicType>,
 }
 
 impl EventPublishToTaskTopicData {
-    pub fn new(topic_name: String, data: PublishToTaskTopic<TopicType>) -> Self {
-        Self { topic_name, data }
+    pub fn new(namespace: String, topic_name: String, data: PublishToTaskTopic<TopicType>) -> Self {
+        Self {
+            namespace,
+            topic_name,
+            data,
+        }
     }
 
     pub async fn handle(&self, thread_data: ThreadData) {
         task_topic_repository::publish_to_task_topic(
             thread_data,
+            self.namespace.clone(),
             self.topic_name.clone(),
             self.data.clone(),
         )
@@ -293,18 +465,24 @@ impl EventPublishToTaskTopicData {
 
 #[derive(Clone)]
 pub struct EventAddSubscriberToTaskTopicData {
+    pub namespace: String,
     pub topic_name: String,
     pub data: AddSubscriberToTaskTopic,
 }
 
 impl EventAddSubscriberToTaskTopicData {
-    pub fn new(topic_name: String, data: AddSubscriberToTaskTopic) -> Self {
-        Self { topic_name, data }
+    pub fn new(namespace: String, topic_name: String, data: AddSubscriberToTaskTopic) -> Self {
+        Self {
+            namespace,
+            topic_name,
+            data,
+        }
     }
 
     pub async fn handle(&self, thread_data: ThreadData) {
         task_topic_repository::add_subscriber_to_task_topic(
             thread_data,
+            self.namespace.clone(),
             self.topic_name.clone(),
             self.data.clone(),
         )
@@ -314,18 +492,24 @@ impl EventAddSubscriberToTaskTopicData {
 
 #[derive(Clone)]
 pub struct EventRemoveSubscriberFromTaskTopicData {
+    pub namespace: String,
     pub topic_name: String,
     pub data: RemoveSubscriberFromTaskTopic,
 }
 
 impl EventRemoveSubscriberFromTaskTopicData {
-    pub fn new(topic_name: String, data: RemoveSubscriberFromTaskTopic) -> Self {
-        Self { topic_name, data }
+    pub fn new(namespace: String, topic_name: String, data: RemoveSubscriberFromTaskTopic) -> Self {
+        Self {
+            namespace,
+            topic_name,
+            data,
+        }
     }
 
     pub async fn handle(&self, thread_data: ThreadData) {
         task_topic_repository::remove_subscriber_from_task_topic(
             thread_data,
+            self.namespace.clone(),
             self.topic_name.clone(),
             self.data.clone(),
         )
@@ -335,18 +519,24 @@ impl EventRemoveSubscriberFromTaskTopicData {
 
 #[derive(Clone)]
 pub struct EventAddPublisherToTaskTopicData {
+    pub namespace: String,
     pub topic_name: String,
     pub data: AddPublisherToTaskTopic,
 }
 
 impl EventAddPublisherToTaskTopicData {
-    pub fn new(topic_name: String, data: AddPublisherToTaskTopic) -> Self {
-        Self { topic_name, data }
+    pub fn new(namespace: String, topic_name: String, data: AddPublisherToTaskTopic) -> Self {
+        Self {
+            namespace,
+            topic_name,
+            data,
+        }
     }
 
     pub async fn handle(&self, thread_data: ThreadData) {
         task_topic_repository::add_publisher_to_task_topic(
             thread_data,
+            self.namespace.clone(),
             self.topic_name.clone(),
             self.data.clone(),
         )
@@ -356,21 +546,385 @@ impl EventAddPublisherToTaskTopicData {
 
 #[derive(Clone)]
 pub struct EventRemovePublisherFromTaskTopicData {
+    pub namespace: String,
     pub topic_name: String,
     pub data: RemovePublisherFromTaskTopic,
 }
 
 impl EventRemovePublisherFromTaskTopicData {
-    pub fn new(topic_name: String, data: RemovePublisherFromTaskTopic) -> Self {
-        Self { topic_name, data }
+    pub fn new(namespace: String, topic_name: String, data: RemovePublisherFromTaskTopic) -> Self {
+        Self {
+            namespace,
+            topic_name,
+            data,
+        }
     }
 
     pub async fn handle(&self, thread_data: ThreadData) {
         task_topic_repository::remove_publisher_from_task_topic(
             thread_data,
+            self.namespace.clone(),
             self.topic_name.clone(),
             self.data.clone(),
         )
         .await;
     }
 }
+
+#[derive(Clone)]
+pub struct EventCreateWildcardSubscriptionData {
+    pub data: CreateWildcardSubscription,
+    pub topics: Vec<String>,
+}
+
+impl EventCreateWildcardSubscriptionData {
+    pub fn new(data: CreateWildcardSubscription, topics: Vec<String>) -> Self {
+        Self { data, topics }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        wildcard_subscription_repository::create_wildcard_subscription(
+            thread_data,
+            self.data.clone(),
+            self.topics.clone(),
+        )
+        .await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventDeleteWildcardSubscriptionData {
+    pub subscriber_name: String,
+    pub pattern: String,
+}
+
+impl EventDeleteWildcardSubscriptionData {
+    pub fn new(subscriber_name: String, pattern: String) -> Self {
+        Self {
+            subscriber_name,
+            pattern,
+        }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        wildcard_subscription_repository::delete_wildcard_subscription(
+            thread_data,
+            self.subscriber_name.clone(),
+            self.pattern.clone(),
+        )
+        .await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventAddTopicToWildcardSubscriptionData {
+    pub subscriber_name: String,
+    pub pattern: String,
+    pub topic_name: String,
+}
+
+impl EventAddTopicToWildcardSubscriptionData {
+    pub fn new(subscriber_name: String, pattern: String, topic_name: String) -> Self {
+        Self {
+            subscriber_name,
+            pattern,
+            topic_name,
+        }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        wildcard_subscription_repository::add_topic_to_wildcard_subscription(
+            thread_data,
+            self.subscriber_name.clone(),
+            self.pattern.clone(),
+            self.topic_name.clone(),
+        )
+        .await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventCreateExchangeData {
+    pub data: CreateExchange,
+}
+
+impl EventCreateExchangeData {
+    pub fn new(data: CreateExchange) -> Self {
+        Self { data }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        exchange_repository::create_exchange(thread_data, self.data.clone()).await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventDeleteExchangeData {
+    pub exchange_name: String,
+}
+
+impl EventDeleteExchangeData {
+    pub fn new(exchange_name: String) -> Self {
+        Self { exchange_name }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        exchange_repository::delete_exchange(thread_data, self.exchange_name.clone()).await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventAddBindingToExchangeData {
+    pub exchange_name: String,
+    pub data: BindingModel,
+}
+
+impl EventAddBindingToExchangeData {
+    pub fn new(exchange_name: String, data: BindingModel) -> Self {
+        Self {
+            exchange_name,
+            data,
+        }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        exchange_repository::add_binding_to_exchange(
+            thread_data,
+            self.exchange_name.clone(),
+            self.data.clone(),
+        )
+        .await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventRemoveBindingFromExchangeData {
+    pub exchange_name: String,
+    pub binding_id: Uuid,
+}
+
+impl EventRemoveBindingFromExchangeData {
+    pub fn new(exchange_name: String, binding_id: Uuid) -> Self {
+        Self {
+            exchange_name,
+            binding_id,
+        }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        exchange_repository::remove_binding_from_exchange(
+            thread_data,
+            self.exchange_name.clone(),
+            self.binding_id,
+        )
+        .await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventAddPublisherToExchangeData {
+    pub exchange_name: String,
+    pub data: AddPublisherToExchange,
+}
+
+impl EventAddPublisherToExchangeData {
+    pub fn new(exchange_name: String, data: AddPublisherToExchange) -> Self {
+        Self {
+            exchange_name,
+            data,
+        }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        exchange_repository::add_publisher_to_exchange(
+            thread_data,
+            self.exchange_name.clone(),
+            self.data.clone(),
+        )
+        .await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventRemovePublisherFromExchangeData {
+    pub exchange_name: String,
+    pub data: RemovePublisherFromExchange,
+}
+
+impl EventRemovePublisherFromExchangeData {
+    pub fn new(exchange_name: String, data: RemovePublisherFromExchange) -> Self {
+        Self {
+            exchange_name,
+            data,
+        }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        exchange_repository::remove_publisher_from_exchange(
+            thread_data,
+            self.exchange_name.clone(),
+            self.data.clone(),
+        )
+        .await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventCreateForwardingRuleData {
+    pub namespace: String,
+    pub data: CreateForwardingRule,
+}
+
+impl EventCreateForwardingRuleData {
+    pub fn new(namespace: String, data: CreateForwardingRule) -> Self {
+        Self { namespace, data }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        forwarding_rule_repository::create_forwarding_rule(
+            thread_data,
+            self.namespace.clone(),
+            self.data.clone(),
+        )
+        .await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventDeleteForwardingRuleData {
+    pub namespace: String,
+    pub rule_name: String,
+}
+
+impl EventDeleteForwardingRuleData {
+    pub fn new(namespace: String, rule_name: String) -> Self {
+        Self {
+            namespace,
+            rule_name,
+        }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        forwarding_rule_repository::delete_forwarding_rule(
+            thread_data,
+            self.namespace.clone(),
+            self.rule_name.clone(),
+        )
+        .await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventSetRetainedMessageData {
+    pub data: RetainedMessageModel<TopicType>,
+}
+
+impl EventSetRetainedMessageData {
+    pub fn new(data: RetainedMessageModel<TopicType>) -> Self {
+        Self { data }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        retained_message_repository::set_retained_message(thread_data, self.data.clone()).await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventDeleteRetainedMessageData {
+    pub topic_name: String,
+}
+
+impl EventDeleteRetainedMessageData {
+    pub fn new(topic_name: String) -> Self {
+        Self { topic_name }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        retained_message_repository::delete_retained_message(thread_data, self.topic_name.clone())
+            .await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventCreateCredentialData {
+    pub credential: Credential,
+}
+
+impl EventCreateCredentialData {
+    pub fn new(credential: Credential) -> Self {
+        Self { credential }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        credential_repository::create_credential(thread_data, self.credential.clone()).await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventDeleteCredentialData {
+    pub namespace: String,
+    pub credential_name: String,
+}
+
+impl EventDeleteCredentialData {
+    pub fn new(namespace: String, credential_name: String) -> Self {
+        Self {
+            namespace,
+            credential_name,
+        }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        credential_repository::delete_credential(
+            thread_data,
+            self.namespace.clone(),
+            self.credential_name.clone(),
+        )
+        .await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventCreateAclGrantData {
+    pub grant: Grant,
+}
+
+impl EventCreateAclGrantData {
+    pub fn new(grant: Grant) -> Self {
+        Self { grant }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        acl_repository::create_grant(thread_data, self.grant.clone()).await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventDeleteAclGrantData {
+    pub grant_id: Uuid,
+}
+
+impl EventDeleteAclGrantData {
+    pub fn new(grant_id: Uuid) -> Self {
+        Self { grant_id }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        acl_repository::delete_grant(thread_data, self.grant_id).await;
+    }
+}
+
+#[derive(Clone)]
+pub struct EventAppendAuditEntryData {
+    pub entry: AuditEntryModel,
+}
+
+impl EventAppendAuditEntryData {
+    pub fn new(entry: AuditEntryModel) -> Self {
+        Self { entry }
+    }
+
+    pub async fn handle(&self, thread_data: ThreadData) {
+        audit_repository::append_entry(thread_data, self.entry.clone()).await;
+    }
+}
@@ -1,13 +1,49 @@
 use crate::event_queue::event::TopicEvent;
 use crate::utils::queue::Queue;
-use crate::{db, STATE};
-use futures::executor::block_on;
+use crate::{db, metrics, STATE};
+use log::error;
 use sqlx::{Pool, Postgres};
 use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
 pub type EventQueue = Queue<TopicEvent>;
 
+static SPAWNED_WORKERS: AtomicUsize = AtomicUsize::new(0);
+static RUNNING_WORKERS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn spawned_workers() -> usize {
+    SPAWNED_WORKERS.load(Ordering::SeqCst)
+}
+
+pub fn running_workers() -> usize {
+    RUNNING_WORKERS.load(Ordering::SeqCst)
+}
+
+/// Counts a worker as running until its thread ends, also when it ends by a panic.
+struct RunningWorker {
+    thread_num: u16,
+}
+
+impl RunningWorker {
+    fn new(thread_num: u16) -> Self {
+        RUNNING_WORKERS.fetch_add(1, Ordering::SeqCst);
+        Self { thread_num }
+    }
+}
+
+impl Drop for RunningWorker {
+    fn drop(&mut self) {
+        RUNNING_WORKERS.fetch_sub(1, Ordering::SeqCst);
+        if std::thread::panicking() {
+            error!(
+                "Worker {} died, its events are no longer persisted",
+                self.thread_num
+            );
+        }
+    }
+}
+
 pub struct ThreadData {
     pub db_connection_pool: Pool<Postgres>,
 }
@@ -58,9 +94,6 @@ fn new_syncflag(initial_state: bool) -> (SyncFlagTx, SyncFlagRx) {
 }
 
 pub async fn create_event_queue_workers() {
-    use std::sync::mpsc::channel;
-    let (results_tx, _) = channel();
-
     let (_, more_jobs_rx) = new_syncflag(true);
 
     use std::thread;
@@ -86,39 +119,36 @@ pub async fn create_event_queue_workers() {
             }
         };
 
-        // Similarly, create a new transmitter for the thread to use
-        let thread_results_tx = results_tx.clone();
-
         // ... and a SyncFlagRx for the thread.
         let thread_more_jobs_rx = more_jobs_rx.clone();
 
         // thread::spawn takes a closure (an anonymous function that "closes"
         // over its environment). The move keyword means it takes ownership of
         // those variables, meaning they can't be used again in the main thread.
+        SPAWNED_WORKERS.fetch_add(1, Ordering::SeqCst);
+        let running = RunningWorker::new(thread_num);
         let handle = thread::spawn(move || {
+            let _running = running;
+
+            // sqlx needs a tokio reactor, so every worker drives its events on its own runtime
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Worker runtime could not be initialized");
+
             let mut work_done = 0;
 
             // Loop while there's expected to be work, looking for work.
             while thread_more_jobs_rx.get().unwrap() {
                 // If work is available, do that work.
                 if let Some(work) = thread_queue.dequeue() {
-                    block_on(work.handle(ThreadData {
+                    runtime.block_on(work.handle(ThreadData {
                         db_connection_pool: thread_db_connection_pool.clone(),
                     }));
 
                     // Record that some work was done.
                     work_done += 1;
-
-                    // Send the work and the result of that work.
-                    //
-                    // Sending could fail. If so, there's no use in
-                    // doing any more work, so abort.
-                    match thread_results_tx.send(work) {
-                        Ok(_) => (),
-                        Err(_) => {
-                            break;
-                        }
-                    }
+                    metrics::record_processed_event(thread_num);
                 }
 
                 // Signal to the operating system that now is a good time
@@ -0,0 +1,81 @@
+use crate::auth::authenticator::Authenticator;
+use crate::auth::identity;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tonic::body::BoxBody;
+use tonic::codegen::{http, BoxFuture, Context, Poll, Service};
+use tonic::server::NamedService;
+use tonic::Status;
+
+const API_KEY_METADATA: &str = "x-api-key";
+
+/// Authenticates every call of the wrapped service with an API key, passed as
+/// `authorization: Bearer <key>` or `x-api-key: <key>` metadata, or with a JWT bearer token. The
+/// call runs on behalf of the identity of the key or token.
+#[derive(Clone)]
+pub struct Authenticated<S> {
+    inner: S,
+    authenticator: Arc<Authenticator>,
+}
+
+impl<S> Authenticated<S> {
+    pub fn new(inner: S, authenticator: Arc<Authenticator>) -> Self {
+        Self {
+            inner,
+            authenticator,
+        }
+    }
+}
+
+impl<S: NamedService> NamedService for Authenticated<S> {
+    const NAME: &'static str = S::NAME;
+}
+
+impl<S> Service<http::Request<BoxBody>> for Authenticated<S>
+where
+    S: Service<http::Request<BoxBody>, Response = http::Response<BoxBody>, Error = Infallible>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<BoxBody>) -> Self::Future {
+        let identity = credential(&req)
+            .ok_or("a valid API key or bearer token is required".to_string())
+            .and_then(|credential| self.authenticator.authenticate(credential));
+        let identity = match identity {
+            Ok(identity) => identity,
+            Err(detail) => {
+                return Box::pin(async move { Ok(Status::unauthenticated(detail).into_http()) })
+            }
+        };
+
+        // the service that was polled ready serves the call, its clone the next one
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        Box::pin(identity::on_behalf_of(Some(identity), async move {
+            inner.call(req).await
+        }))
+    }
+}
+
+fn credential(req: &http::Request<BoxBody>) -> Option<&str> {
+    let bearer_token = req
+        .headers()
+        .get(http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let api_key = req
+        .headers()
+        .get(API_KEY_METADATA)
+        .and_then(|value| value.to_str().ok());
+    bearer_token.or(api_key)
+}
@@ -0,0 +1,38 @@
+use crate::service::error::ServiceError;
+use tonic::Status;
+
+impl From<ServiceError> for Status {
+    fn from(error: ServiceError) -> Self {
+        let message = error.to_string();
+        match error {
+            ServiceError::TopicNotFound(_)
+            | ServiceError::TaskNotFound(_)
+            | ServiceError::CredentialNotFound(_)
+            | ServiceError::GrantNotFound(_)
+            | ServiceError::ExchangeNotFound(_)
+            | ServiceError::BindingNotFound(_)
+            | ServiceError::ForwardingRuleNotFound(_)
+            | ServiceError::WildcardSubscriptionNotFound(_, _) => Status::not_found(message),
+            ServiceError::AlreadyExists(_)
+            | ServiceError::AlreadyAPublisher(_)
+            | ServiceError::AlreadyASubscriber(_)
+            | ServiceError::CredentialExists(_)
+            | ServiceError::GrantExists(_)
+            | ServiceError::ExchangeExists(_)
+            | ServiceError::ForwardingRuleExists(_)
+            | ServiceError::WildcardSubscriptionExists(_, _) => Status::already_exists(message),
+            ServiceError::InvalidName(_)
+            | ServiceError::InvalidPattern(_)
+            | ServiceError::InvalidBinding(_)
+            | ServiceError::InvalidFilter => Status::invalid_argument(message),
+            ServiceError::NotAPublisher(_)
+            | ServiceError::NotASubscriber(_)
+            | ServiceError::IdentityMismatch(_)
+            | ServiceError::PermissionDenied(_) => Status::permission_denied(message),
+            ServiceError::TaskNotLeased(_) => Status::failed_precondition(message),
+            ServiceError::QuotaExceeded(_, _) => Status::resource_exhausted(message),
+            ServiceError::DatabaseUnavailable(_) => Status::unavailable(message),
+            ServiceError::PoisonedLock => Status::internal(message),
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use crate::auth::identity::{self, current_identity};
+use crate::grpc::proto::message_topic_service_server::MessageTopicService;
+use crate::grpc::proto::{
+    AddMessageTopicSubscriber, Empty, Message, MessageList, MessageTopic, MessageTopicList,
+    NewData, PublishMessage, TopicMember, TopicName,
+};
+use crate::model::destination_model::DestinationType;
+use crate::model::message_topic_model::{
+    self, AddPublisherToMessageTopic, AddSubscriberToMessageTopic, CreateMessageTopic,
+    MessageTopicModel, PublishToMessageTopic, RemovePublisherFromMessageTopic,
+    RemoveSubscriberFromMessageTopic,
+};
+use crate::service::{message_topic_service, notification_service};
+use crate::utils::types::TopicType;
+use std::pin::Pin;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+use tonic::{Request, Response, Status};
+
+const SUBSCRIBE_BUFFER: usize = 128;
+
+#[derive(Debug, Default)]
+pub struct MessageTopicGrpc;
+
+impl From<MessageTopicModel> for MessageTopic {
+    fn from(model: MessageTopicModel) -> Self {
+        Self {
+            name: model.name,
+            index: model.index as u64,
+            subscriber: model.subscriber,
+        }
+    }
+}
+
+impl From<message_topic_model::Message<TopicType>> for Message {
+    fn from(message: message_topic_model::Message<TopicType>) -> Self {
+        Self {
+            data: message.data,
+            headers: message.headers,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl MessageTopicService for MessageTopicGrpc {
+    async fn get_all_message_topics(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<MessageTopicList>, Status> {
+        let topics = message_topic_service::get_all_message_topics().await?;
+        Ok(Response::new(MessageTopicList {
+            topics: topics.into_iter().map(MessageTopic::from).collect(),
+        }))
+    }
+
+    async fn get_message_topic(
+        &self,
+        request: Request<TopicName>,
+    ) -> Result<Response<MessageTopic>, Status> {
+        message_topic_service::get_message_topic(request.into_inner().name)
+            .await
+            .map(|topic| Response::new(topic.into()))
+            .map_err(Status::from)
+    }
+
+    async fn create_message_topic(
+        &self,
+        request: Request<TopicName>,
+    ) -> Result<Response<MessageTopic>, Status> {
+        message_topic_service::create_message_topic(CreateMessageTopic::new(
+            request.into_inner().name,
+        ))
+        .await
+        .map(|topic| Response::new(topic.into()))
+        .map_err(Status::from)
+    }
+
+    async fn delete_message_topic(
+        &self,
+        request: Request<TopicName>,
+    ) -> Result<Response<Empty>, Status> {
+        message_topic_service::delete_message_topic(request.into_inner().name)
+            .await
+            .map(|_| Response::new(Empty {}))
+            .map_err(Status::from)
+    }
+
+    async fn add_publisher(
+        &self,
+        request: Request<TopicMember>,
+    ) -> Result<Response<Empty>, Status> {
+        let member = request.into_inner();
+        message_topic_service::add_publisher_to_message_topic(
+            member.topic,
+            AddPublisherToMessageTopic::new(member.identifier),
+        )
+        .await?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn remove_publisher(
+        &self,
+        request: Request<TopicMember>,
+    ) -> Result<Response<Empty>, Status> {
+        let member = request.into_inner();
+        message_topic_service::remove_publisher_from_message_topic(
+            member.topic,
+            RemovePublisherFromMessageTopic::new(member.identifier),
+        )
+        .await?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn add_subscriber(
+        &self,
+        request: Request<AddMessageTopicSubscriber>,
+    ) -> Result<Response<Empty>, Status> {
+        let subscriber = request.into_inner();
+        let filter = match subscriber.filter {
+            Some(filter) => Some(
+                serde_json::from_str(&filter)
+                    .map_err(|_| Status::invalid_argument("filter is not a valid filter"))?,
+            ),
+            None => None,
+        };
+        message_topic_service::add_subscriber_to_message_topic(
+            subscriber.topic,
+            AddSubscriberToMessageTopic::new(subscriber.subscriber, filter),
+        )
+        .await?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn remove_subscriber(
+        &self,
+        request: Request<TopicMember>,
+    ) -> Result<Response<Empty>, Status> {
+        let member = request.into_inner();
+        message_topic_service::remove_subscriber_from_message_topic(
+            member.topic,
+            RemoveSubscriberFromMessageTopic::new(member.identifier),
+        )
+        .await?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn publish(&self, request: Request<PublishMessage>) -> Result<Response<Empty>, Status> {
+        let message = request.into_inner();
+        message_topic_service::publish_to_message_topic(
+            message.topic,
+            message.publisher,
+            PublishToMessageTopic::new(message.data, message.headers),
+        )
+        .await?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn is_there_new_data(
+        &self,
+        request: Request<TopicMember>,
+    ) -> Result<Response<NewData>, Status> {
+        let member = request.into_inner();
+        message_topic_service::is_there_new_data_for_subscriber(member.topic, member.identifier)
+            .await
+            .map(|data| {
+                Response::new(NewData {
+                    new_data: data.new_data,
+                })
+            })
+            .map_err(Status::from)
+    }
+
+    async fn fetch(&self, request: Request<TopicMember>) -> Result<Response<MessageList>, Status> {
+        let member = request.into_inner();
+        message_topic_service::get_new_data_for_subscriber(member.topic, member.identifier)
+            .await
+            .map(|data| {
+                Response::new(MessageList {
+                    messages: data.data.into_iter().map(Message::from).collect(),
+                })
+            })
+            .map_err(Status::from)
+    }
+
+    type SubscribeStream = Pin<Box<dyn Stream<Item = Result<Message, Status>> + Send>>;
+
+    async fn subscribe(
+        &self,
+        request: Request<TopicMember>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let member = request.into_inner();
+        message_topic_service::is_there_new_data_for_subscriber(
+            member.topic.clone(),
+            member.identifier.clone(),
+        )
+        .await?;
+
+        let (tx, rx) = mpsc::channel(SUBSCRIBE_BUFFER);
+        // the stream outlives the call, it keeps polling on behalf of the caller
+        tokio::spawn(identity::on_behalf_of(
+            current_identity(),
+            stream_messages(member, tx),
+        ));
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+async fn stream_messages(member: TopicMember, tx: mpsc::Sender<Result<Message, Status>>) {
+    let mut notifications = notification_service::subscribe_to_notifications();
+
+    loop {
+        let data = message_topic_service::get_new_data_for_subscriber(
+            member.topic.clone(),
+            member.identifier.clone(),
+        )
+        .await;
+        let data = match data {
+            Ok(data) => data,
+            Err(e) => {
+                let _ = tx.send(Err(e.into())).await;
+                return;
+            }
+        };
+        for message in data.data {
+            if tx.send(Ok(message.into())).await.is_err() {
+                return;
+            }
+        }
+
+        // wait until the topic received new data or the client went away
+        loop {
+            tokio::select! {
+                notification = notifications.recv() => match notification {
+                    Ok(destination)
+                        if destination.destination_type == DestinationType::MessageTopic
+                            && destination.destination == member.topic => break,
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(_)) => break,
+                    Err(RecvError::Closed) => return,
+                },
+                _ = tx.closed() => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::time::Duration;
+    use tokio_stream::StreamExt;
+
+    const RECEIVE_TIMEOUT: Duration = Duration::from_secs(5);
+    const TOPIC: &str = "grpc-messages";
+
+    fn member(identifier: &str) -> Request<TopicMember> {
+        Request::new(TopicMember {
+            topic: TOPIC.to_string(),
+            identifier: identifier.to_string(),
+        })
+    }
+
+    async fn publish(service: &MessageTopicGrpc, data: &str) {
+        service
+            .publish(Request::new(PublishMessage {
+                topic: TOPIC.to_string(),
+                publisher: "publisher".to_string(),
+                data: data.to_string(),
+                headers: HashMap::from([("kind".to_string(), "test".to_string())]),
+            }))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn subscriptions_stream_published_messages_until_cancelled() {
+        let service = MessageTopicGrpc;
+        service
+            .create_message_topic(Request::new(TopicName {
+                name: TOPIC.to_string(),
+            }))
+            .await
+            .unwrap();
+        service.add_publisher(member("publisher")).await.unwrap();
+        service
+            .add_subscriber(Request::new(AddMessageTopicSubscriber {
+                topic: TOPIC.to_string(),
+                subscriber: "subscriber".to_string(),
+                filter: None,
+            }))
+            .await
+            .unwrap();
+
+        // messages published before and after subscribing are both streamed
+        publish(&service, "first").await;
+        let mut stream = service
+            .subscribe(member("subscriber"))
+            .await
+            .unwrap()
+            .into_inner();
+        publish(&service, "second").await;
+        for data in ["first", "second"] {
+            let message = tokio::time::timeout(RECEIVE_TIMEOUT, stream.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            assert_eq!(message.data, data);
+            assert_eq!(
+                message.headers.get("kind").map(String::as_str),
+                Some("test")
+            );
+        }
+
+        // once the client is gone the stream stops fetching, later messages wait to be fetched
+        drop(stream);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        publish(&service, "third").await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let messages = service
+            .fetch(member("subscriber"))
+            .await
+            .unwrap()
+            .into_inner();
+        let data: Vec<&str> = messages
+            .messages
+            .iter()
+            .map(|message| message.data.as_str())
+            .collect();
+        assert_eq!(data, vec!["third"]);
+
+        let status = service.subscribe(member("stranger")).await.err().unwrap();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+    }
+}
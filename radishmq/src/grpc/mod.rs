@@ -0,0 +1,10 @@
+mod authentication;
+mod error;
+pub mod message_topic_grpc;
+pub mod server;
+pub mod task_topic_grpc;
+
+#[allow(clippy::all)]
+pub mod proto {
+    tonic::include_proto!("radishmq");
+}
@@ -0,0 +1,55 @@
+use crate::auth::authenticator::Authenticator;
+use crate::grpc::authentication::Authenticated;
+use crate::grpc::message_topic_grpc::MessageTopicGrpc;
+use crate::grpc::proto::message_topic_service_server::MessageTopicServiceServer;
+use crate::grpc::proto::task_topic_service_server::TaskTopicServiceServer;
+use crate::grpc::task_topic_grpc::TaskTopicGrpc;
+use log::error;
+use std::env;
+use std::io;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::Server;
+
+pub async fn start_grpc_server() -> io::Result<()> {
+    let port = match env::var("GRPC_PORT") {
+        Ok(port) => port.parse::<u16>().expect("GRPC_PORT must be a number"),
+        Err(_) => {
+            println!("Skipping gRPC listener...");
+            return Ok(());
+        }
+    };
+    let address = env::var("GRPC_ADDRESS")
+        .or_else(|_| env::var("HTTP_ADDRESS"))
+        .expect("GRPC_ADDRESS or HTTP_ADDRESS must be set");
+
+    let listener = TcpListener::bind((address.as_str(), port)).await?;
+    println!("gRPC listener started on {}:{}", address, port);
+
+    let mut server = Server::builder();
+    let router = match Authenticator::from_env().map(Arc::new) {
+        Some(authenticator) => server
+            .add_service(Authenticated::new(
+                MessageTopicServiceServer::new(MessageTopicGrpc),
+                authenticator.clone(),
+            ))
+            .add_service(Authenticated::new(
+                TaskTopicServiceServer::new(TaskTopicGrpc),
+                authenticator,
+            )),
+        None => server
+            .add_service(MessageTopicServiceServer::new(MessageTopicGrpc))
+            .add_service(TaskTopicServiceServer::new(TaskTopicGrpc)),
+    };
+
+    tokio::spawn(async move {
+        let result = router
+            .serve_with_incoming(TcpListenerStream::new(listener))
+            .await;
+        if let Err(e) = result {
+            error!("GrpcServer::start_grpc_server stopped serving: {}", e);
+        }
+    });
+    Ok(())
+}
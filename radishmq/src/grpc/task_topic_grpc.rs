@@ -0,0 +1,419 @@
+use crate::auth::identity::{self, current_identity};
+use crate::grpc::proto::task_topic_service_server::TaskTopicService;
+use crate::grpc::proto::{
+    AssignedTask, Empty, NewData, PublishTask, PublishedTask, TaskFailure, TaskLookup,
+    TaskProgress, TaskReference, TaskResult, TaskState, TaskStatus, TaskTopic, TaskTopicList,
+    TopicMember, TopicName,
+};
+use crate::model::destination_model::DestinationType;
+use crate::model::task_topic_model::{
+    self, AddPublisherToTaskTopic, AddSubscriberToTaskTopic, CompleteTask, CreateTaskTopic,
+    FailTask, PublishToTaskTopic, RemovePublisherFromTaskTopic, RemoveSubscriberFromTaskTopic,
+    ReportTaskProgress, TaskStatusModel, TaskTopicModel,
+};
+use crate::service::{notification_service, task_topic_service};
+use crate::utils::types::TopicType;
+use std::pin::Pin;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+// tasks are leased when they are sent, so only one is buffered ahead of the client
+const SUBSCRIBE_BUFFER: usize = 1;
+
+#[derive(Debug, Default)]
+pub struct TaskTopicGrpc;
+
+impl From<TaskTopicModel> for TaskTopic {
+    fn from(model: TaskTopicModel) -> Self {
+        Self {
+            name: model.name,
+            subscriber: model.subscriber,
+        }
+    }
+}
+
+impl From<task_topic_model::AssignedTask<TopicType>> for AssignedTask {
+    fn from(task: task_topic_model::AssignedTask<TopicType>) -> Self {
+        Self {
+            id: task.id.to_string(),
+            data: task.data,
+            lease_timeout: task.lease_timeout,
+        }
+    }
+}
+
+impl From<task_topic_model::TaskStatus> for TaskState {
+    fn from(status: task_topic_model::TaskStatus) -> Self {
+        match status {
+            task_topic_model::TaskStatus::Queued => TaskState::Queued,
+            task_topic_model::TaskStatus::InProgress => TaskState::InProgress,
+            task_topic_model::TaskStatus::Succeeded => TaskState::Succeeded,
+            task_topic_model::TaskStatus::Failed => TaskState::Failed,
+        }
+    }
+}
+
+impl From<TaskStatusModel<TopicType>> for TaskStatus {
+    fn from(model: TaskStatusModel<TopicType>) -> Self {
+        Self {
+            id: model.id.to_string(),
+            status: TaskState::from(model.status).into(),
+            progress: model.progress.into(),
+            subscriber: model.subscriber,
+            attempts: model.attempts,
+            result: model.result,
+            error: model.error,
+        }
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn parse_task_id(id: &str) -> Result<Uuid, Status> {
+    Uuid::parse_str(id).map_err(|_| Status::invalid_argument("task id is not a valid uuid"))
+}
+
+#[tonic::async_trait]
+impl TaskTopicService for TaskTopicGrpc {
+    async fn get_all_task_topics(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<TaskTopicList>, Status> {
+        let topics = task_topic_service::get_all_task_topics().await?;
+        Ok(Response::new(TaskTopicList {
+            topics: topics.into_iter().map(TaskTopic::from).collect(),
+        }))
+    }
+
+    async fn get_task_topic(
+        &self,
+        request: Request<TopicName>,
+    ) -> Result<Response<TaskTopic>, Status> {
+        task_topic_service::get_task_topic(request.into_inner().name)
+            .await
+            .map(|topic| Response::new(topic.into()))
+            .map_err(Status::from)
+    }
+
+    async fn create_task_topic(
+        &self,
+        request: Request<TopicName>,
+    ) -> Result<Response<TaskTopic>, Status> {
+        task_topic_service::create_task_topic(CreateTaskTopic::new(request.into_inner().name))
+            .await
+            .map(|topic| Response::new(topic.into()))
+            .map_err(Status::from)
+    }
+
+    async fn delete_task_topic(
+        &self,
+        request: Request<TopicName>,
+    ) -> Result<Response<Empty>, Status> {
+        task_topic_service::delete_task_topic(request.into_inner().name)
+            .await
+            .map(|_| Response::new(Empty {}))
+            .map_err(Status::from)
+    }
+
+    async fn add_publisher(
+        &self,
+        request: Request<TopicMember>,
+    ) -> Result<Response<Empty>, Status> {
+        let member = request.into_inner();
+        task_topic_service::add_publisher_to_task_topic(
+            member.topic,
+            AddPublisherToTaskTopic::new(member.identifier),
+        )
+        .await?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn remove_publisher(
+        &self,
+        request: Request<TopicMember>,
+    ) -> Result<Response<Empty>, Status> {
+        let member = request.into_inner();
+        task_topic_service::remove_publisher_from_task_topic(
+            member.topic,
+            RemovePublisherFromTaskTopic::new(member.identifier),
+        )
+        .await?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn add_subscriber(
+        &self,
+        request: Request<TopicMember>,
+    ) -> Result<Response<Empty>, Status> {
+        let member = request.into_inner();
+        task_topic_service::add_subscriber_to_task_topic(
+            member.topic,
+            AddSubscriberToTaskTopic::new(member.identifier),
+        )
+        .await?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn remove_subscriber(
+        &self,
+        request: Request<TopicMember>,
+    ) -> Result<Response<Empty>, Status> {
+        let member = request.into_inner();
+        task_topic_service::remove_subscriber_from_task_topic(
+            member.topic,
+            RemoveSubscriberFromTaskTopic::new(member.identifier),
+        )
+        .await?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn publish(
+        &self,
+        request: Request<PublishTask>,
+    ) -> Result<Response<PublishedTask>, Status> {
+        let task = request.into_inner();
+        task_topic_service::publish_to_task_topic(
+            task.topic,
+            task.publisher,
+            PublishToTaskTopic::new(task.data),
+        )
+        .await
+        .map(|published| {
+            Response::new(PublishedTask {
+                id: published.id.to_string(),
+            })
+        })
+        .map_err(Status::from)
+    }
+
+    async fn is_there_a_task(
+        &self,
+        request: Request<TopicMember>,
+    ) -> Result<Response<NewData>, Status> {
+        let member = request.into_inner();
+        let new_tasks =
+            task_topic_service::is_there_a_task_for_subscriber(member.topic, member.identifier)
+                .await?;
+        Ok(Response::new(NewData {
+            new_data: new_tasks.new_tasks,
+        }))
+    }
+
+    async fn fetch_task(
+        &self,
+        request: Request<TopicMember>,
+    ) -> Result<Response<AssignedTask>, Status> {
+        let member = request.into_inner();
+        task_topic_service::get_new_task_for_subscriber(member.topic, member.identifier)
+            .await?
+            .map(|task| Response::new(task.into()))
+            .ok_or_else(|| Status::not_found("no task available for the subscriber"))
+    }
+
+    async fn heartbeat(&self, request: Request<TaskReference>) -> Result<Response<Empty>, Status> {
+        let task = request.into_inner();
+        let id = parse_task_id(&task.id)?;
+        task_topic_service::heartbeat_task(task.topic, task.subscriber, id).await?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn report_progress(
+        &self,
+        request: Request<TaskProgress>,
+    ) -> Result<Response<Empty>, Status> {
+        let task = request.into_inner();
+        let id = parse_task_id(&task.id)?;
+        let progress = u8::try_from(task.progress.min(100)).unwrap_or(100);
+        task_topic_service::report_task_progress(
+            task.topic,
+            task.subscriber,
+            id,
+            ReportTaskProgress::new(progress),
+        )
+        .await?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn complete(&self, request: Request<TaskResult>) -> Result<Response<Empty>, Status> {
+        let task = request.into_inner();
+        let id = parse_task_id(&task.id)?;
+        task_topic_service::complete_task(
+            task.topic,
+            task.subscriber,
+            id,
+            CompleteTask::new(task.result),
+        )
+        .await?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn fail(&self, request: Request<TaskFailure>) -> Result<Response<Empty>, Status> {
+        let task = request.into_inner();
+        let id = parse_task_id(&task.id)?;
+        task_topic_service::fail_task(task.topic, task.subscriber, id, FailTask::new(task.error))
+            .await?;
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn get_task_status(
+        &self,
+        request: Request<TaskLookup>,
+    ) -> Result<Response<TaskStatus>, Status> {
+        let lookup = request.into_inner();
+        let id = parse_task_id(&lookup.id)?;
+        task_topic_service::get_task_status(lookup.topic, id)
+            .await
+            .map(|status| Response::new(status.into()))
+            .map_err(Status::from)
+    }
+
+    type SubscribeStream = Pin<Box<dyn Stream<Item = Result<AssignedTask, Status>> + Send>>;
+
+    async fn subscribe(
+        &self,
+        request: Request<TopicMember>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let member = request.into_inner();
+        task_topic_service::is_there_a_task_for_subscriber(
+            member.topic.clone(),
+            member.identifier.clone(),
+        )
+        .await?;
+
+        let (tx, rx) = mpsc::channel(SUBSCRIBE_BUFFER);
+        // the stream outlives the call, it keeps polling on behalf of the caller
+        tokio::spawn(identity::on_behalf_of(
+            current_identity(),
+            stream_tasks(member, tx),
+        ));
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+async fn stream_tasks(member: TopicMember, tx: mpsc::Sender<Result<AssignedTask, Status>>) {
+    let mut notifications = notification_service::subscribe_to_notifications();
+
+    loop {
+        // wait for room in the stream before leasing, so no task is leased to a gone client
+        let Ok(permit) = tx.reserve().await else {
+            return;
+        };
+        let task = task_topic_service::get_new_task_for_subscriber(
+            member.topic.clone(),
+            member.identifier.clone(),
+        )
+        .await;
+        match task {
+            Ok(Some(task)) => {
+                permit.send(Ok(task.into()));
+                continue;
+            }
+            Ok(None) => drop(permit),
+            Err(e) => {
+                permit.send(Err(e.into()));
+                return;
+            }
+        }
+
+        loop {
+            tokio::select! {
+                notification = notifications.recv() => match notification {
+                    Ok(destination)
+                        if destination.destination_type == DestinationType::TaskTopic
+                            && destination.destination == member.topic => break,
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(_)) => break,
+                    Err(RecvError::Closed) => return,
+                },
+                _ = tx.closed() => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio_stream::StreamExt;
+
+    const RECEIVE_TIMEOUT: Duration = Duration::from_secs(5);
+    const TOPIC: &str = "grpc-tasks";
+
+    fn member(identifier: &str) -> Request<TopicMember> {
+        Request::new(TopicMember {
+            topic: TOPIC.to_string(),
+            identifier: identifier.to_string(),
+        })
+    }
+
+    async fn publish(service: &TaskTopicGrpc, data: &str) -> String {
+        service
+            .publish(Request::new(PublishTask {
+                topic: TOPIC.to_string(),
+                publisher: "publisher".to_string(),
+                data: data.to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .id
+    }
+
+    #[tokio::test]
+    async fn subscriptions_lease_tasks_only_while_the_client_is_connected() {
+        let service = TaskTopicGrpc;
+        service
+            .create_task_topic(Request::new(TopicName {
+                name: TOPIC.to_string(),
+            }))
+            .await
+            .unwrap();
+        service.add_publisher(member("publisher")).await.unwrap();
+        service.add_subscriber(member("worker")).await.unwrap();
+
+        let mut stream = service
+            .subscribe(member("worker"))
+            .await
+            .unwrap()
+            .into_inner();
+        let id = publish(&service, "first").await;
+        let task = tokio::time::timeout(RECEIVE_TIMEOUT, stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (task.id.as_str(), task.data.as_str()),
+            (id.as_str(), "first")
+        );
+        service
+            .complete(Request::new(TaskResult {
+                topic: TOPIC.to_string(),
+                subscriber: "worker".to_string(),
+                id,
+                result: "done".to_string(),
+            }))
+            .await
+            .unwrap();
+
+        // a cancelled stream leases no further task, it stays available to the next fetch
+        drop(stream);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let id = publish(&service, "second").await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let task = service
+            .fetch_task(member("worker"))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            (task.id.as_str(), task.data.as_str()),
+            (id.as_str(), "second")
+        );
+    }
+}
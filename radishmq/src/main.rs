@@ -1,16 +1,34 @@
+mod auth;
 mod db;
+mod encryption;
 mod event_queue;
+mod grpc;
+mod metrics;
 mod model;
+mod mqtt;
+mod nats;
 mod repository;
+mod resp;
 mod service;
 mod state;
+mod stomp;
+mod tcp;
 mod topic;
 mod utils;
 mod web;
 
 use crate::db::migration::run_migrations;
+use crate::db::pool::initialize_connection_pool;
+use crate::db::restore::restore_state;
 use crate::event_queue::worker::create_event_queue_workers;
+use crate::grpc::server::start_grpc_server;
+use crate::mqtt::server::start_mqtt_server;
+use crate::nats::server::start_nats_server;
+use crate::resp::server::start_resp_server;
+use crate::service::health_service;
 use crate::state::State;
+use crate::stomp::server::start_stomp_server;
+use crate::tcp::server::start_tcp_server;
 use crate::web::server::start_webserver;
 use lazy_static::lazy_static;
 use std::sync::Arc;
@@ -25,7 +43,25 @@ async fn main() -> std::io::Result<()> {
         .await
         .expect("Cannot run DB migrations: {}");
 
+    let db_connection_pool = match initialize_connection_pool().await {
+        Some(pool) => pool,
+        None => panic!("DB connection pool could not be initialized"),
+    };
+
+    // the health probes are answered while the state is restored, other requests have to wait
+    let webserver = actix_web::rt::spawn(start_webserver(db_connection_pool.clone())?);
+
+    restore_state(&db_connection_pool).await;
+
     create_event_queue_workers().await;
 
-    start_webserver().await
+    start_tcp_server().await?;
+    start_grpc_server().await?;
+    start_mqtt_server().await?;
+    start_stomp_server().await?;
+    start_resp_server().await?;
+    start_nats_server().await?;
+    health_service::mark_started();
+
+    webserver.await?
 }
@@ -0,0 +1,163 @@
+use crate::model::destination_model::DestinationType;
+use lazy_static::lazy_static;
+use prometheus::core::Collector;
+use prometheus::{
+    HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+};
+
+const TOPIC_LABELS: [&str; 3] = ["namespace", "topic_type", "topic"];
+
+lazy_static! {
+    pub static ref REGISTRY: Registry = Registry::new();
+    pub static ref QUOTA_BREACHES: IntCounterVec = register(
+        IntCounterVec::new(
+            Opts::new(
+                "radish_quota_breaches_total",
+                "Operations rejected because they exceeded a quota."
+            ),
+            &["namespace", "scope", "limit"],
+        )
+        .unwrap()
+    );
+    static ref PUBLISHED_MESSAGES: IntCounterVec = register(
+        IntCounterVec::new(
+            Opts::new(
+                "radish_published_messages_total",
+                "Messages and tasks published to a topic."
+            ),
+            &TOPIC_LABELS,
+        )
+        .unwrap()
+    );
+    static ref PUBLISHED_BYTES: IntCounterVec = register(
+        IntCounterVec::new(
+            Opts::new(
+                "radish_published_bytes_total",
+                "Bytes of the messages and tasks published to a topic."
+            ),
+            &TOPIC_LABELS,
+        )
+        .unwrap()
+    );
+    static ref FETCHES: IntCounterVec = register(
+        IntCounterVec::new(
+            Opts::new(
+                "radish_fetches_total",
+                "Fetches of new messages or tasks from a topic."
+            ),
+            &TOPIC_LABELS,
+        )
+        .unwrap()
+    );
+    pub static ref TOPIC_DEPTH: IntGaugeVec = register(
+        IntGaugeVec::new(
+            Opts::new(
+                "radish_topic_depth",
+                "Messages held by a message topic or tasks queued in a task topic."
+            ),
+            &TOPIC_LABELS,
+        )
+        .unwrap()
+    );
+    pub static ref TOPIC_PUBLISHERS: IntGaugeVec = register(
+        IntGaugeVec::new(
+            Opts::new("radish_topic_publishers", "Publishers of a topic."),
+            &TOPIC_LABELS,
+        )
+        .unwrap()
+    );
+    pub static ref TOPIC_SUBSCRIBERS: IntGaugeVec = register(
+        IntGaugeVec::new(
+            Opts::new("radish_topic_subscribers", "Subscribers of a topic."),
+            &TOPIC_LABELS,
+        )
+        .unwrap()
+    );
+    pub static ref SUBSCRIBER_LAG: IntGaugeVec = register(
+        IntGaugeVec::new(
+            Opts::new(
+                "radish_subscriber_lag",
+                "Messages a subscriber of a message topic has not fetched yet."
+            ),
+            &["namespace", "topic", "subscriber"],
+        )
+        .unwrap()
+    );
+    pub static ref EVENT_QUEUE_DEPTH: IntGauge = register(
+        IntGauge::new(
+            "radish_event_queue_depth",
+            "Events waiting to be persisted by a worker."
+        )
+        .unwrap()
+    );
+    static ref PROCESSED_EVENTS: IntCounterVec = register(
+        IntCounterVec::new(
+            Opts::new(
+                "radish_processed_events_total",
+                "Events persisted by a worker."
+            ),
+            &["worker"],
+        )
+        .unwrap()
+    );
+    static ref PERSISTENCE_ERRORS: IntCounterVec = register(
+        IntCounterVec::new(
+            Opts::new(
+                "radish_persistence_errors_total",
+                "Failed reads and writes of a repository."
+            ),
+            &["repository"],
+        )
+        .unwrap()
+    );
+    static ref HTTP_REQUEST_DURATION: HistogramVec = register(
+        HistogramVec::new(
+            HistogramOpts::new(
+                "radish_http_request_duration_seconds",
+                "Time taken to answer an HTTP request."
+            ),
+            &["method", "route", "status"],
+        )
+        .unwrap()
+    );
+}
+
+fn register<T: Collector + Clone + 'static>(collector: T) -> T {
+    REGISTRY
+        .register(Box::new(collector.clone()))
+        .expect("a metric must only be registered once");
+    collector
+}
+
+pub fn record_publish(
+    destination_type: DestinationType,
+    namespace: &str,
+    topic_name: &str,
+    bytes: u64,
+) {
+    let labels = [namespace, destination_type.as_str(), topic_name];
+    PUBLISHED_MESSAGES.with_label_values(&labels).inc();
+    PUBLISHED_BYTES.with_label_values(&labels).inc_by(bytes);
+}
+
+pub fn record_fetch(destination_type: DestinationType, namespace: &str, topic_name: &str) {
+    FETCHES
+        .with_label_values(&[namespace, destination_type.as_str(), topic_name])
+        .inc();
+}
+
+pub fn record_processed_event(worker: u16) {
+    PROCESSED_EVENTS
+        .with_label_values(&[&worker.to_string()])
+        .inc();
+}
+
+pub fn record_persistence_error(repository: &str) {
+    PERSISTENCE_ERRORS.with_label_values(&[repository]).inc();
+}
+
+pub fn observe_http_request(method: &str, route: &str, status: u16, seconds: f64) {
+    HTTP_REQUEST_DURATION
+        .with_label_values(&[method, route, &status.to_string()])
+        .observe(seconds);
+}
@@ -0,0 +1,84 @@
+use crate::auth::permission::Action;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Role {
+    Admin,
+    TopicOwner,
+    Publisher,
+    Subscriber,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::TopicOwner => "topic-owner",
+            Self::Publisher => "publisher",
+            Self::Subscriber => "subscriber",
+        }
+    }
+
+    pub fn allows(&self, action: Action) -> bool {
+        match self {
+            Self::Admin | Self::TopicOwner => true,
+            Self::Publisher => matches!(action, Action::Read | Action::Publish),
+            Self::Subscriber => matches!(action, Action::Read | Action::Subscribe),
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "admin" => Ok(Self::Admin),
+            "topic-owner" => Ok(Self::TopicOwner),
+            "publisher" => Ok(Self::Publisher),
+            "subscriber" => Ok(Self::Subscriber),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct GrantModel {
+    pub id: Uuid,
+    pub principal: String,
+    pub role: Role,
+    pub pattern: String,
+}
+
+impl GrantModel {
+    pub fn new(id: Uuid, principal: String, role: Role, pattern: String) -> Self {
+        Self {
+            id,
+            principal,
+            role,
+            pattern,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CreateGrant {
+    pub principal: String,
+    pub role: Role,
+    pub pattern: String,
+}
+
+#[allow(dead_code)]
+impl CreateGrant {
+    pub fn new(principal: String, role: Role, pattern: String) -> Self {
+        Self {
+            principal,
+            role,
+            pattern,
+        }
+    }
+}
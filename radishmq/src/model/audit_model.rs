@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum AuditAction {
+    TopicCreated,
+    TopicDeleted,
+    PublisherAdded,
+    PublisherRemoved,
+    SubscriberAdded,
+    SubscriberRemoved,
+    GrantCreated,
+    GrantDeleted,
+    CredentialCreated,
+    CredentialDeleted,
+    ExchangeCreated,
+    ExchangeDeleted,
+    BindingAdded,
+    BindingRemoved,
+    ForwardingRuleCreated,
+    ForwardingRuleDeleted,
+    WildcardSubscriptionCreated,
+    WildcardSubscriptionDeleted,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TopicCreated => "topic-created",
+            Self::TopicDeleted => "topic-deleted",
+            Self::PublisherAdded => "publisher-added",
+            Self::PublisherRemoved => "publisher-removed",
+            Self::SubscriberAdded => "subscriber-added",
+            Self::SubscriberRemoved => "subscriber-removed",
+            Self::GrantCreated => "grant-created",
+            Self::GrantDeleted => "grant-deleted",
+            Self::CredentialCreated => "credential-created",
+            Self::CredentialDeleted => "credential-deleted",
+            Self::ExchangeCreated => "exchange-created",
+            Self::ExchangeDeleted => "exchange-deleted",
+            Self::BindingAdded => "binding-added",
+            Self::BindingRemoved => "binding-removed",
+            Self::ForwardingRuleCreated => "forwarding-rule-created",
+            Self::ForwardingRuleDeleted => "forwarding-rule-deleted",
+            Self::WildcardSubscriptionCreated => "wildcard-subscription-created",
+            Self::WildcardSubscriptionDeleted => "wildcard-subscription-deleted",
+        }
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "topic-created" => Ok(Self::TopicCreated),
+            "topic-deleted" => Ok(Self::TopicDeleted),
+            "publisher-added" => Ok(Self::PublisherAdded),
+            "publisher-removed" => Ok(Self::PublisherRemoved),
+            "subscriber-added" => Ok(Self::SubscriberAdded),
+            "subscriber-removed" => Ok(Self::SubscriberRemoved),
+            "grant-created" => Ok(Self::GrantCreated),
+            "grant-deleted" => Ok(Self::GrantDeleted),
+            "credential-created" => Ok(Self::CredentialCreated),
+            "credential-deleted" => Ok(Self::CredentialDeleted),
+            "exchange-created" => Ok(Self::ExchangeCreated),
+            "exchange-deleted" => Ok(Self::ExchangeDeleted),
+            "binding-added" => Ok(Self::BindingAdded),
+            "binding-removed" => Ok(Self::BindingRemoved),
+            "forwarding-rule-created" => Ok(Self::ForwardingRuleCreated),
+            "forwarding-rule-deleted" => Ok(Self::ForwardingRuleDeleted),
+            "wildcard-subscription-created" => Ok(Self::WildcardSubscriptionCreated),
+            "wildcard-subscription-deleted" => Ok(Self::WildcardSubscriptionDeleted),
+            _ => Err(()),
+        }
+    }
+}
+
+/// An entry of the audit log, `before` and `after` hold the state of the resource around the
+/// change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AuditEntryModel {
+    pub id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    pub namespace: String,
+    pub actor: Option<String>,
+    pub address: Option<String>,
+    pub action: AuditAction,
+    pub resource_type: String,
+    pub resource: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub action: Option<AuditAction>,
+    pub resource_type: Option<String>,
+    pub resource: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
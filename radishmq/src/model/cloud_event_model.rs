@@ -0,0 +1,181 @@
+use crate::model::message_topic_model::{Message, PublishToMessageTopic};
+use crate::utils::types::{Headers, TopicType};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub const SPEC_VERSION: &str = "1.0";
+
+/// Prefix of the message headers that carry the attributes of a CloudEvent.
+pub const ATTRIBUTE_HEADER_PREFIX: &str = "ce-";
+
+const MESSAGE_EVENT_TYPE: &str = "radish_mq.message";
+
+/// A CloudEvents 1.0 event in the structured JSON format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CloudEvent {
+    pub specversion: String,
+    pub id: String,
+    pub source: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datacontenttype: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dataschema: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_base64: Option<String>,
+    #[serde(flatten)]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+impl CloudEvent {
+    /// Restores the event from the `ce-` headers of a message. Messages which were not published
+    /// as an event get a generated id, the topic as source and the `radish_mq.message` type.
+    pub fn from_message(message: Message<TopicType>, source: &str) -> Self {
+        let mut attributes: BTreeMap<String, String> = message
+            .headers
+            .into_iter()
+            .filter_map(|(name, value)| {
+                name.strip_prefix(ATTRIBUTE_HEADER_PREFIX)
+                    .map(|attribute| (attribute.to_string(), value))
+            })
+            .collect();
+
+        let datacontenttype = attributes.remove("datacontenttype");
+        let data = if message.data.is_empty() {
+            None
+        } else if is_json_content_type(datacontenttype.as_deref()) {
+            Some(serde_json::from_str(&message.data).unwrap_or(Value::String(message.data)))
+        } else {
+            Some(Value::String(message.data))
+        };
+
+        Self {
+            specversion: attributes
+                .remove("specversion")
+                .unwrap_or_else(|| SPEC_VERSION.to_string()),
+            id: attributes
+                .remove("id")
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+            source: attributes
+                .remove("source")
+                .unwrap_or_else(|| source.to_string()),
+            event_type: attributes
+                .remove("type")
+                .unwrap_or_else(|| MESSAGE_EVENT_TYPE.to_string()),
+            datacontenttype,
+            dataschema: attributes.remove("dataschema"),
+            subject: attributes.remove("subject"),
+            time: attributes.remove("time"),
+            data,
+            data_base64: None,
+            extensions: attributes
+                .into_iter()
+                .map(|(name, value)| (name, Value::String(value)))
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<CloudEvent> for PublishToMessageTopic<TopicType> {
+    type Error = String;
+
+    fn try_from(event: CloudEvent) -> Result<Self, Self::Error> {
+        if event.data_base64.is_some() {
+            return Err("data_base64 is not supported, message data is text".to_string());
+        }
+
+        let data = match event.data {
+            None => String::new(),
+            Some(Value::String(data))
+                if !is_json_content_type(event.datacontenttype.as_deref()) =>
+            {
+                data
+            }
+            Some(data) => data.to_string(),
+        };
+
+        let mut headers = Headers::new();
+        let attributes = [
+            ("specversion", Some(event.specversion)),
+            ("id", Some(event.id)),
+            ("source", Some(event.source)),
+            ("type", Some(event.event_type)),
+            ("datacontenttype", event.datacontenttype),
+            ("dataschema", event.dataschema),
+            ("subject", event.subject),
+            ("time", event.time),
+        ];
+        for (name, value) in attributes {
+            if let Some(value) = value {
+                headers.insert(format!("{}{}", ATTRIBUTE_HEADER_PREFIX, name), value);
+            }
+        }
+        for (name, value) in event.extensions {
+            // the JSON format types extensions, the headers keep their canonical string form
+            let value = match value {
+                Value::String(value) => value,
+                value => value.to_string(),
+            };
+            headers.insert(format!("{}{}", ATTRIBUTE_HEADER_PREFIX, name), value);
+        }
+
+        validate_attribute_headers(&headers)?;
+        Ok(PublishToMessageTopic::new(data, headers))
+    }
+}
+
+/// Checks the `ce-` headers of a message for the required attributes of a CloudEvents 1.0 event.
+pub fn validate_attribute_headers(headers: &Headers) -> Result<(), String> {
+    let specversion = headers.get(&format!("{}specversion", ATTRIBUTE_HEADER_PREFIX));
+    if specversion.map(String::as_str) != Some(SPEC_VERSION) {
+        return Err(format!("specversion must be '{}'", SPEC_VERSION));
+    }
+
+    for attribute in ["id", "source", "type"] {
+        let value = headers.get(&format!("{}{}", ATTRIBUTE_HEADER_PREFIX, attribute));
+        if value.is_none_or(|value| value.is_empty()) {
+            return Err(format!("the '{}' attribute is required", attribute));
+        }
+    }
+
+    let invalid = headers
+        .keys()
+        .filter_map(|name| name.strip_prefix(ATTRIBUTE_HEADER_PREFIX))
+        .find(|attribute| !is_valid_attribute_name(attribute));
+    match invalid {
+        Some(attribute) => Err(format!("'{}' is not a valid attribute name", attribute)),
+        None => Ok(()),
+    }
+}
+
+fn is_valid_attribute_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+}
+
+/// The JSON format treats events without `datacontenttype` as JSON.
+fn is_json_content_type(content_type: Option<&str>) -> bool {
+    let Some(content_type) = content_type else {
+        return true;
+    };
+
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    essence == "application/json" || essence == "text/json" || essence.ends_with("+json")
+}
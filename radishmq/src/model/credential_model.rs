@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CredentialModel {
+    pub name: String,
+    pub principals: Vec<String>,
+}
+
+impl CredentialModel {
+    pub fn new(name: String, principals: Vec<String>) -> Self {
+        Self { name, principals }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CreateCredential {
+    pub name: String,
+    pub principals: Vec<String>,
+}
+
+#[allow(dead_code)]
+impl CreateCredential {
+    pub fn new(name: String, principals: Vec<String>) -> Self {
+        Self { name, principals }
+    }
+}
+
+/// The answer to a created credential, the only time its secret is returned.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CreatedCredential {
+    pub name: String,
+    pub principals: Vec<String>,
+    pub secret: String,
+}
+
+impl CreatedCredential {
+    pub fn new(name: String, principals: Vec<String>, secret: String) -> Self {
+        Self {
+            name,
+            principals,
+            secret,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DestinationType {
+    MessageTopic,
+    TaskTopic,
+}
+
+impl DestinationType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MessageTopic => "message_topic",
+            Self::TaskTopic => "task_topic",
+        }
+    }
+}
+
+impl FromStr for DestinationType {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "message_topic" => Ok(Self::MessageTopic),
+            "task_topic" => Ok(Self::TaskTopic),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub struct TopicDestination {
+    pub destination_type: DestinationType,
+    pub destination: String,
+}
+
+#[allow(dead_code)]
+impl TopicDestination {
+    pub fn new(destination_type: DestinationType, destination: String) -> Self {
+        Self {
+            destination_type,
+            destination,
+        }
+    }
+}
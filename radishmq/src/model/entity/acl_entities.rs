@@ -0,0 +1,10 @@
+use uuid::Uuid;
+
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AclGrantEntity {
+    pub id: Uuid,
+    pub namespace: String,
+    pub principal: String,
+    pub role: String,
+    pub pattern: String,
+}
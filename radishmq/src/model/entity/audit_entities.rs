@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq)]
+pub struct AuditEntryEntity {
+    pub id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    pub namespace: String,
+    pub actor: Option<String>,
+    pub address: Option<String>,
+    pub action: String,
+    pub resource_type: String,
+    pub resource: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
@@ -0,0 +1,11 @@
+use uuid::Uuid;
+
+#[allow(dead_code)]
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CredentialEntity {
+    pub id: Uuid,
+    pub namespace: String,
+    pub credential_name: String,
+    pub secret_hash: String,
+    pub principals: String,
+}
@@ -0,0 +1,15 @@
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DataKeyEntity {
+    pub namespace: String,
+    pub topic_type: String,
+    pub topic_name: String,
+    pub master_key_id: String,
+    pub wrapped_key: Vec<u8>,
+    pub sealed_name: Option<Vec<u8>>,
+}
+
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TopicNameKeyEntity {
+    pub master_key_id: String,
+    pub wrapped_key: Vec<u8>,
+}
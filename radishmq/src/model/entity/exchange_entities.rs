@@ -0,0 +1,30 @@
+use uuid::Uuid;
+
+#[allow(dead_code)]
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExchangeEntity {
+    pub id: Uuid,
+    pub exchange_name: String,
+    pub exchange_type: String,
+}
+
+#[allow(dead_code)]
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExchangeBindingEntity {
+    pub id: Uuid,
+    pub exchange_id: Uuid,
+    pub destination_type: String,
+    pub destination_name: String,
+    pub routing_key: Option<String>,
+    pub arguments: String,
+    pub header_match: String,
+    pub sealed_destination_name: Option<Vec<u8>>,
+}
+
+#[allow(dead_code)]
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExchangePublisherEntity {
+    pub id: Uuid,
+    pub exchange_id: Uuid,
+    pub publisher_name: String,
+}
@@ -0,0 +1,14 @@
+use uuid::Uuid;
+
+#[allow(dead_code)]
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ForwardingRuleEntity {
+    pub id: Uuid,
+    pub rule_name: String,
+    pub source: String,
+    pub destination_type: String,
+    pub destination_name: String,
+    pub header_rewrite: String,
+    pub namespace: String,
+    pub sealed_destination_name: Option<Vec<u8>>,
+}
@@ -1,22 +1,31 @@
 use sqlx::types::Uuid;
+#[allow(dead_code)]
 #[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MessageTopicEntity {
     pub id: Uuid,
-    pub name: String,
+    pub namespace: String,
+    pub topic_name: String,
     pub data_index: i64,
+    pub sealed_name: Option<Vec<u8>>,
 }
 
+#[allow(dead_code)]
 #[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MessageTopicSubscriberEntity {
     pub id: Uuid,
     pub message_topic_id: Uuid,
     pub subscriber_name: String,
     pub subscriber_index: i64,
+    pub filter: Option<String>,
+    pub sealed_name: Option<Vec<u8>>,
+    pub sealed_filter: Option<Vec<u8>>,
 }
 
+#[allow(dead_code)]
 #[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MessageTopicPublisherEntity {
     pub id: Uuid,
     pub message_topic_id: Uuid,
     pub publisher_name: String,
+    pub sealed_name: Option<Vec<u8>>,
 }
@@ -1,2 +1,10 @@
+pub mod acl_entities;
+pub mod audit_entities;
+pub mod credential_entities;
+pub mod data_key_entities;
+pub mod exchange_entities;
+pub mod forwarding_rule_entities;
 pub mod message_topic_entities;
+pub mod retained_message_entities;
 pub mod task_topic_entities;
+pub mod wildcard_subscription_entities;
@@ -0,0 +1,10 @@
+#[allow(dead_code)]
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RetainedMessageEntity {
+    pub topic_name: String,
+    pub data: String,
+    pub headers: String,
+    pub sealed_data: Option<Vec<u8>>,
+    pub sealed_headers: Option<Vec<u8>>,
+    pub sealed_name: Option<Vec<u8>>,
+}
@@ -1,21 +1,28 @@
 use uuid::Uuid;
 
+#[allow(dead_code)]
 #[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TaskTopicEntity {
     pub id: Uuid,
-    pub name: String,
+    pub namespace: String,
+    pub topic_name: String,
+    pub sealed_name: Option<Vec<u8>>,
 }
 
+#[allow(dead_code)]
 #[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TaskTopicSubscriberEntity {
     pub id: Uuid,
     pub task_topic_id: Uuid,
     pub subscriber_name: String,
+    pub sealed_name: Option<Vec<u8>>,
 }
 
+#[allow(dead_code)]
 #[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TaskTopicPublisherEntity {
     pub id: Uuid,
     pub task_topic_id: Uuid,
     pub publisher_name: String,
+    pub sealed_name: Option<Vec<u8>>,
 }
@@ -0,0 +1,18 @@
+use uuid::Uuid;
+
+#[allow(dead_code)]
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WildcardSubscriptionEntity {
+    pub id: Uuid,
+    pub subscriber_name: String,
+    pub pattern: String,
+    pub filter: Option<String>,
+}
+
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WildcardSubscriptionTopicEntity {
+    pub subscriber_name: String,
+    pub pattern: String,
+    pub topic_name: String,
+    pub sealed_topic_name: Option<Vec<u8>>,
+}
@@ -0,0 +1,227 @@
+use crate::model::destination_model::TopicDestination;
+use crate::utils::types::Headers;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExchangeType {
+    Direct,
+    Fanout,
+    Topic,
+    Headers,
+}
+
+impl ExchangeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Direct => "direct",
+            Self::Fanout => "fanout",
+            Self::Topic => "topic",
+            Self::Headers => "headers",
+        }
+    }
+}
+
+impl FromStr for ExchangeType {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "direct" => Ok(Self::Direct),
+            "fanout" => Ok(Self::Fanout),
+            "topic" => Ok(Self::Topic),
+            "headers" => Ok(Self::Headers),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HeaderMatch {
+    #[default]
+    All,
+    Any,
+}
+
+impl HeaderMatch {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Any => "any",
+        }
+    }
+}
+
+impl FromStr for HeaderMatch {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "all" => Ok(Self::All),
+            "any" => Ok(Self::Any),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExchangeModel {
+    pub name: String,
+    pub exchange_type: ExchangeType,
+    pub bindings: Vec<BindingModel>,
+    pub publisher: Vec<String>,
+}
+
+#[allow(dead_code)]
+impl ExchangeModel {
+    pub fn new(
+        name: String,
+        exchange_type: ExchangeType,
+        bindings: Vec<BindingModel>,
+        publisher: Vec<String>,
+    ) -> Self {
+        Self {
+            name,
+            exchange_type,
+            bindings,
+            publisher,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BindingModel {
+    pub id: Uuid,
+    #[serde(flatten)]
+    pub destination: TopicDestination,
+    pub routing_key: Option<String>,
+    #[schema(value_type = HashMap<String, String>)]
+    pub arguments: Headers,
+    pub header_match: HeaderMatch,
+}
+
+#[allow(dead_code)]
+impl BindingModel {
+    pub fn new(
+        id: Uuid,
+        destination: TopicDestination,
+        routing_key: Option<String>,
+        arguments: Headers,
+        header_match: HeaderMatch,
+    ) -> Self {
+        Self {
+            id,
+            destination,
+            routing_key,
+            arguments,
+            header_match,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CreateExchange {
+    pub name: String,
+    pub exchange_type: ExchangeType,
+}
+
+#[allow(dead_code)]
+impl CreateExchange {
+    pub fn new(name: String, exchange_type: ExchangeType) -> Self {
+        Self {
+            name,
+            exchange_type,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AddBindingToExchange {
+    #[serde(flatten)]
+    pub destination: TopicDestination,
+    #[serde(default)]
+    pub routing_key: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = HashMap<String, String>)]
+    pub arguments: Headers,
+    #[serde(default)]
+    pub header_match: HeaderMatch,
+}
+
+#[allow(dead_code)]
+impl AddBindingToExchange {
+    pub fn new(
+        destination: TopicDestination,
+        routing_key: Option<String>,
+        arguments: Headers,
+        header_match: HeaderMatch,
+    ) -> Self {
+        Self {
+            destination,
+            routing_key,
+            arguments,
+            header_match,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AddPublisherToExchange {
+    pub publisher: String,
+}
+
+#[allow(dead_code)]
+impl AddPublisherToExchange {
+    pub fn new(publisher: String) -> Self {
+        Self { publisher }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RemovePublisherFromExchange {
+    pub publisher: String,
+}
+
+#[allow(dead_code)]
+impl RemovePublisherFromExchange {
+    pub fn new(publisher: String) -> Self {
+        Self { publisher }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PublishToExchange<T> {
+    pub data: T,
+    #[serde(default)]
+    pub routing_key: String,
+    #[serde(default)]
+    #[schema(value_type = HashMap<String, String>)]
+    pub headers: Headers,
+}
+
+#[allow(dead_code)]
+impl<T> PublishToExchange<T> {
+    pub fn new(data: T, routing_key: String, headers: Headers) -> Self {
+        Self {
+            data,
+            routing_key,
+            headers,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RoutedMessageExchange {
+    pub destinations: Vec<TopicDestination>,
+}
+
+#[allow(dead_code)]
+impl RoutedMessageExchange {
+    pub fn new(destinations: Vec<TopicDestination>) -> Self {
+        Self { destinations }
+    }
+}
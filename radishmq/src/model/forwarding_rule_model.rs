@@ -0,0 +1,73 @@
+use crate::model::destination_model::TopicDestination;
+use crate::utils::types::Headers;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, ToSchema)]
+pub struct HeaderRewrite {
+    #[serde(default)]
+    #[schema(value_type = HashMap<String, String>)]
+    pub set: Headers,
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+#[allow(dead_code)]
+impl HeaderRewrite {
+    pub fn new(set: Headers, remove: Vec<String>) -> Self {
+        Self { set, remove }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ForwardingRuleModel {
+    pub name: String,
+    pub source: String,
+    #[serde(flatten)]
+    pub destination: TopicDestination,
+    pub header_rewrite: HeaderRewrite,
+}
+
+#[allow(dead_code)]
+impl ForwardingRuleModel {
+    pub fn new(
+        name: String,
+        source: String,
+        destination: TopicDestination,
+        header_rewrite: HeaderRewrite,
+    ) -> Self {
+        Self {
+            name,
+            source,
+            destination,
+            header_rewrite,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CreateForwardingRule {
+    pub name: String,
+    pub source: String,
+    #[serde(flatten)]
+    pub destination: TopicDestination,
+    #[serde(default)]
+    pub header_rewrite: HeaderRewrite,
+}
+
+#[allow(dead_code)]
+impl CreateForwardingRule {
+    pub fn new(
+        name: String,
+        source: String,
+        destination: TopicDestination,
+        header_rewrite: HeaderRewrite,
+    ) -> Self {
+        Self {
+            name,
+            source,
+            destination,
+            header_rewrite,
+        }
+    }
+}
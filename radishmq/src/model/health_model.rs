@@ -0,0 +1,52 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentHealth {
+    pub status: HealthStatus,
+    pub detail: String,
+}
+
+impl ComponentHealth {
+    pub fn new(up: bool, detail: String) -> Self {
+        Self {
+            status: if up {
+                HealthStatus::Up
+            } else {
+                HealthStatus::Down
+            },
+            detail,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthModel {
+    pub status: HealthStatus,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub components: BTreeMap<&'static str, ComponentHealth>,
+}
+
+impl HealthModel {
+    /// The broker is up if every component is up.
+    pub fn new(components: BTreeMap<&'static str, ComponentHealth>) -> Self {
+        let up = components
+            .values()
+            .all(|component| component.status == HealthStatus::Up);
+        Self {
+            status: if up {
+                HealthStatus::Up
+            } else {
+                HealthStatus::Down
+            },
+            components,
+        }
+    }
+}
@@ -1,6 +1,9 @@
+use crate::topic::message_filter::MessageFilter;
+use crate::utils::types::{Headers, TopicType};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct MessageTopicModel {
     pub name: String,
     pub index: usize,
@@ -18,7 +21,7 @@ impl MessageTopicModel {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct CreateMessageTopic {
     pub name: String,
 }
@@ -30,7 +33,7 @@ impl CreateMessageTopic {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct UpdateMessageTopic {
     pub name: String,
 }
@@ -42,19 +45,34 @@ impl UpdateMessageTopic {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct PublishToMessageTopic<T> {
     pub data: T,
+    #[serde(default)]
+    #[schema(value_type = HashMap<String, String>)]
+    pub headers: Headers,
 }
 
 #[allow(dead_code)]
 impl<T> PublishToMessageTopic<T> {
-    pub fn new(data: T) -> Self {
-        Self { data }
+    pub fn new(data: T, headers: Headers) -> Self {
+        Self { data, headers }
+    }
+}
+
+impl PublishToMessageTopic<TopicType> {
+    /// The bytes a message counts against the quotas, its data and its headers.
+    pub fn size(&self) -> u64 {
+        let headers: usize = self
+            .headers
+            .iter()
+            .map(|(name, value)| name.len() + value.len())
+            .sum();
+        (self.data.len() + headers) as u64
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct AddPublisherToMessageTopic {
     pub publisher: String,
 }
@@ -66,7 +84,7 @@ impl AddPublisherToMessageTopic {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct RemovePublisherFromMessageTopic {
     pub publisher: String,
 }
@@ -78,19 +96,21 @@ impl RemovePublisherFromMessageTopic {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct AddSubscriberToMessageTopic {
     pub subscriber: String,
+    #[serde(default)]
+    pub filter: Option<MessageFilter>,
 }
 
 #[allow(dead_code)]
 impl AddSubscriberToMessageTopic {
-    pub fn new(subscriber: String) -> Self {
-        Self { subscriber }
+    pub fn new(subscriber: String, filter: Option<MessageFilter>) -> Self {
+        Self { subscriber, filter }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct RemoveSubscriberFromMessageTopic {
     pub subscriber: String,
 }
@@ -102,7 +122,7 @@ impl RemoveSubscriberFromMessageTopic {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct NewDataMessageTopic {
     pub new_data: bool,
 }
@@ -114,7 +134,7 @@ impl NewDataMessageTopic {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct DataMessageTopic<T> {
     pub data: Vec<T>,
 }
@@ -125,3 +145,39 @@ impl<T> DataMessageTopic<T> {
         Self { data }
     }
 }
+
+impl<T> DataMessageTopic<Message<T>> {
+    /// Drops the headers, the shape clients that predate message headers expect.
+    pub fn without_headers(self) -> DataMessageTopic<T> {
+        DataMessageTopic::new(self.data.into_iter().map(|message| message.data).collect())
+    }
+}
+
+/// Query of the routes fetching data, messages only carry their headers if they are asked for.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct DataQuery {
+    /// Return every message with its headers instead of its data alone
+    #[serde(default)]
+    pub headers: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Message<T> {
+    pub data: T,
+    #[serde(default)]
+    #[schema(value_type = HashMap<String, String>)]
+    pub headers: Headers,
+}
+
+#[allow(dead_code)]
+impl<T> Message<T> {
+    pub fn new(data: T, headers: Headers) -> Self {
+        Self { data, headers }
+    }
+}
+
+impl<T> From<PublishToMessageTopic<T>> for Message<T> {
+    fn from(dts: PublishToMessageTopic<T>) -> Self {
+        Self::new(dts.data, dts.headers)
+    }
+}
@@ -1,3 +1,13 @@
+pub mod acl_model;
+pub mod audit_model;
+pub mod cloud_event_model;
+pub mod credential_model;
+pub mod destination_model;
 pub mod entity;
+pub mod exchange_model;
+pub mod forwarding_rule_model;
+pub mod health_model;
 pub mod message_topic_model;
+pub mod retained_message_model;
 pub mod task_topic_model;
+pub mod wildcard_subscription_model;
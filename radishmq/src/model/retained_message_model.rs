@@ -0,0 +1,21 @@
+use crate::utils::types::Headers;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetainedMessageModel<T> {
+    pub topic: String,
+    pub data: T,
+    #[serde(default)]
+    pub headers: Headers,
+}
+
+#[allow(dead_code)]
+impl<T> RetainedMessageModel<T> {
+    pub fn new(topic: String, data: T, headers: Headers) -> Self {
+        Self {
+            topic,
+            data,
+            headers,
+        }
+    }
+}
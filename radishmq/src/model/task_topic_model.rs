@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct TaskTopicModel {
     pub name: String,
     pub subscriber: Vec<String>,
@@ -13,7 +15,7 @@ impl TaskTopicModel {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct CreateTaskTopic {
     pub name: String,
 }
@@ -25,7 +27,7 @@ impl CreateTaskTopic {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct UpdateTaskTopic {
     pub name: String,
 }
@@ -37,7 +39,7 @@ impl UpdateTaskTopic {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct PublishToTaskTopic<T> {
     pub data: T,
 }
@@ -49,7 +51,7 @@ impl<T> PublishToTaskTopic<T> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct AddPublisherToTaskTopic {
     pub publisher: String,
 }
@@ -61,7 +63,7 @@ impl AddPublisherToTaskTopic {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct RemovePublisherFromTaskTopic {
     pub publisher: String,
 }
@@ -73,7 +75,7 @@ impl RemovePublisherFromTaskTopic {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct AddSubscriberToTaskTopic {
     pub subscriber: String,
 }
@@ -85,7 +87,7 @@ impl AddSubscriberToTaskTopic {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct RemoveSubscriberFromTaskTopic {
     pub subscriber: String,
 }
@@ -97,7 +99,7 @@ impl RemoveSubscriberFromTaskTopic {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct NewTasks {
     pub new_tasks: bool,
 }
@@ -108,7 +110,7 @@ impl NewTasks {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct Task<T> {
     pub data: Vec<T>,
 }
@@ -119,3 +121,87 @@ impl<T> Task<T> {
         Self { data }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Queued,
+    InProgress,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TaskStatusModel<T> {
+    pub id: Uuid,
+    pub status: TaskStatus,
+    pub progress: u8,
+    pub subscriber: Option<String>,
+    pub attempts: u32,
+    pub result: Option<T>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PublishedTask {
+    pub id: Uuid,
+}
+
+impl PublishedTask {
+    pub fn new(id: Uuid) -> Self {
+        Self { id }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AssignedTask<T> {
+    pub id: Uuid,
+    pub data: T,
+    pub lease_timeout: u64,
+}
+
+impl<T> AssignedTask<T> {
+    pub fn new(id: Uuid, data: T, lease_timeout: u64) -> Self {
+        Self {
+            id,
+            data,
+            lease_timeout,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ReportTaskProgress {
+    pub progress: u8,
+}
+
+#[allow(dead_code)]
+impl ReportTaskProgress {
+    pub fn new(progress: u8) -> Self {
+        Self { progress }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CompleteTask<T> {
+    pub result: T,
+}
+
+#[allow(dead_code)]
+impl<T> CompleteTask<T> {
+    pub fn new(result: T) -> Self {
+        Self { result }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FailTask {
+    pub error: String,
+}
+
+#[allow(dead_code)]
+impl FailTask {
+    pub fn new(error: String) -> Self {
+        Self { error }
+    }
+}
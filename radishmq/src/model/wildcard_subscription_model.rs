@@ -0,0 +1,94 @@
+use crate::model::message_topic_model::Message;
+use crate::topic::message_filter::MessageFilter;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WildcardSubscriptionModel {
+    pub subscriber: String,
+    pub pattern: String,
+    pub filter: Option<MessageFilter>,
+    pub topics: Vec<String>,
+}
+
+#[allow(dead_code)]
+impl WildcardSubscriptionModel {
+    pub fn new(
+        subscriber: String,
+        pattern: String,
+        filter: Option<MessageFilter>,
+        topics: Vec<String>,
+    ) -> Self {
+        Self {
+            subscriber,
+            pattern,
+            filter,
+            topics,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CreateWildcardSubscription {
+    pub subscriber: String,
+    pub pattern: String,
+    #[serde(default)]
+    pub filter: Option<MessageFilter>,
+}
+
+#[allow(dead_code)]
+impl CreateWildcardSubscription {
+    pub fn new(subscriber: String, pattern: String, filter: Option<MessageFilter>) -> Self {
+        Self {
+            subscriber,
+            pattern,
+            filter,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TopicDataWildcardSubscription<T> {
+    pub topic: String,
+    pub data: Vec<T>,
+}
+
+#[allow(dead_code)]
+impl<T> TopicDataWildcardSubscription<T> {
+    pub fn new(topic: String, data: Vec<T>) -> Self {
+        Self { topic, data }
+    }
+}
+
+impl<T> TopicDataWildcardSubscription<Message<T>> {
+    pub fn without_headers(self) -> TopicDataWildcardSubscription<T> {
+        TopicDataWildcardSubscription::new(
+            self.topic,
+            self.data.into_iter().map(|message| message.data).collect(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DataWildcardSubscription<T> {
+    pub data: Vec<TopicDataWildcardSubscription<T>>,
+}
+
+#[allow(dead_code)]
+impl<T> DataWildcardSubscription<T> {
+    pub fn new(data: Vec<TopicDataWildcardSubscription<T>>) -> Self {
+        Self { data }
+    }
+}
+
+impl<T> DataWildcardSubscription<Message<T>> {
+    /// Drops the headers, the shape clients that predate message headers expect.
+    pub fn without_headers(self) -> DataWildcardSubscription<T> {
+        DataWildcardSubscription::new(
+            self.data
+                .into_iter()
+                .map(|topic_data| topic_data.without_headers())
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,187 @@
+use crate::mqtt::packet::{malformed, Packet};
+use bytes::{Buf, BytesMut};
+use std::io;
+use tokio_util::codec::{Decoder, Encoder};
+
+const MAX_PACKET_LENGTH: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Default)]
+pub struct MqttCodec;
+
+impl Decoder for MqttCodec {
+    type Item = Packet;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        // fixed header: packet type and flags, followed by the remaining length in 1 to 4 bytes
+        let mut remaining_length = 0usize;
+        let mut length_bytes = 0;
+        loop {
+            let Some(&byte) = src.get(1 + length_bytes) else {
+                return Ok(None);
+            };
+            remaining_length |= ((byte & 0x7F) as usize) << (7 * length_bytes);
+            length_bytes += 1;
+            if byte & 0x80 == 0 {
+                break;
+            }
+            if length_bytes == 4 {
+                return Err(malformed("remaining length uses more than 4 bytes"));
+            }
+        }
+
+        if remaining_length > MAX_PACKET_LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("packet of {} bytes exceeds the limit", remaining_length),
+            ));
+        }
+        let header_length = 1 + length_bytes;
+        if src.len() < header_length + remaining_length {
+            src.reserve(header_length + remaining_length - src.len());
+            return Ok(None);
+        }
+
+        let header = src.get_u8();
+        src.advance(length_bytes);
+        let body = src.split_to(remaining_length).freeze();
+
+        Packet::decode(header, body).map(Some)
+    }
+}
+
+impl Encoder<Packet> for MqttCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, packet: Packet, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        packet.encode(dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mqtt::packet::{ConnectReturnCode, LastWill, Publish, QoS};
+    use bytes::{BufMut, Bytes};
+
+    fn string(dst: &mut BytesMut, value: &str) {
+        dst.put_u16(value.len() as u16);
+        dst.put_slice(value.as_bytes());
+    }
+
+    fn encode(packet: Packet) -> BytesMut {
+        let mut dst = BytesMut::new();
+        MqttCodec.encode(packet, &mut dst).unwrap();
+        dst
+    }
+
+    #[test]
+    fn decodes_a_connect_split_across_reads() {
+        let mut body = BytesMut::new();
+        string(&mut body, "MQTT");
+        body.put_u8(4);
+        // username, password, will retain, will QoS 1, will and clean session
+        body.put_u8(0x80 | 0x40 | 0x20 | 0x08 | 0x04 | 0x02);
+        body.put_u16(30);
+        string(&mut body, "sensor");
+        string(&mut body, "sensors/status");
+        string(&mut body, "offline");
+        string(&mut body, "user");
+        string(&mut body, "secret");
+        let mut packet = BytesMut::new();
+        packet.put_u8(0x10);
+        packet.put_u8(body.len() as u8);
+        packet.put_slice(&body);
+
+        let mut src = packet.split_to(10);
+        assert_eq!(MqttCodec.decode(&mut src).unwrap(), None);
+        src.unsplit(packet);
+        let Some(Packet::Connect(connect)) = MqttCodec.decode(&mut src).unwrap() else {
+            panic!("expected CONNECT");
+        };
+        assert!(src.is_empty());
+
+        assert_eq!(connect.protocol_level, 4);
+        assert!(connect.clean_session);
+        assert_eq!(connect.keep_alive, 30);
+        assert_eq!(connect.client_id, "sensor");
+        assert_eq!(
+            connect.last_will,
+            Some(LastWill {
+                topic: "sensors/status".to_string(),
+                payload: Bytes::from("offline"),
+                qos: QoS::AtLeastOnce,
+                retain: true,
+            })
+        );
+        assert_eq!(connect.username.as_deref(), Some("user"));
+        assert_eq!(connect.password, Some(Bytes::from("secret")));
+    }
+
+    #[test]
+    fn publishes_and_acknowledgements_round_trip() {
+        // the payload needs a remaining length of two bytes
+        let publish = Publish {
+            dup: true,
+            qos: QoS::AtLeastOnce,
+            retain: true,
+            topic: "sensors/kitchen/temp".to_string(),
+            packet_id: Some(7),
+            payload: Bytes::from(vec![b'x'; 300]),
+        };
+        let mut src = encode(Packet::Publish(publish.clone()));
+        assert_eq!(&src[..3], [0x3B, 0xC4, 0x02]);
+        assert_eq!(
+            MqttCodec.decode(&mut src).unwrap(),
+            Some(Packet::Publish(publish))
+        );
+
+        let mut src = encode(Packet::PubAck(7));
+        assert_eq!(MqttCodec.decode(&mut src).unwrap(), Some(Packet::PubAck(7)));
+    }
+
+    #[test]
+    fn encodes_the_packets_of_the_server() {
+        let connack = encode(Packet::ConnAck {
+            session_present: true,
+            return_code: ConnectReturnCode::NotAuthorized,
+        });
+        assert_eq!(&connack[..], [0x20, 0x02, 0x01, 0x05]);
+
+        let suback = encode(Packet::SubAck {
+            packet_id: 258,
+            return_codes: vec![1, 0x80],
+        });
+        assert_eq!(&suback[..], [0x90, 0x04, 0x01, 0x02, 0x01, 0x80]);
+
+        assert_eq!(&encode(Packet::PingResp)[..], [0xD0, 0x00]);
+        assert!(Packet::PingReq.encode(&mut BytesMut::new()).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_and_oversized_packets() {
+        let malformed: [&[u8]; 5] = [
+            // remaining length of more than 4 bytes
+            &[0x30, 0xFF, 0xFF, 0xFF, 0xFF, 0x01],
+            // SUBSCRIBE without topic filters
+            &[0x82, 0x02, 0x00, 0x01],
+            // PUBLISH with QoS 3
+            &[0x36, 0x04, 0x00, 0x01, b'a', 0x00],
+            // CONNACK is only sent by the server
+            &[0x20, 0x02, 0x00, 0x00],
+            // PUBACK without a packet id
+            &[0x40, 0x01, 0x00],
+        ];
+        for packet in malformed {
+            assert!(
+                MqttCodec.decode(&mut BytesMut::from(packet)).is_err(),
+                "{:?}",
+                packet
+            );
+        }
+
+        let mut oversized = BytesMut::from(&[0x30, 0x81, 0x80, 0x80, 0x08][..]);
+        let error = MqttCodec.decode(&mut oversized).unwrap_err();
+        assert!(error.to_string().contains("exceeds the limit"));
+    }
+}
@@ -0,0 +1,4 @@
+pub mod codec;
+pub mod packet;
+pub mod server;
+pub mod session;
@@ -0,0 +1,339 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::io;
+
+pub const PROTOCOL_NAME: &str = "MQTT";
+pub const PROTOCOL_LEVEL: u8 = 4;
+
+pub const CONNECT: u8 = 1;
+pub const CONNACK: u8 = 2;
+pub const PUBLISH: u8 = 3;
+pub const PUBACK: u8 = 4;
+pub const PUBREC: u8 = 5;
+pub const PUBREL: u8 = 6;
+pub const PUBCOMP: u8 = 7;
+pub const SUBSCRIBE: u8 = 8;
+pub const SUBACK: u8 = 9;
+pub const UNSUBSCRIBE: u8 = 10;
+pub const UNSUBACK: u8 = 11;
+pub const PINGREQ: u8 = 12;
+pub const PINGRESP: u8 = 13;
+pub const DISCONNECT: u8 = 14;
+
+pub const SUBSCRIPTION_FAILURE: u8 = 0x80;
+const MAX_REMAINING_LENGTH: usize = 268_435_455;
+
+// the variant names follow the MQTT specification
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum QoS {
+    AtMostOnce = 0,
+    AtLeastOnce = 1,
+    ExactlyOnce = 2,
+}
+
+impl TryFrom<u8> for QoS {
+    type Error = io::Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::AtMostOnce),
+            1 => Ok(Self::AtLeastOnce),
+            2 => Ok(Self::ExactlyOnce),
+            _ => Err(malformed("invalid QoS level")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ConnectReturnCode {
+    Accepted = 0,
+    UnacceptableProtocolVersion = 1,
+    IdentifierRejected = 2,
+    BadUsernameOrPassword = 4,
+    NotAuthorized = 5,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LastWill {
+    pub topic: String,
+    pub payload: Bytes,
+    pub qos: QoS,
+    pub retain: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Connect {
+    pub protocol_name: String,
+    pub protocol_level: u8,
+    pub clean_session: bool,
+    pub keep_alive: u16,
+    pub client_id: String,
+    pub last_will: Option<LastWill>,
+    pub username: Option<String>,
+    pub password: Option<Bytes>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Publish {
+    pub dup: bool,
+    pub qos: QoS,
+    pub retain: bool,
+    pub topic: String,
+    pub packet_id: Option<u16>,
+    pub payload: Bytes,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Packet {
+    Connect(Connect),
+    ConnAck {
+        session_present: bool,
+        return_code: ConnectReturnCode,
+    },
+    Publish(Publish),
+    PubAck(u16),
+    PubRec(u16),
+    PubRel(u16),
+    PubComp(u16),
+    Subscribe {
+        packet_id: u16,
+        filters: Vec<(String, QoS)>,
+    },
+    SubAck {
+        packet_id: u16,
+        return_codes: Vec<u8>,
+    },
+    Unsubscribe {
+        packet_id: u16,
+        filters: Vec<String>,
+    },
+    UnsubAck(u16),
+    PingReq,
+    PingResp,
+    Disconnect,
+}
+
+impl Packet {
+    /// Parses the variable header and payload of a packet whose fixed header has been read.
+    pub fn decode(header: u8, mut body: Bytes) -> io::Result<Self> {
+        let flags = header & 0x0F;
+        let packet = match header >> 4 {
+            CONNECT => Self::Connect(decode_connect(&mut body)?),
+            PUBLISH => Self::Publish(decode_publish(flags, &mut body)?),
+            PUBACK => Self::PubAck(read_u16(&mut body)?),
+            PUBREC => Self::PubRec(read_u16(&mut body)?),
+            PUBREL => Self::PubRel(read_u16(&mut body)?),
+            PUBCOMP => Self::PubComp(read_u16(&mut body)?),
+            SUBSCRIBE => {
+                let packet_id = read_u16(&mut body)?;
+                let mut filters = Vec::new();
+                while body.has_remaining() {
+                    let filter = read_string(&mut body)?;
+                    let qos = QoS::try_from(read_u8(&mut body)?)?;
+                    filters.push((filter, qos));
+                }
+                if filters.is_empty() {
+                    return Err(malformed("SUBSCRIBE without topic filters"));
+                }
+                Self::Subscribe { packet_id, filters }
+            }
+            UNSUBSCRIBE => {
+                let packet_id = read_u16(&mut body)?;
+                let mut filters = Vec::new();
+                while body.has_remaining() {
+                    filters.push(read_string(&mut body)?);
+                }
+                if filters.is_empty() {
+                    return Err(malformed("UNSUBSCRIBE without topic filters"));
+                }
+                Self::Unsubscribe { packet_id, filters }
+            }
+            PINGREQ => Self::PingReq,
+            DISCONNECT => Self::Disconnect,
+            packet_type => {
+                return Err(malformed(&format!(
+                    "packet type {} is not sent by clients",
+                    packet_type
+                )))
+            }
+        };
+        Ok(packet)
+    }
+
+    /// Writes the packet including its fixed header, only packets sent by the server are supported.
+    pub fn encode(&self, dst: &mut BytesMut) -> io::Result<()> {
+        let mut body = BytesMut::new();
+        let header = match self {
+            Self::ConnAck {
+                session_present,
+                return_code,
+            } => {
+                body.put_u8(u8::from(*session_present));
+                body.put_u8(*return_code as u8);
+                CONNACK << 4
+            }
+            Self::Publish(publish) => {
+                write_string(&mut body, &publish.topic);
+                if let Some(packet_id) = publish.packet_id {
+                    body.put_u16(packet_id);
+                }
+                body.put_slice(&publish.payload);
+                (PUBLISH << 4)
+                    | (u8::from(publish.dup) << 3)
+                    | ((publish.qos as u8) << 1)
+                    | u8::from(publish.retain)
+            }
+            Self::PubAck(packet_id) => {
+                body.put_u16(*packet_id);
+                PUBACK << 4
+            }
+            Self::SubAck {
+                packet_id,
+                return_codes,
+            } => {
+                body.put_u16(*packet_id);
+                body.put_slice(return_codes);
+                SUBACK << 4
+            }
+            Self::UnsubAck(packet_id) => {
+                body.put_u16(*packet_id);
+                UNSUBACK << 4
+            }
+            Self::PingResp => PINGRESP << 4,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "packet is not sent by the server",
+                ))
+            }
+        };
+
+        dst.put_u8(header);
+        write_remaining_length(dst, body.len())?;
+        dst.put_slice(&body);
+        Ok(())
+    }
+}
+
+fn decode_connect(body: &mut Bytes) -> io::Result<Connect> {
+    let protocol_name = read_string(body)?;
+    let protocol_level = read_u8(body)?;
+    let flags = read_u8(body)?;
+    let keep_alive = read_u16(body)?;
+
+    if flags & 0x01 != 0 {
+        return Err(malformed("reserved CONNECT flag is set"));
+    }
+
+    let client_id = read_string(body)?;
+    let last_will = if flags & 0x04 != 0 {
+        Some(LastWill {
+            topic: read_string(body)?,
+            payload: read_binary(body)?,
+            qos: QoS::try_from((flags >> 3) & 0x03)?,
+            retain: flags & 0x20 != 0,
+        })
+    } else {
+        None
+    };
+    let username = if flags & 0x80 != 0 {
+        Some(read_string(body)?)
+    } else {
+        None
+    };
+    let password = if flags & 0x40 != 0 {
+        Some(read_binary(body)?)
+    } else {
+        None
+    };
+
+    Ok(Connect {
+        protocol_name,
+        protocol_level,
+        clean_session: flags & 0x02 != 0,
+        keep_alive,
+        client_id,
+        last_will,
+        username,
+        password,
+    })
+}
+
+fn decode_publish(flags: u8, body: &mut Bytes) -> io::Result<Publish> {
+    let qos = QoS::try_from((flags >> 1) & 0x03)?;
+    let topic = read_string(body)?;
+    let packet_id = match qos {
+        QoS::AtMostOnce => None,
+        _ => Some(read_u16(body)?),
+    };
+
+    Ok(Publish {
+        dup: flags & 0x08 != 0,
+        qos,
+        retain: flags & 0x01 != 0,
+        topic,
+        packet_id,
+        payload: body.split_off(0),
+    })
+}
+
+fn read_u8(body: &mut Bytes) -> io::Result<u8> {
+    if body.remaining() < 1 {
+        return Err(malformed("packet is too short"));
+    }
+    Ok(body.get_u8())
+}
+
+fn read_u16(body: &mut Bytes) -> io::Result<u16> {
+    if body.remaining() < 2 {
+        return Err(malformed("packet is too short"));
+    }
+    Ok(body.get_u16())
+}
+
+fn read_binary(body: &mut Bytes) -> io::Result<Bytes> {
+    let length = read_u16(body)? as usize;
+    if body.remaining() < length {
+        return Err(malformed("packet is too short"));
+    }
+    Ok(body.split_to(length))
+}
+
+fn read_string(body: &mut Bytes) -> io::Result<String> {
+    let bytes = read_binary(body)?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| malformed("string is not valid UTF-8"))
+}
+
+fn write_string(dst: &mut BytesMut, value: &str) {
+    dst.put_u16(value.len() as u16);
+    dst.put_slice(value.as_bytes());
+}
+
+fn write_remaining_length(dst: &mut BytesMut, mut length: usize) -> io::Result<()> {
+    if length > MAX_REMAINING_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "packet exceeds the maximum size",
+        ));
+    }
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+        if length > 0 {
+            byte |= 0x80;
+        }
+        dst.put_u8(byte);
+        if length == 0 {
+            return Ok(());
+        }
+    }
+}
+
+pub fn malformed(reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("malformed MQTT packet: {}", reason),
+    )
+}
@@ -0,0 +1,714 @@
+use crate::auth::authenticator::Authenticator;
+use crate::auth::identity::{self, Identity};
+use crate::auth::permission::Action;
+use crate::model::destination_model::{DestinationType, TopicDestination};
+use crate::model::message_topic_model::{Message, PublishToMessageTopic};
+use crate::model::wildcard_subscription_model::CreateWildcardSubscription;
+use crate::mqtt::codec::MqttCodec;
+use crate::mqtt::packet::{
+    malformed, Connect, ConnectReturnCode, LastWill, Packet, Publish, QoS, PROTOCOL_LEVEL,
+    PROTOCOL_NAME, SUBSCRIPTION_FAILURE,
+};
+use crate::mqtt::session::{
+    close_session, is_valid_client_id, open_session, parse_subscriber_name, subscriber_name,
+    to_mqtt_topic, to_topic_name, to_topic_pattern, Session,
+};
+use crate::service::acl_service::is_authorized;
+use crate::service::{
+    message_topic_service, notification_service, retained_message_service,
+    wildcard_subscription_service,
+};
+use crate::utils::namespace::DEFAULT_NAMESPACE;
+use crate::utils::peer_address;
+use crate::utils::topic_pattern::TopicPattern;
+use crate::utils::types::Headers;
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use log::warn;
+use std::collections::HashMap;
+use std::env;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{sleep_until, timeout, Instant};
+use tokio_util::codec::Framed;
+use uuid::Uuid;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub async fn start_mqtt_server() -> io::Result<()> {
+    let port = match env::var("MQTT_PORT") {
+        Ok(port) => port.parse::<u16>().expect("MQTT_PORT must be a number"),
+        Err(_) => {
+            println!("Skipping MQTT listener...");
+            return Ok(());
+        }
+    };
+    let address = env::var("MQTT_ADDRESS")
+        .or_else(|_| env::var("HTTP_ADDRESS"))
+        .expect("MQTT_ADDRESS or HTTP_ADDRESS must be set");
+
+    let listener = TcpListener::bind((address.as_str(), port)).await?;
+    println!("MQTT listener started on {}:{}", address, port);
+
+    let authenticator = Authenticator::from_env().map(Arc::new);
+    tokio::spawn(accept_connections(listener, authenticator));
+    Ok(())
+}
+
+async fn accept_connections(listener: TcpListener, authenticator: Option<Arc<Authenticator>>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, address)) => {
+                let authenticator = authenticator.clone();
+                tokio::spawn(peer_address::scope(address.ip().to_string(), async move {
+                    if let Err(e) = handle_connection(stream, authenticator).await {
+                        warn!("MqttServer::handle_connection closed a connection: {}", e);
+                    }
+                }));
+            }
+            Err(e) => warn!(
+                "MqttServer::accept_connections could not accept a connection: {}",
+                e
+            ),
+        }
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    authenticator: Option<Arc<Authenticator>>,
+) -> io::Result<()> {
+    let mut framed = Framed::new(stream, MqttCodec);
+
+    let connect = match timeout(CONNECT_TIMEOUT, framed.next()).await {
+        Ok(Some(Ok(Packet::Connect(connect)))) => connect,
+        Ok(Some(Err(e))) => return Err(e),
+        Ok(Some(Ok(_))) => return Err(malformed("first packet must be CONNECT")),
+        Ok(None) | Err(_) => return Ok(()),
+    };
+
+    if connect.protocol_name != PROTOCOL_NAME || connect.protocol_level != PROTOCOL_LEVEL {
+        return reject(&mut framed, ConnectReturnCode::UnacceptableProtocolVersion).await;
+    }
+
+    // only clients with a clean session may leave the id to the server
+    let client_id = if connect.client_id.is_empty() && connect.clean_session {
+        Uuid::new_v4().simple().to_string()
+    } else {
+        connect.client_id.clone()
+    };
+    if !is_valid_client_id(&client_id) {
+        return reject(&mut framed, ConnectReturnCode::IdentifierRejected).await;
+    }
+
+    let identity = match authenticator {
+        Some(authenticator) => match authenticate(&authenticator, &connect, &client_id) {
+            Ok(identity) => Some(identity),
+            Err(return_code) => return reject(&mut framed, return_code).await,
+        },
+        None => None,
+    };
+
+    let session = open_session(client_id, connect.clean_session);
+    let mut connection = Connection::new(framed, session, &connect);
+    identity::on_behalf_of(identity, async {
+        let result = connection.run(connect.keep_alive).await;
+        connection.close().await;
+        result
+    })
+    .await
+}
+
+/// The password of CONNECT carries the API key or JWT, the username is not used. The client id is
+/// the identifier the client publishes as, so it has to be a principal of the credential.
+fn authenticate(
+    authenticator: &Authenticator,
+    connect: &Connect,
+    client_id: &str,
+) -> Result<Identity, ConnectReturnCode> {
+    let credential = connect
+        .password
+        .as_ref()
+        .and_then(|password| std::str::from_utf8(password).ok())
+        .ok_or(ConnectReturnCode::BadUsernameOrPassword)?;
+    let identity = authenticator.authenticate(credential).map_err(|e| {
+        warn!("MqttServer::authenticate rejected a connection: {}", e);
+        ConnectReturnCode::BadUsernameOrPassword
+    })?;
+
+    // sessions, retained messages and wildcard subscriptions only exist in the default namespace
+    if identity.namespace != DEFAULT_NAMESPACE || !identity.acts_as(client_id) {
+        return Err(ConnectReturnCode::NotAuthorized);
+    }
+    Ok(identity)
+}
+
+async fn reject(
+    framed: &mut Framed<TcpStream, MqttCodec>,
+    return_code: ConnectReturnCode,
+) -> io::Result<()> {
+    framed
+        .send(Packet::ConnAck {
+            session_present: false,
+            return_code,
+        })
+        .await
+}
+
+struct Subscription {
+    pattern: TopicPattern,
+    qos: QoS,
+}
+
+struct Connection {
+    framed: Framed<TcpStream, MqttCodec>,
+    session: Session,
+    clean_session: bool,
+    last_will: Option<LastWill>,
+    subscriptions: HashMap<String, Subscription>,
+}
+
+impl Connection {
+    fn new(framed: Framed<TcpStream, MqttCodec>, session: Session, connect: &Connect) -> Self {
+        Self {
+            framed,
+            session,
+            clean_session: connect.clean_session,
+            last_will: connect.last_will.clone(),
+            subscriptions: HashMap::new(),
+        }
+    }
+
+    async fn run(&mut self, keep_alive: u16) -> io::Result<()> {
+        if self.clean_session {
+            self.remove_stored_subscriptions().await;
+        } else {
+            self.restore_subscriptions().await;
+        }
+
+        self.framed
+            .send(Packet::ConnAck {
+                session_present: self.session.present || !self.subscriptions.is_empty(),
+                return_code: ConnectReturnCode::Accepted,
+            })
+            .await?;
+
+        let redeliveries = match self.session.inflight.lock() {
+            Ok(inflight) => inflight.redeliveries(),
+            Err(_) => panic!("MqttServer::run tried to lock a poisoned mutex"),
+        };
+        for publish in redeliveries {
+            self.framed.send(Packet::Publish(publish)).await?;
+        }
+        let filters: Vec<String> = self.subscriptions.keys().cloned().collect();
+        for filter in filters {
+            self.deliver(&filter).await?;
+        }
+
+        // the client has to send something within one and a half keep alive periods
+        let idle_timeout = Duration::from_millis(u64::from(keep_alive) * 1500);
+        let mut deadline = Instant::now() + idle_timeout;
+        let mut notifications = notification_service::subscribe_to_notifications();
+
+        loop {
+            tokio::select! {
+                _ = self.session.cancel.cancelled() => return Ok(()),
+                _ = sleep_until(deadline), if keep_alive > 0 => return Ok(()),
+                packet = self.framed.next() => {
+                    let Some(packet) = packet else {
+                        return Ok(());
+                    };
+                    deadline = Instant::now() + idle_timeout;
+                    match packet? {
+                        Packet::Disconnect => {
+                            self.last_will = None;
+                            return Ok(());
+                        }
+                        packet => self.handle_packet(packet).await?,
+                    }
+                }
+                notification = notifications.recv() => {
+                    let destination = match notification {
+                        Ok(destination) => Some(destination),
+                        Err(RecvError::Lagged(_)) => None,
+                        Err(RecvError::Closed) => return Ok(()),
+                    };
+                    self.deliver_notification(destination).await?;
+                }
+            }
+        }
+    }
+
+    async fn handle_packet(&mut self, packet: Packet) -> io::Result<()> {
+        match packet {
+            Packet::Publish(publish) => self.handle_publish(publish).await,
+            Packet::PubAck(packet_id) => {
+                match self.session.inflight.lock() {
+                    Ok(mut inflight) => inflight.acknowledge(packet_id),
+                    Err(_) => panic!("MqttServer::handle_packet tried to lock a poisoned mutex"),
+                }
+                Ok(())
+            }
+            Packet::Subscribe { packet_id, filters } => {
+                self.handle_subscribe(packet_id, filters).await
+            }
+            Packet::Unsubscribe { packet_id, filters } => {
+                for filter in filters {
+                    self.unsubscribe(&filter).await;
+                }
+                self.framed.send(Packet::UnsubAck(packet_id)).await
+            }
+            Packet::PingReq => self.framed.send(Packet::PingResp).await,
+            Packet::PubRec(_) | Packet::PubRel(_) | Packet::PubComp(_) => {
+                Err(malformed("QoS 2 is not supported"))
+            }
+            _ => Err(malformed("unexpected packet")),
+        }
+    }
+
+    async fn handle_publish(&mut self, publish: Publish) -> io::Result<()> {
+        if publish.qos == QoS::ExactlyOnce {
+            return Err(malformed("QoS 2 is not supported"));
+        }
+        let topic_name = to_topic_name(&publish.topic)
+            .ok_or_else(|| malformed("PUBLISH to an invalid topic name"))?;
+        let payload = String::from_utf8(publish.payload.to_vec())
+            .map_err(|_| malformed("payload is not valid UTF-8"))?;
+
+        let persisted = publish_message(
+            &self.session.client_id,
+            topic_name,
+            payload,
+            publish.retain,
+            publish.qos,
+        )
+        .await;
+
+        // QoS 1 publishes are only acknowledged once they are persisted, so the client resends otherwise
+        match (publish.packet_id, persisted) {
+            (Some(packet_id), true) => self.framed.send(Packet::PubAck(packet_id)).await,
+            (Some(_), false) => {
+                warn!("MqttServer::handle_publish could not persist a QoS 1 publish");
+                Ok(())
+            }
+            (None, _) => Ok(()),
+        }
+    }
+
+    async fn handle_subscribe(
+        &mut self,
+        packet_id: u16,
+        filters: Vec<(String, QoS)>,
+    ) -> io::Result<()> {
+        let mut return_codes = Vec::new();
+        let mut subscribed = Vec::new();
+        for (filter, qos) in filters {
+            match self.subscribe(&filter, qos.min(QoS::AtLeastOnce)).await {
+                Some(granted) => {
+                    return_codes.push(granted as u8);
+                    subscribed.push(filter);
+                }
+                None => return_codes.push(SUBSCRIPTION_FAILURE),
+            }
+        }
+        self.framed
+            .send(Packet::SubAck {
+                packet_id,
+                return_codes,
+            })
+            .await?;
+
+        for filter in subscribed {
+            self.send_retained_messages(&filter).await?;
+        }
+        Ok(())
+    }
+
+    async fn subscribe(&mut self, filter: &str, qos: QoS) -> Option<QoS> {
+        let pattern = to_topic_pattern(filter)?;
+
+        match self.subscriptions.get(filter) {
+            Some(subscription) if subscription.qos == qos => return Some(qos),
+            Some(_) => self.unsubscribe(filter).await,
+            None => {}
+        }
+
+        let subscriber = subscriber_name(&self.session.client_id, qos, filter);
+        identity::with_principal(
+            subscriber.clone(),
+            wildcard_subscription_service::create_wildcard_subscription(
+                CreateWildcardSubscription::new(subscriber, pattern.as_str().to_string(), None),
+            ),
+        )
+        .await
+        .ok()?;
+
+        self.subscriptions
+            .insert(filter.to_string(), Subscription { pattern, qos });
+        Some(qos)
+    }
+
+    async fn unsubscribe(&mut self, filter: &str) {
+        if let Some(subscription) = self.subscriptions.remove(filter) {
+            let subscriber = subscriber_name(&self.session.client_id, subscription.qos, filter);
+            let removed = identity::with_principal(
+                subscriber.clone(),
+                wildcard_subscription_service::delete_wildcard_subscription(
+                    subscriber,
+                    subscription.pattern.as_str().to_string(),
+                ),
+            )
+            .await;
+            if let Err(e) = removed {
+                warn!(
+                    "MqttServer::unsubscribe could not remove a wildcard subscription: {}",
+                    e
+                );
+            }
+        }
+    }
+
+    async fn restore_subscriptions(&mut self) {
+        for subscription in wildcard_subscription_service::get_all_wildcard_subscriptions()
+            .await
+            .unwrap_or_default()
+        {
+            let Some((qos, filter)) =
+                parse_subscriber_name(&self.session.client_id, &subscription.subscriber)
+            else {
+                continue;
+            };
+            if let Some(pattern) = to_topic_pattern(&filter) {
+                self.subscriptions
+                    .insert(filter, Subscription { pattern, qos });
+            }
+        }
+    }
+
+    async fn remove_stored_subscriptions(&mut self) {
+        self.restore_subscriptions().await;
+        let filters: Vec<String> = self.subscriptions.keys().cloned().collect();
+        for filter in filters {
+            self.unsubscribe(&filter).await;
+        }
+    }
+
+    async fn deliver_notification(
+        &mut self,
+        destination: Option<TopicDestination>,
+    ) -> io::Result<()> {
+        // without a destination notifications were missed, so every subscription is checked
+        let filters: Vec<String> = self
+            .subscriptions
+            .iter()
+            .filter(|(_, subscription)| {
+                destination.as_ref().is_none_or(|destination| {
+                    destination.destination_type == DestinationType::MessageTopic
+                        && subscription.pattern.matches(&destination.destination)
+                })
+            })
+            .map(|(filter, _)| filter.clone())
+            .collect();
+
+        for filter in filters {
+            self.deliver(&filter).await?;
+        }
+        Ok(())
+    }
+
+    async fn deliver(&mut self, filter: &str) -> io::Result<()> {
+        let Some(qos) = self
+            .subscriptions
+            .get(filter)
+            .map(|subscription| subscription.qos)
+        else {
+            return Ok(());
+        };
+        let subscriber = subscriber_name(&self.session.client_id, qos, filter);
+        let data = identity::with_principal(
+            subscriber.clone(),
+            wildcard_subscription_service::get_new_data_for_wildcard_subscriber(subscriber),
+        )
+        .await;
+
+        for topic_data in data.map(|data| data.data).unwrap_or_default() {
+            let topic = to_mqtt_topic(&topic_data.topic);
+            for message in topic_data.data {
+                self.send_publish(topic.clone(), message.data, qos, false)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn send_retained_messages(&mut self, filter: &str) -> io::Result<()> {
+        let Some(subscription) = self.subscriptions.get(filter) else {
+            return Ok(());
+        };
+        let qos = subscription.qos;
+        let retained_messages =
+            retained_message_service::get_retained_messages(&subscription.pattern);
+
+        for retained_message in retained_messages
+            .into_iter()
+            .filter(|retained_message| is_authorized(Action::Subscribe, &retained_message.topic))
+        {
+            self.send_publish(
+                to_mqtt_topic(&retained_message.topic),
+                retained_message.data,
+                qos,
+                true,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn send_publish(
+        &mut self,
+        topic: String,
+        payload: String,
+        qos: QoS,
+        retain: bool,
+    ) -> io::Result<()> {
+        let mut publish = Publish {
+            dup: false,
+            qos,
+            retain,
+            topic,
+            packet_id: None,
+            payload: Bytes::from(payload),
+        };
+        if qos == QoS::AtLeastOnce {
+            publish = match self.session.inflight.lock() {
+                Ok(mut inflight) => inflight.track(publish),
+                Err(_) => panic!("MqttServer::send_publish tried to lock a poisoned mutex"),
+            };
+        }
+        self.framed.send(Packet::Publish(publish)).await
+    }
+
+    async fn close(&mut self) {
+        // the will is only published when the client went away without a DISCONNECT
+        if let Some(last_will) = self.last_will.take() {
+            match (
+                to_topic_name(&last_will.topic),
+                String::from_utf8(last_will.payload.to_vec()),
+            ) {
+                (Some(topic_name), Ok(payload)) => {
+                    publish_message(
+                        &self.session.client_id,
+                        topic_name,
+                        payload,
+                        last_will.retain,
+                        QoS::AtMostOnce,
+                    )
+                    .await;
+                }
+                _ => warn!("MqttServer::close could not publish an invalid last will"),
+            }
+        }
+
+        if close_session(&self.session, self.clean_session) && self.clean_session {
+            self.remove_stored_subscriptions().await;
+        }
+    }
+}
+
+/// MQTT has no topic administration, so topics are created and clients registered as
+/// publishers on their first publish.
+async fn publish_message(
+    client_id: &str,
+    topic_name: String,
+    payload: String,
+    retain: bool,
+    qos: QoS,
+) -> bool {
+    let retained = retain.then(|| Message::new(payload.clone(), Headers::new()));
+    let dts = PublishToMessageTopic::new(payload, Headers::new());
+    let published = match message_topic_service::ensure_publisher_of_message_topic(
+        topic_name.clone(),
+        client_id.to_string(),
+    )
+    .await
+    {
+        Err(e) => Err(e),
+        Ok(()) if qos == QoS::AtMostOnce => message_topic_service::publish_to_message_topic(
+            topic_name.clone(),
+            client_id.to_string(),
+            dts,
+        )
+        .await
+        .map(|_| true),
+        Ok(()) => match message_topic_service::publish_to_message_topic_with_ack(
+            topic_name.clone(),
+            client_id.to_string(),
+            dts,
+        )
+        .await
+        {
+            Ok(persisted) => Ok(persisted.await.unwrap_or(false)),
+            Err(e) => Err(e),
+        },
+    };
+    // only a publish the client was allowed to make replaces the retained message
+    if let (Some(message), Ok(_)) = (retained, &published) {
+        retained_message_service::set_retained_message(topic_name.clone(), message);
+    }
+    published.unwrap_or_else(|e| {
+        warn!(
+            "MqttServer::publish_message could not publish to '{}': {}",
+            topic_name, e
+        );
+        false
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_queue::event::TopicEvent;
+    use crate::STATE;
+    use bytes::{BufMut, BytesMut};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    const RECEIVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+    // without event queue workers nothing is persisted, so publishes are acknowledged once queued
+    fn acknowledge_publishes() {
+        tokio::spawn(async {
+            loop {
+                let event = STATE.event_queue.lock().unwrap().dequeue();
+                match event {
+                    Some(TopicEvent::PublishMessageTopic(data)) => {
+                        if let Some(ack) = &data.ack {
+                            ack.acknowledge(true);
+                        }
+                    }
+                    Some(_) => {}
+                    None => tokio::time::sleep(Duration::from_millis(10)).await,
+                }
+            }
+        });
+    }
+
+    fn string(dst: &mut BytesMut, value: &str) {
+        dst.put_u16(value.len() as u16);
+        dst.put_slice(value.as_bytes());
+    }
+
+    async fn send(stream: &mut TcpStream, header: u8, body: BytesMut) {
+        let mut packet = BytesMut::new();
+        packet.put_u8(header);
+        packet.put_u8(body.len() as u8);
+        packet.put_slice(&body);
+        stream.write_all(&packet).await.unwrap();
+    }
+
+    async fn receive(stream: &mut TcpStream) -> (u8, Bytes) {
+        timeout(RECEIVE_TIMEOUT, async {
+            let header = stream.read_u8().await.unwrap();
+            let mut length = 0usize;
+            for shift in (0..4).map(|byte| byte * 7) {
+                let byte = stream.read_u8().await.unwrap();
+                length |= ((byte & 0x7F) as usize) << shift;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            stream.read_exact(&mut body).await.unwrap();
+            (header, Bytes::from(body))
+        })
+        .await
+        .expect("no packet was received in time")
+    }
+
+    async fn receive_publish(stream: &mut TcpStream) -> Publish {
+        let (header, body) = receive(stream).await;
+        match Packet::decode(header, body).unwrap() {
+            Packet::Publish(publish) => publish,
+            packet => panic!("expected PUBLISH, got {:?}", packet),
+        }
+    }
+
+    async fn connect(port: u16, client_id: &str, last_will: Option<(&str, &str)>) -> TcpStream {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let mut body = BytesMut::new();
+        string(&mut body, PROTOCOL_NAME);
+        body.put_u8(PROTOCOL_LEVEL);
+        body.put_u8(if last_will.is_some() { 0x06 } else { 0x02 });
+        body.put_u16(0);
+        string(&mut body, client_id);
+        if let Some((topic, payload)) = last_will {
+            string(&mut body, topic);
+            string(&mut body, payload);
+        }
+        send(&mut stream, 0x10, body).await;
+
+        assert_eq!(receive(&mut stream).await, (0x20, Bytes::from(&[0, 0][..])));
+        stream
+    }
+
+    async fn publish(stream: &mut TcpStream, topic: &str, payload: &str, retain: bool) {
+        let mut body = BytesMut::new();
+        string(&mut body, topic);
+        body.put_u16(1);
+        body.put_slice(payload.as_bytes());
+        send(stream, 0x32 | u8::from(retain), body).await;
+
+        assert_eq!(receive(stream).await, (0x40, Bytes::from(&[0, 1][..])));
+    }
+
+    async fn subscribe(stream: &mut TcpStream, filter: &str, qos: QoS) {
+        let mut body = BytesMut::new();
+        body.put_u16(1);
+        string(&mut body, filter);
+        body.put_u8(qos as u8);
+        send(stream, 0x82, body).await;
+
+        assert_eq!(
+            receive(stream).await,
+            (0x90, Bytes::from(vec![0, 1, qos as u8]))
+        );
+    }
+
+    #[tokio::test]
+    async fn subscribers_get_retained_messages_publishes_and_last_wills() {
+        acknowledge_publishes();
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(accept_connections(listener, None));
+
+        let mut publisher = connect(port, "mqtt-publisher", Some(("mqtttest/will", "gone"))).await;
+        publish(&mut publisher, "mqtttest/status", "online", true).await;
+
+        let mut subscriber = connect(port, "mqtt-subscriber", None).await;
+        subscribe(&mut subscriber, "mqtttest/status", QoS::AtMostOnce).await;
+        let retained = receive_publish(&mut subscriber).await;
+        assert!(retained.retain);
+        assert_eq!(retained.topic, "mqtttest/status");
+        assert_eq!(retained.payload, "online");
+
+        subscribe(&mut subscriber, "mqtttest/sensors/+", QoS::AtLeastOnce).await;
+        publish(&mut publisher, "mqtttest/sensors/temp", "21", false).await;
+        let delivered = receive_publish(&mut subscriber).await;
+        assert!(!delivered.retain);
+        assert_eq!(delivered.qos, QoS::AtLeastOnce);
+        assert_eq!(delivered.topic, "mqtttest/sensors/temp");
+        assert_eq!(delivered.payload, "21");
+        let mut puback = BytesMut::new();
+        puback.put_u16(delivered.packet_id.unwrap());
+        send(&mut subscriber, 0x40, puback).await;
+
+        // the connection is dropped without a DISCONNECT, so the will is published
+        subscribe(&mut subscriber, "mqtttest/will", QoS::AtMostOnce).await;
+        drop(publisher);
+        let will = receive_publish(&mut subscriber).await;
+        assert_eq!(will.topic, "mqtttest/will");
+        assert_eq!(will.payload, "gone");
+    }
+}
@@ -0,0 +1,187 @@
+use crate::mqtt::packet::{Publish, QoS};
+use crate::utils::topic_pattern::{
+    TopicPattern, MULTI_SEGMENT_WILDCARD, SINGLE_SEGMENT_WILDCARD, TOPIC_NAME_SEPARATOR,
+};
+use lazy_static::lazy_static;
+use log::warn;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+const MQTT_SEPARATOR: char = '/';
+const MQTT_SINGLE_LEVEL_WILDCARD: &str = "+";
+const MQTT_MULTI_LEVEL_WILDCARD: &str = "#";
+const SUBSCRIBER_PREFIX: &str = "mqtt";
+const SUBSCRIBER_SEPARATOR: char = ':';
+
+lazy_static! {
+    static ref SESSIONS: Mutex<HashMap<String, StoredSession>> = Mutex::new(HashMap::new());
+}
+
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(0);
+
+/// QoS 1 messages sent to the client that have not been acknowledged yet.
+#[derive(Debug, Default)]
+pub struct Inflight {
+    messages: BTreeMap<u16, Publish>,
+    next_packet_id: u16,
+}
+
+impl Inflight {
+    pub fn track(&mut self, mut publish: Publish) -> Publish {
+        self.next_packet_id = self.next_packet_id.checked_add(1).unwrap_or(1);
+        publish.packet_id = Some(self.next_packet_id);
+        self.messages.insert(self.next_packet_id, publish.clone());
+        publish
+    }
+
+    pub fn acknowledge(&mut self, packet_id: u16) {
+        if self.messages.remove(&packet_id).is_none() {
+            warn!("MqttSession::acknowledge got a PUBACK for an unknown packet id");
+        }
+    }
+
+    pub fn redeliveries(&self) -> Vec<Publish> {
+        self.messages
+            .values()
+            .cloned()
+            .map(|mut publish| {
+                publish.dup = true;
+                publish
+            })
+            .collect()
+    }
+}
+
+struct StoredSession {
+    connection_id: u64,
+    cancel: CancellationToken,
+    inflight: Arc<Mutex<Inflight>>,
+}
+
+pub struct Session {
+    pub client_id: String,
+    pub present: bool,
+    pub cancel: CancellationToken,
+    pub inflight: Arc<Mutex<Inflight>>,
+    connection_id: u64,
+}
+
+/// Registers a connection for the client id. A connection that is still open for the same
+/// client is cancelled, a persistent session keeps its unacknowledged messages.
+pub fn open_session(client_id: String, clean_session: bool) -> Session {
+    let connection_id = NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed);
+    let cancel = CancellationToken::new();
+
+    if let Ok(mut sessions) = SESSIONS.lock() {
+        let previous = sessions.remove(&client_id);
+        if let Some(previous) = &previous {
+            previous.cancel.cancel();
+        }
+
+        let (present, inflight) = match previous {
+            Some(previous) if !clean_session => (true, previous.inflight),
+            _ => (false, Arc::new(Mutex::new(Inflight::default()))),
+        };
+
+        sessions.insert(
+            client_id.clone(),
+            StoredSession {
+                connection_id,
+                cancel: cancel.clone(),
+                inflight: inflight.clone(),
+            },
+        );
+
+        Session {
+            client_id,
+            present,
+            cancel,
+            inflight,
+            connection_id,
+        }
+    } else {
+        panic!("MqttSession::open_session tried to lock a poisoned mutex");
+    }
+}
+
+/// Returns false when another connection took over the session in the meantime.
+pub fn close_session(session: &Session, clean_session: bool) -> bool {
+    if let Ok(mut sessions) = SESSIONS.lock() {
+        let owned = sessions
+            .get(&session.client_id)
+            .is_some_and(|stored| stored.connection_id == session.connection_id);
+        if owned && clean_session {
+            sessions.remove(&session.client_id);
+        }
+        owned
+    } else {
+        panic!("MqttSession::close_session tried to lock a poisoned mutex");
+    }
+}
+
+/// Client ids become part of subscriber names, so they must not contain the name separator.
+pub fn is_valid_client_id(client_id: &str) -> bool {
+    !client_id.is_empty() && !client_id.contains(SUBSCRIBER_SEPARATOR)
+}
+
+/// Every MQTT subscription is a wildcard subscription named after the client, QoS and filter.
+pub fn subscriber_name(client_id: &str, qos: QoS, filter: &str) -> String {
+    format!(
+        "{}{sep}{}{sep}{}{sep}{}",
+        SUBSCRIBER_PREFIX,
+        client_id,
+        qos as u8,
+        filter,
+        sep = SUBSCRIBER_SEPARATOR
+    )
+}
+
+pub fn parse_subscriber_name(client_id: &str, subscriber_name: &str) -> Option<(QoS, String)> {
+    let mut parts = subscriber_name.splitn(4, SUBSCRIBER_SEPARATOR);
+    if parts.next()? != SUBSCRIBER_PREFIX || parts.next()? != client_id {
+        return None;
+    }
+    let qos = QoS::try_from(parts.next()?.parse::<u8>().ok()?).ok()?;
+    Some((qos, parts.next()?.to_string()))
+}
+
+/// Maps an MQTT topic name like `sensors/kitchen` to the topic name `sensors.kitchen`.
+pub fn to_topic_name(mqtt_topic: &str) -> Option<String> {
+    let levels: Vec<&str> = mqtt_topic.split(MQTT_SEPARATOR).collect();
+    let valid = levels.iter().all(|level| {
+        !level.is_empty()
+            && !level.contains(TOPIC_NAME_SEPARATOR)
+            && !level.contains(SINGLE_SEGMENT_WILDCARD)
+            && !level.contains(MULTI_SEGMENT_WILDCARD)
+            && !level.contains(MQTT_SINGLE_LEVEL_WILDCARD)
+    });
+    valid.then(|| levels.join(&TOPIC_NAME_SEPARATOR.to_string()))
+}
+
+/// Maps an MQTT topic filter with `+` and `#` wildcards to a topic pattern.
+pub fn to_topic_pattern(mqtt_filter: &str) -> Option<TopicPattern> {
+    let mut segments = Vec::new();
+    for level in mqtt_filter.split(MQTT_SEPARATOR) {
+        match level {
+            MQTT_SINGLE_LEVEL_WILDCARD => segments.push(SINGLE_SEGMENT_WILDCARD),
+            MQTT_MULTI_LEVEL_WILDCARD => segments.push(MULTI_SEGMENT_WILDCARD),
+            _ if level.contains(TOPIC_NAME_SEPARATOR)
+                || level.contains(MQTT_SINGLE_LEVEL_WILDCARD) =>
+            {
+                return None
+            }
+            _ => segments.push(level),
+        }
+    }
+    // `#` is only allowed as the last level
+    if segments[..segments.len() - 1].contains(&MULTI_SEGMENT_WILDCARD) {
+        return None;
+    }
+    TopicPattern::parse(&segments.join(&TOPIC_NAME_SEPARATOR.to_string()))
+}
+
+pub fn to_mqtt_topic(topic_name: &str) -> String {
+    topic_name.replace(TOPIC_NAME_SEPARATOR, &MQTT_SEPARATOR.to_string())
+}
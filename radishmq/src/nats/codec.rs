@@ -0,0 +1,346 @@
+use crate::nats::protocol::{
+    ClientOp, ConnectOptions, ServerOp, MAXIMUM_PAYLOAD_VIOLATION, MAX_CONTROL_LINE, MAX_PAYLOAD,
+    UNKNOWN_OPERATION,
+};
+use bytes::{Buf, BufMut, BytesMut};
+use std::io;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Decodes client operations and encodes server operations. Errors carry the message the server
+/// reports with `-ERR` before closing the connection.
+#[derive(Debug, Default)]
+pub struct NatsCodec;
+
+impl Decoder for NatsCodec {
+    type Item = ClientOp;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(line_end) = src.iter().position(|byte| *byte == b'\n') else {
+            if src.len() > MAX_CONTROL_LINE {
+                return Err(invalid(UNKNOWN_OPERATION));
+            }
+            return Ok(None);
+        };
+        if line_end > MAX_CONTROL_LINE {
+            return Err(invalid(UNKNOWN_OPERATION));
+        }
+        let line = std::str::from_utf8(&src[..line_end])
+            .map_err(|_| invalid(UNKNOWN_OPERATION))?
+            .trim_end_matches('\r')
+            .to_string();
+        let (operation, arguments) = line
+            .split_once([' ', '\t'])
+            .map(|(operation, arguments)| (operation, arguments.trim()))
+            .unwrap_or((line.as_str(), ""));
+        let arguments: Vec<&str> = arguments.split_whitespace().collect();
+
+        let op = match (operation.to_uppercase().as_str(), arguments.as_slice()) {
+            ("CONNECT", _) => {
+                let options = line[operation.len()..].trim();
+                let options: ConnectOptions =
+                    serde_json::from_str(options).map_err(|_| invalid(UNKNOWN_OPERATION))?;
+                ClientOp::Connect(options)
+            }
+            ("PUB", [subject, size]) => return decode_pub(src, line_end, subject, None, size),
+            ("PUB", [subject, reply_to, size]) => {
+                return decode_pub(src, line_end, subject, Some(reply_to), size)
+            }
+            ("SUB", [subject, sid]) => ClientOp::Sub {
+                subject: subject.to_string(),
+                queue_group: None,
+                sid: sid.to_string(),
+            },
+            ("SUB", [subject, queue_group, sid]) => ClientOp::Sub {
+                subject: subject.to_string(),
+                queue_group: Some(queue_group.to_string()),
+                sid: sid.to_string(),
+            },
+            ("UNSUB", [sid]) => ClientOp::Unsub {
+                sid: sid.to_string(),
+                max_msgs: None,
+            },
+            ("UNSUB", [sid, max_msgs]) => ClientOp::Unsub {
+                sid: sid.to_string(),
+                max_msgs: Some(
+                    max_msgs
+                        .parse::<u64>()
+                        .map_err(|_| invalid(UNKNOWN_OPERATION))?,
+                ),
+            },
+            ("PING", []) => ClientOp::Ping,
+            ("PONG", []) => ClientOp::Pong,
+            _ => return Err(invalid(UNKNOWN_OPERATION)),
+        };
+
+        src.advance(line_end + 1);
+        Ok(Some(op))
+    }
+}
+
+impl Encoder<ServerOp> for NatsCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: ServerOp, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        match item {
+            ServerOp::Info(info) => {
+                let info = serde_json::to_string(&info).map_err(io::Error::other)?;
+                dst.put_slice(format!("INFO {}\r\n", info).as_bytes());
+            }
+            ServerOp::Msg {
+                subject,
+                sid,
+                reply_to,
+                payload,
+            } => {
+                let line = match reply_to {
+                    Some(reply_to) => {
+                        format!("MSG {} {} {} {}\r\n", subject, sid, reply_to, payload.len())
+                    }
+                    None => format!("MSG {} {} {}\r\n", subject, sid, payload.len()),
+                };
+                dst.put_slice(line.as_bytes());
+                dst.put_slice(&payload);
+                dst.put_slice(b"\r\n");
+            }
+            ServerOp::Ping => dst.put_slice(b"PING\r\n"),
+            ServerOp::Pong => dst.put_slice(b"PONG\r\n"),
+            ServerOp::Ok => dst.put_slice(b"+OK\r\n"),
+            ServerOp::Err(message) => dst.put_slice(format!("-ERR '{}'\r\n", message).as_bytes()),
+        }
+        Ok(())
+    }
+}
+
+// the payload follows the control line and is terminated by CRLF
+fn decode_pub(
+    src: &mut BytesMut,
+    line_end: usize,
+    subject: &str,
+    reply_to: Option<&str>,
+    size: &str,
+) -> io::Result<Option<ClientOp>> {
+    let size = size
+        .parse::<usize>()
+        .map_err(|_| invalid(UNKNOWN_OPERATION))?;
+    if size > MAX_PAYLOAD {
+        return Err(invalid(MAXIMUM_PAYLOAD_VIOLATION));
+    }
+
+    let payload_start = line_end + 1;
+    if src.len() < payload_start + size + 2 {
+        src.reserve(payload_start + size + 2 - src.len());
+        return Ok(None);
+    }
+    if &src[payload_start + size..payload_start + size + 2] != b"\r\n" {
+        return Err(invalid(UNKNOWN_OPERATION));
+    }
+    src.advance(payload_start);
+    let payload = src.split_to(size).freeze();
+    src.advance(2);
+
+    Ok(Some(ClientOp::Pub {
+        subject: subject.to_string(),
+        reply_to: reply_to.map(str::to_string),
+        payload,
+    }))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nats::protocol::ServerInfo;
+    use bytes::Bytes;
+
+    fn decode(src: &mut BytesMut) -> io::Result<Option<ClientOp>> {
+        NatsCodec.decode(src)
+    }
+
+    fn error_message(src: &[u8]) -> String {
+        decode(&mut BytesMut::from(src)).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn control_lines_are_decoded() {
+        let mut src = BytesMut::from(
+            &b"CONNECT {\"verbose\":true,\"user\":\"alice\",\"auth_token\":\"secret\",\"lang\":\"go\"}\r\n\
+               sub orders.* 1\r\nSUB orders.eu workers 2\r\nUNSUB 1\r\nUNSUB 2 5\r\nPING\r\nPONG\r\n"[..],
+        );
+
+        assert_eq!(
+            decode(&mut src).unwrap(),
+            Some(ClientOp::Connect(ConnectOptions {
+                verbose: true,
+                user: Some("alice".to_string()),
+                auth_token: Some("secret".to_string()),
+                ..Default::default()
+            }))
+        );
+        // operation names are case insensitive
+        assert_eq!(
+            decode(&mut src).unwrap(),
+            Some(ClientOp::Sub {
+                subject: "orders.*".to_string(),
+                queue_group: None,
+                sid: "1".to_string(),
+            })
+        );
+        assert_eq!(
+            decode(&mut src).unwrap(),
+            Some(ClientOp::Sub {
+                subject: "orders.eu".to_string(),
+                queue_group: Some("workers".to_string()),
+                sid: "2".to_string(),
+            })
+        );
+        assert_eq!(
+            decode(&mut src).unwrap(),
+            Some(ClientOp::Unsub {
+                sid: "1".to_string(),
+                max_msgs: None,
+            })
+        );
+        assert_eq!(
+            decode(&mut src).unwrap(),
+            Some(ClientOp::Unsub {
+                sid: "2".to_string(),
+                max_msgs: Some(5),
+            })
+        );
+        assert_eq!(decode(&mut src).unwrap(), Some(ClientOp::Ping));
+        assert_eq!(decode(&mut src).unwrap(), Some(ClientOp::Pong));
+        assert_eq!(decode(&mut src).unwrap(), None);
+    }
+
+    #[test]
+    fn publishes_wait_for_their_payload() {
+        let command = b"PUB orders.eu _INBOX.1 7\r\nhi\r\nyou\r\nPUB orders 0\r\n\r\n";
+        let first = b"PUB orders.eu _INBOX.1 7\r\nhi\r\nyou\r\n".len();
+
+        // a frame split anywhere is only decoded once the payload and its CRLF arrived
+        for length in 0..first {
+            let mut src = BytesMut::from(&command[..length]);
+            assert_eq!(
+                decode(&mut src).unwrap(),
+                None,
+                "prefix of {} bytes",
+                length
+            );
+            assert_eq!(src.len(), length);
+        }
+
+        let mut src = BytesMut::from(&command[..]);
+        assert_eq!(
+            decode(&mut src).unwrap(),
+            Some(ClientOp::Pub {
+                subject: "orders.eu".to_string(),
+                reply_to: Some("_INBOX.1".to_string()),
+                payload: Bytes::from_static(b"hi\r\nyou"),
+            })
+        );
+        assert_eq!(
+            decode(&mut src).unwrap(),
+            Some(ClientOp::Pub {
+                subject: "orders".to_string(),
+                reply_to: None,
+                payload: Bytes::new(),
+            })
+        );
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn unsupported_and_malformed_operations_are_rejected() {
+        // headers are not offered in INFO, so HPUB is unknown like any other operation
+        assert_eq!(
+            error_message(b"HPUB orders 12 14\r\nNATS/1.0\r\n\r\nhi\r\n"),
+            UNKNOWN_OPERATION
+        );
+        for line in [
+            &b"FOO\r\n"[..],
+            b"PUB orders\r\n",
+            b"PUB orders many\r\n",
+            b"PUB orders 2\r\nhiXX",
+            b"SUB\r\n",
+            b"UNSUB 1 all\r\n",
+            b"PING now\r\n",
+            b"CONNECT {\r\n",
+        ] {
+            assert_eq!(error_message(line), UNKNOWN_OPERATION, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn limits_are_enforced_before_buffering() {
+        let oversized = format!("PUB orders {}\r\n", MAX_PAYLOAD + 1);
+        assert_eq!(
+            error_message(oversized.as_bytes()),
+            MAXIMUM_PAYLOAD_VIOLATION
+        );
+
+        // a payload of exactly the limit is waited for
+        let mut src = BytesMut::from(format!("PUB orders {}\r\n", MAX_PAYLOAD).as_bytes());
+        assert_eq!(decode(&mut src).unwrap(), None);
+
+        // control lines may not grow beyond the limit, terminated or not
+        let long_line = vec![b'A'; MAX_CONTROL_LINE + 1];
+        assert_eq!(error_message(&long_line), UNKNOWN_OPERATION);
+        let mut terminated = long_line;
+        terminated.extend_from_slice(b"\r\n");
+        assert_eq!(error_message(&terminated), UNKNOWN_OPERATION);
+    }
+
+    #[test]
+    fn server_operations_are_encoded() {
+        let mut dst = BytesMut::new();
+        let info = ServerInfo {
+            server_id: "radish".to_string(),
+            server_name: "radish".to_string(),
+            version: "1".to_string(),
+            proto: 1,
+            host: "127.0.0.1".to_string(),
+            port: 4222,
+            headers: false,
+            auth_required: false,
+            max_payload: MAX_PAYLOAD,
+            client_id: 3,
+        };
+        for op in [
+            ServerOp::Info(info),
+            ServerOp::Msg {
+                subject: "orders".to_string(),
+                sid: "1".to_string(),
+                reply_to: Some("_INBOX.1".to_string()),
+                payload: Bytes::from_static(b"hi"),
+            },
+            ServerOp::Msg {
+                subject: "orders".to_string(),
+                sid: "2".to_string(),
+                reply_to: None,
+                payload: Bytes::new(),
+            },
+            ServerOp::Ping,
+            ServerOp::Pong,
+            ServerOp::Ok,
+            ServerOp::Err(UNKNOWN_OPERATION.to_string()),
+        ] {
+            NatsCodec.encode(op, &mut dst).unwrap();
+        }
+
+        let encoded = std::str::from_utf8(&dst).unwrap();
+        let (info, rest) = encoded.split_once("\r\n").unwrap();
+        let info: serde_json::Value =
+            serde_json::from_str(info.strip_prefix("INFO ").unwrap()).unwrap();
+        assert_eq!(info["headers"], false);
+        assert_eq!(info["max_payload"], MAX_PAYLOAD);
+        assert_eq!(
+            rest,
+            "MSG orders 1 _INBOX.1 2\r\nhi\r\nMSG orders 2 0\r\n\r\nPING\r\nPONG\r\n+OK\r\n\
+             -ERR 'Unknown Protocol Operation'\r\n"
+        );
+    }
+}
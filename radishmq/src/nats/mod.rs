@@ -0,0 +1,3 @@
+pub mod codec;
+pub mod protocol;
+pub mod server;
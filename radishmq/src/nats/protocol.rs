@@ -0,0 +1,73 @@
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+pub const MAX_PAYLOAD: usize = 1024 * 1024;
+pub const MAX_CONTROL_LINE: usize = 4096;
+
+pub const UNKNOWN_OPERATION: &str = "Unknown Protocol Operation";
+pub const MAXIMUM_PAYLOAD_VIOLATION: &str = "Maximum Payload Violation";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientOp {
+    Connect(ConnectOptions),
+    Pub {
+        subject: String,
+        reply_to: Option<String>,
+        payload: Bytes,
+    },
+    Sub {
+        subject: String,
+        queue_group: Option<String>,
+        sid: String,
+    },
+    Unsub {
+        sid: String,
+        max_msgs: Option<u64>,
+    },
+    Ping,
+    Pong,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerOp {
+    Info(ServerInfo),
+    Msg {
+        subject: String,
+        sid: String,
+        reply_to: Option<String>,
+        payload: Bytes,
+    },
+    Ping,
+    Pong,
+    Ok,
+    Err(String),
+}
+
+/// The fields of `CONNECT` the server uses, all others are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ConnectOptions {
+    #[serde(default)]
+    pub verbose: bool,
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub pass: Option<String>,
+    #[serde(default)]
+    pub auth_token: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ServerInfo {
+    pub server_id: String,
+    pub server_name: String,
+    pub version: String,
+    pub proto: u8,
+    pub host: String,
+    pub port: u16,
+    pub headers: bool,
+    pub auth_required: bool,
+    pub max_payload: usize,
+    pub client_id: u64,
+}
@@ -0,0 +1,548 @@
+use crate::auth::authenticator::Authenticator;
+use crate::auth::identity::{self, Identity};
+use crate::model::destination_model::{DestinationType, TopicDestination};
+use crate::model::message_topic_model::PublishToMessageTopic;
+use crate::model::wildcard_subscription_model::CreateWildcardSubscription;
+use crate::nats::codec::NatsCodec;
+use crate::nats::protocol::{ClientOp, ConnectOptions, ServerInfo, ServerOp, MAX_PAYLOAD};
+use crate::service::{message_topic_service, notification_service, wildcard_subscription_service};
+use crate::utils::namespace::DEFAULT_NAMESPACE;
+use crate::utils::peer_address;
+use crate::utils::topic_pattern::{
+    is_valid_topic_name, TopicPattern, MULTI_SEGMENT_WILDCARD, SINGLE_SEGMENT_WILDCARD,
+    TOPIC_NAME_SEPARATOR,
+};
+use crate::utils::types::Headers;
+use futures::{SinkExt, StreamExt};
+use lazy_static::lazy_static;
+use log::warn;
+use std::collections::HashMap;
+use std::env;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{interval_at, timeout, Instant};
+use tokio_util::codec::Framed;
+use uuid::Uuid;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const PING_INTERVAL: Duration = Duration::from_secs(120);
+const MAX_PINGS_OUTSTANDING: u32 = 2;
+const SUBSCRIBER_PREFIX: &str = "nats:";
+// connections without a user publish under one shared identity
+const ANONYMOUS_IDENTITY: &str = "nats";
+const AUTHORIZATION_VIOLATION: &str = "Authorization Violation";
+// the reply subject of a publish is stored as a message header
+const REPLY_TO_HEADER: &str = "nats-reply-to";
+// NATS' full wildcard matches one or more tokens
+const FULL_WILDCARD: &str = ">";
+
+lazy_static! {
+    static ref CLIENT_IDS: AtomicU64 = AtomicU64::new(1);
+    // members of every queue group on this server, the group's subscription is deleted with its last member
+    static ref QUEUE_GROUPS: Mutex<HashMap<String, usize>> = Mutex::new(HashMap::new());
+}
+
+pub async fn start_nats_server() -> io::Result<()> {
+    let port = match env::var("NATS_PORT") {
+        Ok(port) => port.parse::<u16>().expect("NATS_PORT must be a number"),
+        Err(_) => {
+            println!("Skipping NATS listener...");
+            return Ok(());
+        }
+    };
+    let address = env::var("NATS_ADDRESS")
+        .or_else(|_| env::var("HTTP_ADDRESS"))
+        .expect("NATS_ADDRESS or HTTP_ADDRESS must be set");
+
+    remove_stale_subscriptions().await;
+
+    let listener = TcpListener::bind((address.as_str(), port)).await?;
+    println!("NATS listener started on {}:{}", address, port);
+
+    let server_id = Uuid::new_v4().simple().to_string().to_uppercase();
+    let authenticator = Authenticator::from_env().map(Arc::new);
+    tokio::spawn(accept_connections(
+        listener,
+        authenticator,
+        server_id,
+        address,
+        port,
+    ));
+    Ok(())
+}
+
+// NATS subscriptions do not outlive their connection, so the ones restored from a previous run are dropped
+async fn remove_stale_subscriptions() {
+    for subscription in wildcard_subscription_service::get_all_wildcard_subscriptions()
+        .await
+        .unwrap_or_default()
+    {
+        if subscription.subscriber.starts_with(SUBSCRIBER_PREFIX) {
+            let removed = wildcard_subscription_service::delete_wildcard_subscription(
+                subscription.subscriber,
+                subscription.pattern,
+            )
+            .await;
+            if let Err(e) = removed {
+                warn!(
+                    "NatsServer::remove_stale_subscriptions could not remove a subscription: {}",
+                    e
+                );
+            }
+        }
+    }
+}
+
+async fn accept_connections(
+    listener: TcpListener,
+    authenticator: Option<Arc<Authenticator>>,
+    server_id: String,
+    host: String,
+    port: u16,
+) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, address)) => {
+                let info = ServerInfo {
+                    server_id: server_id.clone(),
+                    server_name: "radishmq".to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                    proto: 1,
+                    host: host.clone(),
+                    port,
+                    headers: false,
+                    auth_required: authenticator.is_some(),
+                    max_payload: MAX_PAYLOAD,
+                    client_id: CLIENT_IDS.fetch_add(1, Ordering::Relaxed),
+                };
+                let authenticator = authenticator.clone();
+                tokio::spawn(peer_address::scope(address.ip().to_string(), async move {
+                    if let Err(e) = handle_connection(stream, info, authenticator).await {
+                        warn!("NatsServer::handle_connection closed a connection: {}", e);
+                    }
+                }));
+            }
+            Err(e) => warn!(
+                "NatsServer::accept_connections could not accept a connection: {}",
+                e
+            ),
+        }
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    info: ServerInfo,
+    authenticator: Option<Arc<Authenticator>>,
+) -> io::Result<()> {
+    let client_id = info.client_id;
+    let mut framed = Framed::new(stream, NatsCodec);
+    framed.send(ServerOp::Info(info)).await?;
+
+    let options = match timeout(CONNECT_TIMEOUT, framed.next()).await {
+        Ok(Some(Ok(ClientOp::Connect(options)))) => options,
+        Ok(Some(Ok(_))) => {
+            return framed
+                .send(ServerOp::Err("Expected CONNECT".to_string()))
+                .await
+        }
+        Ok(Some(Err(e))) => return framed.send(ServerOp::Err(e.to_string())).await,
+        Ok(None) | Err(_) => return Ok(()),
+    };
+
+    let (identity, identifier) = match authenticator {
+        Some(authenticator) => match authenticate(&authenticator, &options) {
+            Some((identity, identifier)) => (Some(identity), identifier),
+            None => {
+                return framed
+                    .send(ServerOp::Err(AUTHORIZATION_VIOLATION.to_string()))
+                    .await
+            }
+        },
+        None => (
+            None,
+            options
+                .user
+                .clone()
+                .unwrap_or_else(|| ANONYMOUS_IDENTITY.to_string()),
+        ),
+    };
+    if options.verbose {
+        framed.send(ServerOp::Ok).await?;
+    }
+
+    let mut connection = Connection {
+        framed,
+        client_id,
+        identity: identifier,
+        verbose: options.verbose,
+        subscriptions: HashMap::new(),
+        pings_outstanding: 0,
+    };
+    identity::on_behalf_of(identity, async {
+        let result = connection.run().await;
+        connection.close().await;
+        result
+    })
+    .await
+}
+
+/// The `auth_token` or the `pass` of CONNECT carries the API key or JWT. The `user` is the
+/// identifier the client publishes as, without one the first principal of the credential is used.
+fn authenticate(
+    authenticator: &Authenticator,
+    options: &ConnectOptions,
+) -> Option<(Identity, String)> {
+    let credential = options.auth_token.as_ref().or(options.pass.as_ref())?;
+    let identity = authenticator
+        .authenticate(credential)
+        .map_err(|e| warn!("NatsServer::authenticate rejected a connection: {}", e))
+        .ok()?;
+    let identifier = options
+        .user
+        .clone()
+        .or_else(|| identity.principals.first().cloned())?;
+
+    // subscriptions are wildcard subscriptions, which only exist in the default namespace
+    (identity.namespace == DEFAULT_NAMESPACE && identity.acts_as(&identifier))
+        .then_some((identity, identifier))
+}
+
+/// Maps a NATS subject with `*` and `>` wildcards to a topic pattern.
+fn to_topic_pattern(subject: &str) -> Option<TopicPattern> {
+    let tokens: Vec<&str> = subject.split(TOPIC_NAME_SEPARATOR).collect();
+    let mut segments = Vec::new();
+    for (index, token) in tokens.iter().enumerate() {
+        match *token {
+            FULL_WILDCARD if index == tokens.len() - 1 => {
+                segments.push(SINGLE_SEGMENT_WILDCARD);
+                segments.push(MULTI_SEGMENT_WILDCARD);
+            }
+            FULL_WILDCARD | MULTI_SEGMENT_WILDCARD => return None,
+            token => segments.push(token),
+        }
+    }
+    TopicPattern::parse(&segments.join(&TOPIC_NAME_SEPARATOR.to_string()))
+}
+
+fn is_valid_subject(subject: &str) -> bool {
+    is_valid_topic_name(subject)
+        && subject
+            .split(TOPIC_NAME_SEPARATOR)
+            .all(|token| token != FULL_WILDCARD)
+}
+
+fn join_queue_group(subscriber: &str) -> bool {
+    if let Ok(mut queue_groups) = QUEUE_GROUPS.lock() {
+        let members = queue_groups.entry(subscriber.to_string()).or_insert(0);
+        *members += 1;
+        *members == 1
+    } else {
+        panic!("NatsServer::join_queue_group() tried to lock a poisoned mutex");
+    }
+}
+
+fn leave_queue_group(subscriber: &str) -> bool {
+    if let Ok(mut queue_groups) = QUEUE_GROUPS.lock() {
+        let Some(members) = queue_groups.get_mut(subscriber) else {
+            return false;
+        };
+        *members -= 1;
+        if *members == 0 {
+            queue_groups.remove(subscriber);
+            return true;
+        }
+        false
+    } else {
+        panic!("NatsServer::leave_queue_group() tried to lock a poisoned mutex");
+    }
+}
+
+struct Subscription {
+    pattern: TopicPattern,
+    // queue group members share one subscriber and compete for its messages
+    subscriber: String,
+    queue_group: bool,
+    remaining: Option<u64>,
+}
+
+struct Connection {
+    framed: Framed<TcpStream, NatsCodec>,
+    client_id: u64,
+    identity: String,
+    verbose: bool,
+    subscriptions: HashMap<String, Subscription>,
+    pings_outstanding: u32,
+}
+
+impl Connection {
+    async fn run(&mut self) -> io::Result<()> {
+        let mut pings = interval_at(Instant::now() + PING_INTERVAL, PING_INTERVAL);
+        let mut notifications = notification_service::subscribe_to_notifications();
+
+        loop {
+            tokio::select! {
+                op = self.framed.next() => {
+                    match op {
+                        Some(Ok(op)) => self.handle_op(op).await?,
+                        Some(Err(e)) if e.kind() == io::ErrorKind::InvalidData => {
+                            return self.framed.send(ServerOp::Err(e.to_string())).await;
+                        }
+                        Some(Err(e)) => return Err(e),
+                        None => return Ok(()),
+                    }
+                }
+                _ = pings.tick() => {
+                    if self.pings_outstanding >= MAX_PINGS_OUTSTANDING {
+                        return self
+                            .framed
+                            .send(ServerOp::Err("Stale Connection".to_string()))
+                            .await;
+                    }
+                    self.pings_outstanding += 1;
+                    self.framed.send(ServerOp::Ping).await?;
+                }
+                notification = notifications.recv() => {
+                    let destination = match notification {
+                        Ok(destination) => Some(destination),
+                        // missed notifications are recovered by checking every subscription
+                        Err(RecvError::Lagged(_)) => None,
+                        Err(RecvError::Closed) => return Ok(()),
+                    };
+                    self.deliver_notification(destination).await?;
+                }
+            }
+        }
+    }
+
+    async fn handle_op(&mut self, op: ClientOp) -> io::Result<()> {
+        let result = match op {
+            ClientOp::Connect(_) => Ok(()),
+            ClientOp::Pub {
+                subject,
+                reply_to,
+                payload,
+            } => {
+                let payload = String::from_utf8(payload.to_vec())
+                    .map_err(|_| "Payload must be valid UTF-8".to_string());
+                match payload {
+                    Ok(payload) => self.publish(subject, reply_to, payload).await,
+                    Err(e) => Err(e),
+                }
+            }
+            ClientOp::Sub {
+                subject,
+                queue_group,
+                sid,
+            } => self.subscribe(subject, queue_group, sid).await,
+            ClientOp::Unsub { sid, max_msgs } => {
+                self.unsubscribe(&sid, max_msgs).await;
+                Ok(())
+            }
+            ClientOp::Ping => return self.framed.send(ServerOp::Pong).await,
+            ClientOp::Pong => {
+                self.pings_outstanding = 0;
+                return Ok(());
+            }
+        };
+
+        match result {
+            Ok(()) if self.verbose => self.framed.send(ServerOp::Ok).await,
+            Ok(()) => Ok(()),
+            Err(message) => self.framed.send(ServerOp::Err(message)).await,
+        }
+    }
+
+    async fn publish(
+        &mut self,
+        subject: String,
+        reply_to: Option<String>,
+        payload: String,
+    ) -> Result<(), String> {
+        if !is_valid_subject(&subject) {
+            return Err("Invalid Publish Subject".to_string());
+        }
+
+        let mut headers = Headers::new();
+        if let Some(reply_to) = reply_to {
+            headers.insert(REPLY_TO_HEADER.to_string(), reply_to);
+        }
+
+        // NATS has no subject administration, topics are created on their first publish
+        message_topic_service::ensure_publisher_of_message_topic(
+            subject.clone(),
+            self.identity.clone(),
+        )
+        .await
+        .map_err(|_| "Permissions Violation for Publish".to_string())?;
+        message_topic_service::publish_to_message_topic(
+            subject,
+            self.identity.clone(),
+            PublishToMessageTopic::new(payload, headers),
+        )
+        .await
+        .map_err(|_| "Permissions Violation for Publish".to_string())
+    }
+
+    async fn subscribe(
+        &mut self,
+        subject: String,
+        queue_group: Option<String>,
+        sid: String,
+    ) -> Result<(), String> {
+        let pattern = to_topic_pattern(&subject).ok_or("Invalid Subject")?;
+        if self.subscriptions.contains_key(&sid) {
+            return Err("Duplicate Subscription Id".to_string());
+        }
+
+        let subscriber = match &queue_group {
+            Some(queue_group) => {
+                format!("{}queue:{}:{}", SUBSCRIBER_PREFIX, queue_group, subject)
+            }
+            None => format!("{}{}:{}", SUBSCRIBER_PREFIX, self.client_id, sid),
+        };
+        let create = match queue_group {
+            Some(_) => join_queue_group(&subscriber),
+            None => true,
+        };
+        if create {
+            let created = identity::with_principal(
+                subscriber.clone(),
+                wildcard_subscription_service::create_wildcard_subscription(
+                    CreateWildcardSubscription::new(
+                        subscriber.clone(),
+                        pattern.as_str().to_string(),
+                        None,
+                    ),
+                ),
+            )
+            .await;
+            if created.is_err() {
+                if queue_group.is_some() {
+                    leave_queue_group(&subscriber);
+                }
+                return Err("Invalid Subject".to_string());
+            }
+        }
+
+        self.subscriptions.insert(
+            sid,
+            Subscription {
+                pattern,
+                subscriber,
+                queue_group: queue_group.is_some(),
+                remaining: None,
+            },
+        );
+        Ok(())
+    }
+
+    async fn unsubscribe(&mut self, sid: &str, max_msgs: Option<u64>) {
+        // with a maximum the subscription ends once that many further messages were delivered
+        if let Some(max_msgs) = max_msgs.filter(|max_msgs| *max_msgs > 0) {
+            if let Some(subscription) = self.subscriptions.get_mut(sid) {
+                subscription.remaining = Some(max_msgs);
+                return;
+            }
+        }
+        if let Some(subscription) = self.subscriptions.remove(sid) {
+            remove_subscription(subscription).await;
+        }
+    }
+
+    async fn deliver_notification(
+        &mut self,
+        destination: Option<TopicDestination>,
+    ) -> io::Result<()> {
+        let sids: Vec<String> = self
+            .subscriptions
+            .iter()
+            .filter(|(_, subscription)| {
+                destination.as_ref().is_none_or(|destination| {
+                    destination.destination_type == DestinationType::MessageTopic
+                        && subscription.pattern.matches(&destination.destination)
+                })
+            })
+            .map(|(sid, _)| sid.clone())
+            .collect();
+
+        for sid in sids {
+            self.deliver(&sid).await?;
+        }
+        Ok(())
+    }
+
+    async fn deliver(&mut self, sid: &str) -> io::Result<()> {
+        let Some(subscription) = self.subscriptions.get(sid) else {
+            return Ok(());
+        };
+        let data = identity::with_principal(
+            subscription.subscriber.clone(),
+            wildcard_subscription_service::get_new_data_for_wildcard_subscriber(
+                subscription.subscriber.clone(),
+            ),
+        )
+        .await;
+
+        for topic_data in data.map(|data| data.data).unwrap_or_default() {
+            for mut message in topic_data.data {
+                let Some(subscription) = self.subscriptions.get_mut(sid) else {
+                    return Ok(());
+                };
+                if let Some(remaining) = subscription.remaining.as_mut() {
+                    *remaining -= 1;
+                }
+
+                self.framed
+                    .send(ServerOp::Msg {
+                        subject: topic_data.topic.clone(),
+                        sid: sid.to_string(),
+                        reply_to: message.headers.remove(REPLY_TO_HEADER),
+                        payload: message.data.into(),
+                    })
+                    .await?;
+
+                if self
+                    .subscriptions
+                    .get(sid)
+                    .is_some_and(|subscription| subscription.remaining == Some(0))
+                {
+                    if let Some(subscription) = self.subscriptions.remove(sid) {
+                        remove_subscription(subscription).await;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) {
+        for (_, subscription) in self.subscriptions.drain() {
+            remove_subscription(subscription).await;
+        }
+    }
+}
+
+async fn remove_subscription(subscription: Subscription) {
+    if subscription.queue_group && !leave_queue_group(&subscription.subscriber) {
+        return;
+    }
+    let removed = identity::with_principal(
+        subscription.subscriber.clone(),
+        wildcard_subscription_service::delete_wildcard_subscription(
+            subscription.subscriber,
+            subscription.pattern.as_str().to_string(),
+        ),
+    )
+    .await;
+    if let Err(e) = removed {
+        warn!(
+            "NatsServer::remove_subscription could not remove a subscription: {}",
+            e
+        );
+    }
+}
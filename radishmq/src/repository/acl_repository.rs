@@ -0,0 +1,66 @@
+use crate::auth::grant::Grant;
+use crate::event_queue::worker::ThreadData;
+use crate::metrics::record_persistence_error;
+use crate::model::entity::acl_entities::AclGrantEntity;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+pub async fn create_grant(thread_data: ThreadData, grant: Grant) {
+    let result = sqlx::query(
+        r#"
+            INSERT INTO acl_grant (id, namespace, principal, role, pattern)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+    )
+    .bind(grant.id)
+    .bind(grant.namespace.clone())
+    .bind(grant.principal.clone())
+    .bind(grant.role.as_str())
+    .bind(grant.pattern.as_str())
+    .execute(&thread_data.db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!("Error while creating ACL grant: {}", e);
+            record_persistence_error("acl");
+        }
+    }
+}
+
+pub async fn delete_grant(thread_data: ThreadData, grant_id: Uuid) {
+    let result = sqlx::query(
+        r#"
+            DELETE FROM acl_grant
+            WHERE id = $1
+            "#,
+    )
+    .bind(grant_id)
+    .execute(&thread_data.db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!("Error while deleting ACL grant: {}", e);
+            record_persistence_error("acl");
+        }
+    }
+}
+
+pub async fn get_all_grants(db_connection_pool: &Pool<Postgres>) -> Vec<AclGrantEntity> {
+    let entities = sqlx::query_as::<_, AclGrantEntity>(
+        r#"
+            SELECT * FROM acl_grant
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the ACL grants: {}", e);
+        record_persistence_error("acl");
+        Vec::new()
+    })
+}
@@ -0,0 +1,74 @@
+use crate::event_queue::worker::ThreadData;
+use crate::metrics::record_persistence_error;
+use crate::model::audit_model::{AuditEntryModel, AuditFilter};
+use crate::model::entity::audit_entities::AuditEntryEntity;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+pub async fn append_entry(thread_data: ThreadData, entry: AuditEntryModel) {
+    let result = sqlx::query(
+        r#"
+            INSERT INTO audit_log (id, occurred_at, namespace, actor, address, action, resource_type, resource, before, after)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+    )
+    .bind(entry.id)
+    .bind(entry.occurred_at)
+    .bind(entry.namespace.clone())
+    .bind(entry.actor.clone())
+    .bind(entry.address.clone())
+    .bind(entry.action.as_str())
+    .bind(entry.resource_type.clone())
+    .bind(entry.resource.clone())
+    .bind(entry.before.clone())
+    .bind(entry.after.clone())
+    .execute(&thread_data.db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!("Error while appending to the audit log: {}", e);
+            record_persistence_error("audit");
+        }
+    }
+}
+
+/// The entries of a namespace matching `filter` in the order they occurred, at most `limit` if
+/// given. With `after` set only the entries following that `(occurred_at, id)` are returned.
+pub async fn get_entries(
+    db_connection_pool: &Pool<Postgres>,
+    namespace: String,
+    filter: AuditFilter,
+    after: Option<(DateTime<Utc>, Uuid)>,
+    limit: Option<i64>,
+) -> Result<Vec<AuditEntryEntity>, sqlx::Error> {
+    sqlx::query_as::<_, AuditEntryEntity>(
+        r#"
+            SELECT * FROM audit_log
+            WHERE namespace = $1
+              AND ($2::text IS NULL OR actor = $2)
+              AND ($3::text IS NULL OR action = $3)
+              AND ($4::text IS NULL OR resource_type = $4)
+              AND ($5::text IS NULL OR resource = $5)
+              AND ($6::timestamptz IS NULL OR occurred_at >= $6)
+              AND ($7::timestamptz IS NULL OR occurred_at < $7)
+              AND ($8::timestamptz IS NULL OR (occurred_at, id) > ($8, $9))
+            ORDER BY occurred_at, id
+            LIMIT $10
+            "#,
+    )
+    .bind(namespace)
+    .bind(filter.actor)
+    .bind(filter.action.map(|action| action.as_str()))
+    .bind(filter.resource_type)
+    .bind(filter.resource)
+    .bind(filter.since)
+    .bind(filter.until)
+    .bind(after.map(|(occurred_at, _)| occurred_at))
+    .bind(after.map(|(_, id)| id))
+    .bind(limit)
+    .fetch_all(db_connection_pool)
+    .await
+}
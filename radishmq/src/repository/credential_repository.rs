@@ -0,0 +1,69 @@
+use crate::auth::credential::Credential;
+use crate::event_queue::worker::ThreadData;
+use crate::metrics::record_persistence_error;
+use crate::model::entity::credential_entities::CredentialEntity;
+use sqlx::{Pool, Postgres};
+
+pub async fn create_credential(thread_data: ThreadData, credential: Credential) {
+    let result = sqlx::query(
+        r#"
+            INSERT INTO credential (namespace, credential_name, secret_hash, principals)
+            VALUES ($1, $2, $3, $4)
+            "#,
+    )
+    .bind(credential.namespace.clone())
+    .bind(credential.name.clone())
+    .bind(credential.secret_hash.clone())
+    .bind(serde_json::to_string(&credential.principals).unwrap())
+    .execute(&thread_data.db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!("Error while creating credential: {}", e);
+            record_persistence_error("credential");
+        }
+    }
+}
+
+pub async fn delete_credential(
+    thread_data: ThreadData,
+    namespace: String,
+    credential_name: String,
+) {
+    let result = sqlx::query(
+        r#"
+            DELETE FROM credential
+            WHERE namespace = $1 AND credential_name = $2
+            "#,
+    )
+    .bind(namespace)
+    .bind(credential_name.clone())
+    .execute(&thread_data.db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!("Error while deleting credential: {}", e);
+            record_persistence_error("credential");
+        }
+    }
+}
+
+pub async fn get_all_credentials(db_connection_pool: &Pool<Postgres>) -> Vec<CredentialEntity> {
+    let entities = sqlx::query_as::<_, CredentialEntity>(
+        r#"
+            SELECT * FROM credential
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the credentials: {}", e);
+        record_persistence_error("credential");
+        Vec::new()
+    })
+}
@@ -0,0 +1,215 @@
+use crate::encryption::data_key::DataKeyId;
+use crate::encryption::{self, WrappedDataKey, WrappedTopicNameKey};
+use crate::metrics::record_persistence_error;
+use crate::model::entity::data_key_entities::{DataKeyEntity, TopicNameKeyEntity};
+use crate::utils::namespace::DEFAULT_NAMESPACE;
+use sqlx::{Pool, Postgres};
+
+/// Makes sure a topic has a stored data key before anything of it is sealed. A new key is stored
+/// first and read back, so a key another worker stored at the same time wins and nothing is sealed
+/// with a key that is not in the DB.
+pub async fn ensure_data_key(
+    db_connection_pool: &Pool<Postgres>,
+    id: &DataKeyId,
+) -> Result<(), String> {
+    if encryption::has_data_key(id) {
+        return Ok(());
+    }
+    let Some(data_key) = encryption::generate_data_key(id.clone()) else {
+        return Ok(());
+    };
+
+    let result = sqlx::query(
+        r#"
+            INSERT INTO topic_data_key (namespace, topic_type, topic_name, master_key_id, wrapped_key, sealed_name)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT DO NOTHING
+            "#,
+    )
+    .bind(data_key.id.namespace.clone())
+    .bind(data_key.id.destination_type.as_str())
+    .bind(encryption::topic_name_lookup(id))
+    .bind(data_key.master_key_id.clone())
+    .bind(data_key.wrapped_key.clone())
+    .bind(encryption::seal_topic_name(id))
+    .execute(db_connection_pool)
+    .await;
+
+    if let Err(e) = result {
+        return Err(format!("the data key could not be stored: {}", e));
+    }
+
+    let stored = sqlx::query_as::<_, DataKeyEntity>(
+        r#"
+            SELECT * FROM topic_data_key
+            WHERE namespace = $1 AND topic_type = $2 AND topic_name = $3
+            "#,
+    )
+    .bind(id.namespace.clone())
+    .bind(id.destination_type.as_str())
+    .bind(encryption::topic_name_lookup(id))
+    .fetch_one(db_connection_pool)
+    .await
+    .map_err(|e| format!("the data key could not be read back: {}", e))?;
+
+    let stored = WrappedDataKey {
+        id: id.clone(),
+        master_key_id: stored.master_key_id,
+        wrapped_key: stored.wrapped_key,
+    };
+    if let Some(rewrapped) = encryption::restore_data_key(&stored)? {
+        rewrap_data_key(db_connection_pool, rewrapped).await;
+    }
+    Ok(())
+}
+
+/// Deletes the data key of a topic once neither the topic nor a retained message uses it, the
+/// retained messages of the default namespace share the key of their message topic.
+pub async fn delete_unused_data_key(db_connection_pool: &Pool<Postgres>, id: &DataKeyId) {
+    let result = sqlx::query(
+        r#"
+            DELETE FROM topic_data_key
+            WHERE namespace = $1 AND topic_type = $2 AND topic_name = $3
+            AND NOT EXISTS (
+                SELECT 1 FROM message_topic
+                WHERE $2 = 'message_topic' AND namespace = $1 AND topic_name = $3
+            )
+            AND NOT EXISTS (
+                SELECT 1 FROM task_topic
+                WHERE $2 = 'task_topic' AND namespace = $1 AND topic_name = $3
+            )
+            AND NOT EXISTS (
+                SELECT 1 FROM retained_message
+                WHERE $2 = 'message_topic' AND $1 = $4 AND topic_name = $3
+            )
+            "#,
+    )
+    .bind(id.namespace.clone())
+    .bind(id.destination_type.as_str())
+    .bind(encryption::topic_name_lookup(id))
+    .bind(DEFAULT_NAMESPACE)
+    .execute(db_connection_pool)
+    .await;
+
+    match result {
+        Ok(result) if result.rows_affected() > 0 => encryption::forget_data_key(id),
+        Ok(_) => {}
+        Err(e) => {
+            println!("Error while deleting data key: {}", e);
+            record_persistence_error("data_key");
+        }
+    }
+}
+
+/// Stores a data key wrapped by another master key.
+pub async fn rewrap_data_key(db_connection_pool: &Pool<Postgres>, data_key: WrappedDataKey) {
+    let result = sqlx::query(
+        r#"
+            UPDATE topic_data_key SET master_key_id = $4, wrapped_key = $5
+            WHERE namespace = $1 AND topic_type = $2 AND topic_name = $3
+            "#,
+    )
+    .bind(data_key.id.namespace.clone())
+    .bind(data_key.id.destination_type.as_str())
+    .bind(encryption::topic_name_lookup(&data_key.id))
+    .bind(data_key.master_key_id.clone())
+    .bind(data_key.wrapped_key.clone())
+    .execute(db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!("Error while rewrapping data key: {}", e);
+            record_persistence_error("data_key");
+        }
+    }
+}
+
+pub async fn get_all_data_keys(db_connection_pool: &Pool<Postgres>) -> Vec<DataKeyEntity> {
+    let entities = sqlx::query_as::<_, DataKeyEntity>(
+        r#"
+            SELECT * FROM topic_data_key
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the data keys: {}", e);
+        record_persistence_error("data_key");
+        Vec::new()
+    })
+}
+
+/// Replaces the plaintext topic name of a data key stored before topic names were sealed.
+pub async fn seal_data_key_name(
+    db_connection_pool: &Pool<Postgres>,
+    id: &DataKeyId,
+    stored_name: String,
+) {
+    let result = sqlx::query(
+        r#"
+            UPDATE topic_data_key SET topic_name = $4, sealed_name = $5
+            WHERE namespace = $1 AND topic_type = $2 AND topic_name = $3 AND sealed_name IS NULL
+            "#,
+    )
+    .bind(id.namespace.clone())
+    .bind(id.destination_type.as_str())
+    .bind(stored_name)
+    .bind(encryption::topic_name_lookup(id))
+    .bind(encryption::seal_topic_name(id))
+    .execute(db_connection_pool)
+    .await;
+
+    if let Err(e) = result {
+        println!("Error while sealing data key name: {}", e);
+        record_persistence_error("data_key");
+    }
+}
+
+/// Restores the topic name key, it is generated and stored on the first start with encryption.
+pub async fn ensure_topic_name_key(db_connection_pool: &Pool<Postgres>) -> Result<(), String> {
+    // a new key is only stored if there is none yet, the stored one is read back either way
+    if let Some(topic_name_key) = encryption::generate_topic_name_key() {
+        sqlx::query(
+            r#"
+                INSERT INTO topic_name_key (master_key_id, wrapped_key)
+                VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+                "#,
+        )
+        .bind(topic_name_key.master_key_id)
+        .bind(topic_name_key.wrapped_key)
+        .execute(db_connection_pool)
+        .await
+        .map_err(|e| format!("the topic name key could not be stored: {}", e))?;
+    }
+
+    let stored = sqlx::query_as::<_, TopicNameKeyEntity>(
+        r#"
+            SELECT master_key_id, wrapped_key FROM topic_name_key
+            "#,
+    )
+    .fetch_one(db_connection_pool)
+    .await
+    .map_err(|e| format!("the topic name key could not be read: {}", e))?;
+
+    let stored = WrappedTopicNameKey {
+        master_key_id: stored.master_key_id,
+        wrapped_key: stored.wrapped_key,
+    };
+    if let Some(rewrapped) = encryption::restore_topic_name_key(&stored)? {
+        sqlx::query(
+            r#"
+                UPDATE topic_name_key SET master_key_id = $1, wrapped_key = $2
+                "#,
+        )
+        .bind(rewrapped.master_key_id)
+        .bind(rewrapped.wrapped_key)
+        .execute(db_connection_pool)
+        .await
+        .map_err(|e| format!("the topic name key could not be rewrapped: {}", e))?;
+    }
+    Ok(())
+}
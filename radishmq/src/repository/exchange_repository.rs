@@ -0,0 +1,304 @@
+use crate::encryption::data_key::DataKeyId;
+use crate::encryption::{seal_topic_name, topic_name_lookup};
+use crate::event_queue::worker::ThreadData;
+use crate::metrics::record_persistence_error;
+use crate::model::entity::exchange_entities::{
+    ExchangeBindingEntity, ExchangeEntity, ExchangePublisherEntity,
+};
+use crate::model::exchange_model::{
+    AddPublisherToExchange, BindingModel, CreateExchange, RemovePublisherFromExchange,
+};
+use crate::utils::namespace::DEFAULT_NAMESPACE;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+pub async fn create_exchange(thread_data: ThreadData, data: CreateExchange) {
+    let result = sqlx::query(
+        r#"
+            INSERT INTO exchange (exchange_name, exchange_type)
+            VALUES ($1, $2)
+            "#,
+    )
+    .bind(data.name.clone())
+    .bind(data.exchange_type.as_str())
+    .execute(&thread_data.db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!("Error while creating exchange: {}", e);
+            record_persistence_error("exchange");
+        }
+    }
+}
+
+pub async fn delete_exchange(thread_data: ThreadData, exchange_name: String) {
+    let result = sqlx::query(
+        r#"
+            DELETE FROM exchange
+            WHERE exchange_name = $1
+            "#,
+    )
+    .bind(exchange_name.clone())
+    .execute(&thread_data.db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!("Error while deleting exchange: {}", e);
+            record_persistence_error("exchange");
+        }
+    }
+}
+
+async fn get_exchange_by_name(
+    db_connection_pool: &Pool<Postgres>,
+    exchange_name: String,
+) -> Option<ExchangeEntity> {
+    let exchange = sqlx::query_as::<_, ExchangeEntity>(
+        r#"
+            SELECT * FROM exchange
+            WHERE exchange_name = $1
+            "#,
+    )
+    .bind(exchange_name.clone())
+    .fetch_optional(db_connection_pool)
+    .await;
+
+    exchange.unwrap_or_else(|e| {
+        println!("Error while fetching the exchange: {}", e);
+        record_persistence_error("exchange");
+        None
+    })
+}
+
+pub async fn add_binding_to_exchange(
+    thread_data: ThreadData,
+    exchange_name: String,
+    data: BindingModel,
+) {
+    let exchange =
+        get_exchange_by_name(&thread_data.db_connection_pool, exchange_name.clone()).await;
+
+    match exchange {
+        Some(exchange) => {
+            // exchanges only exist in the default namespace
+            let key_id = DataKeyId::new(
+                data.destination.destination_type,
+                DEFAULT_NAMESPACE.to_string(),
+                data.destination.destination,
+            );
+            let result = sqlx::query(
+                r#"
+                    INSERT INTO exchange_binding (id, exchange_id, destination_type, destination_name, routing_key, arguments, header_match, sealed_destination_name)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                    "#,
+            )
+            .bind(data.id)
+            .bind(exchange.id)
+            .bind(key_id.destination_type.as_str())
+            .bind(topic_name_lookup(&key_id))
+            .bind(data.routing_key)
+            .bind(serde_json::to_string(&data.arguments).unwrap())
+            .bind(data.header_match.as_str())
+            .bind(seal_topic_name(&key_id))
+            .execute(&thread_data.db_connection_pool)
+            .await;
+
+            match result {
+                Ok(_) => {}
+                Err(e) => {
+                    println!("Error while adding binding to exchange: {}", e);
+                    record_persistence_error("exchange");
+                }
+            }
+        }
+        None => {
+            println!("Exchange not found!");
+        }
+    }
+}
+
+pub async fn remove_binding_from_exchange(
+    thread_data: ThreadData,
+    exchange_name: String,
+    binding_id: Uuid,
+) {
+    let exchange =
+        get_exchange_by_name(&thread_data.db_connection_pool, exchange_name.clone()).await;
+
+    match exchange {
+        Some(exchange) => {
+            let result = sqlx::query(
+                r#"
+                    DELETE FROM exchange_binding
+                    WHERE exchange_id = $1 AND id = $2
+                    "#,
+            )
+            .bind(exchange.id)
+            .bind(binding_id)
+            .execute(&thread_data.db_connection_pool)
+            .await;
+
+            match result {
+                Ok(_) => {}
+                Err(e) => {
+                    println!("Error while removing binding from exchange: {}", e);
+                    record_persistence_error("exchange");
+                }
+            }
+        }
+        None => {
+            println!("Exchange not found!");
+        }
+    }
+}
+
+pub async fn add_publisher_to_exchange(
+    thread_data: ThreadData,
+    exchange_name: String,
+    data: AddPublisherToExchange,
+) {
+    let exchange =
+        get_exchange_by_name(&thread_data.db_connection_pool, exchange_name.clone()).await;
+
+    match exchange {
+        Some(exchange) => {
+            let result = sqlx::query(
+                r#"
+                    INSERT INTO exchange_publisher (exchange_id, publisher_name)
+                    VALUES ($1, $2)
+                    "#,
+            )
+            .bind(exchange.id)
+            .bind(data.publisher)
+            .execute(&thread_data.db_connection_pool)
+            .await;
+
+            match result {
+                Ok(_) => {}
+                Err(e) => {
+                    println!("Error while adding publisher to exchange: {}", e);
+                    record_persistence_error("exchange");
+                }
+            }
+        }
+        None => {
+            println!("Exchange not found!");
+        }
+    }
+}
+
+pub async fn remove_publisher_from_exchange(
+    thread_data: ThreadData,
+    exchange_name: String,
+    data: RemovePublisherFromExchange,
+) {
+    let exchange =
+        get_exchange_by_name(&thread_data.db_connection_pool, exchange_name.clone()).await;
+
+    match exchange {
+        Some(exchange) => {
+            let result = sqlx::query(
+                r#"
+                    DELETE FROM exchange_publisher
+                    WHERE exchange_id = $1 AND publisher_name = $2
+                    "#,
+            )
+            .bind(exchange.id)
+            .bind(data.publisher)
+            .execute(&thread_data.db_connection_pool)
+            .await;
+
+            match result {
+                Ok(_) => {}
+                Err(e) => {
+                    println!("Error while removing publisher from exchange: {}", e);
+                    record_persistence_error("exchange");
+                }
+            }
+        }
+        None => {
+            println!("Exchange not found!");
+        }
+    }
+}
+
+pub async fn get_all_exchanges(db_connection_pool: &Pool<Postgres>) -> Vec<ExchangeEntity> {
+    let entities = sqlx::query_as::<_, ExchangeEntity>(
+        r#"
+            SELECT * FROM exchange
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the exchanges: {}", e);
+        record_persistence_error("exchange");
+        Vec::new()
+    })
+}
+
+pub async fn get_all_exchange_bindings(
+    db_connection_pool: &Pool<Postgres>,
+) -> Vec<ExchangeBindingEntity> {
+    let entities = sqlx::query_as::<_, ExchangeBindingEntity>(
+        r#"
+            SELECT * FROM exchange_binding
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the exchange bindings: {}", e);
+        record_persistence_error("exchange");
+        Vec::new()
+    })
+}
+
+pub async fn get_all_exchange_publishers(
+    db_connection_pool: &Pool<Postgres>,
+) -> Vec<ExchangePublisherEntity> {
+    let entities = sqlx::query_as::<_, ExchangePublisherEntity>(
+        r#"
+            SELECT * FROM exchange_publisher
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the exchange publishers: {}", e);
+        record_persistence_error("exchange");
+        Vec::new()
+    })
+}
+
+/// Replaces the plaintext destination of a binding written before encryption was enabled.
+pub async fn seal_exchange_binding_destination(
+    db_connection_pool: &Pool<Postgres>,
+    id: Uuid,
+    destination_name: String,
+    sealed_destination_name: Option<Vec<u8>>,
+) {
+    let result = sqlx::query(
+        r#"
+            UPDATE exchange_binding SET destination_name = $2, sealed_destination_name = $3
+            WHERE id = $1
+            "#,
+    )
+    .bind(id)
+    .bind(destination_name)
+    .bind(sealed_destination_name)
+    .execute(db_connection_pool)
+    .await;
+
+    if let Err(e) = result {
+        println!("Error while sealing exchange binding destination: {}", e);
+        record_persistence_error("exchange");
+    }
+}
@@ -0,0 +1,107 @@
+use crate::encryption::data_key::DataKeyId;
+use crate::encryption::{seal_topic_name, topic_name_lookup};
+use crate::event_queue::worker::ThreadData;
+use crate::metrics::record_persistence_error;
+use crate::model::entity::forwarding_rule_entities::ForwardingRuleEntity;
+use crate::model::forwarding_rule_model::CreateForwardingRule;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+pub async fn create_forwarding_rule(
+    thread_data: ThreadData,
+    namespace: String,
+    data: CreateForwardingRule,
+) {
+    let key_id = DataKeyId::new(
+        data.destination.destination_type,
+        namespace.clone(),
+        data.destination.destination.clone(),
+    );
+    let result = sqlx::query(
+        r#"
+            INSERT INTO forwarding_rule (rule_name, source, destination_type, destination_name, header_rewrite, namespace, sealed_destination_name)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+    )
+    .bind(data.name.clone())
+    .bind(data.source.clone())
+    .bind(data.destination.destination_type.as_str())
+    .bind(topic_name_lookup(&key_id))
+    .bind(serde_json::to_string(&data.header_rewrite).unwrap())
+    .bind(namespace)
+    .bind(seal_topic_name(&key_id))
+    .execute(&thread_data.db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!("Error while creating forwarding rule: {}", e);
+            record_persistence_error("forwarding_rule");
+        }
+    }
+}
+
+pub async fn delete_forwarding_rule(thread_data: ThreadData, namespace: String, rule_name: String) {
+    let result = sqlx::query(
+        r#"
+            DELETE FROM forwarding_rule
+            WHERE namespace = $1 AND rule_name = $2
+            "#,
+    )
+    .bind(namespace)
+    .bind(rule_name.clone())
+    .execute(&thread_data.db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!("Error while deleting forwarding rule: {}", e);
+            record_persistence_error("forwarding_rule");
+        }
+    }
+}
+
+pub async fn get_all_forwarding_rules(
+    db_connection_pool: &Pool<Postgres>,
+) -> Vec<ForwardingRuleEntity> {
+    let entities = sqlx::query_as::<_, ForwardingRuleEntity>(
+        r#"
+            SELECT * FROM forwarding_rule
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the forwarding rules: {}", e);
+        record_persistence_error("forwarding_rule");
+        Vec::new()
+    })
+}
+
+/// Replaces the plaintext destination of a rule written before encryption was enabled.
+pub async fn seal_forwarding_rule_destination(
+    db_connection_pool: &Pool<Postgres>,
+    id: Uuid,
+    destination_name: String,
+    sealed_destination_name: Option<Vec<u8>>,
+) {
+    let result = sqlx::query(
+        r#"
+            UPDATE forwarding_rule SET destination_name = $2, sealed_destination_name = $3
+            WHERE id = $1
+            "#,
+    )
+    .bind(id)
+    .bind(destination_name)
+    .bind(sealed_destination_name)
+    .execute(db_connection_pool)
+    .await;
+
+    if let Err(e) = result {
+        println!("Error while sealing forwarding rule destination: {}", e);
+        record_persistence_error("forwarding_rule");
+    }
+}
@@ -1,20 +1,39 @@
+use crate::encryption::data_key::DataKeyId;
+use crate::encryption::{identity_lookup, seal, seal_identity, seal_topic_name, topic_name_lookup};
 use crate::event_queue::worker::ThreadData;
-use crate::model::entity::message_topic_entities::MessageTopicEntity;
+use crate::metrics::record_persistence_error;
+use crate::model::destination_model::DestinationType;
+use crate::model::entity::message_topic_entities::{
+    MessageTopicEntity, MessageTopicPublisherEntity, MessageTopicSubscriberEntity,
+};
 use crate::model::message_topic_model::{
     AddPublisherToMessageTopic, AddSubscriberToMessageTopic, CreateMessageTopic,
     PublishToMessageTopic, RemovePublisherFromMessageTopic, RemoveSubscriberFromMessageTopic,
 };
+use crate::repository::data_key_repository::{delete_unused_data_key, ensure_data_key};
 use crate::utils::types::TopicType;
 use sqlx::{Pool, Postgres};
+use uuid::Uuid;
 
-pub async fn create_message_topic(thread_data: ThreadData, data: CreateMessageTopic) {
+pub async fn create_message_topic(
+    thread_data: ThreadData,
+    namespace: String,
+    data: CreateMessageTopic,
+) {
+    let key_id = DataKeyId::new(
+        DestinationType::MessageTopic,
+        namespace.clone(),
+        data.name.clone(),
+    );
     let result = sqlx::query(
         r#"
-            INSERT INTO message_topic (topic_name)
-            VALUES ($1)
+            INSERT INTO message_topic (namespace, topic_name, sealed_name)
+            VALUES ($1, $2, $3)
             "#,
     )
-    .bind(data.name.clone())
+    .bind(namespace)
+    .bind(topic_name_lookup(&key_id))
+    .bind(seal_topic_name(&key_id))
     .execute(&thread_data.db_connection_pool)
     .await;
 
@@ -22,56 +41,74 @@ pub async fn create_message_topic(thread_data: ThreadData, data: CreateMessageTo
         Ok(_) => {}
         Err(e) => {
             println!("Error while creating message topic: {}", e);
+            record_persistence_error("message_topic");
         }
     }
 }
 
-pub async fn delete_message_topic(thread_data: ThreadData, topic_name: String) {
+pub async fn delete_message_topic(thread_data: ThreadData, namespace: String, topic_name: String) {
+    let key_id = DataKeyId::new(DestinationType::MessageTopic, namespace.clone(), topic_name);
     let result = sqlx::query(
         r#"
             DELETE FROM message_topic
-            WHERE topic_name = $1
+            WHERE namespace = $1 AND topic_name = $2
             "#,
     )
-    .bind(topic_name.clone())
+    .bind(namespace)
+    .bind(topic_name_lookup(&key_id))
     .execute(&thread_data.db_connection_pool)
     .await;
 
     match result {
-        Ok(_) => {}
+        Ok(_) => {
+            delete_unused_data_key(&thread_data.db_connection_pool, &key_id).await;
+        }
         Err(e) => {
             println!("Error while deleting message topic: {}", e);
+            record_persistence_error("message_topic");
         }
     }
 }
 
 async fn get_message_topic_by_name(
     db_connection_pool: &Pool<Postgres>,
+    namespace: String,
     topic_name: String,
 ) -> Option<MessageTopicEntity> {
     let topic = sqlx::query_as::<_, MessageTopicEntity>(
         r#"
             SELECT * FROM message_topic
-            WHERE topic_name = $1
+            WHERE namespace = $1 AND topic_name = $2
             "#,
     )
-    .bind(topic_name.clone())
+    .bind(namespace.clone())
+    .bind(topic_name_lookup(&DataKeyId::new(
+        DestinationType::MessageTopic,
+        namespace,
+        topic_name,
+    )))
     .fetch_optional(db_connection_pool)
     .await;
 
     topic.unwrap_or_else(|e| {
         println!("Error while fetching the message topic: {}", e);
+        record_persistence_error("message_topic");
         None
     })
 }
 
 pub async fn publish_to_message_topic(
     thread_data: ThreadData,
+    namespace: String,
     topic_name: String,
     _data: PublishToMessageTopic<TopicType>,
-) {
-    let topic =
-        get_message_topic_by_name(&thread_data.db_connection_pool, topic_name.clone()).await;
+) -> bool {
+    let topic = get_message_topic_by_name(
+        &thread_data.db_connection_pool,
+        namespace,
+        topic_name.clone(),
+    )
+    .await;
 
     match topic {
         Some(topic) => {
@@ -86,36 +123,65 @@ pub async fn publish_to_message_topic(
             .await;
 
             match result {
-                Ok(_) => {}
+                Ok(_) => true,
                 Err(e) => {
                     println!("Error while publishing to message topic: {}", e);
+                    record_persistence_error("message_topic");
+                    false
                 }
             }
         }
         None => {
             println!("Topic not found!");
+            false
         }
     }
 }
 
 pub async fn add_publisher_to_message_topic(
     thread_data: ThreadData,
+    namespace: String,
     topic_name: String,
     data: AddPublisherToMessageTopic,
 ) {
-    let topic =
-        get_message_topic_by_name(&thread_data.db_connection_pool, topic_name.clone()).await;
+    let key_id = DataKeyId::new(
+        DestinationType::MessageTopic,
+        namespace.clone(),
+        topic_name.clone(),
+    );
+    let topic = get_message_topic_by_name(
+        &thread_data.db_connection_pool,
+        namespace,
+        topic_name.clone(),
+    )
+    .await;
 
     match topic {
         Some(topic) => {
+            let (publisher_name, sealed_name) =
+                match ensure_data_key(&thread_data.db_connection_pool, &key_id)
+                    .await
+                    .and_then(|_| seal_identity(&key_id, &data.publisher))
+                {
+                    Ok(sealed) => sealed,
+                    Err(e) => {
+                        println!(
+                            "Error while sealing the publisher of a message topic: {}",
+                            e
+                        );
+                        record_persistence_error("message_topic");
+                        return;
+                    }
+                };
             let result = sqlx::query(
                 r#"
-                    INSERT INTO message_topic_publisher (message_topic_id, publisher_name)
-                    VALUES ($1, $2)
+                    INSERT INTO message_topic_publisher (message_topic_id, publisher_name, sealed_name)
+                    VALUES ($1, $2, $3)
                     "#,
             )
             .bind(topic.id)
-            .bind(data.publisher)
+            .bind(publisher_name)
+            .bind(sealed_name)
             .execute(&thread_data.db_connection_pool)
             .await;
 
@@ -123,6 +189,7 @@ pub async fn add_publisher_to_message_topic(
                 Ok(_) => {}
                 Err(e) => {
                     println!("Error while adding publisher to message topic: {}", e);
+                    record_persistence_error("message_topic");
                 }
             }
         }
@@ -134,11 +201,21 @@ pub async fn add_publisher_to_message_topic(
 
 pub async fn remove_publisher_from_message_topic(
     thread_data: ThreadData,
+    namespace: String,
     topic_name: String,
     data: RemovePublisherFromMessageTopic,
 ) {
-    let topic =
-        get_message_topic_by_name(&thread_data.db_connection_pool, topic_name.clone()).await;
+    let key_id = DataKeyId::new(
+        DestinationType::MessageTopic,
+        namespace.clone(),
+        topic_name.clone(),
+    );
+    let topic = get_message_topic_by_name(
+        &thread_data.db_connection_pool,
+        namespace,
+        topic_name.clone(),
+    )
+    .await;
 
     match topic {
         Some(topic) => {
@@ -149,7 +226,7 @@ pub async fn remove_publisher_from_message_topic(
                     "#,
             )
             .bind(topic.id)
-            .bind(data.publisher)
+            .bind(identity_lookup(&key_id, &data.publisher))
             .execute(&thread_data.db_connection_pool)
             .await;
 
@@ -157,6 +234,7 @@ pub async fn remove_publisher_from_message_topic(
                 Ok(_) => {}
                 Err(e) => {
                     println!("Error while removing publisher from message topic: {}", e);
+                    record_persistence_error("message_topic");
                 }
             }
         }
@@ -168,23 +246,68 @@ pub async fn remove_publisher_from_message_topic(
 
 pub async fn add_subscriber_to_message_topic(
     thread_data: ThreadData,
+    namespace: String,
     topic_name: String,
     data: AddSubscriberToMessageTopic,
 ) {
-    let topic =
-        get_message_topic_by_name(&thread_data.db_connection_pool, topic_name.clone()).await;
+    let key_id = DataKeyId::new(
+        DestinationType::MessageTopic,
+        namespace.clone(),
+        topic_name.clone(),
+    );
+    let topic = get_message_topic_by_name(
+        &thread_data.db_connection_pool,
+        namespace,
+        topic_name.clone(),
+    )
+    .await;
 
     match topic {
         Some(topic) => {
+            let (subscriber_name, sealed_name) =
+                match ensure_data_key(&thread_data.db_connection_pool, &key_id)
+                    .await
+                    .and_then(|_| seal_identity(&key_id, &data.subscriber))
+                {
+                    Ok(sealed) => sealed,
+                    Err(e) => {
+                        println!(
+                            "Error while sealing the subscriber of a message topic: {}",
+                            e
+                        );
+                        record_persistence_error("message_topic");
+                        return;
+                    }
+                };
+            let filter = match data.filter.as_ref().map(serde_json::to_string).transpose() {
+                Ok(filter) => filter,
+                Err(e) => {
+                    println!("Error while serializing the filter of a subscriber: {}", e);
+                    record_persistence_error("message_topic");
+                    return;
+                }
+            };
+            let sealed_filter = match filter.as_deref().map(|filter| seal(&key_id, filter)) {
+                Some(Ok(sealed_filter)) => sealed_filter,
+                Some(Err(e)) => {
+                    println!("Error while sealing the filter of a subscriber: {}", e);
+                    record_persistence_error("message_topic");
+                    return;
+                }
+                None => None,
+            };
             let result = sqlx::query(
                 r#"
-                    INSERT INTO message_topic_subscriber (message_topic_id, subscriber_name, subscriber_index)
-                    VALUES ($1, $2, $3)
+                    INSERT INTO message_topic_subscriber (message_topic_id, subscriber_name, subscriber_index, filter, sealed_name, sealed_filter)
+                    VALUES ($1, $2, $3, $4, $5, $6)
                     "#,
             )
                 .bind(topic.id)
-                .bind(data.subscriber)
+                .bind(subscriber_name)
                 .bind(topic.data_index)
+                .bind(filter.filter(|_| sealed_filter.is_none()))
+                .bind(sealed_name)
+                .bind(sealed_filter)
                 .execute(&thread_data.db_connection_pool)
                 .await;
 
@@ -192,6 +315,7 @@ pub async fn add_subscriber_to_message_topic(
                 Ok(_) => {}
                 Err(e) => {
                     println!("Error while adding subscriber to message topic: {}", e);
+                    record_persistence_error("message_topic");
                 }
             }
         }
@@ -203,11 +327,21 @@ pub async fn add_subscriber_to_message_topic(
 
 pub async fn remove_subscriber_from_message_topic(
     thread_data: ThreadData,
+    namespace: String,
     topic_name: String,
     data: RemoveSubscriberFromMessageTopic,
 ) {
-    let topic =
-        get_message_topic_by_name(&thread_data.db_connection_pool, topic_name.clone()).await;
+    let key_id = DataKeyId::new(
+        DestinationType::MessageTopic,
+        namespace.clone(),
+        topic_name.clone(),
+    );
+    let topic = get_message_topic_by_name(
+        &thread_data.db_connection_pool,
+        namespace,
+        topic_name.clone(),
+    )
+    .await;
 
     match topic {
         Some(topic) => {
@@ -218,7 +352,7 @@ pub async fn remove_subscriber_from_message_topic(
                     "#,
             )
             .bind(topic.id)
-            .bind(data.subscriber)
+            .bind(identity_lookup(&key_id, &data.subscriber))
             .execute(&thread_data.db_connection_pool)
             .await;
 
@@ -226,6 +360,7 @@ pub async fn remove_subscriber_from_message_topic(
                 Ok(_) => {}
                 Err(e) => {
                     println!("Error while removing subscriber from message topic: {}", e);
+                    record_persistence_error("message_topic");
                 }
             }
         }
@@ -238,12 +373,22 @@ pub async fn remove_subscriber_from_message_topic(
 #[allow(dead_code)]
 pub async fn fetch_data_from_message_topic(
     thread_data: ThreadData,
+    namespace: String,
     topic_name: String,
     subscriber: String,
     subscriber_index: usize,
 ) {
-    let topic =
-        get_message_topic_by_name(&thread_data.db_connection_pool, topic_name.clone()).await;
+    let key_id = DataKeyId::new(
+        DestinationType::MessageTopic,
+        namespace.clone(),
+        topic_name.clone(),
+    );
+    let topic = get_message_topic_by_name(
+        &thread_data.db_connection_pool,
+        namespace,
+        topic_name.clone(),
+    )
+    .await;
 
     match topic {
         Some(topic) => {
@@ -255,7 +400,7 @@ pub async fn fetch_data_from_message_topic(
             )
             .bind(subscriber_index as i64)
             .bind(topic.id)
-            .bind(subscriber)
+            .bind(identity_lookup(&key_id, &subscriber))
             .execute(&thread_data.db_connection_pool)
             .await;
 
@@ -263,6 +408,7 @@ pub async fn fetch_data_from_message_topic(
                 Ok(_) => {}
                 Err(e) => {
                     println!("Error while updating the subscriber index: {}", e);
+                    record_persistence_error("message_topic");
                 }
             }
         }
@@ -274,11 +420,16 @@ pub async fn fetch_data_from_message_topic(
 
 pub async fn reset_index_of_message_topic(
     thread_data: ThreadData,
+    namespace: String,
     topic_name: String,
     index_subtrahend: usize,
 ) {
-    let topic =
-        get_message_topic_by_name(&thread_data.db_connection_pool, topic_name.clone()).await;
+    let topic = get_message_topic_by_name(
+        &thread_data.db_connection_pool,
+        namespace,
+        topic_name.clone(),
+    )
+    .await;
 
     match topic {
         Some(topic) => {
@@ -297,6 +448,7 @@ pub async fn reset_index_of_message_topic(
                 Ok(_) => {}
                 Err(e) => {
                     println!("Error while resetting index of message topic: {}", e);
+                    record_persistence_error("message_topic");
                 }
             }
         }
@@ -305,3 +457,135 @@ pub async fn reset_index_of_message_topic(
         }
     }
 }
+
+pub async fn get_all_message_topics(
+    db_connection_pool: &Pool<Postgres>,
+) -> Vec<MessageTopicEntity> {
+    let entities = sqlx::query_as::<_, MessageTopicEntity>(
+        r#"
+            SELECT * FROM message_topic
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the message topics: {}", e);
+        record_persistence_error("message_topic");
+        Vec::new()
+    })
+}
+
+pub async fn get_all_message_topic_subscribers(
+    db_connection_pool: &Pool<Postgres>,
+) -> Vec<MessageTopicSubscriberEntity> {
+    let entities = sqlx::query_as::<_, MessageTopicSubscriberEntity>(
+        r#"
+            SELECT * FROM message_topic_subscriber
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the message topic subscribers: {}", e);
+        record_persistence_error("message_topic");
+        Vec::new()
+    })
+}
+
+pub async fn get_all_message_topic_publishers(
+    db_connection_pool: &Pool<Postgres>,
+) -> Vec<MessageTopicPublisherEntity> {
+    let entities = sqlx::query_as::<_, MessageTopicPublisherEntity>(
+        r#"
+            SELECT * FROM message_topic_publisher
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the message topic publishers: {}", e);
+        record_persistence_error("message_topic");
+        Vec::new()
+    })
+}
+
+/// Replaces the plaintext identity of a publisher row written before encryption was enabled.
+pub async fn seal_message_topic_publisher(
+    db_connection_pool: &Pool<Postgres>,
+    id: Uuid,
+    publisher_name: String,
+    sealed_name: Option<Vec<u8>>,
+) {
+    let result = sqlx::query(
+        r#"
+            UPDATE message_topic_publisher SET publisher_name = $2, sealed_name = $3
+            WHERE id = $1
+            "#,
+    )
+    .bind(id)
+    .bind(publisher_name)
+    .bind(sealed_name)
+    .execute(db_connection_pool)
+    .await;
+
+    if let Err(e) = result {
+        println!("Error while sealing message topic publisher: {}", e);
+        record_persistence_error("message_topic");
+    }
+}
+
+/// Replaces the plaintext identity and filter of a subscriber row written before encryption was
+/// enabled.
+pub async fn seal_message_topic_subscriber(
+    db_connection_pool: &Pool<Postgres>,
+    id: Uuid,
+    subscriber_name: String,
+    sealed_name: Option<Vec<u8>>,
+    sealed_filter: Option<Vec<u8>>,
+) {
+    let result = sqlx::query(
+        r#"
+            UPDATE message_topic_subscriber SET subscriber_name = $2, sealed_name = $3, filter = NULL, sealed_filter = $4
+            WHERE id = $1
+            "#,
+    )
+    .bind(id)
+    .bind(subscriber_name)
+    .bind(sealed_name)
+    .bind(sealed_filter)
+    .execute(db_connection_pool)
+    .await;
+
+    if let Err(e) = result {
+        println!("Error while sealing message topic subscriber: {}", e);
+        record_persistence_error("message_topic");
+    }
+}
+
+/// Replaces the plaintext name of a topic row written before encryption was enabled.
+pub async fn seal_message_topic_name(
+    db_connection_pool: &Pool<Postgres>,
+    id: Uuid,
+    topic_name: String,
+    sealed_name: Option<Vec<u8>>,
+) {
+    let result = sqlx::query(
+        r#"
+            UPDATE message_topic SET topic_name = $2, sealed_name = $3
+            WHERE id = $1
+            "#,
+    )
+    .bind(id)
+    .bind(topic_name)
+    .bind(sealed_name)
+    .execute(db_connection_pool)
+    .await;
+
+    if let Err(e) = result {
+        println!("Error while sealing message topic name: {}", e);
+        record_persistence_error("message_topic");
+    }
+}
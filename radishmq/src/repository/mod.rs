@@ -1,2 +1,10 @@
+pub mod acl_repository;
+pub mod audit_repository;
+pub mod credential_repository;
+pub mod data_key_repository;
+pub mod exchange_repository;
+pub mod forwarding_rule_repository;
 pub mod message_topic_repository;
+pub mod retained_message_repository;
 pub mod task_topic_repository;
+pub mod wildcard_subscription_repository;
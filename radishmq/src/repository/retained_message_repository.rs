@@ -0,0 +1,120 @@
+use crate::encryption::data_key::DataKeyId;
+use crate::encryption::{seal, seal_topic_name, topic_name_lookup};
+use crate::event_queue::worker::ThreadData;
+use crate::metrics::record_persistence_error;
+use crate::model::entity::retained_message_entities::RetainedMessageEntity;
+use crate::model::retained_message_model::RetainedMessageModel;
+use crate::repository::data_key_repository::{delete_unused_data_key, ensure_data_key};
+use crate::utils::types::TopicType;
+use sqlx::{Pool, Postgres};
+
+pub async fn set_retained_message(thread_data: ThreadData, data: RetainedMessageModel<TopicType>) {
+    let key_id = DataKeyId::for_retained_message(data.topic.clone());
+    let headers = serde_json::to_string(&data.headers).unwrap();
+    let sealed = ensure_data_key(&thread_data.db_connection_pool, &key_id)
+        .await
+        .and_then(|_| Ok((seal(&key_id, &data.data)?, seal(&key_id, &headers)?)));
+    let (sealed_data, sealed_headers) = match sealed {
+        Ok(sealed) => sealed,
+        Err(e) => {
+            println!("Error while sealing retained message: {}", e);
+            record_persistence_error("retained_message");
+            return;
+        }
+    };
+
+    // sealed messages keep empty placeholders in the plaintext columns
+    let (data_column, headers_column) = match sealed_data {
+        Some(_) => (String::new(), "{}".to_string()),
+        None => (data.data.clone(), headers),
+    };
+    let result = sqlx::query(
+        r#"
+            INSERT INTO retained_message (topic_name, data, headers, sealed_data, sealed_headers, sealed_name)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (topic_name) DO UPDATE SET data = $2, headers = $3, sealed_data = $4, sealed_headers = $5, sealed_name = $6
+            "#,
+    )
+    .bind(topic_name_lookup(&key_id))
+    .bind(data_column)
+    .bind(headers_column)
+    .bind(sealed_data)
+    .bind(sealed_headers)
+    .bind(seal_topic_name(&key_id))
+    .execute(&thread_data.db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!("Error while setting retained message: {}", e);
+            record_persistence_error("retained_message");
+        }
+    }
+}
+
+pub async fn delete_retained_message(thread_data: ThreadData, topic_name: String) {
+    let key_id = DataKeyId::for_retained_message(topic_name);
+    let result = sqlx::query(
+        r#"
+            DELETE FROM retained_message
+            WHERE topic_name = $1
+            "#,
+    )
+    .bind(topic_name_lookup(&key_id))
+    .execute(&thread_data.db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {
+            delete_unused_data_key(&thread_data.db_connection_pool, &key_id).await;
+        }
+        Err(e) => {
+            println!("Error while deleting retained message: {}", e);
+            record_persistence_error("retained_message");
+        }
+    }
+}
+
+pub async fn get_all_retained_messages(
+    db_connection_pool: &Pool<Postgres>,
+) -> Vec<RetainedMessageEntity> {
+    let entities = sqlx::query_as::<_, RetainedMessageEntity>(
+        r#"
+            SELECT * FROM retained_message
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the retained messages: {}", e);
+        record_persistence_error("retained_message");
+        Vec::new()
+    })
+}
+
+/// Replaces the plaintext topic name of a retained message written before encryption was enabled.
+pub async fn seal_retained_message_name(
+    db_connection_pool: &Pool<Postgres>,
+    stored_name: String,
+    topic_name: String,
+    sealed_name: Option<Vec<u8>>,
+) {
+    let result = sqlx::query(
+        r#"
+            UPDATE retained_message SET topic_name = $2, sealed_name = $3
+            WHERE topic_name = $1 AND sealed_name IS NULL
+            "#,
+    )
+    .bind(stored_name)
+    .bind(topic_name)
+    .bind(sealed_name)
+    .execute(db_connection_pool)
+    .await;
+
+    if let Err(e) = result {
+        println!("Error while sealing retained message name: {}", e);
+        record_persistence_error("retained_message");
+    }
+}
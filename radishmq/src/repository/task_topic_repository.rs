@@ -1,20 +1,35 @@
+use crate::encryption::data_key::DataKeyId;
+use crate::encryption::{identity_lookup, seal_identity, seal_topic_name, topic_name_lookup};
 use crate::event_queue::worker::ThreadData;
-use crate::model::entity::task_topic_entities::TaskTopicEntity;
+use crate::metrics::record_persistence_error;
+use crate::model::destination_model::DestinationType;
+use crate::model::entity::task_topic_entities::{
+    TaskTopicEntity, TaskTopicPublisherEntity, TaskTopicSubscriberEntity,
+};
 use crate::model::task_topic_model::{
     AddPublisherToTaskTopic, AddSubscriberToTaskTopic, CreateTaskTopic, PublishToTaskTopic,
     RemovePublisherFromTaskTopic, RemoveSubscriberFromTaskTopic,
 };
+use crate::repository::data_key_repository::{delete_unused_data_key, ensure_data_key};
 use crate::utils::types::TopicType;
 use sqlx::{Pool, Postgres};
+use uuid::Uuid;
 
-pub async fn create_task_topic(thread_data: ThreadData, data: CreateTaskTopic) {
+pub async fn create_task_topic(thread_data: ThreadData, namespace: String, data: CreateTaskTopic) {
+    let key_id = DataKeyId::new(
+        DestinationType::TaskTopic,
+        namespace.clone(),
+        data.name.clone(),
+    );
     let result = sqlx::query(
         r#"
-            INSERT INTO task_topic (topic_name)
-            VALUES ($1)
+            INSERT INTO task_topic (namespace, topic_name, sealed_name)
+            VALUES ($1, $2, $3)
             "#,
     )
-    .bind(data.name.clone())
+    .bind(namespace)
+    .bind(topic_name_lookup(&key_id))
+    .bind(seal_topic_name(&key_id))
     .execute(&thread_data.db_connection_pool)
     .await;
 
@@ -22,40 +37,52 @@ pub async fn create_task_topic(thread_data: ThreadData, data: CreateTaskTopic) {
         Ok(_) => {}
         Err(e) => {
             println!("Error while creating task topic: {}", e);
+            record_persistence_error("task_topic");
         }
     }
 }
 
-pub async fn delete_task_topic(thread_data: ThreadData, topic_name: String) {
+pub async fn delete_task_topic(thread_data: ThreadData, namespace: String, topic_name: String) {
+    let key_id = DataKeyId::new(DestinationType::TaskTopic, namespace.clone(), topic_name);
     let result = sqlx::query(
         r#"
             DELETE FROM task_topic
-            WHERE topic_name = $1
+            WHERE namespace = $1 AND topic_name = $2
             "#,
     )
-    .bind(topic_name.clone())
+    .bind(namespace)
+    .bind(topic_name_lookup(&key_id))
     .execute(&thread_data.db_connection_pool)
     .await;
 
     match result {
-        Ok(_) => {}
+        Ok(_) => {
+            delete_unused_data_key(&thread_data.db_connection_pool, &key_id).await;
+        }
         Err(e) => {
             println!("Error while deleting task topic: {}", e);
+            record_persistence_error("task_topic");
         }
     }
 }
 
 async fn get_task_topic_by_name(
     db_connection_pool: &Pool<Postgres>,
+    namespace: String,
     topic_name: String,
 ) -> Option<TaskTopicEntity> {
     let topic = sqlx::query_as::<_, TaskTopicEntity>(
         r#"
             SELECT * FROM task_topic
-            WHERE topic_name = $1
+            WHERE namespace = $1 AND topic_name = $2
             "#,
     )
-    .bind(topic_name.clone())
+    .bind(namespace.clone())
+    .bind(topic_name_lookup(&DataKeyId::new(
+        DestinationType::TaskTopic,
+        namespace,
+        topic_name,
+    )))
     .fetch_optional(db_connection_pool)
     .await;
 
@@ -63,6 +90,7 @@ async fn get_task_topic_by_name(
         Ok(topic) => topic,
         Err(e) => {
             println!("Error while fetching the task topic: {}", e);
+            record_persistence_error("task_topic");
             None
         }
     }
@@ -71,6 +99,7 @@ async fn get_task_topic_by_name(
 #[allow(dead_code)]
 pub async fn publish_to_task_topic(
     _thread_data: ThreadData,
+    _namespace: String,
     _topic_name: String,
     _data: PublishToTaskTopic<TopicType>,
 ) {
@@ -79,21 +108,45 @@ pub async fn publish_to_task_topic(
 
 pub async fn add_publisher_to_task_topic(
     thread_data: ThreadData,
+    namespace: String,
     topic_name: String,
     data: AddPublisherToTaskTopic,
 ) {
-    let topic = get_task_topic_by_name(&thread_data.db_connection_pool, topic_name.clone()).await;
+    let key_id = DataKeyId::new(
+        DestinationType::TaskTopic,
+        namespace.clone(),
+        topic_name.clone(),
+    );
+    let topic = get_task_topic_by_name(
+        &thread_data.db_connection_pool,
+        namespace,
+        topic_name.clone(),
+    )
+    .await;
 
     match topic {
         Some(topic) => {
+            let (publisher_name, sealed_name) =
+                match ensure_data_key(&thread_data.db_connection_pool, &key_id)
+                    .await
+                    .and_then(|_| seal_identity(&key_id, &data.publisher))
+                {
+                    Ok(sealed) => sealed,
+                    Err(e) => {
+                        println!("Error while sealing the publisher of a task topic: {}", e);
+                        record_persistence_error("task_topic");
+                        return;
+                    }
+                };
             let result = sqlx::query(
                 r#"
-                    INSERT INTO task_topic_publisher (topic_id, publisher)
-                    VALUES ($1, $2)
+                    INSERT INTO task_topic_publisher (task_topic_id, publisher_name, sealed_name)
+                    VALUES ($1, $2, $3)
                     "#,
             )
             .bind(topic.id)
-            .bind(data.publisher.clone())
+            .bind(publisher_name)
+            .bind(sealed_name)
             .execute(&thread_data.db_connection_pool)
             .await;
 
@@ -101,6 +154,7 @@ pub async fn add_publisher_to_task_topic(
                 Ok(_) => {}
                 Err(e) => {
                     println!("Error while adding publisher to task topic: {}", e);
+                    record_persistence_error("task_topic");
                 }
             }
         }
@@ -112,21 +166,32 @@ pub async fn add_publisher_to_task_topic(
 
 pub async fn remove_publisher_from_task_topic(
     thread_data: ThreadData,
+    namespace: String,
     topic_name: String,
     data: RemovePublisherFromTaskTopic,
 ) {
-    let topic = get_task_topic_by_name(&thread_data.db_connection_pool, topic_name.clone()).await;
+    let key_id = DataKeyId::new(
+        DestinationType::TaskTopic,
+        namespace.clone(),
+        topic_name.clone(),
+    );
+    let topic = get_task_topic_by_name(
+        &thread_data.db_connection_pool,
+        namespace,
+        topic_name.clone(),
+    )
+    .await;
 
     match topic {
         Some(topic) => {
             let result = sqlx::query(
                 r#"
                     DELETE FROM task_topic_publisher
-                    WHERE topic_id = $1 AND publisher = $2
+                    WHERE task_topic_id = $1 AND publisher_name = $2
                     "#,
             )
             .bind(topic.id)
-            .bind(data.publisher.clone())
+            .bind(identity_lookup(&key_id, &data.publisher))
             .execute(&thread_data.db_connection_pool)
             .await;
 
@@ -134,6 +199,7 @@ pub async fn remove_publisher_from_task_topic(
                 Ok(_) => {}
                 Err(e) => {
                     println!("Error while removing publisher from task topic: {}", e);
+                    record_persistence_error("task_topic");
                 }
             }
         }
@@ -145,21 +211,45 @@ pub async fn remove_publisher_from_task_topic(
 
 pub async fn add_subscriber_to_task_topic(
     thread_data: ThreadData,
+    namespace: String,
     topic_name: String,
     data: AddSubscriberToTaskTopic,
 ) {
-    let topic = get_task_topic_by_name(&thread_data.db_connection_pool, topic_name.clone()).await;
+    let key_id = DataKeyId::new(
+        DestinationType::TaskTopic,
+        namespace.clone(),
+        topic_name.clone(),
+    );
+    let topic = get_task_topic_by_name(
+        &thread_data.db_connection_pool,
+        namespace,
+        topic_name.clone(),
+    )
+    .await;
 
     match topic {
         Some(topic) => {
+            let (subscriber_name, sealed_name) =
+                match ensure_data_key(&thread_data.db_connection_pool, &key_id)
+                    .await
+                    .and_then(|_| seal_identity(&key_id, &data.subscriber))
+                {
+                    Ok(sealed) => sealed,
+                    Err(e) => {
+                        println!("Error while sealing the subscriber of a task topic: {}", e);
+                        record_persistence_error("task_topic");
+                        return;
+                    }
+                };
             let result = sqlx::query(
                 r#"
-                    INSERT INTO task_topic_subscriber (topic_id, subscriber)
-                    VALUES ($1, $2)
+                    INSERT INTO task_topic_subscriber (task_topic_id, subscriber_name, sealed_name)
+                    VALUES ($1, $2, $3)
                     "#,
             )
             .bind(topic.id)
-            .bind(data.subscriber.clone())
+            .bind(subscriber_name)
+            .bind(sealed_name)
             .execute(&thread_data.db_connection_pool)
             .await;
 
@@ -167,6 +257,7 @@ pub async fn add_subscriber_to_task_topic(
                 Ok(_) => {}
                 Err(e) => {
                     println!("Error while adding subscriber to task topic: {}", e);
+                    record_persistence_error("task_topic");
                 }
             }
         }
@@ -178,21 +269,32 @@ pub async fn add_subscriber_to_task_topic(
 
 pub async fn remove_subscriber_from_task_topic(
     thread_data: ThreadData,
+    namespace: String,
     topic_name: String,
     data: RemoveSubscriberFromTaskTopic,
 ) {
-    let topic = get_task_topic_by_name(&thread_data.db_connection_pool, topic_name.clone()).await;
+    let key_id = DataKeyId::new(
+        DestinationType::TaskTopic,
+        namespace.clone(),
+        topic_name.clone(),
+    );
+    let topic = get_task_topic_by_name(
+        &thread_data.db_connection_pool,
+        namespace,
+        topic_name.clone(),
+    )
+    .await;
 
     match topic {
         Some(topic) => {
             let result = sqlx::query(
                 r#"
                     DELETE FROM task_topic_subscriber
-                    WHERE topic_id = $1 AND subscriber = $2
+                    WHERE task_topic_id = $1 AND subscriber_name = $2
                     "#,
             )
             .bind(topic.id)
-            .bind(data.subscriber.clone())
+            .bind(identity_lookup(&key_id, &data.subscriber))
             .execute(&thread_data.db_connection_pool)
             .await;
 
@@ -200,6 +302,7 @@ pub async fn remove_subscriber_from_task_topic(
                 Ok(_) => {}
                 Err(e) => {
                     println!("Error while removing subscriber from task topic: {}", e);
+                    record_persistence_error("task_topic");
                 }
             }
         }
@@ -212,8 +315,136 @@ pub async fn remove_subscriber_from_task_topic(
 #[allow(dead_code)]
 pub async fn fetch_task_from_task_topic(
     _thread_data: ThreadData,
+    _namespace: String,
     _topic_name: String,
     _subscriber: String,
 ) {
     // doe nothing for now
 }
+
+pub async fn get_all_task_topics(db_connection_pool: &Pool<Postgres>) -> Vec<TaskTopicEntity> {
+    let entities = sqlx::query_as::<_, TaskTopicEntity>(
+        r#"
+            SELECT * FROM task_topic
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the task topics: {}", e);
+        record_persistence_error("task_topic");
+        Vec::new()
+    })
+}
+
+pub async fn get_all_task_topic_subscribers(
+    db_connection_pool: &Pool<Postgres>,
+) -> Vec<TaskTopicSubscriberEntity> {
+    let entities = sqlx::query_as::<_, TaskTopicSubscriberEntity>(
+        r#"
+            SELECT * FROM task_topic_subscriber
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the task topic subscribers: {}", e);
+        record_persistence_error("task_topic");
+        Vec::new()
+    })
+}
+
+pub async fn get_all_task_topic_publishers(
+    db_connection_pool: &Pool<Postgres>,
+) -> Vec<TaskTopicPublisherEntity> {
+    let entities = sqlx::query_as::<_, TaskTopicPublisherEntity>(
+        r#"
+            SELECT * FROM task_topic_publisher
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the task topic publishers: {}", e);
+        record_persistence_error("task_topic");
+        Vec::new()
+    })
+}
+
+/// Replaces the plaintext identity of a publisher row written before encryption was enabled.
+pub async fn seal_task_topic_publisher(
+    db_connection_pool: &Pool<Postgres>,
+    id: Uuid,
+    publisher_name: String,
+    sealed_name: Option<Vec<u8>>,
+) {
+    let result = sqlx::query(
+        r#"
+            UPDATE task_topic_publisher SET publisher_name = $2, sealed_name = $3
+            WHERE id = $1
+            "#,
+    )
+    .bind(id)
+    .bind(publisher_name)
+    .bind(sealed_name)
+    .execute(db_connection_pool)
+    .await;
+
+    if let Err(e) = result {
+        println!("Error while sealing task topic publisher: {}", e);
+        record_persistence_error("task_topic");
+    }
+}
+
+/// Replaces the plaintext identity of a subscriber row written before encryption was enabled.
+pub async fn seal_task_topic_subscriber(
+    db_connection_pool: &Pool<Postgres>,
+    id: Uuid,
+    subscriber_name: String,
+    sealed_name: Option<Vec<u8>>,
+) {
+    let result = sqlx::query(
+        r#"
+            UPDATE task_topic_subscriber SET subscriber_name = $2, sealed_name = $3
+            WHERE id = $1
+            "#,
+    )
+    .bind(id)
+    .bind(subscriber_name)
+    .bind(sealed_name)
+    .execute(db_connection_pool)
+    .await;
+
+    if let Err(e) = result {
+        println!("Error while sealing task topic subscriber: {}", e);
+        record_persistence_error("task_topic");
+    }
+}
+
+/// Replaces the plaintext name of a topic row written before encryption was enabled.
+pub async fn seal_task_topic_name(
+    db_connection_pool: &Pool<Postgres>,
+    id: Uuid,
+    topic_name: String,
+    sealed_name: Option<Vec<u8>>,
+) {
+    let result = sqlx::query(
+        r#"
+            UPDATE task_topic SET topic_name = $2, sealed_name = $3
+            WHERE id = $1
+            "#,
+    )
+    .bind(id)
+    .bind(topic_name)
+    .bind(sealed_name)
+    .execute(db_connection_pool)
+    .await;
+
+    if let Err(e) = result {
+        println!("Error while sealing task topic name: {}", e);
+        record_persistence_error("task_topic");
+    }
+}
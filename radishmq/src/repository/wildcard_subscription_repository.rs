@@ -0,0 +1,229 @@
+use crate::encryption::data_key::DataKeyId;
+use crate::encryption::{seal_topic_name, topic_name_lookup};
+use crate::event_queue::worker::ThreadData;
+use crate::metrics::record_persistence_error;
+use crate::model::destination_model::DestinationType;
+use crate::model::entity::wildcard_subscription_entities::{
+    WildcardSubscriptionEntity, WildcardSubscriptionTopicEntity,
+};
+use crate::model::wildcard_subscription_model::CreateWildcardSubscription;
+use crate::utils::namespace::DEFAULT_NAMESPACE;
+use sqlx::{Pool, Postgres};
+
+pub async fn create_wildcard_subscription(
+    thread_data: ThreadData,
+    data: CreateWildcardSubscription,
+    topics: Vec<String>,
+) {
+    let filter = match data.filter.as_ref().map(serde_json::to_string).transpose() {
+        Ok(filter) => filter,
+        Err(e) => {
+            println!(
+                "Error while serializing the filter of a wildcard subscription: {}",
+                e
+            );
+            record_persistence_error("wildcard_subscription");
+            return;
+        }
+    };
+    let result = sqlx::query(
+        r#"
+            INSERT INTO message_topic_wildcard_subscriber (subscriber_name, pattern, filter)
+            VALUES ($1, $2, $3)
+            "#,
+    )
+    .bind(data.subscriber.clone())
+    .bind(data.pattern.clone())
+    .bind(filter)
+    .execute(&thread_data.db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!("Error while creating wildcard subscription: {}", e);
+            record_persistence_error("wildcard_subscription");
+            return;
+        }
+    }
+
+    for topic_name in topics {
+        insert_wildcard_subscription_topic(
+            &thread_data.db_connection_pool,
+            data.subscriber.clone(),
+            data.pattern.clone(),
+            topic_name,
+        )
+        .await;
+    }
+}
+
+pub async fn delete_wildcard_subscription(
+    thread_data: ThreadData,
+    subscriber_name: String,
+    pattern: String,
+) {
+    let result = sqlx::query(
+        r#"
+            DELETE FROM message_topic_wildcard_subscriber
+            WHERE subscriber_name = $1 AND pattern = $2
+            "#,
+    )
+    .bind(subscriber_name.clone())
+    .bind(pattern.clone())
+    .execute(&thread_data.db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!("Error while deleting wildcard subscription: {}", e);
+            record_persistence_error("wildcard_subscription");
+        }
+    }
+
+    let result = sqlx::query(
+        r#"
+            DELETE FROM message_topic_wildcard_subscriber_topic
+            WHERE subscriber_name = $1 AND pattern = $2
+            "#,
+    )
+    .bind(subscriber_name)
+    .bind(pattern)
+    .execute(&thread_data.db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!(
+                "Error while deleting the topics of a wildcard subscription: {}",
+                e
+            );
+            record_persistence_error("wildcard_subscription");
+        }
+    }
+}
+
+pub async fn add_topic_to_wildcard_subscription(
+    thread_data: ThreadData,
+    subscriber_name: String,
+    pattern: String,
+    topic_name: String,
+) {
+    insert_wildcard_subscription_topic(
+        &thread_data.db_connection_pool,
+        subscriber_name,
+        pattern,
+        topic_name,
+    )
+    .await;
+}
+
+async fn insert_wildcard_subscription_topic(
+    db_connection_pool: &Pool<Postgres>,
+    subscriber_name: String,
+    pattern: String,
+    topic_name: String,
+) {
+    // wildcard subscriptions only exist in the default namespace
+    let key_id = DataKeyId::new(
+        DestinationType::MessageTopic,
+        DEFAULT_NAMESPACE.to_string(),
+        topic_name,
+    );
+    let result = sqlx::query(
+        r#"
+            INSERT INTO message_topic_wildcard_subscriber_topic (subscriber_name, pattern, topic_name, sealed_topic_name)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            "#,
+    )
+    .bind(subscriber_name)
+    .bind(pattern)
+    .bind(topic_name_lookup(&key_id))
+    .bind(seal_topic_name(&key_id))
+    .execute(db_connection_pool)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(e) => {
+            println!(
+                "Error while adding a topic to a wildcard subscription: {}",
+                e
+            );
+            record_persistence_error("wildcard_subscription");
+        }
+    }
+}
+
+pub async fn get_all_wildcard_subscriptions(
+    db_connection_pool: &Pool<Postgres>,
+) -> Vec<WildcardSubscriptionEntity> {
+    let entities = sqlx::query_as::<_, WildcardSubscriptionEntity>(
+        r#"
+            SELECT * FROM message_topic_wildcard_subscriber
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!("Error while fetching the wildcard subscriptions: {}", e);
+        record_persistence_error("wildcard_subscription");
+        Vec::new()
+    })
+}
+
+pub async fn get_all_wildcard_subscription_topics(
+    db_connection_pool: &Pool<Postgres>,
+) -> Vec<WildcardSubscriptionTopicEntity> {
+    let entities = sqlx::query_as::<_, WildcardSubscriptionTopicEntity>(
+        r#"
+            SELECT * FROM message_topic_wildcard_subscriber_topic
+            "#,
+    )
+    .fetch_all(db_connection_pool)
+    .await;
+
+    entities.unwrap_or_else(|e| {
+        println!(
+            "Error while fetching the topics of the wildcard subscriptions: {}",
+            e
+        );
+        record_persistence_error("wildcard_subscription");
+        Vec::new()
+    })
+}
+
+/// Replaces the plaintext topic name of a wildcard subscription topic written before encryption
+/// was enabled.
+pub async fn seal_wildcard_subscription_topic_name(
+    db_connection_pool: &Pool<Postgres>,
+    topic: WildcardSubscriptionTopicEntity,
+    topic_name: String,
+    sealed_topic_name: Option<Vec<u8>>,
+) {
+    let result = sqlx::query(
+        r#"
+            UPDATE message_topic_wildcard_subscriber_topic SET topic_name = $4, sealed_topic_name = $5
+            WHERE subscriber_name = $1 AND pattern = $2 AND topic_name = $3
+            "#,
+    )
+    .bind(topic.subscriber_name)
+    .bind(topic.pattern)
+    .bind(topic.topic_name)
+    .bind(topic_name)
+    .bind(sealed_topic_name)
+    .execute(db_connection_pool)
+    .await;
+
+    if let Err(e) = result {
+        println!(
+            "Error while sealing the topic name of a wildcard subscription: {}",
+            e
+        );
+        record_persistence_error("wildcard_subscription");
+    }
+}
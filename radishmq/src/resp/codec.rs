@@ -0,0 +1,269 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use std::io;
+use tokio_util::codec::{Decoder, Encoder};
+
+const MAX_COMMAND_LENGTH: usize = 16 * 1024 * 1024;
+const MAX_ARGUMENTS: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RespValue {
+    Simple(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Bytes),
+    Array(Vec<RespValue>),
+    Null,
+    NullArray,
+}
+
+impl RespValue {
+    pub fn ok() -> Self {
+        Self::Simple("OK".to_string())
+    }
+
+    pub fn error(message: &str) -> Self {
+        Self::Error(format!("ERR {}", message))
+    }
+
+    pub fn bulk(value: impl Into<String>) -> Self {
+        Self::Bulk(Bytes::from(value.into()))
+    }
+}
+
+/// Decodes client commands into their arguments and encodes replies.
+#[derive(Debug, Default)]
+pub struct RespCodec;
+
+impl Decoder for RespCodec {
+    type Item = Vec<Bytes>;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        loop {
+            if src.is_empty() {
+                return Ok(None);
+            }
+            let decoded = if src[0] == b'*' {
+                decode_array(src)?
+            } else {
+                decode_inline(src)?
+            };
+            match decoded {
+                Some((arguments, length)) => {
+                    src.advance(length);
+                    // empty inline lines are ignored like redis does
+                    if !arguments.is_empty() {
+                        return Ok(Some(arguments));
+                    }
+                }
+                None if src.len() > MAX_COMMAND_LENGTH => {
+                    return Err(invalid("command exceeds the maximum length"))
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+impl Encoder<RespValue> for RespCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: RespValue, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        match item {
+            RespValue::Simple(value) => write_line(dst, b'+', value.as_bytes()),
+            RespValue::Error(message) => write_line(dst, b'-', message.as_bytes()),
+            RespValue::Integer(value) => write_line(dst, b':', value.to_string().as_bytes()),
+            RespValue::Bulk(value) => {
+                write_line(dst, b'$', value.len().to_string().as_bytes());
+                dst.put_slice(&value);
+                dst.put_slice(b"\r\n");
+            }
+            RespValue::Array(values) => {
+                write_line(dst, b'*', values.len().to_string().as_bytes());
+                for value in values {
+                    self.encode(value, dst)?;
+                }
+            }
+            RespValue::Null => dst.put_slice(b"$-1\r\n"),
+            RespValue::NullArray => dst.put_slice(b"*-1\r\n"),
+        }
+        Ok(())
+    }
+}
+
+fn write_line(dst: &mut BytesMut, prefix: u8, value: &[u8]) {
+    dst.put_u8(prefix);
+    dst.put_slice(value);
+    dst.put_slice(b"\r\n");
+}
+
+// returns the arguments and the number of bytes they occupied once the command is complete
+fn decode_array(src: &[u8]) -> io::Result<Option<(Vec<Bytes>, usize)>> {
+    let Some((count, mut position)) = read_number(src, 1)? else {
+        return Ok(None);
+    };
+    if count > MAX_ARGUMENTS {
+        return Err(invalid("too many arguments"));
+    }
+
+    // the count is announced before any argument arrived, so it only sizes the first allocation
+    let mut arguments = Vec::with_capacity(count.min(64));
+    for _ in 0..count {
+        if position >= src.len() {
+            return Ok(None);
+        }
+        if src[position] != b'$' {
+            return Err(invalid("expected a bulk string"));
+        }
+        let Some((length, start)) = read_number(src, position + 1)? else {
+            return Ok(None);
+        };
+        if length > MAX_COMMAND_LENGTH {
+            return Err(invalid("bulk string exceeds the maximum length"));
+        }
+        if src.len() < start + length + 2 {
+            return Ok(None);
+        }
+        if &src[start + length..start + length + 2] != b"\r\n" {
+            return Err(invalid("bulk string is not terminated by CRLF"));
+        }
+        arguments.push(Bytes::copy_from_slice(&src[start..start + length]));
+        position = start + length + 2;
+    }
+    Ok(Some((arguments, position)))
+}
+
+fn decode_inline(src: &[u8]) -> io::Result<Option<(Vec<Bytes>, usize)>> {
+    let Some(end) = src.iter().position(|byte| *byte == b'\n') else {
+        return Ok(None);
+    };
+    let line =
+        std::str::from_utf8(&src[..end]).map_err(|_| invalid("inline command is not UTF-8"))?;
+    let arguments = line
+        .split_whitespace()
+        .map(|argument| Bytes::copy_from_slice(argument.as_bytes()))
+        .collect();
+    Ok(Some((arguments, end + 1)))
+}
+
+// reads a CRLF terminated number starting at `start`, returns it and the position after the CRLF
+fn read_number(src: &[u8], start: usize) -> io::Result<Option<(usize, usize)>> {
+    let Some(end) = src[start.min(src.len())..]
+        .windows(2)
+        .position(|window| window == b"\r\n")
+    else {
+        return Ok(None);
+    };
+    let number = std::str::from_utf8(&src[start..start + end])
+        .ok()
+        .and_then(|number| number.parse::<usize>().ok())
+        .ok_or_else(|| invalid("invalid length"))?;
+    Ok(Some((number, start + end + 2)))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(src: &mut BytesMut) -> io::Result<Option<Vec<Bytes>>> {
+        RespCodec.decode(src)
+    }
+
+    fn arguments(values: &[&str]) -> Vec<Bytes> {
+        values
+            .iter()
+            .map(|value| Bytes::copy_from_slice(value.as_bytes()))
+            .collect()
+    }
+
+    #[test]
+    fn inline_commands_are_split_on_whitespace() {
+        let mut src = BytesMut::from(&b"\r\n\nPING\r\nSET  key \t value\n"[..]);
+
+        // empty lines are skipped, CR and runs of whitespace only separate arguments
+        assert_eq!(decode(&mut src).unwrap(), Some(arguments(&["PING"])));
+        assert_eq!(
+            decode(&mut src).unwrap(),
+            Some(arguments(&["SET", "key", "value"]))
+        );
+        assert_eq!(decode(&mut src).unwrap(), None);
+        assert!(src.is_empty());
+
+        let mut src = BytesMut::from(&b"GET \xff\r\n"[..]);
+        assert!(decode(&mut src).is_err());
+    }
+
+    #[test]
+    fn array_commands_wait_until_they_are_complete() {
+        let command = b"*3\r\n$7\r\nPUBLISH\r\n$6\r\norders\r\n$9\r\nhi\r\nthere\r\n";
+
+        // every prefix is incomplete and leaves the buffer untouched
+        for length in 0..command.len() {
+            let mut src = BytesMut::from(&command[..length]);
+            assert_eq!(
+                decode(&mut src).unwrap(),
+                None,
+                "prefix of {} bytes",
+                length
+            );
+            assert_eq!(src.len(), length);
+        }
+
+        // bulk strings may contain CRLF, pipelined commands are decoded one after another
+        let mut src = BytesMut::from(&command[..]);
+        src.extend_from_slice(b"*1\r\n$4\r\nPING\r\n");
+        assert_eq!(
+            decode(&mut src).unwrap(),
+            Some(arguments(&["PUBLISH", "orders", "hi\r\nthere"]))
+        );
+        assert_eq!(decode(&mut src).unwrap(), Some(arguments(&["PING"])));
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn malformed_and_oversized_commands_are_rejected() {
+        for command in [
+            &b"*1\r\n:4\r\nPING\r\n"[..],
+            &b"*1\r\n$4\r\nPINGxx"[..],
+            &b"*x\r\n"[..],
+            &b"*1\r\n$-1\r\n"[..],
+            format!("*{}\r\n", MAX_ARGUMENTS + 1).as_bytes(),
+            format!("*1\r\n${}\r\n", MAX_COMMAND_LENGTH + 1).as_bytes(),
+        ] {
+            let mut src = BytesMut::from(command);
+            assert!(decode(&mut src).is_err(), "{:?}", command);
+        }
+
+        // an announced count alone is not trusted, the command waits for its arguments
+        let mut src = BytesMut::from(format!("*{}\r\n", MAX_ARGUMENTS).as_bytes());
+        assert_eq!(decode(&mut src).unwrap(), None);
+
+        // incomplete commands may not grow beyond the maximum length
+        let mut src = BytesMut::from(&vec![b'a'; MAX_COMMAND_LENGTH + 1][..]);
+        assert!(decode(&mut src).is_err());
+    }
+
+    #[test]
+    fn replies_are_encoded_as_resp2() {
+        let mut dst = BytesMut::new();
+        let reply = RespValue::Array(vec![
+            RespValue::ok(),
+            RespValue::error("unknown command"),
+            RespValue::Integer(-2),
+            RespValue::bulk("hi"),
+            RespValue::Null,
+            RespValue::NullArray,
+            RespValue::Array(Vec::new()),
+        ]);
+        RespCodec.encode(reply, &mut dst).unwrap();
+
+        assert_eq!(
+            &dst[..],
+            &b"*7\r\n+OK\r\n-ERR unknown command\r\n:-2\r\n$2\r\nhi\r\n$-1\r\n*-1\r\n*0\r\n"[..]
+        );
+    }
+}
@@ -0,0 +1,819 @@
+use crate::auth::authenticator::Authenticator;
+use crate::auth::identity::{self, Identity};
+use crate::model::destination_model::{DestinationType, TopicDestination};
+use crate::model::message_topic_model::{
+    AddSubscriberToMessageTopic, Message, PublishToMessageTopic, RemoveSubscriberFromMessageTopic,
+};
+use crate::model::task_topic_model::{
+    AddSubscriberToTaskTopic, CompleteTask, PublishToTaskTopic, RemoveSubscriberFromTaskTopic,
+};
+use crate::resp::codec::{RespCodec, RespValue};
+use crate::service::error::{ServiceError, ServiceResult};
+use crate::service::{message_topic_service, notification_service, task_topic_service};
+use crate::utils::peer_address;
+use crate::utils::types::{Headers, TopicType};
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use log::warn;
+use std::collections::HashSet;
+use std::env;
+use std::io;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use tokio::time::{timeout_at, Instant};
+use tokio_util::codec::Framed;
+use uuid::Uuid;
+
+// the field of stream entries that holds the message data, all other fields are headers
+const DATA_FIELD: &str = "data";
+const REDIS_VERSION: &str = "7.0.0";
+
+type CommandResult = Result<RespValue, String>;
+
+pub async fn start_resp_server() -> io::Result<()> {
+    let port = match env::var("RESP_PORT") {
+        Ok(port) => port.parse::<u16>().expect("RESP_PORT must be a number"),
+        Err(_) => {
+            println!("Skipping RESP listener...");
+            return Ok(());
+        }
+    };
+    let address = env::var("RESP_ADDRESS")
+        .or_else(|_| env::var("HTTP_ADDRESS"))
+        .expect("RESP_ADDRESS or HTTP_ADDRESS must be set");
+
+    let listener = TcpListener::bind((address.as_str(), port)).await?;
+    println!("RESP listener started on {}:{}", address, port);
+
+    let authenticator = Authenticator::from_env().map(Arc::new);
+    tokio::spawn(accept_connections(listener, authenticator));
+    Ok(())
+}
+
+async fn accept_connections(listener: TcpListener, authenticator: Option<Arc<Authenticator>>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, address)) => {
+                let authenticator = authenticator.clone();
+                tokio::spawn(peer_address::scope(address.ip().to_string(), async move {
+                    if let Err(e) = handle_connection(stream, authenticator).await {
+                        warn!("RespServer::handle_connection closed a connection: {}", e);
+                    }
+                }));
+            }
+            Err(e) => warn!(
+                "RespServer::accept_connections could not accept a connection: {}",
+                e
+            ),
+        }
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    authenticator: Option<Arc<Authenticator>>,
+) -> io::Result<()> {
+    let mut connection = Connection {
+        framed: Framed::new(stream, RespCodec),
+        authenticator,
+        authenticated: None,
+        identity: format!("resp:{}", Uuid::new_v4().simple()),
+        anonymous: true,
+        subscribed: HashSet::new(),
+        channels: Vec::new(),
+        notifications: None,
+        stream_ids: StreamIds::default(),
+    };
+    let result = connection.run().await;
+    identity::on_behalf_of(connection.authenticated.clone(), connection.close()).await;
+    result
+}
+
+fn wrong_arguments(command: &str) -> String {
+    format!(
+        "wrong number of arguments for '{}' command",
+        command.to_lowercase()
+    )
+}
+
+fn text(argument: &Bytes) -> Result<String, String> {
+    String::from_utf8(argument.to_vec()).map_err(|_| "arguments must be valid UTF-8".to_string())
+}
+
+fn texts(arguments: &[Bytes]) -> Result<Vec<String>, String> {
+    arguments.iter().map(text).collect()
+}
+
+// blocking timeouts are given in seconds for lists and in milliseconds for streams, 0 blocks forever
+fn deadline(timeout: &Bytes, unit: Duration) -> Result<Option<Instant>, String> {
+    let timeout = text(timeout)?
+        .parse::<f64>()
+        .ok()
+        .filter(|timeout| timeout.is_finite() && *timeout >= 0.0)
+        .ok_or("timeout is not a float or out of range")?;
+    Ok((timeout > 0.0).then(|| Instant::now() + unit.mul_f64(timeout)))
+}
+
+/// Waits for new data on one of the topics, returns false once the deadline has passed.
+async fn wait_for_data(
+    notifications: &mut Receiver<TopicDestination>,
+    destination_type: DestinationType,
+    topics: &[String],
+    deadline: Option<Instant>,
+) -> bool {
+    loop {
+        let notification = match deadline {
+            Some(deadline) => match timeout_at(deadline, notifications.recv()).await {
+                Ok(notification) => notification,
+                Err(_) => return false,
+            },
+            None => notifications.recv().await,
+        };
+        match notification {
+            Ok(destination)
+                if destination.destination_type == destination_type
+                    && topics.contains(&destination.destination) =>
+            {
+                return true
+            }
+            Ok(_) => {}
+            // missed notifications are recovered by checking every topic again
+            Err(RecvError::Lagged(_)) => return true,
+            Err(RecvError::Closed) => return false,
+        }
+    }
+}
+
+async fn next_notification(
+    notifications: &mut Option<Receiver<TopicDestination>>,
+) -> Result<TopicDestination, RecvError> {
+    match notifications {
+        Some(notifications) => notifications.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Generates redis style stream ids, `<milliseconds>-<sequence>`.
+#[derive(Default)]
+struct StreamIds {
+    last_millis: u128,
+    sequence: u64,
+}
+
+impl StreamIds {
+    fn next(&mut self) -> String {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_millis())
+            .unwrap_or_default();
+        if millis > self.last_millis {
+            self.last_millis = millis;
+            self.sequence = 0;
+        } else {
+            self.sequence += 1;
+        }
+        format!("{}-{}", self.last_millis, self.sequence)
+    }
+}
+
+struct Connection {
+    framed: Framed<TcpStream, RespCodec>,
+    authenticator: Option<Arc<Authenticator>>,
+    // with authentication enabled, commands run on behalf of the identity of `AUTH`
+    authenticated: Option<Identity>,
+    identity: String,
+    anonymous: bool,
+    // subscribers this connection added, anonymous ones are removed again when it closes
+    subscribed: HashSet<(DestinationType, String, String)>,
+    // pub/sub channels, the connection only accepts pub/sub commands while there are any
+    channels: Vec<String>,
+    notifications: Option<Receiver<TopicDestination>>,
+    stream_ids: StreamIds,
+}
+
+impl Connection {
+    async fn run(&mut self) -> io::Result<()> {
+        loop {
+            tokio::select! {
+                command = self.framed.next() => {
+                    let Some(command) = command else {
+                        return Ok(());
+                    };
+                    let authenticated = self.authenticated.clone();
+                    if !identity::on_behalf_of(authenticated, self.handle_command(command?)).await? {
+                        return Ok(());
+                    }
+                }
+                notification = next_notification(&mut self.notifications) => {
+                    let channels = match notification {
+                        Ok(destination)
+                            if destination.destination_type == DestinationType::MessageTopic
+                                && self.channels.contains(&destination.destination) =>
+                        {
+                            vec![destination.destination]
+                        }
+                        Ok(_) => continue,
+                        Err(RecvError::Lagged(_)) => self.channels.clone(),
+                        Err(RecvError::Closed) => return Ok(()),
+                    };
+                    for channel in channels {
+                        let authenticated = self.authenticated.clone();
+                        identity::on_behalf_of(authenticated, self.deliver_channel(&channel)).await?;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns false when the connection has to be closed.
+    async fn handle_command(&mut self, arguments: Vec<Bytes>) -> io::Result<bool> {
+        let command = String::from_utf8_lossy(&arguments[0]).to_uppercase();
+        let arguments = &arguments[1..];
+
+        if !self.channels.is_empty()
+            && !["SUBSCRIBE", "UNSUBSCRIBE", "PING", "QUIT"].contains(&command.as_str())
+        {
+            let message = format!(
+                "Can't execute '{}': only SUBSCRIBE / UNSUBSCRIBE / PING / QUIT are allowed in this context",
+                command.to_lowercase()
+            );
+            self.framed.send(RespValue::error(&message)).await?;
+            return Ok(true);
+        }
+
+        if self.authenticator.is_some()
+            && self.authenticated.is_none()
+            && !["PING", "ECHO", "QUIT", "AUTH"].contains(&command.as_str())
+        {
+            self.framed
+                .send(RespValue::Error(
+                    "NOAUTH Authentication required.".to_string(),
+                ))
+                .await?;
+            return Ok(true);
+        }
+
+        let result = match command.as_str() {
+            "PING" => self.ping(arguments),
+            "ECHO" => match arguments {
+                [message] => Ok(RespValue::Bulk(message.clone())),
+                _ => Err(wrong_arguments(&command)),
+            },
+            "QUIT" => {
+                self.framed.send(RespValue::ok()).await?;
+                return Ok(false);
+            }
+            "AUTH" => self.auth(arguments),
+            "SELECT" => match arguments {
+                [index] if index.as_ref() == b"0" => Ok(RespValue::ok()),
+                [_] => Err("DB index is out of range".to_string()),
+                _ => Err(wrong_arguments(&command)),
+            },
+            "CLIENT" => self.client(arguments),
+            "COMMAND" => Ok(RespValue::Array(Vec::new())),
+            "INFO" => Ok(self.info().await),
+            "LPUSH" | "RPUSH" => self.push(&command, arguments).await,
+            "BLPOP" | "BRPOP" => self.pop(&command, arguments).await,
+            "XADD" => self.add_to_stream(arguments).await,
+            "XREAD" => self.read_streams(&command, arguments, None).await,
+            "XREADGROUP" => match arguments {
+                [group, group_name, _consumer, rest @ ..]
+                    if group.eq_ignore_ascii_case(b"GROUP") =>
+                {
+                    match text(group_name) {
+                        Ok(group_name) => self.read_streams(&command, rest, Some(group_name)).await,
+                        Err(e) => Err(e),
+                    }
+                }
+                _ => Err(wrong_arguments(&command)),
+            },
+            // messages count as consumed once they have been read
+            "XACK" => match arguments {
+                [_, _, ids @ ..] if !ids.is_empty() => Ok(RespValue::Integer(ids.len() as i64)),
+                _ => Err(wrong_arguments(&command)),
+            },
+            "PUBLISH" => self.publish(&command, arguments).await,
+            "SUBSCRIBE" => {
+                self.subscribe(&command, arguments).await?;
+                return Ok(true);
+            }
+            "UNSUBSCRIBE" => {
+                self.unsubscribe(arguments).await?;
+                return Ok(true);
+            }
+            _ => Err(format!("unknown command '{}'", command.to_lowercase())),
+        };
+
+        let reply = result.unwrap_or_else(|message| RespValue::error(&message));
+        self.framed.send(reply).await?;
+        Ok(true)
+    }
+
+    fn ping(&self, arguments: &[Bytes]) -> CommandResult {
+        let message = match arguments {
+            [] => None,
+            [message] => Some(message.clone()),
+            _ => return Err(wrong_arguments("PING")),
+        };
+        // in pub/sub mode a PING is answered like a published message
+        if !self.channels.is_empty() {
+            return Ok(RespValue::Array(vec![
+                RespValue::bulk("pong"),
+                RespValue::Bulk(message.unwrap_or_default()),
+            ]));
+        }
+        Ok(match message {
+            Some(message) => RespValue::Bulk(message),
+            None => RespValue::Simple("PONG".to_string()),
+        })
+    }
+
+    // the username becomes the identity used for publishing and subscribing
+    fn auth(&mut self, arguments: &[Bytes]) -> CommandResult {
+        if !self.subscribed.is_empty() {
+            return Err("AUTH has to be sent before subscribing".to_string());
+        }
+        let Some(authenticator) = &self.authenticator else {
+            let [username, _password] = arguments else {
+                return Err("AUTH requires a username and a password".to_string());
+            };
+            self.identity = text(username)?;
+            self.anonymous = false;
+            return Ok(RespValue::ok());
+        };
+
+        // the password is the API key or JWT, without a username the first principal is used
+        let (username, password) = match arguments {
+            [password] => (None, password),
+            [username, password] => (Some(text(username)?), password),
+            _ => return Err(wrong_arguments("AUTH")),
+        };
+        let identity = authenticator
+            .authenticate(&text(password)?)
+            .map_err(|e| {
+                warn!("RespServer::auth rejected a connection: {}", e);
+                "WRONGPASS invalid username-password pair or user is disabled."
+            })
+            .and_then(
+                |identity| match username.or_else(|| identity.principals.first().cloned()) {
+                    Some(username) if identity.acts_as(&username) => Ok((identity, username)),
+                    _ => Err("WRONGPASS invalid username-password pair or user is disabled."),
+                },
+            );
+        match identity {
+            Ok((identity, username)) => {
+                self.authenticated = Some(identity);
+                self.identity = username;
+                self.anonymous = false;
+                Ok(RespValue::ok())
+            }
+            // like redis, the error carries its own code instead of ERR
+            Err(message) => {
+                self.authenticated = None;
+                Ok(RespValue::Error(message.to_string()))
+            }
+        }
+    }
+
+    fn client(&self, arguments: &[Bytes]) -> CommandResult {
+        let subcommand = arguments
+            .first()
+            .map(|subcommand| String::from_utf8_lossy(subcommand).to_uppercase())
+            .ok_or_else(|| wrong_arguments("CLIENT"))?;
+        match subcommand.as_str() {
+            "SETNAME" | "SETINFO" => Ok(RespValue::ok()),
+            "GETNAME" => Ok(RespValue::Null),
+            "ID" => Ok(RespValue::Integer(0)),
+            _ => Err(format!(
+                "unknown subcommand '{}'",
+                subcommand.to_lowercase()
+            )),
+        }
+    }
+
+    async fn info(&self) -> RespValue {
+        let message_topics = message_topic_service::get_all_message_topics()
+            .await
+            .map(|topics| topics.len())
+            .unwrap_or_default();
+        let task_topics = task_topic_service::get_all_task_topics()
+            .await
+            .map(|topics| topics.len())
+            .unwrap_or_default();
+        let info = format!(
+            "# Server\r\nredis_version:{}\r\nredis_mode:standalone\r\nradish_mq_version:{}\r\n\r\n# Keyspace\r\nmessage_topics:{}\r\ntask_topics:{}\r\n",
+            REDIS_VERSION,
+            env!("CARGO_PKG_VERSION"),
+            message_topics,
+            task_topics
+        );
+        RespValue::bulk(info)
+    }
+
+    // both ends of the list append, tasks are always handed out in publish order
+    async fn push(&mut self, command: &str, arguments: &[Bytes]) -> CommandResult {
+        let [key, values @ ..] = arguments else {
+            return Err(wrong_arguments(command));
+        };
+        if values.is_empty() {
+            return Err(wrong_arguments(command));
+        }
+        let topic = text(key)?;
+        let values = texts(values)?;
+
+        for value in &values {
+            task_topic_service::publish_to_task_topic(
+                topic.clone(),
+                self.identity.clone(),
+                PublishToTaskTopic::new(value.clone()),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+        Ok(RespValue::Integer(values.len() as i64))
+    }
+
+    async fn pop(&mut self, command: &str, arguments: &[Bytes]) -> CommandResult {
+        let [keys @ .., timeout] = arguments else {
+            return Err(wrong_arguments(command));
+        };
+        if keys.is_empty() {
+            return Err(wrong_arguments(command));
+        }
+        let deadline = deadline(timeout, Duration::from_secs(1))?;
+        let topics = texts(keys)?;
+        for topic in &topics {
+            self.subscribe_to_task_topic(topic)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        let mut notifications = notification_service::subscribe_to_notifications();
+        loop {
+            for topic in &topics {
+                let Some(task) = task_topic_service::get_new_task_for_subscriber(
+                    topic.clone(),
+                    self.identity.clone(),
+                )
+                .await
+                .map_err(|e| e.to_string())?
+                else {
+                    continue;
+                };
+
+                // a popped task is done, there is no way to acknowledge it later
+                task_topic_service::complete_task(
+                    topic.clone(),
+                    self.identity.clone(),
+                    task.id,
+                    CompleteTask::new(TopicType::new()),
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+                return Ok(RespValue::Array(vec![
+                    RespValue::bulk(topic.clone()),
+                    RespValue::bulk(task.data),
+                ]));
+            }
+            if !wait_for_data(
+                &mut notifications,
+                DestinationType::TaskTopic,
+                &topics,
+                deadline,
+            )
+            .await
+            {
+                return Ok(RespValue::NullArray);
+            }
+        }
+    }
+
+    async fn add_to_stream(&mut self, arguments: &[Bytes]) -> CommandResult {
+        let [key, id, fields @ ..] = arguments else {
+            return Err(wrong_arguments("XADD"));
+        };
+        if fields.is_empty() || fields.len() % 2 != 0 {
+            return Err(wrong_arguments("XADD"));
+        }
+        if id.as_ref() != b"*" {
+            return Err("only generated ids ('*') are supported".to_string());
+        }
+        let topic = text(key)?;
+
+        let mut data = None;
+        let mut headers = Headers::new();
+        for pair in texts(fields)?.chunks(2) {
+            if pair[0] == DATA_FIELD {
+                data = Some(pair[1].clone());
+            } else {
+                headers.insert(pair[0].clone(), pair[1].clone());
+            }
+        }
+        let data = data.ok_or(format!("XADD requires a '{}' field", DATA_FIELD))?;
+
+        message_topic_service::publish_to_message_topic(
+            topic,
+            self.identity.clone(),
+            PublishToMessageTopic::new(data, headers),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(RespValue::bulk(self.stream_ids.next()))
+    }
+
+    // reads continue at the offset of the subscriber, which is the group or the connection's identity
+    async fn read_streams(
+        &mut self,
+        command: &str,
+        arguments: &[Bytes],
+        group: Option<String>,
+    ) -> CommandResult {
+        let mut deadline_option = None;
+        let mut blocking = false;
+        let mut position = 0;
+        while position < arguments.len() {
+            let option = String::from_utf8_lossy(&arguments[position]).to_uppercase();
+            match (option.as_str(), arguments.get(position + 1)) {
+                ("STREAMS", _) => break,
+                // every new message is returned, so the count is only validated
+                ("COUNT", Some(count)) => {
+                    text(count)?
+                        .parse::<usize>()
+                        .map_err(|_| "value is not an integer or out of range")?;
+                    position += 2;
+                }
+                ("BLOCK", Some(timeout)) => {
+                    deadline_option = deadline(timeout, Duration::from_millis(1))?;
+                    blocking = true;
+                    position += 2;
+                }
+                ("NOACK", _) if group.is_some() => position += 1,
+                _ => return Err("syntax error".to_string()),
+            }
+        }
+
+        let streams = arguments.get(position + 1..).unwrap_or_default();
+        if streams.is_empty() || streams.len() % 2 != 0 {
+            return Err(format!(
+                "Unbalanced '{}' list of streams: for each stream key an ID must be specified",
+                command.to_lowercase()
+            ));
+        }
+        let (keys, ids) = streams.split_at(streams.len() / 2);
+        let topics = texts(keys)?;
+
+        // only new messages exist for a group, there are no pending entries to read again
+        let mut readable = Vec::new();
+        for (topic, id) in topics.iter().zip(ids) {
+            if group.is_some() && id.as_ref() != b">" {
+                continue;
+            }
+            readable.push(topic.clone());
+        }
+        let subscriber = group.unwrap_or_else(|| self.identity.clone());
+        for topic in &readable {
+            self.subscribe_to_message_topic(topic, &subscriber)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        let mut notifications = notification_service::subscribe_to_notifications();
+        loop {
+            let mut entries = Vec::new();
+            for topic in &readable {
+                let messages = message_topic_service::get_new_data_for_subscriber(
+                    topic.clone(),
+                    subscriber.clone(),
+                )
+                .await
+                .map_err(|e| e.to_string())?
+                .data;
+                if !messages.is_empty() {
+                    entries.push(RespValue::Array(vec![
+                        RespValue::bulk(topic.clone()),
+                        RespValue::Array(
+                            messages
+                                .into_iter()
+                                .map(|message| self.stream_entry(message))
+                                .collect(),
+                        ),
+                    ]));
+                }
+            }
+            if !entries.is_empty() {
+                return Ok(RespValue::Array(entries));
+            }
+            if !blocking
+                || readable.is_empty()
+                || !wait_for_data(
+                    &mut notifications,
+                    DestinationType::MessageTopic,
+                    &readable,
+                    deadline_option,
+                )
+                .await
+            {
+                return Ok(RespValue::NullArray);
+            }
+        }
+    }
+
+    fn stream_entry(&mut self, message: Message<TopicType>) -> RespValue {
+        let mut fields = vec![RespValue::bulk(DATA_FIELD), RespValue::bulk(message.data)];
+        for (name, value) in message.headers {
+            fields.push(RespValue::bulk(name));
+            fields.push(RespValue::bulk(value));
+        }
+        RespValue::Array(vec![
+            RespValue::bulk(self.stream_ids.next()),
+            RespValue::Array(fields),
+        ])
+    }
+
+    async fn publish(&mut self, command: &str, arguments: &[Bytes]) -> CommandResult {
+        let [channel, message] = arguments else {
+            return Err(wrong_arguments(command));
+        };
+        let channel = text(channel)?;
+
+        message_topic_service::publish_to_message_topic(
+            channel.clone(),
+            self.identity.clone(),
+            PublishToMessageTopic::new(text(message)?, Headers::new()),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let receivers = message_topic_service::get_message_topic(channel)
+            .await
+            .map(|topic| topic.subscriber.len())
+            .unwrap_or_default();
+        Ok(RespValue::Integer(receivers as i64))
+    }
+
+    async fn subscribe(&mut self, command: &str, arguments: &[Bytes]) -> io::Result<()> {
+        if arguments.is_empty() {
+            return self
+                .framed
+                .send(RespValue::error(&wrong_arguments(command)))
+                .await;
+        }
+        let channels = match texts(arguments) {
+            Ok(channels) => channels,
+            Err(message) => return self.framed.send(RespValue::error(&message)).await,
+        };
+
+        for channel in channels {
+            let identity = self.identity.clone();
+            if let Err(e) = self.subscribe_to_message_topic(&channel, &identity).await {
+                self.framed.send(RespValue::error(&e.to_string())).await?;
+                continue;
+            }
+            if !self.channels.contains(&channel) {
+                self.channels.push(channel.clone());
+            }
+            if self.notifications.is_none() {
+                self.notifications = Some(notification_service::subscribe_to_notifications());
+            }
+            self.framed
+                .send(RespValue::Array(vec![
+                    RespValue::bulk("subscribe"),
+                    RespValue::bulk(channel.clone()),
+                    RespValue::Integer(self.channels.len() as i64),
+                ]))
+                .await?;
+            self.deliver_channel(&channel).await?;
+        }
+        Ok(())
+    }
+
+    async fn unsubscribe(&mut self, arguments: &[Bytes]) -> io::Result<()> {
+        let channels = match texts(arguments) {
+            Ok(channels) if channels.is_empty() => self.channels.clone(),
+            Ok(channels) => channels,
+            Err(message) => return self.framed.send(RespValue::error(&message)).await,
+        };
+
+        if channels.is_empty() {
+            return self
+                .framed
+                .send(RespValue::Array(vec![
+                    RespValue::bulk("unsubscribe"),
+                    RespValue::Null,
+                    RespValue::Integer(0),
+                ]))
+                .await;
+        }
+        for channel in channels {
+            self.channels.retain(|subscribed| *subscribed != channel);
+            self.framed
+                .send(RespValue::Array(vec![
+                    RespValue::bulk("unsubscribe"),
+                    RespValue::bulk(channel),
+                    RespValue::Integer(self.channels.len() as i64),
+                ]))
+                .await?;
+        }
+        if self.channels.is_empty() {
+            self.notifications = None;
+        }
+        Ok(())
+    }
+
+    async fn deliver_channel(&mut self, channel: &str) -> io::Result<()> {
+        let Ok(data) = message_topic_service::get_new_data_for_subscriber(
+            channel.to_string(),
+            self.identity.clone(),
+        )
+        .await
+        else {
+            return Ok(());
+        };
+        for message in data.data {
+            self.framed
+                .send(RespValue::Array(vec![
+                    RespValue::bulk("message"),
+                    RespValue::bulk(channel),
+                    RespValue::bulk(message.data),
+                ]))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Adds the subscriber to the message topic if needed.
+    async fn subscribe_to_message_topic(
+        &mut self,
+        topic: &str,
+        subscriber: &str,
+    ) -> ServiceResult<()> {
+        match message_topic_service::add_subscriber_to_message_topic(
+            topic.to_string(),
+            AddSubscriberToMessageTopic::new(subscriber.to_string(), None),
+        )
+        .await
+        {
+            Ok(()) => {
+                self.subscribed.insert((
+                    DestinationType::MessageTopic,
+                    topic.to_string(),
+                    subscriber.to_string(),
+                ));
+                Ok(())
+            }
+            Err(ServiceError::AlreadyASubscriber(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn subscribe_to_task_topic(&mut self, topic: &str) -> ServiceResult<()> {
+        match task_topic_service::add_subscriber_to_task_topic(
+            topic.to_string(),
+            AddSubscriberToTaskTopic::new(self.identity.clone()),
+        )
+        .await
+        {
+            Ok(()) => {
+                self.subscribed.insert((
+                    DestinationType::TaskTopic,
+                    topic.to_string(),
+                    self.identity.clone(),
+                ));
+                Ok(())
+            }
+            Err(ServiceError::AlreadyASubscriber(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    // groups and logged in identities keep their offsets, anonymous connections cannot come back
+    async fn close(&mut self) {
+        for (destination_type, topic, subscriber) in self.subscribed.drain() {
+            if !self.anonymous || subscriber != self.identity {
+                continue;
+            }
+            let removed = match destination_type {
+                DestinationType::MessageTopic => {
+                    message_topic_service::remove_subscriber_from_message_topic(
+                        topic,
+                        RemoveSubscriberFromMessageTopic::new(subscriber),
+                    )
+                    .await
+                }
+                DestinationType::TaskTopic => {
+                    task_topic_service::remove_subscriber_from_task_topic(
+                        topic,
+                        RemoveSubscriberFromTaskTopic::new(subscriber),
+                    )
+                    .await
+                }
+            };
+            if let Err(e) = removed {
+                warn!("RespServer::close could not remove a subscriber: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,378 @@
+use crate::auth::grant::Grant;
+use crate::auth::identity::{current_identity, Identity};
+use crate::auth::permission::Action;
+use crate::auth::ADMIN_PRINCIPAL;
+use crate::event_queue::event::{EventCreateAclGrantData, EventDeleteAclGrantData, TopicEvent};
+use crate::model::acl_model::{CreateGrant, GrantModel, Role};
+use crate::model::audit_model::AuditAction;
+use crate::service::audit_service::{self, details};
+use crate::service::error::{ServiceError, ServiceResult};
+use crate::utils::namespace::current_namespace;
+use crate::utils::topic_pattern::{TopicPattern, MULTI_SEGMENT_WILDCARD};
+use crate::STATE;
+use log::warn;
+use uuid::Uuid;
+
+const MAX_NAME_LENGTH: usize = 255;
+
+const ACL_GRANT_RESOURCE: &str = "acl_grant";
+
+/// Fails if the caller is authenticated and neither a grant of its principals nor a permission of
+/// its token allows `action` on the topic. Calls without an identity are trusted.
+pub fn authorize(action: Action, topic_name: &str) -> ServiceResult<()> {
+    match current_identity() {
+        Some(identity) if !is_allowed(&identity, action, topic_name) => Err(
+            ServiceError::PermissionDenied(format!("{}:{}", action, topic_name)),
+        ),
+        _ => Ok(()),
+    }
+}
+
+pub fn is_authorized(action: Action, topic_name: &str) -> bool {
+    authorize(action, topic_name).is_ok()
+}
+
+/// Like `authorize`, for every topic the pattern matches, e.g. the source of a forwarding rule.
+pub fn authorize_pattern(action: Action, pattern: &TopicPattern) -> ServiceResult<()> {
+    match current_identity() {
+        Some(identity)
+            if !identity.may_pattern(action, pattern)
+                && !is_granted(&identity, |grant| grant.allows_pattern(action, pattern)) =>
+        {
+            Err(ServiceError::PermissionDenied(format!(
+                "{}:{}",
+                action,
+                pattern.as_str()
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Authorizes registering or removing a publisher or subscriber. Callers may register themselves
+/// with the publish or subscribe action, whoever may manage the topic may register anybody.
+pub fn authorize_registration(
+    action: Action,
+    topic_name: &str,
+    identifier: &str,
+) -> ServiceResult<()> {
+    match current_identity() {
+        Some(identity) if !is_allowed(&identity, Action::Manage, topic_name) => {
+            if !identity.acts_as(identifier) {
+                return Err(ServiceError::IdentityMismatch(identifier.to_string()));
+            }
+            authorize(action, topic_name)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn is_allowed(identity: &Identity, action: Action, topic_name: &str) -> bool {
+    identity.may(action, topic_name)
+        || is_granted(identity, |grant| grant.allows(action, topic_name))
+}
+
+fn is_granted(identity: &Identity, allows: impl Fn(&Grant) -> bool) -> bool {
+    if identity.acts_as(ADMIN_PRINCIPAL) {
+        return true;
+    }
+
+    let namespace = current_namespace();
+    if let Ok(grants) = STATE.acl_grants.lock() {
+        grants.iter().any(|grant| {
+            grant.namespace == namespace && identity.acts_as(&grant.principal) && allows(grant)
+        })
+    } else {
+        warn!("AclService::is_granted tried to lock a poisoned mutex");
+        false
+    }
+}
+
+/// The ACL of a namespace is managed by the admin principal and by admins of every topic of the
+/// namespace.
+fn authorize_acl_management() -> ServiceResult<()> {
+    let Some(identity) = current_identity() else {
+        return Ok(());
+    };
+    if identity.acts_as(ADMIN_PRINCIPAL) {
+        return Ok(());
+    }
+
+    let namespace = current_namespace();
+    let grants = STATE
+        .acl_grants
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let is_admin = grants.iter().any(|grant| {
+        grant.namespace == namespace
+            && identity.acts_as(&grant.principal)
+            && grant.role == Role::Admin
+            && grant.pattern.as_str() == MULTI_SEGMENT_WILDCARD
+    });
+    is_admin
+        .then_some(())
+        .ok_or(ServiceError::PermissionDenied(format!(
+            "{}:{}",
+            Role::Admin.as_str(),
+            MULTI_SEGMENT_WILDCARD
+        )))
+}
+
+pub async fn get_all_grants(principal: Option<String>) -> ServiceResult<Vec<GrantModel>> {
+    let namespace = current_namespace();
+    authorize_acl_management()?;
+    let grants = STATE
+        .acl_grants
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    Ok(grants
+        .iter()
+        .filter(|grant| grant.namespace == namespace)
+        .filter(|grant| principal.as_ref().is_none_or(|p| &grant.principal == p))
+        .map(|grant| grant.to_model())
+        .collect())
+}
+
+pub async fn get_grant(id: Uuid) -> ServiceResult<GrantModel> {
+    let namespace = current_namespace();
+    authorize_acl_management()?;
+    let grants = STATE
+        .acl_grants
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    grants
+        .iter()
+        .find(|grant| grant.namespace == namespace && grant.id == id)
+        .map(|grant| grant.to_model())
+        .ok_or(ServiceError::GrantNotFound(id))
+}
+
+pub async fn create_grant(dts: CreateGrant) -> ServiceResult<GrantModel> {
+    let namespace = current_namespace();
+    authorize_acl_management()?;
+    if dts.principal.is_empty() || dts.principal.len() > MAX_NAME_LENGTH {
+        return Err(ServiceError::InvalidName(dts.principal));
+    }
+    let pattern = TopicPattern::parse(&dts.pattern)
+        .filter(|_| dts.pattern.len() <= MAX_NAME_LENGTH)
+        .ok_or_else(|| ServiceError::InvalidPattern(dts.pattern.clone()))?;
+
+    let mut grants = STATE
+        .acl_grants
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    if let Some(existing) = grants.iter().find(|grant| {
+        grant.namespace == namespace
+            && grant.principal == dts.principal
+            && grant.role == dts.role
+            && grant.pattern == pattern
+    }) {
+        return Err(ServiceError::GrantExists(existing.id));
+    }
+
+    let grant = Grant::new(Uuid::new_v4(), namespace, dts.principal, dts.role, pattern);
+    grants.push(grant.clone());
+
+    create_create_grant_event(grant.clone());
+
+    let model = grant.to_model();
+    audit_service::record(
+        AuditAction::GrantCreated,
+        ACL_GRANT_RESOURCE,
+        &grant.id.to_string(),
+        None,
+        details(&model),
+    );
+    Ok(model)
+}
+
+fn create_create_grant_event(grant: Grant) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::CreateAclGrant(EventCreateAclGrantData::new(grant));
+        queue.enqueue(event);
+    } else {
+        warn!("AclService::create_create_grant_event tried to lock a poisoned mutex");
+    }
+}
+
+pub async fn delete_grant(id: Uuid) -> ServiceResult<GrantModel> {
+    let namespace = current_namespace();
+    authorize_acl_management()?;
+    let mut grants = STATE
+        .acl_grants
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let index = grants
+        .iter()
+        .position(|grant| grant.namespace == namespace && grant.id == id)
+        .ok_or(ServiceError::GrantNotFound(id))?;
+    let grant = grants.remove(index);
+
+    create_delete_grant_event(id);
+
+    let model = grant.to_model();
+    audit_service::record(
+        AuditAction::GrantDeleted,
+        ACL_GRANT_RESOURCE,
+        &id.to_string(),
+        details(&model),
+        None,
+    );
+    Ok(model)
+}
+
+fn create_delete_grant_event(grant_id: Uuid) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::DeleteAclGrant(EventDeleteAclGrantData::new(grant_id));
+        queue.enqueue(event);
+    } else {
+        warn!("AclService::create_delete_grant_event tried to lock a poisoned mutex");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::identity;
+    use crate::auth::permission::Permission;
+    use crate::utils::namespace;
+
+    fn bob(permissions: &[&str]) -> Identity {
+        Identity::new("bob".to_string(), vec!["acl-grant-test.bob".to_string()]).with_permissions(
+            permissions
+                .iter()
+                .map(|permission| Permission::parse(permission).unwrap())
+                .collect(),
+        )
+    }
+
+    async fn grant(namespace: &str, principal: &str, role: Role, pattern: &str) {
+        let grant = CreateGrant::new(principal.to_string(), role, pattern.to_string());
+        namespace::scope(namespace.to_string(), create_grant(grant))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn grants_allow_their_role_on_matching_topics() {
+        grant(
+            "acl-grant-test",
+            "acl-grant-test.bob",
+            Role::Publisher,
+            "orders.*",
+        )
+        .await;
+
+        let checks = async {
+            assert!(is_authorized(Action::Publish, "orders.eu"));
+            assert!(is_authorized(Action::Read, "orders.eu"));
+            assert!(!is_authorized(Action::Subscribe, "orders.eu"));
+            assert!(!is_authorized(Action::Manage, "orders.eu"));
+            assert!(!is_authorized(Action::Publish, "orders.eu.de"));
+            assert!(matches!(
+                authorize(Action::Publish, "billing"),
+                Err(ServiceError::PermissionDenied(_))
+            ));
+        };
+        identity::scope(
+            bob(&[]),
+            namespace::scope("acl-grant-test".to_string(), checks),
+        )
+        .await;
+
+        // grants only apply in the namespace they are created in
+        identity::scope(bob(&[]), async {
+            assert!(!is_authorized(Action::Publish, "orders.eu"));
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn token_permissions_and_the_admin_need_no_grant() {
+        identity::scope(bob(&["subscribe:acl-token-test.#"]), async {
+            assert!(is_authorized(Action::Subscribe, "acl-token-test.orders.eu"));
+            assert!(!is_authorized(Action::Publish, "acl-token-test.orders.eu"));
+            let pattern = TopicPattern::parse("acl-token-test.orders.*").unwrap();
+            assert!(authorize_pattern(Action::Subscribe, &pattern).is_ok());
+            let wider = TopicPattern::parse("#").unwrap();
+            assert!(authorize_pattern(Action::Subscribe, &wider).is_err());
+        })
+        .await;
+
+        let admin = Identity::new(
+            ADMIN_PRINCIPAL.to_string(),
+            vec![ADMIN_PRINCIPAL.to_string()],
+        );
+        identity::scope(admin, async {
+            assert!(is_authorized(Action::Manage, "acl-token-test.orders"));
+        })
+        .await;
+
+        // calls without an identity run without authentication or come from the broker
+        assert!(is_authorized(Action::Manage, "acl-token-test.orders"));
+    }
+
+    #[tokio::test]
+    async fn only_managers_register_other_identifiers() {
+        identity::scope(bob(&["publish:acl-registration-test"]), async {
+            assert!(authorize_registration(
+                Action::Publish,
+                "acl-registration-test",
+                "acl-grant-test.bob"
+            )
+            .is_ok());
+            assert!(matches!(
+                authorize_registration(Action::Publish, "acl-registration-test", "mallory"),
+                Err(ServiceError::IdentityMismatch(_))
+            ));
+            assert!(matches!(
+                authorize_registration(
+                    Action::Subscribe,
+                    "acl-registration-test",
+                    "acl-grant-test.bob"
+                ),
+                Err(ServiceError::PermissionDenied(_))
+            ));
+        })
+        .await;
+
+        identity::scope(bob(&["manage:acl-registration-test"]), async {
+            assert!(
+                authorize_registration(Action::Subscribe, "acl-registration-test", "mallory")
+                    .is_ok()
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn the_acl_is_managed_by_admins_of_every_topic() {
+        let create = || {
+            let grant = CreateGrant::new(
+                "acl-admin-test.carol".to_string(),
+                Role::Subscriber,
+                "orders".to_string(),
+            );
+            identity::scope(
+                bob(&[]),
+                namespace::scope("acl-admin-test".to_string(), create_grant(grant)),
+            )
+        };
+
+        grant(
+            "acl-admin-test",
+            "acl-grant-test.bob",
+            Role::Admin,
+            "orders.#",
+        )
+        .await;
+        assert!(matches!(
+            create().await,
+            Err(ServiceError::PermissionDenied(_))
+        ));
+
+        grant("acl-admin-test", "acl-grant-test.bob", Role::Admin, "#").await;
+        assert!(create().await.is_ok());
+        assert!(matches!(create().await, Err(ServiceError::GrantExists(_))));
+    }
+}
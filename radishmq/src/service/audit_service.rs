@@ -0,0 +1,130 @@
+use crate::auth::identity::current_identity;
+use crate::auth::ADMIN_PRINCIPAL;
+use crate::event_queue::event::{EventAppendAuditEntryData, TopicEvent};
+use crate::model::audit_model::{AuditAction, AuditEntryModel, AuditFilter};
+use crate::repository::audit_repository;
+use crate::service::credential_service::authorize_identifier;
+use crate::service::error::{ServiceError, ServiceResult};
+use crate::utils::namespace::current_namespace;
+use crate::utils::peer_address::current_peer_address;
+use crate::STATE;
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream};
+use log::warn;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+const EXPORT_PAGE_SIZE: i64 = 500;
+
+/// The state of a resource as recorded in the audit log.
+pub fn details<T: Serialize>(value: &T) -> Option<Value> {
+    serde_json::to_value(value).ok()
+}
+
+/// Appends a change of the running request to the audit log, recording the caller, its address
+/// and the namespace the change happened in.
+pub fn record(
+    action: AuditAction,
+    resource_type: &str,
+    resource: &str,
+    before: Option<Value>,
+    after: Option<Value>,
+) {
+    let entry = AuditEntryModel {
+        id: Uuid::new_v4(),
+        occurred_at: Utc::now(),
+        namespace: current_namespace(),
+        actor: current_identity().map(|identity| identity.name),
+        address: current_peer_address(),
+        action,
+        resource_type: resource_type.to_string(),
+        resource: resource.to_string(),
+        before,
+        after,
+    };
+
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::AppendAuditEntry(EventAppendAuditEntryData::new(entry));
+        queue.enqueue(event);
+    } else {
+        warn!("AuditService::record tried to lock a poisoned mutex");
+    }
+}
+
+pub async fn get_entries(
+    db_connection_pool: &Pool<Postgres>,
+    filter: AuditFilter,
+    limit: Option<i64>,
+) -> ServiceResult<Vec<AuditEntryModel>> {
+    let namespace = current_namespace();
+    authorize_identifier(ADMIN_PRINCIPAL)?;
+    get_page(db_connection_pool, namespace, filter, None, limit).await
+}
+
+/// Every entry matching `filter`, read from the database one page at a time as the stream is
+/// polled. The caller is authorized up front, the stream ends after the first failed page.
+pub fn export_entries(
+    db_connection_pool: Pool<Postgres>,
+    filter: AuditFilter,
+) -> ServiceResult<impl Stream<Item = ServiceResult<Vec<AuditEntryModel>>>> {
+    let namespace = current_namespace();
+    authorize_identifier(ADMIN_PRINCIPAL)?;
+
+    Ok(stream::unfold(Some(None), move |cursor| {
+        let db_connection_pool = db_connection_pool.clone();
+        let namespace = namespace.clone();
+        let filter = filter.clone();
+        async move {
+            let after = cursor?;
+            match get_page(
+                &db_connection_pool,
+                namespace,
+                filter,
+                after,
+                Some(EXPORT_PAGE_SIZE),
+            )
+            .await
+            {
+                Ok(entries) if entries.is_empty() => None,
+                Ok(entries) => {
+                    let next = entries.last().map(|entry| (entry.occurred_at, entry.id));
+                    Some((Ok(entries), Some(next)))
+                }
+                Err(e) => Some((Err(e), None)),
+            }
+        }
+    }))
+}
+
+async fn get_page(
+    db_connection_pool: &Pool<Postgres>,
+    namespace: String,
+    filter: AuditFilter,
+    after: Option<(DateTime<Utc>, Uuid)>,
+    limit: Option<i64>,
+) -> ServiceResult<Vec<AuditEntryModel>> {
+    let entities =
+        audit_repository::get_entries(db_connection_pool, namespace, filter, after, limit)
+            .await
+            .map_err(|e| ServiceError::DatabaseUnavailable(e.to_string()))?;
+
+    Ok(entities
+        .into_iter()
+        .filter_map(|entity| {
+            Some(AuditEntryModel {
+                id: entity.id,
+                occurred_at: entity.occurred_at,
+                namespace: entity.namespace,
+                actor: entity.actor,
+                address: entity.address,
+                action: entity.action.parse().ok()?,
+                resource_type: entity.resource_type,
+                resource: entity.resource,
+                before: entity.before,
+                after: entity.after,
+            })
+        })
+        .collect())
+}
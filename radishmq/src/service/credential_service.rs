@@ -0,0 +1,175 @@
+use crate::auth::credential::{generate_secret, hash_secret, Credential};
+use crate::auth::identity::{current_identity, Identity};
+use crate::auth::ADMIN_PRINCIPAL;
+use crate::event_queue::event::{EventCreateCredentialData, EventDeleteCredentialData, TopicEvent};
+use crate::model::audit_model::AuditAction;
+use crate::model::credential_model::{CreateCredential, CreatedCredential, CredentialModel};
+use crate::service::audit_service::{self, details};
+use crate::service::error::{ServiceError, ServiceResult};
+use crate::utils::namespace::current_namespace;
+use crate::STATE;
+use log::warn;
+use std::env;
+
+const MAX_NAME_LENGTH: usize = 255;
+
+const CREDENTIAL_RESOURCE: &str = "credential";
+
+/// Fails if the caller is authenticated but may not act as `identifier`. Calls without an
+/// identity are trusted, they either run without authentication or come from within the broker.
+pub fn authorize_identifier(identifier: &str) -> ServiceResult<()> {
+    match current_identity() {
+        Some(identity) if !identity.acts_as(identifier) => {
+            Err(ServiceError::IdentityMismatch(identifier.to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Looks up the identity of an API key, `AUTH_ADMIN_API_KEY` authenticates as the admin.
+pub fn authenticate(secret: &str) -> Option<Identity> {
+    let secret_hash = hash_secret(secret);
+
+    if let Ok(admin_secret) = env::var("AUTH_ADMIN_API_KEY") {
+        if hash_secret(&admin_secret) == secret_hash {
+            return Some(Identity::new(
+                ADMIN_PRINCIPAL.to_string(),
+                vec![ADMIN_PRINCIPAL.to_string()],
+            ));
+        }
+    }
+
+    if let Ok(credentials) = STATE.credentials.lock() {
+        credentials
+            .iter()
+            .find(|credential| credential.secret_hash == secret_hash)
+            .map(|credential| {
+                Identity::new(credential.name.clone(), credential.principals.clone())
+                    .with_namespace(credential.namespace.clone())
+            })
+    } else {
+        warn!("CredentialService::authenticate tried to lock a poisoned mutex");
+        None
+    }
+}
+
+pub async fn get_all_credentials() -> ServiceResult<Vec<CredentialModel>> {
+    let namespace = current_namespace();
+    authorize_identifier(ADMIN_PRINCIPAL)?;
+    let credentials = STATE
+        .credentials
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    Ok(credentials
+        .iter()
+        .filter(|credential| credential.namespace == namespace)
+        .map(|credential| credential.to_model())
+        .collect())
+}
+
+pub async fn get_credential(name: String) -> ServiceResult<CredentialModel> {
+    let namespace = current_namespace();
+    authorize_identifier(ADMIN_PRINCIPAL)?;
+    let credentials = STATE
+        .credentials
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    credentials
+        .iter()
+        .find(|credential| credential.namespace == namespace && credential.name == name)
+        .map(|credential| credential.to_model())
+        .ok_or(ServiceError::CredentialNotFound(name))
+}
+
+pub async fn create_credential(dts: CreateCredential) -> ServiceResult<CreatedCredential> {
+    let namespace = current_namespace();
+    authorize_identifier(ADMIN_PRINCIPAL)?;
+    if dts.name.is_empty() || dts.name.len() > MAX_NAME_LENGTH {
+        return Err(ServiceError::InvalidName(dts.name));
+    }
+    if let Some(principal) = dts
+        .principals
+        .iter()
+        .find(|principal| principal.is_empty() || principal.len() > MAX_NAME_LENGTH)
+    {
+        return Err(ServiceError::InvalidName(principal.clone()));
+    }
+
+    let mut credentials = STATE
+        .credentials
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    if credentials
+        .iter()
+        .any(|credential| credential.namespace == namespace && credential.name == dts.name)
+    {
+        return Err(ServiceError::CredentialExists(dts.name));
+    }
+
+    let secret = generate_secret();
+    let credential = Credential::new(namespace, dts.name, hash_secret(&secret), dts.principals);
+    credentials.push(credential.clone());
+
+    create_create_credential_event(credential.clone());
+    // the secret is only ever shown to the creator
+    audit_service::record(
+        AuditAction::CredentialCreated,
+        CREDENTIAL_RESOURCE,
+        &credential.name,
+        None,
+        details(&credential.to_model()),
+    );
+
+    Ok(CreatedCredential::new(
+        credential.name,
+        credential.principals,
+        secret,
+    ))
+}
+
+fn create_create_credential_event(credential: Credential) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::CreateCredential(EventCreateCredentialData::new(credential));
+        queue.enqueue(event);
+    } else {
+        warn!("CredentialService::create_create_credential_event tried to lock a poisoned mutex");
+    }
+}
+
+pub async fn delete_credential(name: String) -> ServiceResult<CredentialModel> {
+    let namespace = current_namespace();
+    authorize_identifier(ADMIN_PRINCIPAL)?;
+    let mut credentials = STATE
+        .credentials
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let index = credentials
+        .iter()
+        .position(|credential| credential.namespace == namespace && credential.name == name)
+        .ok_or_else(|| ServiceError::CredentialNotFound(name.clone()))?;
+    let credential = credentials.remove(index);
+
+    create_delete_credential_event(namespace, name.clone());
+
+    let model = credential.to_model();
+    audit_service::record(
+        AuditAction::CredentialDeleted,
+        CREDENTIAL_RESOURCE,
+        &name,
+        details(&model),
+        None,
+    );
+    Ok(model)
+}
+
+fn create_delete_credential_event(namespace: String, credential_name: String) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::DeleteCredential(EventDeleteCredentialData::new(
+            namespace,
+            credential_name,
+        ));
+        queue.enqueue(event);
+    } else {
+        warn!("CredentialService::create_delete_credential_event tried to lock a poisoned mutex");
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt;
+use uuid::Uuid;
+
+/// The reasons a topic operation can be rejected, shared by all listeners which map them to
+/// their own status codes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServiceError {
+    TopicNotFound(String),
+    AlreadyExists(String),
+    InvalidName(String),
+    InvalidFilter,
+    NotAPublisher(String),
+    NotASubscriber(String),
+    AlreadyAPublisher(String),
+    AlreadyASubscriber(String),
+    TaskNotFound(Uuid),
+    TaskNotLeased(Uuid),
+    IdentityMismatch(String),
+    PermissionDenied(String),
+    CredentialNotFound(String),
+    CredentialExists(String),
+    GrantNotFound(Uuid),
+    GrantExists(Uuid),
+    InvalidPattern(String),
+    ExchangeNotFound(String),
+    ExchangeExists(String),
+    BindingNotFound(Uuid),
+    InvalidBinding(String),
+    ForwardingRuleNotFound(String),
+    ForwardingRuleExists(String),
+    /// The subscriber and, if only one subscription was asked for, its pattern.
+    WildcardSubscriptionNotFound(String, Option<String>),
+    WildcardSubscriptionExists(String, String),
+    /// The exceeded quota and, for rate limits, the seconds until a retry can succeed. Storage and
+    /// topic limits have none, they only pass once data or topics are removed.
+    QuotaExceeded(String, Option<u64>),
+    DatabaseUnavailable(String),
+    PoisonedLock,
+}
+
+pub type ServiceResult<T> = Result<T, ServiceError>;
+
+impl ServiceError {
+    /// A stable, machine readable name of the error.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ServiceError::TopicNotFound(_) => "topic-not-found",
+            ServiceError::AlreadyExists(_) => "already-exists",
+            ServiceError::InvalidName(_) => "invalid-name",
+            ServiceError::InvalidFilter => "invalid-filter",
+            ServiceError::NotAPublisher(_) => "not-a-publisher",
+            ServiceError::NotASubscriber(_) => "not-a-subscriber",
+            ServiceError::AlreadyAPublisher(_) => "already-a-publisher",
+            ServiceError::AlreadyASubscriber(_) => "already-a-subscriber",
+            ServiceError::TaskNotFound(_) => "task-not-found",
+            ServiceError::TaskNotLeased(_) => "task-not-leased",
+            ServiceError::IdentityMismatch(_) => "identity-mismatch",
+            ServiceError::PermissionDenied(_) => "permission-denied",
+            ServiceError::CredentialNotFound(_) => "credential-not-found",
+            ServiceError::CredentialExists(_) => "credential-exists",
+            ServiceError::GrantNotFound(_) => "grant-not-found",
+            ServiceError::GrantExists(_) => "grant-exists",
+            ServiceError::InvalidPattern(_) => "invalid-pattern",
+            ServiceError::ExchangeNotFound(_) => "exchange-not-found",
+            ServiceError::ExchangeExists(_) => "exchange-exists",
+            ServiceError::BindingNotFound(_) => "binding-not-found",
+            ServiceError::InvalidBinding(_) => "invalid-binding",
+            ServiceError::ForwardingRuleNotFound(_) => "forwarding-rule-not-found",
+            ServiceError::ForwardingRuleExists(_) => "forwarding-rule-exists",
+            ServiceError::WildcardSubscriptionNotFound(_, _) => "wildcard-subscription-not-found",
+            ServiceError::WildcardSubscriptionExists(_, _) => "wildcard-subscription-exists",
+            ServiceError::QuotaExceeded(_, _) => "quota-exceeded",
+            ServiceError::DatabaseUnavailable(_) => "database-unavailable",
+            ServiceError::PoisonedLock => "internal-error",
+        }
+    }
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceError::TopicNotFound(topic) => write!(f, "topic '{}' does not exist", topic),
+            ServiceError::AlreadyExists(topic) => write!(f, "topic '{}' already exists", topic),
+            ServiceError::InvalidName(name) => write!(f, "'{}' is not a valid name", name),
+            ServiceError::InvalidFilter => write!(f, "the message filter is not valid"),
+            ServiceError::NotAPublisher(identifier) => {
+                write!(f, "'{}' is not a publisher of the topic", identifier)
+            }
+            ServiceError::NotASubscriber(identifier) => {
+                write!(f, "'{}' is not a subscriber of the topic", identifier)
+            }
+            ServiceError::AlreadyAPublisher(identifier) => {
+                write!(f, "'{}' is already a publisher of the topic", identifier)
+            }
+            ServiceError::AlreadyASubscriber(identifier) => {
+                write!(f, "'{}' is already a subscriber of the topic", identifier)
+            }
+            ServiceError::TaskNotFound(id) => write!(f, "task '{}' does not exist", id),
+            ServiceError::TaskNotLeased(id) => {
+                write!(f, "task '{}' is not leased by the subscriber", id)
+            }
+            ServiceError::IdentityMismatch(identifier) => {
+                write!(f, "the caller may not act as '{}'", identifier)
+            }
+            ServiceError::PermissionDenied(permission) => {
+                write!(f, "the caller lacks the permission '{}'", permission)
+            }
+            ServiceError::CredentialNotFound(name) => {
+                write!(f, "credential '{}' does not exist", name)
+            }
+            ServiceError::CredentialExists(name) => {
+                write!(f, "credential '{}' already exists", name)
+            }
+            ServiceError::GrantNotFound(id) => write!(f, "grant '{}' does not exist", id),
+            ServiceError::GrantExists(id) => write!(f, "an equal grant '{}' already exists", id),
+            ServiceError::InvalidPattern(pattern) => {
+                write!(f, "'{}' is not a valid topic pattern", pattern)
+            }
+            ServiceError::ExchangeNotFound(name) => write!(f, "exchange '{}' does not exist", name),
+            ServiceError::ExchangeExists(name) => write!(f, "exchange '{}' already exists", name),
+            ServiceError::BindingNotFound(id) => write!(f, "binding '{}' does not exist", id),
+            ServiceError::InvalidBinding(reason) => {
+                write!(f, "the binding is not valid: {}", reason)
+            }
+            ServiceError::ForwardingRuleNotFound(name) => {
+                write!(f, "forwarding rule '{}' does not exist", name)
+            }
+            ServiceError::ForwardingRuleExists(name) => {
+                write!(f, "forwarding rule '{}' already exists", name)
+            }
+            ServiceError::WildcardSubscriptionNotFound(subscriber, None) => {
+                write!(f, "'{}' has no wildcard subscription", subscriber)
+            }
+            ServiceError::WildcardSubscriptionNotFound(subscriber, Some(pattern)) => {
+                write!(
+                    f,
+                    "'{}' has no wildcard subscription to '{}'",
+                    subscriber, pattern
+                )
+            }
+            ServiceError::WildcardSubscriptionExists(subscriber, pattern) => {
+                write!(
+                    f,
+                    "'{}' already has a wildcard subscription to '{}'",
+                    subscriber, pattern
+                )
+            }
+            ServiceError::QuotaExceeded(quota, _) => write!(f, "the quota {} is exceeded", quota),
+            ServiceError::DatabaseUnavailable(error) => {
+                write!(f, "the database is unavailable: {}", error)
+            }
+            ServiceError::PoisonedLock => write!(f, "the topic state is unavailable"),
+        }
+    }
+}
+
+impl std::error::Error for ServiceError {}
@@ -0,0 +1,460 @@
+use crate::auth::permission::Action;
+use crate::event_queue::event::{
+    EventAddBindingToExchangeData, EventAddPublisherToExchangeData, EventCreateExchangeData,
+    EventDeleteExchangeData, EventRemoveBindingFromExchangeData,
+    EventRemovePublisherFromExchangeData, TopicEvent,
+};
+use crate::model::audit_model::AuditAction;
+use crate::model::exchange_model::{
+    AddBindingToExchange, AddPublisherToExchange, BindingModel, CreateExchange, ExchangeModel,
+    PublishToExchange, RemovePublisherFromExchange, RoutedMessageExchange,
+};
+use crate::model::message_topic_model::Message;
+use crate::service::acl_service::{authorize, authorize_registration, is_authorized};
+use crate::service::audit_service::{self, details};
+use crate::service::credential_service::authorize_identifier;
+use crate::service::error::{ServiceError, ServiceResult};
+use crate::service::routing_service::{destination_exists, route_to_destination};
+use crate::topic::exchange::{Binding, Exchange};
+use crate::utils::topic_pattern::is_valid_topic_name;
+use crate::utils::types::TopicType;
+use crate::STATE;
+use log::warn;
+use uuid::Uuid;
+
+const EXCHANGE_RESOURCE: &str = "exchange";
+
+pub async fn get_all_exchanges() -> ServiceResult<Vec<ExchangeModel>> {
+    let exchanges = STATE
+        .exchanges
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    Ok(exchanges
+        .iter()
+        .filter(|exchange| is_authorized(Action::Read, &exchange.name))
+        .map(|exchange| exchange.to_model())
+        .collect())
+}
+
+pub async fn get_exchange(exchange_name: String) -> ServiceResult<ExchangeModel> {
+    authorize(Action::Read, &exchange_name)?;
+    let exchanges = STATE
+        .exchanges
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    exchanges
+        .iter()
+        .find(|exchange| exchange.name == exchange_name)
+        .map(|exchange| exchange.to_model())
+        .ok_or(ServiceError::ExchangeNotFound(exchange_name))
+}
+
+pub async fn create_exchange(dts: CreateExchange) -> ServiceResult<ExchangeModel> {
+    if !is_valid_topic_name(&dts.name) {
+        return Err(ServiceError::InvalidName(dts.name));
+    }
+    authorize(Action::Manage, &dts.name)?;
+    let mut exchanges = STATE
+        .exchanges
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    if exchanges.iter().any(|exchange| exchange.name == dts.name) {
+        return Err(ServiceError::ExchangeExists(dts.name));
+    }
+    let exchange = Exchange::new(dts.name.clone(), dts.exchange_type);
+    exchanges.push(exchange.clone());
+
+    create_create_exchange_event(dts);
+
+    let model = exchange.to_model();
+    audit_service::record(
+        AuditAction::ExchangeCreated,
+        EXCHANGE_RESOURCE,
+        &model.name,
+        None,
+        details(&model),
+    );
+    Ok(model)
+}
+
+fn create_create_exchange_event(dts: CreateExchange) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::CreateExchange(EventCreateExchangeData::new(dts));
+        queue.enqueue(event);
+    } else {
+        warn!("ExchangeService::create_create_exchange_event tried to lock a poisoned mutex");
+    }
+}
+
+pub async fn delete_exchange(exchange_name: String) -> ServiceResult<ExchangeModel> {
+    authorize(Action::Manage, &exchange_name)?;
+    let mut exchanges = STATE
+        .exchanges
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let index = exchanges
+        .iter()
+        .position(|exchange| exchange.name == exchange_name)
+        .ok_or_else(|| ServiceError::ExchangeNotFound(exchange_name.clone()))?;
+    let exchange = exchanges.remove(index);
+
+    create_delete_exchange_event(exchange_name.clone());
+
+    let model = exchange.to_model();
+    audit_service::record(
+        AuditAction::ExchangeDeleted,
+        EXCHANGE_RESOURCE,
+        &exchange_name,
+        details(&model),
+        None,
+    );
+    Ok(model)
+}
+
+fn create_delete_exchange_event(exchange_name: String) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::DeleteExchange(EventDeleteExchangeData::new(exchange_name));
+        queue.enqueue(event);
+    } else {
+        warn!("ExchangeService::create_delete_exchange_event tried to lock a poisoned mutex");
+    }
+}
+
+pub async fn add_binding_to_exchange(
+    exchange_name: String,
+    dts: AddBindingToExchange,
+) -> ServiceResult<BindingModel> {
+    // routing publishes on behalf of the binding, so its creator has to be allowed to publish
+    authorize(Action::Manage, &exchange_name)?;
+    authorize(Action::Publish, &dts.destination.destination)?;
+    // checked before the exchange lock is taken, so binding never holds two state locks
+    if !destination_exists(&dts.destination)? {
+        return Err(ServiceError::TopicNotFound(dts.destination.destination));
+    }
+    let mut exchanges = STATE
+        .exchanges
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let exchange = exchanges
+        .iter_mut()
+        .find(|exchange| exchange.name == exchange_name)
+        .ok_or_else(|| ServiceError::ExchangeNotFound(exchange_name.clone()))?;
+    exchange
+        .validate_binding(dts.routing_key.as_deref(), &dts.arguments)
+        .map_err(ServiceError::InvalidBinding)?;
+
+    let binding = Binding::new(
+        Uuid::new_v4(),
+        dts.destination,
+        dts.routing_key,
+        dts.arguments,
+        dts.header_match,
+    );
+    exchange.add_binding(binding.clone());
+
+    let model = binding.to_model();
+    audit_service::record(
+        AuditAction::BindingAdded,
+        EXCHANGE_RESOURCE,
+        &exchange_name,
+        None,
+        details(&model),
+    );
+    create_add_binding_to_exchange_event(exchange_name, model.clone());
+
+    Ok(model)
+}
+
+fn create_add_binding_to_exchange_event(exchange_name: String, binding: BindingModel) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::AddBindingExchange(EventAddBindingToExchangeData::new(
+            exchange_name,
+            binding,
+        ));
+        queue.enqueue(event);
+    } else {
+        warn!(
+            "ExchangeService::create_add_binding_to_exchange_event tried to lock a poisoned mutex"
+        );
+    }
+}
+
+pub async fn remove_binding_from_exchange(
+    exchange_name: String,
+    binding_id: Uuid,
+) -> ServiceResult<BindingModel> {
+    authorize(Action::Manage, &exchange_name)?;
+    let mut exchanges = STATE
+        .exchanges
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let exchange = exchanges
+        .iter_mut()
+        .find(|exchange| exchange.name == exchange_name)
+        .ok_or_else(|| ServiceError::ExchangeNotFound(exchange_name.clone()))?;
+    let binding = exchange
+        .remove_binding(binding_id)
+        .ok_or(ServiceError::BindingNotFound(binding_id))?;
+
+    let model = binding.to_model();
+    audit_service::record(
+        AuditAction::BindingRemoved,
+        EXCHANGE_RESOURCE,
+        &exchange_name,
+        details(&model),
+        None,
+    );
+    create_remove_binding_from_exchange_event(exchange_name, binding_id);
+
+    Ok(model)
+}
+
+fn create_remove_binding_from_exchange_event(exchange_name: String, binding_id: Uuid) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::RemoveBindingExchange(EventRemoveBindingFromExchangeData::new(
+            exchange_name,
+            binding_id,
+        ));
+        queue.enqueue(event);
+    } else {
+        warn!("ExchangeService::create_remove_binding_from_exchange_event tried to lock a poisoned mutex");
+    }
+}
+
+pub async fn add_publisher_to_exchange(
+    exchange_name: String,
+    dts: AddPublisherToExchange,
+) -> ServiceResult<()> {
+    authorize_registration(Action::Publish, &exchange_name, &dts.publisher)?;
+    let mut exchanges = STATE
+        .exchanges
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let exchange = exchanges
+        .iter_mut()
+        .find(|exchange| exchange.name == exchange_name)
+        .ok_or_else(|| ServiceError::ExchangeNotFound(exchange_name.clone()))?;
+    if exchange.is_publisher(dts.publisher.clone()) {
+        return Err(ServiceError::AlreadyAPublisher(dts.publisher));
+    }
+    exchange.add_publisher(dts.publisher.clone());
+
+    audit_service::record(
+        AuditAction::PublisherAdded,
+        EXCHANGE_RESOURCE,
+        &exchange_name,
+        None,
+        details(&dts),
+    );
+    create_add_publisher_to_exchange_event(exchange_name, dts);
+    Ok(())
+}
+
+fn create_add_publisher_to_exchange_event(exchange_name: String, dts: AddPublisherToExchange) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::AddPublisherExchange(EventAddPublisherToExchangeData::new(
+            exchange_name,
+            dts,
+        ));
+        queue.enqueue(event);
+    } else {
+        warn!(
+            "ExchangeService::create_add_publisher_to_exchange_event tried to lock a poisoned mutex"
+        );
+    }
+}
+
+pub async fn remove_publisher_from_exchange(
+    exchange_name: String,
+    dts: RemovePublisherFromExchange,
+) -> ServiceResult<()> {
+    authorize_registration(Action::Publish, &exchange_name, &dts.publisher)?;
+    let mut exchanges = STATE
+        .exchanges
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let exchange = exchanges
+        .iter_mut()
+        .find(|exchange| exchange.name == exchange_name)
+        .ok_or_else(|| ServiceError::ExchangeNotFound(exchange_name.clone()))?;
+    if !exchange.is_publisher(dts.publisher.clone()) {
+        return Err(ServiceError::NotAPublisher(dts.publisher));
+    }
+    exchange.remove_publisher(dts.publisher.clone());
+
+    audit_service::record(
+        AuditAction::PublisherRemoved,
+        EXCHANGE_RESOURCE,
+        &exchange_name,
+        details(&dts),
+        None,
+    );
+    create_remove_publisher_from_exchange_event(exchange_name, dts);
+    Ok(())
+}
+
+fn create_remove_publisher_from_exchange_event(
+    exchange_name: String,
+    dts: RemovePublisherFromExchange,
+) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::RemovePublisherExchange(EventRemovePublisherFromExchangeData::new(
+            exchange_name,
+            dts,
+        ));
+        queue.enqueue(event);
+    } else {
+        warn!("ExchangeService::create_remove_publisher_from_exchange_event tried to lock a poisoned mutex");
+    }
+}
+
+pub async fn publish_to_exchange(
+    exchange_name: String,
+    publisher_identifier: String,
+    dts: PublishToExchange<TopicType>,
+) -> ServiceResult<RoutedMessageExchange> {
+    authorize_identifier(&publisher_identifier)?;
+    authorize(Action::Publish, &exchange_name)?;
+
+    // the exchange lock is released before routing, so publishing never holds two state locks
+    let destinations = {
+        let exchanges = STATE
+            .exchanges
+            .lock()
+            .map_err(|_| ServiceError::PoisonedLock)?;
+        let exchange = exchanges
+            .iter()
+            .find(|exchange| exchange.name == exchange_name)
+            .ok_or_else(|| ServiceError::ExchangeNotFound(exchange_name.clone()))?;
+        if !exchange.is_publisher(publisher_identifier.clone()) {
+            return Err(ServiceError::NotAPublisher(publisher_identifier));
+        }
+        exchange.route(&dts.routing_key, &dts.headers)
+    };
+
+    // an exceeded quota answers the publish, destinations routed before it keep the message
+    let message = Message::new(dts.data, dts.headers);
+    let mut routed = Vec::new();
+    for destination in destinations {
+        if route_to_destination(&destination, &publisher_identifier, message.clone())? {
+            routed.push(destination);
+        }
+    }
+
+    Ok(RoutedMessageExchange::new(routed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::identity::{self, Identity};
+    use crate::auth::permission::Permission;
+    use crate::model::exchange_model::ExchangeType;
+    use crate::topic::message_topic::MessageTopic;
+    use crate::utils::namespace::DEFAULT_NAMESPACE;
+    use actix_web::http::StatusCode;
+    use actix_web::ResponseError;
+    use serde_json::json;
+
+    fn alice() -> Identity {
+        Identity::new("alice".to_string(), vec!["alice".to_string()])
+    }
+
+    fn assert_forbidden<T: std::fmt::Debug>(result: ServiceResult<T>) {
+        let error = result.expect_err("alice has no grant");
+        assert_eq!(error.status_code(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn denies_exchange_operations_without_a_grant() {
+        let name = "acl-test.exchange".to_string();
+        create_exchange(CreateExchange::new(name.clone(), ExchangeType::Fanout))
+            .await
+            .unwrap();
+        let binding = || -> AddBindingToExchange {
+            serde_json::from_value(json!({
+                "destination": "acl-test.topic",
+                "destination_type": "message_topic"
+            }))
+            .unwrap()
+        };
+
+        identity::scope(alice(), async {
+            let exchanges = get_all_exchanges().await.unwrap();
+            assert!(exchanges.iter().all(|exchange| exchange.name != name));
+            assert_forbidden(get_exchange(name.clone()).await);
+            assert_forbidden(
+                create_exchange(CreateExchange::new(
+                    "acl-test.other".to_string(),
+                    ExchangeType::Fanout,
+                ))
+                .await,
+            );
+            assert_forbidden(delete_exchange(name.clone()).await);
+            assert_forbidden(add_binding_to_exchange(name.clone(), binding()).await);
+            assert_forbidden(remove_binding_from_exchange(name.clone(), Uuid::new_v4()).await);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn binding_needs_publish_on_the_destination() {
+        let name = "acl-test.binding".to_string();
+        create_exchange(CreateExchange::new(name.clone(), ExchangeType::Fanout))
+            .await
+            .unwrap();
+        let binding: AddBindingToExchange = serde_json::from_value(json!({
+            "destination": "acl-test.private",
+            "destination_type": "message_topic"
+        }))
+        .unwrap();
+        let owner =
+            alice().with_permissions(vec![Permission::parse("manage:acl-test.binding").unwrap()]);
+
+        identity::scope(owner, async {
+            assert!(get_exchange(name.clone()).await.is_ok());
+            assert_forbidden(add_binding_to_exchange(name.clone(), binding).await);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn headers_binding_needs_arguments_and_an_existing_destination() {
+        let name = "validation-test.headers".to_string();
+        create_exchange(CreateExchange::new(name.clone(), ExchangeType::Headers))
+            .await
+            .unwrap();
+        STATE.message_topics.lock().unwrap().push(MessageTopic::new(
+            DEFAULT_NAMESPACE.to_string(),
+            "validation-test.topic".to_string(),
+        ));
+        let binding = |destination: &str, arguments: serde_json::Value| -> AddBindingToExchange {
+            serde_json::from_value(json!({
+                "destination": destination,
+                "destination_type": "message_topic",
+                "arguments": arguments
+            }))
+            .unwrap()
+        };
+
+        assert!(matches!(
+            add_binding_to_exchange(name.clone(), binding("validation-test.topic", json!({})))
+                .await,
+            Err(ServiceError::InvalidBinding(_))
+        ));
+        assert!(matches!(
+            add_binding_to_exchange(
+                name.clone(),
+                binding("validation-test.missing", json!({"format": "pdf"}))
+            )
+            .await,
+            Err(ServiceError::TopicNotFound(_))
+        ));
+        assert!(add_binding_to_exchange(
+            name.clone(),
+            binding("validation-test.topic", json!({"format": "pdf"}))
+        )
+        .await
+        .is_ok());
+    }
+}
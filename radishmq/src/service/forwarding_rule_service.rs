@@ -0,0 +1,348 @@
+use crate::auth::permission::Action;
+use crate::event_queue::event::{
+    EventCreateForwardingRuleData, EventDeleteForwardingRuleData, TopicEvent,
+};
+use crate::model::audit_model::AuditAction;
+use crate::model::destination_model::DestinationType;
+use crate::model::forwarding_rule_model::{CreateForwardingRule, ForwardingRuleModel};
+use crate::model::message_topic_model::{Message, PublishToMessageTopic};
+use crate::model::task_topic_model::PublishToTaskTopic;
+use crate::service::acl_service::{authorize, authorize_pattern, is_authorized};
+use crate::service::audit_service::{self, details};
+use crate::service::error::{ServiceError, ServiceResult};
+use crate::service::{message_topic_service, task_topic_service};
+use crate::topic::forwarding_rule::ForwardingRule;
+use crate::utils::namespace::current_namespace;
+use crate::utils::topic_pattern::{is_valid_topic_name, TopicPattern};
+use crate::utils::types::TopicType;
+use crate::STATE;
+use log::warn;
+
+const FORWARDING_RULE_RESOURCE: &str = "forwarding_rule";
+
+pub async fn get_all_forwarding_rules() -> ServiceResult<Vec<ForwardingRuleModel>> {
+    let namespace = current_namespace();
+    let rules = STATE
+        .forwarding_rules
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    Ok(rules
+        .iter()
+        .filter(|rule| rule.namespace == namespace && is_authorized(Action::Read, &rule.name))
+        .map(|rule| rule.to_model())
+        .collect())
+}
+
+pub async fn get_forwarding_rule(rule_name: String) -> ServiceResult<ForwardingRuleModel> {
+    let namespace = current_namespace();
+    authorize(Action::Read, &rule_name)?;
+    let rules = STATE
+        .forwarding_rules
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    rules
+        .iter()
+        .find(|rule| rule.is_named(&namespace, &rule_name))
+        .map(|rule| rule.to_model())
+        .ok_or(ServiceError::ForwardingRuleNotFound(rule_name))
+}
+
+pub async fn create_forwarding_rule(
+    dts: CreateForwardingRule,
+) -> ServiceResult<ForwardingRuleModel> {
+    let namespace = current_namespace();
+    if !is_valid_topic_name(&dts.name) {
+        return Err(ServiceError::InvalidName(dts.name));
+    }
+    if !is_valid_topic_name(&dts.destination.destination) {
+        return Err(ServiceError::InvalidName(dts.destination.destination));
+    }
+    let source = TopicPattern::parse(&dts.source)
+        .ok_or_else(|| ServiceError::InvalidPattern(dts.source.clone()))?;
+    // forwarding publishes on behalf of the rule, so its creator has to be allowed to subscribe
+    // to every source topic and to publish to the destination
+    authorize(Action::Manage, &dts.name)?;
+    authorize_pattern(Action::Subscribe, &source)?;
+    authorize(Action::Publish, &dts.destination.destination)?;
+
+    let mut rules = STATE
+        .forwarding_rules
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    if rules
+        .iter()
+        .any(|rule| rule.is_named(&namespace, &dts.name))
+    {
+        return Err(ServiceError::ForwardingRuleExists(dts.name));
+    }
+    let rule = ForwardingRule::new(
+        namespace.clone(),
+        dts.name.clone(),
+        source,
+        dts.destination.clone(),
+        dts.header_rewrite.clone(),
+    );
+    rules.push(rule.clone());
+
+    create_create_forwarding_rule_event(namespace, dts);
+
+    let model = rule.to_model();
+    audit_service::record(
+        AuditAction::ForwardingRuleCreated,
+        FORWARDING_RULE_RESOURCE,
+        &model.name,
+        None,
+        details(&model),
+    );
+    Ok(model)
+}
+
+fn create_create_forwarding_rule_event(namespace: String, dts: CreateForwardingRule) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event =
+            TopicEvent::CreateForwardingRule(EventCreateForwardingRuleData::new(namespace, dts));
+        queue.enqueue(event);
+    } else {
+        warn!("ForwardingRuleService::create_create_forwarding_rule_event tried to lock a poisoned mutex");
+    }
+}
+
+pub async fn delete_forwarding_rule(rule_name: String) -> ServiceResult<ForwardingRuleModel> {
+    let namespace = current_namespace();
+    authorize(Action::Manage, &rule_name)?;
+    let mut rules = STATE
+        .forwarding_rules
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let index = rules
+        .iter()
+        .position(|rule| rule.is_named(&namespace, &rule_name))
+        .ok_or_else(|| ServiceError::ForwardingRuleNotFound(rule_name.clone()))?;
+    let rule = rules.remove(index);
+
+    create_delete_forwarding_rule_event(namespace, rule_name.clone());
+
+    let model = rule.to_model();
+    audit_service::record(
+        AuditAction::ForwardingRuleDeleted,
+        FORWARDING_RULE_RESOURCE,
+        &rule_name,
+        details(&model),
+        None,
+    );
+    Ok(model)
+}
+
+fn create_delete_forwarding_rule_event(namespace: String, rule_name: String) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::DeleteForwardingRule(EventDeleteForwardingRuleData::new(
+            namespace, rule_name,
+        ));
+        queue.enqueue(event);
+    } else {
+        warn!("ForwardingRuleService::create_delete_forwarding_rule_event tried to lock a poisoned mutex");
+    }
+}
+
+/// Forwards a message published to a topic along the matching rules of its namespace. A
+/// destination whose quota is exceeded is skipped, the message already reached the topic it was
+/// published to.
+pub fn forward_message(topic_name: &str, publisher_identifier: &str, message: Message<TopicType>) {
+    forward_message_from(
+        &current_namespace(),
+        topic_name,
+        publisher_identifier,
+        message,
+        &mut vec![topic_name.to_string()],
+    );
+}
+
+// every message topic is visited at most once per publish, so rule cycles cannot loop forever
+fn forward_message_from(
+    namespace: &str,
+    topic_name: &str,
+    publisher_identifier: &str,
+    message: Message<TopicType>,
+    visited: &mut Vec<String>,
+) {
+    let rules: Vec<ForwardingRule> = if let Ok(rules) = STATE.forwarding_rules.lock() {
+        rules
+            .iter()
+            .filter(|rule| rule.matches(namespace, topic_name))
+            .cloned()
+            .collect()
+    } else {
+        warn!("ForwardingRuleService::forward_message tried to lock a poisoned mutex");
+        return;
+    };
+
+    for rule in rules {
+        let forwarded = rule.rewrite(message.clone());
+        let destination = rule.destination.destination.clone();
+
+        match rule.destination.destination_type {
+            DestinationType::MessageTopic => {
+                if visited.contains(&destination) {
+                    warn!(
+                        "ForwardingRuleService::forward_message skipped rule '{}' because message_topic '{}' already received the message",
+                        rule.name, destination
+                    );
+                    continue;
+                }
+                visited.push(destination.clone());
+
+                match message_topic_service::append_to_message_topic(
+                    destination.clone(),
+                    publisher_identifier,
+                    PublishToMessageTopic::new(forwarded.data.clone(), forwarded.headers.clone()),
+                ) {
+                    Ok(()) => forward_message_from(
+                        namespace,
+                        &destination,
+                        publisher_identifier,
+                        forwarded,
+                        visited,
+                    ),
+                    Err(e) => warn!(
+                        "ForwardingRuleService::forward_message could not apply rule '{}': {}",
+                        rule.name, e
+                    ),
+                }
+            }
+            DestinationType::TaskTopic => {
+                if let Err(e) = task_topic_service::route_to_task_topic(
+                    destination,
+                    publisher_identifier,
+                    PublishToTaskTopic::new(forwarded.data),
+                ) {
+                    warn!(
+                        "ForwardingRuleService::forward_message could not apply rule '{}': {}",
+                        rule.name, e
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::identity::{self, Identity};
+    use crate::auth::permission::Permission;
+    use crate::model::destination_model::TopicDestination;
+    use crate::topic::message_topic::MessageTopic;
+    use crate::utils::namespace::{self, DEFAULT_NAMESPACE};
+    use actix_web::http::StatusCode;
+    use actix_web::ResponseError;
+
+    fn alice(permissions: &[&str]) -> Identity {
+        Identity::new("alice".to_string(), vec!["alice".to_string()]).with_permissions(
+            permissions
+                .iter()
+                .map(|permission| Permission::parse(permission).unwrap())
+                .collect(),
+        )
+    }
+
+    fn rule(name: &str, source: &str) -> CreateForwardingRule {
+        CreateForwardingRule::new(
+            name.to_string(),
+            source.to_string(),
+            TopicDestination::new(DestinationType::MessageTopic, "acl-test.audit".to_string()),
+            Default::default(),
+        )
+    }
+
+    fn assert_forbidden<T: std::fmt::Debug>(result: ServiceResult<T>) {
+        let error = result.expect_err("alice is not allowed to");
+        assert_eq!(error.status_code(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn denies_forwarding_rule_operations_without_a_grant() {
+        create_forwarding_rule(rule("acl-test.rule", "acl-test.orders"))
+            .await
+            .unwrap();
+
+        identity::scope(alice(&[]), async {
+            let rules = get_all_forwarding_rules().await.unwrap();
+            assert!(rules.iter().all(|rule| rule.name != "acl-test.rule"));
+            assert_forbidden(get_forwarding_rule("acl-test.rule".to_string()).await);
+            assert_forbidden(
+                create_forwarding_rule(rule("acl-test.other", "acl-test.orders")).await,
+            );
+            assert_forbidden(delete_forwarding_rule("acl-test.rule".to_string()).await);
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn source_pattern_needs_subscribe_on_every_matching_topic() {
+        let permissions = [
+            "manage:acl-test.rules.#",
+            "subscribe:acl-test.orders.*",
+            "publish:acl-test.audit",
+        ];
+
+        identity::scope(alice(&permissions), async {
+            assert_forbidden(
+                create_forwarding_rule(rule("acl-test.rules.wide", "acl-test.orders.#")).await,
+            );
+            assert!(
+                create_forwarding_rule(rule("acl-test.rules.narrow", "acl-test.orders.*"))
+                    .await
+                    .is_ok()
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn rules_only_forward_within_their_namespace() {
+        let depth = |namespace: &str, name: &str| {
+            STATE
+                .message_topics
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|topic| topic.is_named(namespace, name))
+                .unwrap()
+                .depth()
+        };
+        if let Ok(mut topics) = STATE.message_topics.lock() {
+            for namespace in [DEFAULT_NAMESPACE, "ns-test"] {
+                topics.push(MessageTopic::new(
+                    namespace.to_string(),
+                    "ns-test.audit".to_string(),
+                ));
+            }
+        }
+        let rule = CreateForwardingRule::new(
+            "ns-test.rule".to_string(),
+            "ns-test.orders".to_string(),
+            TopicDestination::new(DestinationType::MessageTopic, "ns-test.audit".to_string()),
+            Default::default(),
+        );
+        namespace::scope("ns-test".to_string(), create_forwarding_rule(rule))
+            .await
+            .unwrap();
+
+        forward_message(
+            "ns-test.orders",
+            "p",
+            Message::new("a".to_string(), Default::default()),
+        );
+        assert_eq!(depth(DEFAULT_NAMESPACE, "ns-test.audit"), 0);
+
+        namespace::scope("ns-test".to_string(), async {
+            forward_message(
+                "ns-test.orders",
+                "p",
+                Message::new("b".to_string(), Default::default()),
+            );
+        })
+        .await;
+        assert_eq!(depth("ns-test", "ns-test.audit"), 1);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::event_queue::worker::{running_workers, spawned_workers};
+use crate::model::health_model::{ComponentHealth, HealthModel};
+use crate::STATE;
+use sqlx::{Pool, Postgres};
+use std::collections::BTreeMap;
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+const DATABASE_TIMEOUT: Duration = Duration::from_secs(2);
+
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Marks the state as restored and the workers and listeners as started.
+pub fn mark_started() {
+    STARTED.store(true, Ordering::SeqCst);
+}
+
+pub fn is_started() -> bool {
+    STARTED.load(Ordering::SeqCst)
+}
+
+fn max_event_queue_depth() -> usize {
+    env::var("HEALTH_MAX_EVENT_QUEUE_DEPTH")
+        .unwrap_or("10000".to_string())
+        .parse()
+        .unwrap_or(10000)
+}
+
+/// The broker is live as long as it answers, the components are checked by `readiness`.
+pub fn liveness() -> HealthModel {
+    HealthModel::new(BTreeMap::new())
+}
+
+/// Checks whether the broker can serve requests: the state is loaded, Postgres answers, every
+/// worker is running and the workers keep up with the events.
+pub async fn readiness(db_connection_pool: &Pool<Postgres>) -> HealthModel {
+    let mut components = BTreeMap::new();
+    components.insert("startup", startup_health());
+    components.insert("database", database_health(db_connection_pool).await);
+    components.insert("workers", workers_health());
+    components.insert("event_queue", event_queue_health());
+    HealthModel::new(components)
+}
+
+fn startup_health() -> ComponentHealth {
+    if is_started() {
+        ComponentHealth::new(true, "the broker has started".to_string())
+    } else {
+        ComponentHealth::new(false, "the broker is still starting".to_string())
+    }
+}
+
+async fn database_health(db_connection_pool: &Pool<Postgres>) -> ComponentHealth {
+    let result = tokio::time::timeout(
+        DATABASE_TIMEOUT,
+        sqlx::query("SELECT 1").execute(db_connection_pool),
+    )
+    .await;
+
+    match result {
+        Ok(Ok(_)) => ComponentHealth::new(true, "Postgres is reachable".to_string()),
+        Ok(Err(e)) => ComponentHealth::new(false, format!("Postgres is not reachable: {}", e)),
+        Err(_) => ComponentHealth::new(
+            false,
+            format!(
+                "Postgres did not answer within {} seconds",
+                DATABASE_TIMEOUT.as_secs()
+            ),
+        ),
+    }
+}
+
+fn workers_health() -> ComponentHealth {
+    let spawned = spawned_workers();
+    let running = running_workers();
+    ComponentHealth::new(
+        running == spawned && spawned > 0,
+        format!("{} of {} workers are running", running, spawned),
+    )
+}
+
+fn event_queue_health() -> ComponentHealth {
+    let max_depth = max_event_queue_depth();
+    match STATE.event_queue.lock() {
+        Ok(queue) => {
+            let depth = queue.len();
+            ComponentHealth::new(
+                depth <= max_depth,
+                format!(
+                    "{} events are waiting, at most {} may wait",
+                    depth, max_depth
+                ),
+            )
+        }
+        Err(_) => ComponentHealth::new(false, "the event queue is poisoned".to_string()),
+    }
+}
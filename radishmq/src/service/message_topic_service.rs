@@ -1,89 +1,148 @@
+use crate::auth::permission::Action;
 use crate::event_queue::event::{
     EventAddPublisherToMessageTopicData, EventAddSubscriberToMessageTopicData,
     EventCreateMessageTopicData, EventDeleteMessageTopicData, EventFetchDataFromMessageTopicData,
     EventPublishToMessageTopicData, EventRemovePublisherFromMessageTopicData,
-    EventRemoveSubscriberFromMessageTopicData, TopicEvent,
+    EventRemoveSubscriberFromMessageTopicData, PersistenceAck, TopicEvent,
 };
+use crate::metrics;
+use crate::model::audit_model::AuditAction;
+use crate::model::destination_model::DestinationType;
 use crate::model::message_topic_model::{
     AddPublisherToMessageTopic, AddSubscriberToMessageTopic, CreateMessageTopic, DataMessageTopic,
-    MessageTopicModel, NewDataMessageTopic, PublishToMessageTopic, RemovePublisherFromMessageTopic,
-    RemoveSubscriberFromMessageTopic,
+    Message, MessageTopicModel, NewDataMessageTopic, PublishToMessageTopic,
+    RemovePublisherFromMessageTopic, RemoveSubscriberFromMessageTopic,
+};
+use crate::service::acl_service::{authorize, authorize_registration, is_authorized};
+use crate::service::audit_service::{self, details};
+use crate::service::credential_service::authorize_identifier;
+use crate::service::error::{ServiceError, ServiceResult};
+use crate::service::{
+    forwarding_rule_service, notification_service, quota_service, wildcard_subscription_service,
 };
 use crate::topic::message_topic::MessageTopic;
+use crate::utils::namespace::current_namespace;
+use crate::utils::topic_pattern::is_valid_topic_name;
 use crate::utils::types::TopicType;
 use crate::STATE;
 use log::warn;
+use tokio::sync::oneshot;
 
-pub async fn get_all_message_topics() -> Vec<MessageTopicModel> {
-    if let Ok(topics) = STATE.message_topics.lock() {
-        topics.iter().map(|topic| topic.to_model()).collect()
-    } else {
-        warn!("TopicService::get_all_message_topics tried to lock a poisoned mutex");
-        Vec::new()
-    }
+pub async fn get_all_message_topics() -> ServiceResult<Vec<MessageTopicModel>> {
+    let namespace = current_namespace();
+    let topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    Ok(topics
+        .iter()
+        .filter(|topic| topic.namespace == namespace)
+        .filter(|topic| is_authorized(Action::Read, &topic.name))
+        .map(|topic| topic.to_model())
+        .collect())
 }
 
-pub async fn get_message_topic(topic_name: String) -> Option<MessageTopicModel> {
-    if let Ok(topics) = STATE.message_topics.lock() {
-        topics
-            .iter()
-            .find(|topic| topic.name == topic_name)
-            .map(|topic| topic.to_model())
-    } else {
-        warn!("TopicService::get_message_topic tried to lock a poisoned mutex");
-        None
-    }
+pub async fn get_message_topic(topic_name: String) -> ServiceResult<MessageTopicModel> {
+    let namespace = current_namespace();
+    authorize(Action::Read, &topic_name)?;
+    let topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    topics
+        .iter()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .map(|topic| topic.to_model())
+        .ok_or(ServiceError::TopicNotFound(topic_name))
 }
 
-pub async fn create_message_topic(dts: CreateMessageTopic) -> Option<MessageTopicModel> {
-    if let Ok(mut topics) = STATE.message_topics.lock() {
-        let topic_name = dts.name.clone();
-        if topics.iter().any(|topic| topic.name == topic_name) {
-            warn!("TopicService::create_message_topic tried to create a message_topic that already exists");
-            return None;
-        }
-        let topic = MessageTopic::new(topic_name.clone());
-        topics.push(topic.clone());
+pub async fn create_message_topic(dts: CreateMessageTopic) -> ServiceResult<MessageTopicModel> {
+    let namespace = current_namespace();
+    authorize(Action::Manage, &dts.name)?;
+    quota_service::admit_topic_creation(&namespace)?;
+    let mut topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic_name = dts.name.clone();
+    if !is_valid_topic_name(&topic_name) {
+        return Err(ServiceError::InvalidName(topic_name));
+    }
+    if topics
+        .iter()
+        .any(|topic| topic.is_named(&namespace, &topic_name))
+    {
+        return Err(ServiceError::AlreadyExists(topic_name));
+    }
+    let mut topic = MessageTopic::new(namespace.clone(), topic_name.clone());
 
-        create_create_message_topic_event(dts.clone());
+    create_create_message_topic_event(namespace.clone(), dts.clone());
 
-        Some(topic.to_model())
-    } else {
-        warn!("TopicService::create_message_topic tried to lock a poisoned mutex");
-        None
+    for subscriber in
+        wildcard_subscription_service::claim_new_message_topic(&namespace, &topic_name)
+    {
+        topic.add_subscriber(subscriber.subscriber.clone(), subscriber.filter.clone());
+
+        create_add_subscriber_to_message_topic_event(
+            namespace.clone(),
+            topic_name.clone(),
+            subscriber,
+        );
     }
+    topics.push(topic.clone());
+
+    let model = topic.to_model();
+    audit_service::record(
+        AuditAction::TopicCreated,
+        DestinationType::MessageTopic.as_str(),
+        &model.name,
+        None,
+        details(&model),
+    );
+    Ok(model)
 }
 
-fn create_create_message_topic_event(dts: CreateMessageTopic) {
+fn create_create_message_topic_event(namespace: String, dts: CreateMessageTopic) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
-        let event = TopicEvent::CreateMessageTopic(EventCreateMessageTopicData::new(dts));
+        let event =
+            TopicEvent::CreateMessageTopic(EventCreateMessageTopicData::new(namespace, dts));
         queue.enqueue(event);
     } else {
         warn!("TopicService::create_create_message_topic_event tried to lock a poisoned mutex");
     }
 }
 
-pub async fn delete_message_topic(topic_name: String) -> Option<MessageTopicModel> {
-    if let Ok(mut topics) = STATE.message_topics.lock() {
-        if let Some(index) = topics.iter().position(|topic| topic.name == topic_name) {
-            let topic = topics.remove(index);
+pub async fn delete_message_topic(topic_name: String) -> ServiceResult<MessageTopicModel> {
+    let namespace = current_namespace();
+    authorize(Action::Manage, &topic_name)?;
+    let mut topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let index = topics
+        .iter()
+        .position(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    let topic = topics.remove(index);
 
-            create_delete_message_topic_event(topic_name.clone());
+    quota_service::release_topic(DestinationType::MessageTopic, &namespace, &topic_name);
+    create_delete_message_topic_event(namespace.clone(), topic_name.clone());
 
-            Some(topic.to_model())
-        } else {
-            warn!("TopicService::delete_message_topic tried to delete a message_topic that does not exist");
-            None
-        }
-    } else {
-        warn!("TopicService::delete_message_topic tried to lock a poisoned mutex");
-        None
-    }
+    let model = topic.to_model();
+    audit_service::record(
+        AuditAction::TopicDeleted,
+        DestinationType::MessageTopic.as_str(),
+        &topic_name,
+        details(&model),
+        None,
+    );
+    Ok(model)
 }
 
-fn create_delete_message_topic_event(topic_name: String) {
+fn create_delete_message_topic_event(namespace: String, topic_name: String) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
-        let event = TopicEvent::DeleteMessageTopic(EventDeleteMessageTopicData::new(topic_name));
+        let event =
+            TopicEvent::DeleteMessageTopic(EventDeleteMessageTopicData::new(namespace, topic_name));
         queue.enqueue(event);
     } else {
         warn!("TopicService::create_delete_message_topic_event tried to lock a poisoned mutex");
@@ -94,59 +153,221 @@ pub async fn publish_to_message_topic(
     topic_name: String,
     publisher_identifier: String,
     dts: PublishToMessageTopic<TopicType>,
-) {
-    if let Ok(mut topics) = STATE.message_topics.lock() {
-        if let Some(topic) = topics.iter_mut().find(|topic| topic.name == topic_name) {
-            // check if publisher_identifier is a publisher of the topic
-            if topic.is_publisher(publisher_identifier.clone()) {
-                topic.publish(dts.data.clone());
-
-                create_publish_to_message_topic_event(topic_name.clone(), dts.clone());
-            } else {
-                warn!("TopicService::publish_to_message_topic tried to publish to a message_topic that the publisher is not registered to");
-            }
-        } else {
-            warn!("TopicService::publish_to_message_topic tried to publish to a message_topic that does not exist");
+) -> ServiceResult<()> {
+    publish(topic_name, publisher_identifier, dts, None)
+}
+
+/// Publishes like `publish_to_message_topic`, the returned receiver resolves once the publish
+/// has been persisted.
+pub async fn publish_to_message_topic_with_ack(
+    topic_name: String,
+    publisher_identifier: String,
+    dts: PublishToMessageTopic<TopicType>,
+) -> ServiceResult<oneshot::Receiver<bool>> {
+    let (ack, persisted) = PersistenceAck::new();
+    publish(topic_name, publisher_identifier, dts, Some(ack)).map(|_| persisted)
+}
+
+fn publish(
+    topic_name: String,
+    publisher_identifier: String,
+    dts: PublishToMessageTopic<TopicType>,
+    ack: Option<PersistenceAck>,
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    authorize_identifier(&publisher_identifier)?;
+    authorize(Action::Publish, &topic_name)?;
+    {
+        let mut topics = STATE
+            .message_topics
+            .lock()
+            .map_err(|_| ServiceError::PoisonedLock)?;
+        let topic = topics
+            .iter_mut()
+            .find(|topic| topic.is_named(&namespace, &topic_name))
+            .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+        if !topic.is_publisher(publisher_identifier.clone()) {
+            return Err(ServiceError::NotAPublisher(publisher_identifier));
         }
-    } else {
-        warn!("TopicService::publish_to_message_topic tried to lock a poisoned mutex");
+        quota_service::admit_publish(
+            DestinationType::MessageTopic,
+            &namespace,
+            &topic_name,
+            &publisher_identifier,
+            dts.size(),
+        )?;
+        topic.publish(Message::from(dts.clone()));
+        quota_service::store(
+            DestinationType::MessageTopic,
+            &namespace,
+            &topic_name,
+            dts.size(),
+        );
+        metrics::record_publish(
+            DestinationType::MessageTopic,
+            &namespace,
+            &topic_name,
+            dts.size(),
+        );
+
+        create_publish_to_message_topic_event(
+            namespace.clone(),
+            topic_name.clone(),
+            dts.clone(),
+            ack,
+        );
+        notification_service::notify_new_data(
+            DestinationType::MessageTopic,
+            &namespace,
+            &topic_name,
+        );
     }
+
+    // forwarding publishes into other topics, so it runs after the topic lock is released
+    forwarding_rule_service::forward_message(
+        &topic_name,
+        &publisher_identifier,
+        Message::from(dts),
+    );
+    Ok(())
+}
+
+/// Publishes a message routed by an exchange binding or a forwarding rule. Routing is not
+/// authorized again, the binding or rule was authorized to publish to the topic when it was created.
+/// The quotas of the topic and of the original publisher still apply.
+pub fn route_to_message_topic(
+    topic_name: String,
+    publisher_identifier: &str,
+    dts: PublishToMessageTopic<TopicType>,
+) -> ServiceResult<()> {
+    append_to_message_topic(topic_name.clone(), publisher_identifier, dts.clone())?;
+    forwarding_rule_service::forward_message(&topic_name, publisher_identifier, Message::from(dts));
+    Ok(())
+}
+
+/// Stores a routed message without forwarding it, authorized like `route_to_message_topic`.
+pub fn append_to_message_topic(
+    topic_name: String,
+    publisher_identifier: &str,
+    dts: PublishToMessageTopic<TopicType>,
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    let mut topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    quota_service::admit_publish(
+        DestinationType::MessageTopic,
+        &namespace,
+        &topic_name,
+        publisher_identifier,
+        dts.size(),
+    )?;
+    topic.publish(Message::from(dts.clone()));
+    quota_service::store(
+        DestinationType::MessageTopic,
+        &namespace,
+        &topic_name,
+        dts.size(),
+    );
+    metrics::record_publish(
+        DestinationType::MessageTopic,
+        &namespace,
+        &topic_name,
+        dts.size(),
+    );
+
+    create_publish_to_message_topic_event(namespace.clone(), topic_name.clone(), dts, None);
+    notification_service::notify_new_data(DestinationType::MessageTopic, &namespace, &topic_name);
+    Ok(())
 }
 
 fn create_publish_to_message_topic_event(
+    namespace: String,
     topic_name: String,
     dts: PublishToMessageTopic<TopicType>,
+    ack: Option<PersistenceAck>,
 ) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
-        let event =
-            TopicEvent::PublishMessageTopic(EventPublishToMessageTopicData::new(topic_name, dts));
+        let event = TopicEvent::PublishMessageTopic(EventPublishToMessageTopicData::new(
+            namespace, topic_name, dts, ack,
+        ));
         queue.enqueue(event);
     } else {
         warn!("TopicService::create_publish_to_message_topic_event tried to lock a poisoned mutex");
     }
 }
 
-pub async fn add_publisher_to_message_topic(topic_name: String, dts: AddPublisherToMessageTopic) {
-    if let Ok(mut topics) = STATE.message_topics.lock() {
-        if let Some(topic) = topics.iter_mut().find(|topic| topic.name == topic_name) {
-            topic.add_publisher(dts.publisher.clone());
+pub async fn add_publisher_to_message_topic(
+    topic_name: String,
+    dts: AddPublisherToMessageTopic,
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    authorize_registration(Action::Publish, &topic_name, &dts.publisher)?;
+    let mut topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    if topic.is_publisher(dts.publisher.clone()) {
+        return Err(ServiceError::AlreadyAPublisher(dts.publisher));
+    }
+    topic.add_publisher(dts.publisher.clone());
 
-            create_add_publisher_to_message_topic_event(topic_name.clone(), dts.clone());
-        } else {
-            warn!("TopicService::add_publisher_to_message_topic tried to add a publisher to a message_topic that does not exist");
-        }
-    } else {
-        warn!("TopicService::add_publisher_to_message_topic tried to lock a poisoned mutex");
+    audit_service::record(
+        AuditAction::PublisherAdded,
+        DestinationType::MessageTopic.as_str(),
+        &topic_name,
+        None,
+        details(&dts),
+    );
+    create_add_publisher_to_message_topic_event(namespace.clone(), topic_name, dts);
+    Ok(())
+}
+
+/// Creates the message topic if it does not exist and registers the identifier as a publisher, for
+/// protocols without topic administration.
+pub async fn ensure_publisher_of_message_topic(
+    topic_name: String,
+    identifier: String,
+) -> ServiceResult<()> {
+    let add_publisher = || {
+        add_publisher_to_message_topic(
+            topic_name.clone(),
+            AddPublisherToMessageTopic::new(identifier.clone()),
+        )
+    };
+    // creating the topic needs the manage permission, publishers of an existing topic do not
+    match add_publisher().await {
+        Ok(()) | Err(ServiceError::AlreadyAPublisher(_)) => return Ok(()),
+        Err(ServiceError::TopicNotFound(_)) => {}
+        Err(e) => return Err(e),
+    }
+    match create_message_topic(CreateMessageTopic::new(topic_name.clone())).await {
+        Ok(_) | Err(ServiceError::AlreadyExists(_)) => {}
+        Err(e) => return Err(e),
+    }
+    match add_publisher().await {
+        Ok(()) | Err(ServiceError::AlreadyAPublisher(_)) => Ok(()),
+        Err(e) => Err(e),
     }
 }
 
 fn create_add_publisher_to_message_topic_event(
+    namespace: String,
     topic_name: String,
     dts: AddPublisherToMessageTopic,
 ) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
         let event = TopicEvent::AddPublisherMessageTopic(EventAddPublisherToMessageTopicData::new(
-            topic_name, dts,
+            namespace, topic_name, dts,
         ));
         queue.enqueue(event);
     } else {
@@ -159,27 +380,41 @@ fn create_add_publisher_to_message_topic_event(
 pub async fn remove_publisher_from_message_topic(
     topic_name: String,
     dts: RemovePublisherFromMessageTopic,
-) {
-    if let Ok(mut topics) = STATE.message_topics.lock() {
-        if let Some(topic) = topics.iter_mut().find(|topic| topic.name == topic_name) {
-            topic.remove_publisher(dts.publisher.clone());
-
-            create_remove_publisher_from_message_topic_event(topic_name.clone(), dts.clone());
-        } else {
-            warn!("TopicService::remove_publisher_from_message_topic tried to remove a publisher from a message_topic that does not exist");
-        }
-    } else {
-        warn!("TopicService::remove_publisher_from_message_topic tried to lock a poisoned mutex");
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    authorize_registration(Action::Publish, &topic_name, &dts.publisher)?;
+    let mut topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    if !topic.is_publisher(dts.publisher.clone()) {
+        return Err(ServiceError::NotAPublisher(dts.publisher));
     }
+    topic.remove_publisher(dts.publisher.clone());
+
+    audit_service::record(
+        AuditAction::PublisherRemoved,
+        DestinationType::MessageTopic.as_str(),
+        &topic_name,
+        details(&dts),
+        None,
+    );
+    create_remove_publisher_from_message_topic_event(namespace.clone(), topic_name, dts);
+    Ok(())
 }
 
 fn create_remove_publisher_from_message_topic_event(
+    namespace: String,
     topic_name: String,
     dts: RemovePublisherFromMessageTopic,
 ) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
         let event = TopicEvent::RemovePublisherMessageTopic(
-            EventRemovePublisherFromMessageTopicData::new(topic_name, dts),
+            EventRemovePublisherFromMessageTopicData::new(namespace, topic_name, dts),
         );
         queue.enqueue(event);
     } else {
@@ -189,27 +424,48 @@ fn create_remove_publisher_from_message_topic_event(
     }
 }
 
-pub async fn add_subscriber_to_message_topic(topic_name: String, dts: AddSubscriberToMessageTopic) {
-    if let Ok(mut topics) = STATE.message_topics.lock() {
-        if let Some(topic) = topics.iter_mut().find(|topic| topic.name == topic_name) {
-            topic.add_subscriber(dts.subscriber.clone());
+pub async fn add_subscriber_to_message_topic(
+    topic_name: String,
+    dts: AddSubscriberToMessageTopic,
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    authorize_registration(Action::Subscribe, &topic_name, &dts.subscriber)?;
+    if dts.filter.as_ref().is_some_and(|filter| !filter.is_valid()) {
+        return Err(ServiceError::InvalidFilter);
+    }
 
-            create_add_subscriber_to_message_topic_event(topic_name.clone(), dts.clone());
-        } else {
-            warn!("TopicService::add_subscriber_to_message_topic tried to subscribe to a message_topic that does not exist");
-        }
-    } else {
-        warn!("TopicService::add_subscriber_to_message_topic tried to lock a poisoned mutex");
+    let mut topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    if topic.is_subscriber(dts.subscriber.clone()) {
+        return Err(ServiceError::AlreadyASubscriber(dts.subscriber));
     }
+    topic.add_subscriber(dts.subscriber.clone(), dts.filter.clone());
+
+    audit_service::record(
+        AuditAction::SubscriberAdded,
+        DestinationType::MessageTopic.as_str(),
+        &topic_name,
+        None,
+        details(&dts),
+    );
+    create_add_subscriber_to_message_topic_event(namespace.clone(), topic_name, dts);
+    Ok(())
 }
 
-fn create_add_subscriber_to_message_topic_event(
+pub fn create_add_subscriber_to_message_topic_event(
+    namespace: String,
     topic_name: String,
     dts: AddSubscriberToMessageTopic,
 ) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
         let event = TopicEvent::AddSubscriberMessageTopic(
-            EventAddSubscriberToMessageTopicData::new(topic_name, dts),
+            EventAddSubscriberToMessageTopicData::new(namespace, topic_name, dts),
         );
         queue.enqueue(event);
     } else {
@@ -222,27 +478,41 @@ fn create_add_subscriber_to_message_topic_event(
 pub async fn remove_subscriber_from_message_topic(
     topic_name: String,
     dts: RemoveSubscriberFromMessageTopic,
-) {
-    if let Ok(mut topics) = STATE.message_topics.lock() {
-        if let Some(topic) = topics.iter_mut().find(|topic| topic.name == topic_name) {
-            topic.remove_subscriber(dts.subscriber.clone());
-
-            create_remove_subscriber_from_message_topic_event(topic_name.clone(), dts.clone());
-        } else {
-            warn!("TopicService::remove_subscriber_from_message_topic tried to unsubscribe from a message_topic that does not exist");
-        }
-    } else {
-        warn!("TopicService::remove_subscriber_from_message_topic tried to lock a poisoned mutex");
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    authorize_registration(Action::Subscribe, &topic_name, &dts.subscriber)?;
+    let mut topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    if !topic.is_subscriber(dts.subscriber.clone()) {
+        return Err(ServiceError::NotASubscriber(dts.subscriber));
     }
+    topic.remove_subscriber(dts.subscriber.clone());
+
+    audit_service::record(
+        AuditAction::SubscriberRemoved,
+        DestinationType::MessageTopic.as_str(),
+        &topic_name,
+        details(&dts),
+        None,
+    );
+    create_remove_subscriber_from_message_topic_event(namespace.clone(), topic_name, dts);
+    Ok(())
 }
 
-fn create_remove_subscriber_from_message_topic_event(
+pub fn create_remove_subscriber_from_message_topic_event(
+    namespace: String,
     topic_name: String,
     dts: RemoveSubscriberFromMessageTopic,
 ) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
         let event = TopicEvent::RemoveSubscriberMessageTopic(
-            EventRemoveSubscriberFromMessageTopicData::new(topic_name, dts),
+            EventRemoveSubscriberFromMessageTopicData::new(namespace, topic_name, dts),
         );
         queue.enqueue(event);
     } else {
@@ -253,55 +523,67 @@ fn create_remove_subscriber_from_message_topic_event(
 pub async fn is_there_new_data_for_subscriber(
     topic_name: String,
     identifier: String,
-) -> Option<NewDataMessageTopic> {
-    if let Ok(mut topics) = STATE.message_topics.lock() {
-        if let Some(topic) = topics.iter_mut().find(|topic| topic.name == topic_name) {
-            let new_data = topic.new_data_to_fetch_for_subscriber(identifier);
-            Some(NewDataMessageTopic { new_data })
-        } else {
-            warn!("TopicService::is_there_new_data_for_subscriber tried to get new data for a message_topic that does not exist");
-            None
-        }
-    } else {
-        warn!("TopicService::is_there_new_data_for_subscriber tried to lock a poisoned mutex");
-        None
+) -> ServiceResult<NewDataMessageTopic> {
+    let namespace = current_namespace();
+    authorize_identifier(&identifier)?;
+    authorize(Action::Subscribe, &topic_name)?;
+    let mut topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or(ServiceError::TopicNotFound(topic_name))?;
+    if !topic.is_subscriber(identifier.clone()) {
+        return Err(ServiceError::NotASubscriber(identifier));
     }
+    let new_data = topic.new_data_to_fetch_for_subscriber(identifier);
+    Ok(NewDataMessageTopic { new_data })
 }
 
 pub async fn get_new_data_for_subscriber(
     topic_name: String,
     identifier: String,
-) -> Option<DataMessageTopic<TopicType>> {
-    if let Ok(mut topics) = STATE.message_topics.lock() {
-        if let Some(topic) = topics.iter_mut().find(|topic| topic.name == topic_name) {
-            let data = topic
-                .get_data_for_subscriber(identifier.clone())
-                .map(|data| DataMessageTopic { data });
-
-            create_fetch_data_from_topic_event(
-                topic_name.clone(),
-                identifier.clone(),
-                topic.get_subscriber_index(identifier.clone()),
-            );
-
-            data
-        } else {
-            warn!("TopicService::get_new_data_for_subscriber tried to get new data for a message_topic that does not exist");
-            None
-        }
-    } else {
-        warn!("TopicService::get_new_data_for_subscriber tried to lock a poisoned mutex");
-        None
+) -> ServiceResult<DataMessageTopic<Message<TopicType>>> {
+    let namespace = current_namespace();
+    authorize_identifier(&identifier)?;
+    authorize(Action::Subscribe, &topic_name)?;
+    let mut topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    if !topic.is_subscriber(identifier.clone()) {
+        return Err(ServiceError::NotASubscriber(identifier));
     }
+    let data = topic
+        .get_data_for_subscriber(identifier.clone())
+        .unwrap_or_default();
+    metrics::record_fetch(DestinationType::MessageTopic, &namespace, &topic_name);
+
+    create_fetch_data_from_topic_event(
+        namespace.clone(),
+        topic_name,
+        identifier.clone(),
+        topic.get_subscriber_index(identifier),
+    );
+
+    Ok(DataMessageTopic { data })
 }
 
-fn create_fetch_data_from_topic_event(
+pub fn create_fetch_data_from_topic_event(
+    namespace: String,
     topic_name: String,
     subscriber_name: String,
     subscriber_index: usize,
 ) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
         let event = TopicEvent::FetchDataMessageTopic(EventFetchDataFromMessageTopicData::new(
+            namespace,
             topic_name,
             subscriber_name,
             subscriber_index,
@@ -0,0 +1,103 @@
+use crate::auth::identity::current_identity;
+use crate::auth::ADMIN_PRINCIPAL;
+use crate::metrics::{
+    EVENT_QUEUE_DEPTH, REGISTRY, SUBSCRIBER_LAG, TOPIC_DEPTH, TOPIC_PUBLISHERS, TOPIC_SUBSCRIBERS,
+};
+use crate::model::destination_model::DestinationType;
+use crate::service::error::{ServiceError, ServiceResult};
+use crate::STATE;
+use prometheus::TextEncoder;
+
+/// Renders every metric in the Prometheus text format. The gauges are read from the topics on
+/// every scrape, so deleted topics and subscribers drop out of them. The metrics are labeled with
+/// the topics of every namespace, so only the admin of the default namespace may read them.
+pub fn render() -> ServiceResult<String> {
+    if current_identity().is_some_and(|identity| !identity.enters_every_namespace()) {
+        return Err(ServiceError::IdentityMismatch(ADMIN_PRINCIPAL.to_string()));
+    }
+    update_gauges()?;
+    Ok(TextEncoder::new()
+        .encode_to_string(&REGISTRY.gather())
+        .expect("the registered metrics are always valid"))
+}
+
+fn update_gauges() -> ServiceResult<()> {
+    for gauge in [
+        &*TOPIC_DEPTH,
+        &*TOPIC_PUBLISHERS,
+        &*TOPIC_SUBSCRIBERS,
+        &*SUBSCRIBER_LAG,
+    ] {
+        gauge.reset();
+    }
+
+    {
+        let topics = STATE
+            .message_topics
+            .lock()
+            .map_err(|_| ServiceError::PoisonedLock)?;
+        for topic in topics.iter() {
+            let labels = [
+                topic.namespace.as_str(),
+                DestinationType::MessageTopic.as_str(),
+                topic.name.as_str(),
+            ];
+            let lags = topic.subscriber_lags();
+            TOPIC_DEPTH
+                .with_label_values(&labels)
+                .set(topic.depth() as i64);
+            TOPIC_PUBLISHERS
+                .with_label_values(&labels)
+                .set(topic.publisher_count() as i64);
+            TOPIC_SUBSCRIBERS
+                .with_label_values(&labels)
+                .set(lags.len() as i64);
+            for (subscriber, lag) in lags {
+                SUBSCRIBER_LAG
+                    .with_label_values(&[&topic.namespace, &topic.name, &subscriber])
+                    .set(lag as i64);
+            }
+        }
+    }
+
+    {
+        let topics = STATE
+            .task_topics
+            .lock()
+            .map_err(|_| ServiceError::PoisonedLock)?;
+        for topic in topics.iter() {
+            let labels = [
+                topic.namespace.as_str(),
+                DestinationType::TaskTopic.as_str(),
+                topic.name.as_str(),
+            ];
+            TOPIC_DEPTH
+                .with_label_values(&labels)
+                .set(topic.depth() as i64);
+            let publishers = topic
+                .publisher
+                .lock()
+                .map_err(|_| ServiceError::PoisonedLock)?
+                .len();
+            TOPIC_PUBLISHERS
+                .with_label_values(&labels)
+                .set(publishers as i64);
+            let subscribers = topic
+                .subscriber
+                .lock()
+                .map_err(|_| ServiceError::PoisonedLock)?
+                .len();
+            TOPIC_SUBSCRIBERS
+                .with_label_values(&labels)
+                .set(subscribers as i64);
+        }
+    }
+
+    let queue_depth = STATE
+        .event_queue
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?
+        .len();
+    EVENT_QUEUE_DEPTH.set(queue_depth as i64);
+    Ok(())
+}
@@ -1,2 +1,15 @@
+pub mod acl_service;
+pub mod audit_service;
+pub mod credential_service;
+pub mod error;
+pub mod exchange_service;
+pub mod forwarding_rule_service;
+pub mod health_service;
 pub mod message_topic_service;
+pub mod metrics_service;
+pub mod notification_service;
+pub mod quota_service;
+pub mod retained_message_service;
+pub mod routing_service;
 pub mod task_topic_service;
+pub mod wildcard_subscription_service;
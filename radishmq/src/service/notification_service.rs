@@ -0,0 +1,20 @@
+use crate::model::destination_model::{DestinationType, TopicDestination};
+use crate::utils::namespace::DEFAULT_NAMESPACE;
+use crate::STATE;
+use tokio::sync::broadcast::Receiver;
+
+pub fn notify_new_data(destination_type: DestinationType, namespace: &str, topic_name: &str) {
+    // the push frontends only serve the default namespace
+    if namespace != DEFAULT_NAMESPACE {
+        return;
+    }
+    // nobody listening is not an error, push frontends subscribe only while clients are connected
+    let _ = STATE.notifications.send(TopicDestination::new(
+        destination_type,
+        topic_name.to_string(),
+    ));
+}
+
+pub fn subscribe_to_notifications() -> Receiver<TopicDestination> {
+    STATE.notifications.subscribe()
+}
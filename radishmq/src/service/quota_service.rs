@@ -0,0 +1,232 @@
+use crate::metrics::QUOTA_BREACHES;
+use crate::model::destination_model::DestinationType;
+use crate::service::error::{ServiceError, ServiceResult};
+use crate::topic::quota::{Limit, QuotaScope, QuotaUsage, TokenBucket};
+use crate::STATE;
+use lazy_static::lazy_static;
+use log::warn;
+use std::collections::HashMap;
+use std::env;
+use std::time::Duration;
+
+// every limit is configured by an environment variable, a limit that is not set is unlimited
+const QUOTAS: [(&str, Limit, &str); 9] = [
+    (
+        "publisher",
+        Limit::MessagesPerSecond,
+        "QUOTA_PUBLISHER_MESSAGES_PER_SECOND",
+    ),
+    (
+        "publisher",
+        Limit::BytesPerSecond,
+        "QUOTA_PUBLISHER_BYTES_PER_SECOND",
+    ),
+    (
+        "topic",
+        Limit::MessagesPerSecond,
+        "QUOTA_TOPIC_MESSAGES_PER_SECOND",
+    ),
+    (
+        "topic",
+        Limit::BytesPerSecond,
+        "QUOTA_TOPIC_BYTES_PER_SECOND",
+    ),
+    ("topic", Limit::StoredBytes, "QUOTA_TOPIC_STORED_BYTES"),
+    (
+        "namespace",
+        Limit::MessagesPerSecond,
+        "QUOTA_NAMESPACE_MESSAGES_PER_SECOND",
+    ),
+    (
+        "namespace",
+        Limit::BytesPerSecond,
+        "QUOTA_NAMESPACE_BYTES_PER_SECOND",
+    ),
+    (
+        "namespace",
+        Limit::StoredBytes,
+        "QUOTA_NAMESPACE_STORED_BYTES",
+    ),
+    ("namespace", Limit::Topics, "QUOTA_NAMESPACE_TOPICS"),
+];
+
+lazy_static! {
+    static ref QUOTA_SETTINGS: HashMap<(&'static str, Limit), u64> = quota_settings_from_env();
+}
+
+fn quota_settings_from_env() -> HashMap<(&'static str, Limit), u64> {
+    QUOTAS
+        .iter()
+        .filter_map(|(kind, limit, variable)| {
+            let value = env::var(variable).ok()?;
+            let value = value
+                .parse::<u64>()
+                .ok()
+                .filter(|value| *value > 0)
+                .unwrap_or_else(|| panic!("{} must be a positive number", variable));
+            Some(((*kind, *limit), value))
+        })
+        .collect()
+}
+
+fn quota(scope: &QuotaScope, limit: Limit) -> Option<u64> {
+    QUOTA_SETTINGS.get(&(scope.kind(), limit)).copied()
+}
+
+fn exceeded(scope: &QuotaScope, limit: Limit, retry_after: Option<Duration>) -> ServiceError {
+    QUOTA_BREACHES
+        .with_label_values(&[scope.namespace(), scope.kind(), limit.as_str()])
+        .inc();
+    ServiceError::QuotaExceeded(
+        format!("{} of the {}", limit.as_str(), scope),
+        // Retry-After has a resolution of seconds, rounding down would invite an early retry
+        retry_after.map(|wait| wait.as_secs() + u64::from(wait.subsec_nanos() > 0)),
+    )
+}
+
+fn topic_scope(destination_type: DestinationType, namespace: &str, topic_name: &str) -> QuotaScope {
+    QuotaScope::Topic {
+        namespace: namespace.to_string(),
+        destination_type,
+        name: topic_name.to_string(),
+    }
+}
+
+/// Admits a publish of `bytes` to a topic against the rate limits of the publisher, the topic and
+/// the namespace and the bytes they may store. Nothing is counted if a limit is exceeded.
+pub fn admit_publish(
+    destination_type: DestinationType,
+    namespace: &str,
+    topic_name: &str,
+    publisher: &str,
+    bytes: u64,
+) -> ServiceResult<()> {
+    let publisher = QuotaScope::Publisher {
+        namespace: namespace.to_string(),
+        identifier: publisher.to_string(),
+    };
+    let topic = topic_scope(destination_type, namespace, topic_name);
+    let namespace = QuotaScope::Namespace(namespace.to_string());
+
+    let mut usage = STATE
+        .quota_usage
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    usage.evict_full_buckets();
+
+    for scope in [&topic, &namespace] {
+        if let Some(max) = quota(scope, Limit::StoredBytes) {
+            if stored_bytes(&usage, scope) + bytes > max {
+                return Err(exceeded(scope, Limit::StoredBytes, None));
+            }
+        }
+    }
+
+    let rates: Vec<(QuotaScope, Limit, u64, u64)> = [publisher, topic, namespace]
+        .into_iter()
+        .flat_map(|scope| {
+            [
+                (Limit::MessagesPerSecond, 1),
+                (Limit::BytesPerSecond, bytes),
+            ]
+            .into_iter()
+            .filter_map(move |(limit, amount)| {
+                quota(&scope, limit).map(|rate| (scope.clone(), limit, rate, amount))
+            })
+        })
+        .collect();
+
+    for (scope, limit, rate, amount) in &rates {
+        let bucket = usage
+            .buckets
+            .entry((scope.clone(), *limit))
+            .or_insert_with(|| TokenBucket::new(*rate));
+        if let Some(wait) = bucket.wait_time(*amount) {
+            return Err(exceeded(scope, *limit, Some(wait)));
+        }
+    }
+    for (scope, limit, _, amount) in rates {
+        if let Some(bucket) = usage.buckets.get_mut(&(scope, limit)) {
+            bucket.take(amount);
+        }
+    }
+    Ok(())
+}
+
+fn stored_bytes(usage: &QuotaUsage, scope: &QuotaScope) -> u64 {
+    usage.stored_bytes.get(scope).copied().unwrap_or_default()
+}
+
+/// Fails if the namespace already holds as many message and task topics as it may.
+pub fn admit_topic_creation(namespace: &str) -> ServiceResult<()> {
+    let scope = QuotaScope::Namespace(namespace.to_string());
+    let Some(max) = quota(&scope, Limit::Topics) else {
+        return Ok(());
+    };
+
+    // the topic lists are locked one after the other, topic services never hold both
+    let message_topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?
+        .iter()
+        .filter(|topic| topic.namespace == namespace)
+        .count();
+    let task_topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?
+        .iter()
+        .filter(|topic| topic.namespace == namespace)
+        .count();
+
+    if (message_topics + task_topics) as u64 >= max {
+        return Err(exceeded(&scope, Limit::Topics, None));
+    }
+    Ok(())
+}
+
+/// Counts data appended to a topic towards the stored bytes of the topic and its namespace.
+pub fn store(destination_type: DestinationType, namespace: &str, topic_name: &str, bytes: u64) {
+    if let Ok(mut usage) = STATE.quota_usage.lock() {
+        for scope in [
+            topic_scope(destination_type, namespace, topic_name),
+            QuotaScope::Namespace(namespace.to_string()),
+        ] {
+            *usage.stored_bytes.entry(scope).or_default() += bytes;
+        }
+    } else {
+        warn!("QuotaService::store tried to lock a poisoned mutex");
+    }
+}
+
+/// Releases data a topic no longer holds.
+pub fn release(destination_type: DestinationType, namespace: &str, topic_name: &str, bytes: u64) {
+    if let Ok(mut usage) = STATE.quota_usage.lock() {
+        for scope in [
+            topic_scope(destination_type, namespace, topic_name),
+            QuotaScope::Namespace(namespace.to_string()),
+        ] {
+            if let Some(stored) = usage.stored_bytes.get_mut(&scope) {
+                *stored = stored.saturating_sub(bytes);
+            }
+        }
+    } else {
+        warn!("QuotaService::release tried to lock a poisoned mutex");
+    }
+}
+
+/// Releases everything a deleted topic held.
+pub fn release_topic(destination_type: DestinationType, namespace: &str, topic_name: &str) {
+    let scope = topic_scope(destination_type, namespace, topic_name);
+    let bytes = if let Ok(mut usage) = STATE.quota_usage.lock() {
+        usage
+            .buckets
+            .retain(|(bucket_scope, _), _| bucket_scope != &scope);
+        usage.stored_bytes.remove(&scope).unwrap_or_default()
+    } else {
+        warn!("QuotaService::release_topic tried to lock a poisoned mutex");
+        return;
+    };
+    release(destination_type, namespace, topic_name, bytes);
+}
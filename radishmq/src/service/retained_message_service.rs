@@ -0,0 +1,67 @@
+use crate::event_queue::event::{
+    EventDeleteRetainedMessageData, EventSetRetainedMessageData, TopicEvent,
+};
+use crate::model::message_topic_model::Message;
+use crate::model::retained_message_model::RetainedMessageModel;
+use crate::utils::topic_pattern::TopicPattern;
+use crate::utils::types::TopicType;
+use crate::STATE;
+use log::warn;
+
+/// Keeps the message as the last known value of the topic, an empty message clears it.
+pub fn set_retained_message(topic_name: String, message: Message<TopicType>) {
+    if let Ok(mut retained_messages) = STATE.retained_messages.lock() {
+        if message.data.is_empty() {
+            if retained_messages.remove(&topic_name).is_some() {
+                create_delete_retained_message_event(topic_name);
+            }
+        } else {
+            retained_messages.insert(topic_name.clone(), message.clone());
+            create_set_retained_message_event(RetainedMessageModel::new(
+                topic_name,
+                message.data,
+                message.headers,
+            ));
+        }
+    } else {
+        warn!("RetainedMessageService::set_retained_message tried to lock a poisoned mutex");
+    }
+}
+
+pub fn get_retained_messages(pattern: &TopicPattern) -> Vec<RetainedMessageModel<TopicType>> {
+    if let Ok(retained_messages) = STATE.retained_messages.lock() {
+        retained_messages
+            .iter()
+            .filter(|(topic_name, _)| pattern.matches(topic_name))
+            .map(|(topic_name, message)| {
+                RetainedMessageModel::new(
+                    topic_name.clone(),
+                    message.data.clone(),
+                    message.headers.clone(),
+                )
+            })
+            .collect()
+    } else {
+        warn!("RetainedMessageService::get_retained_messages tried to lock a poisoned mutex");
+        Vec::new()
+    }
+}
+
+fn create_set_retained_message_event(dts: RetainedMessageModel<TopicType>) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::SetRetainedMessage(EventSetRetainedMessageData::new(dts));
+        queue.enqueue(event);
+    } else {
+        warn!("RetainedMessageService::create_set_retained_message_event tried to lock a poisoned mutex");
+    }
+}
+
+fn create_delete_retained_message_event(topic_name: String) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event =
+            TopicEvent::DeleteRetainedMessage(EventDeleteRetainedMessageData::new(topic_name));
+        queue.enqueue(event);
+    } else {
+        warn!("RetainedMessageService::create_delete_retained_message_event tried to lock a poisoned mutex");
+    }
+}
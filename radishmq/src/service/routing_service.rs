@@ -0,0 +1,60 @@
+use crate::model::destination_model::{DestinationType, TopicDestination};
+use crate::model::message_topic_model::{Message, PublishToMessageTopic};
+use crate::model::task_topic_model::PublishToTaskTopic;
+use crate::service::error::{ServiceError, ServiceResult};
+use crate::service::{message_topic_service, task_topic_service};
+use crate::utils::namespace::current_namespace;
+use crate::utils::types::TopicType;
+use crate::STATE;
+use log::warn;
+
+pub fn destination_exists(destination: &TopicDestination) -> ServiceResult<bool> {
+    let namespace = current_namespace();
+    let name = &destination.destination;
+    match destination.destination_type {
+        DestinationType::MessageTopic => Ok(STATE
+            .message_topics
+            .lock()
+            .map_err(|_| ServiceError::PoisonedLock)?
+            .iter()
+            .any(|topic| topic.is_named(&namespace, name))),
+        DestinationType::TaskTopic => Ok(STATE
+            .task_topics
+            .lock()
+            .map_err(|_| ServiceError::PoisonedLock)?
+            .iter()
+            .any(|topic| topic.is_named(&namespace, name))),
+    }
+}
+
+/// Routes a message to a destination and tells whether it arrived. An exceeded quota fails the
+/// routing, other errors are logged and skip the destination.
+pub fn route_to_destination(
+    destination: &TopicDestination,
+    publisher_identifier: &str,
+    message: Message<TopicType>,
+) -> ServiceResult<bool> {
+    let routed = match destination.destination_type {
+        DestinationType::MessageTopic => message_topic_service::route_to_message_topic(
+            destination.destination.clone(),
+            publisher_identifier,
+            PublishToMessageTopic::new(message.data, message.headers),
+        ),
+        DestinationType::TaskTopic => task_topic_service::route_to_task_topic(
+            destination.destination.clone(),
+            publisher_identifier,
+            PublishToTaskTopic::new(message.data),
+        ),
+    };
+    match routed {
+        Ok(()) => Ok(true),
+        Err(e @ ServiceError::QuotaExceeded(_, _)) => Err(e),
+        Err(e) => {
+            warn!(
+                "RoutingService::route_to_destination could not route to '{}': {}",
+                destination.destination, e
+            );
+            Ok(false)
+        }
+    }
+}
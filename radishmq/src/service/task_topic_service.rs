@@ -1,120 +1,190 @@
+use crate::auth::permission::Action;
 use crate::event_queue::event::{
     EventAddPublisherToTaskTopicData, EventAddSubscriberToTaskTopicData, EventCreateTaskTopicData,
     EventDeleteTaskTopicData, EventPublishToTaskTopicData, EventRemovePublisherFromTaskTopicData,
     EventRemoveSubscriberFromTaskTopicData, TopicEvent,
 };
+use crate::metrics;
+use crate::model::audit_model::AuditAction;
+use crate::model::destination_model::DestinationType;
 use crate::model::task_topic_model::{
-    AddPublisherToTaskTopic, AddSubscriberToTaskTopic, CreateTaskTopic, NewTasks,
-    PublishToTaskTopic, RemovePublisherFromTaskTopic, RemoveSubscriberFromTaskTopic,
-    TaskTopicModel,
+    AddPublisherToTaskTopic, AddSubscriberToTaskTopic, AssignedTask, CompleteTask, CreateTaskTopic,
+    FailTask, NewTasks, PublishToTaskTopic, PublishedTask, RemovePublisherFromTaskTopic,
+    RemoveSubscriberFromTaskTopic, ReportTaskProgress, TaskStatusModel, TaskTopicModel,
 };
+use crate::service::acl_service::{authorize, authorize_registration, is_authorized};
+use crate::service::audit_service::{self, details};
+use crate::service::credential_service::authorize_identifier;
+use crate::service::error::{ServiceError, ServiceResult};
+use crate::service::{notification_service, quota_service};
 use crate::topic::task_topic::TaskTopic;
+use crate::utils::namespace::current_namespace;
 use crate::utils::types::TopicType;
 use crate::STATE;
 use log::warn;
+use std::env;
+use std::time::Duration;
+use uuid::Uuid;
 
-pub async fn get_all_task_topics() -> Vec<TaskTopicModel> {
-    if let Ok(topics) = STATE.task_topics.lock() {
-        topics.iter().map(|topic| topic.to_model()).collect()
-    } else {
-        warn!("TopicService::get_all_task_topics tried to lock a poisoned mutex");
-        Vec::new()
-    }
+fn task_lease_timeout() -> Duration {
+    let seconds = env::var("TASK_LEASE_TIMEOUT")
+        .unwrap_or("30".to_string())
+        .parse()
+        .unwrap_or(30);
+    Duration::from_secs(seconds)
 }
 
-pub async fn get_task_topic(topic_name: String) -> Option<TaskTopicModel> {
-    if let Ok(topics) = STATE.task_topics.lock() {
-        topics
-            .iter()
-            .find(|topic| topic.name == topic_name)
-            .map(|topic| topic.to_model())
-    } else {
-        warn!("TopicService::get_task_topic tried to lock a poisoned mutex");
-        None
-    }
+fn task_result_retention() -> Duration {
+    let seconds = env::var("TASK_RESULT_RETENTION")
+        .unwrap_or("3600".to_string())
+        .parse()
+        .unwrap_or(3600);
+    Duration::from_secs(seconds)
 }
 
-pub async fn create_task_topic(dts: CreateTaskTopic) -> Option<TaskTopicModel> {
-    if let Ok(mut topics) = STATE.task_topics.lock() {
-        let topic_name = dts.name.clone();
-        if topics.iter().any(|topic| topic.name == topic_name) {
-            warn!(
-                "TopicService::create_task_topic tried to create a task_topic that already exists"
-            );
-            return None;
-        }
-        let topic = TaskTopic::new(topic_name.clone());
-        topics.push(topic.clone());
-
-        create_create_task_topic_event(dts.clone());
-
-        Some(topic.to_model())
-    } else {
-        warn!("TopicService::create_task_topic tried to lock a poisoned mutex");
-        None
+pub async fn get_all_task_topics() -> ServiceResult<Vec<TaskTopicModel>> {
+    let namespace = current_namespace();
+    let topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    Ok(topics
+        .iter()
+        .filter(|topic| topic.namespace == namespace)
+        .filter(|topic| is_authorized(Action::Read, &topic.name))
+        .map(|topic| topic.to_model())
+        .collect())
+}
+
+pub async fn get_task_topic(topic_name: String) -> ServiceResult<TaskTopicModel> {
+    let namespace = current_namespace();
+    authorize(Action::Read, &topic_name)?;
+    let topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    topics
+        .iter()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .map(|topic| topic.to_model())
+        .ok_or(ServiceError::TopicNotFound(topic_name))
+}
+
+pub async fn create_task_topic(dts: CreateTaskTopic) -> ServiceResult<TaskTopicModel> {
+    let namespace = current_namespace();
+    authorize(Action::Manage, &dts.name)?;
+    quota_service::admit_topic_creation(&namespace)?;
+    let mut topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic_name = dts.name.clone();
+    if topics
+        .iter()
+        .any(|topic| topic.is_named(&namespace, &topic_name))
+    {
+        return Err(ServiceError::AlreadyExists(topic_name));
     }
+    let topic = TaskTopic::new(namespace.clone(), topic_name.clone());
+    topics.push(topic.clone());
+
+    create_create_task_topic_event(namespace.clone(), dts);
+
+    let model = topic.to_model();
+    audit_service::record(
+        AuditAction::TopicCreated,
+        DestinationType::TaskTopic.as_str(),
+        &model.name,
+        None,
+        details(&model),
+    );
+    Ok(model)
 }
 
-fn create_create_task_topic_event(dts: CreateTaskTopic) {
+fn create_create_task_topic_event(namespace: String, dts: CreateTaskTopic) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
-        let event = TopicEvent::CreateTaskTopic(EventCreateTaskTopicData::new(dts));
+        let event = TopicEvent::CreateTaskTopic(EventCreateTaskTopicData::new(namespace, dts));
         queue.enqueue(event);
     } else {
         warn!("TopicService::create_create_task_topic_event tried to lock a poisoned mutex");
     }
 }
 
-pub async fn delete_task_topic(topic_name: String) -> Option<TaskTopicModel> {
-    if let Ok(mut topics) = STATE.task_topics.lock() {
-        if let Some(index) = topics.iter().position(|topic| topic.name == topic_name) {
-            let topic = topics.remove(index);
+pub async fn delete_task_topic(topic_name: String) -> ServiceResult<TaskTopicModel> {
+    let namespace = current_namespace();
+    authorize(Action::Manage, &topic_name)?;
+    let mut topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let index = topics
+        .iter()
+        .position(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    let topic = topics.remove(index);
 
-            create_delete_task_topic_event(topic_name.clone());
+    quota_service::release_topic(DestinationType::TaskTopic, &namespace, &topic_name);
+    create_delete_task_topic_event(namespace.clone(), topic_name.clone());
 
-            Some(topic.to_model())
-        } else {
-            warn!(
-                "TopicService::delete_task_topic tried to delete a task_topic that does not exist"
-            );
-            None
-        }
-    } else {
-        warn!("TopicService::delete_task_topic tried to lock a poisoned mutex");
-        None
-    }
+    let model = topic.to_model();
+    audit_service::record(
+        AuditAction::TopicDeleted,
+        DestinationType::TaskTopic.as_str(),
+        &topic_name,
+        details(&model),
+        None,
+    );
+    Ok(model)
 }
 
-fn create_delete_task_topic_event(topic_name: String) {
+fn create_delete_task_topic_event(namespace: String, topic_name: String) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
-        let event = TopicEvent::DeleteTaskTopic(EventDeleteTaskTopicData::new(topic_name));
+        let event =
+            TopicEvent::DeleteTaskTopic(EventDeleteTaskTopicData::new(namespace, topic_name));
         queue.enqueue(event);
     } else {
         warn!("TopicService::create_delete_task_topic_event tried to lock a poisoned mutex");
     }
 }
 
-pub async fn add_publisher_to_task_topic(topic_name: String, dts: AddPublisherToTaskTopic) {
-    if let Ok(mut topics) = STATE.task_topics.lock() {
-        if let Some(topic) = topics.iter_mut().find(|topic| topic.name == topic_name) {
-            if !topic.is_publisher(dts.publisher.clone()) {
-                topic.add_publisher(dts.publisher.clone());
-
-                create_add_publisher_to_task_topic_event(topic_name.clone(), dts.clone())
-            } else {
-                warn!("TopicService::add_publisher_to_task_topic tried to add a publisher to a task_topic that the publisher is already a publisher of");
-            }
-        } else {
-            warn!("TopicService::add_publisher_to_task_topic tried to add a publisher to a task_topic that does not exist");
-        }
-    } else {
-        warn!("TopicService::add_publisher_to_task_topic tried to lock a poisoned mutex");
+pub async fn add_publisher_to_task_topic(
+    topic_name: String,
+    dts: AddPublisherToTaskTopic,
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    authorize_registration(Action::Publish, &topic_name, &dts.publisher)?;
+    let mut topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    if topic.is_publisher(dts.publisher.clone()) {
+        return Err(ServiceError::AlreadyAPublisher(dts.publisher));
     }
+    topic.add_publisher(dts.publisher.clone());
+
+    audit_service::record(
+        AuditAction::PublisherAdded,
+        DestinationType::TaskTopic.as_str(),
+        &topic_name,
+        None,
+        details(&dts),
+    );
+    create_add_publisher_to_task_topic_event(namespace.clone(), topic_name, dts);
+    Ok(())
 }
 
-fn create_add_publisher_to_task_topic_event(topic_name: String, dts: AddPublisherToTaskTopic) {
+fn create_add_publisher_to_task_topic_event(
+    namespace: String,
+    topic_name: String,
+    dts: AddPublisherToTaskTopic,
+) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
         let event = TopicEvent::AddPublisherTaskTopic(EventAddPublisherToTaskTopicData::new(
-            topic_name, dts,
+            namespace, topic_name, dts,
         ));
         queue.enqueue(event);
     } else {
@@ -127,31 +197,41 @@ fn create_add_publisher_to_task_topic_event(topic_name: String, dts: AddPublishe
 pub async fn remove_publisher_from_task_topic(
     topic_name: String,
     dts: RemovePublisherFromTaskTopic,
-) {
-    if let Ok(mut topics) = STATE.task_topics.lock() {
-        if let Some(topic) = topics.iter_mut().find(|topic| topic.name == topic_name) {
-            if topic.is_publisher(dts.publisher.clone()) {
-                topic.remove_publisher(dts.publisher.clone());
-
-                create_remove_publisher_from_task_topic_event(topic_name.clone(), dts.clone())
-            } else {
-                warn!("TopicService::remove_publisher_from_task_topic tried to remove a publisher from a task_topic that the publisher is not a publisher of");
-            }
-        } else {
-            warn!("TopicService::remove_publisher_from_task_topic tried to remove a publisher from a task_topic that does not exist");
-        }
-    } else {
-        warn!("TopicService::remove_publisher_from_task_topic tried to lock a poisoned mutex");
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    authorize_registration(Action::Publish, &topic_name, &dts.publisher)?;
+    let mut topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    if !topic.is_publisher(dts.publisher.clone()) {
+        return Err(ServiceError::NotAPublisher(dts.publisher));
     }
+    topic.remove_publisher(dts.publisher.clone());
+
+    audit_service::record(
+        AuditAction::PublisherRemoved,
+        DestinationType::TaskTopic.as_str(),
+        &topic_name,
+        details(&dts),
+        None,
+    );
+    create_remove_publisher_from_task_topic_event(namespace.clone(), topic_name, dts);
+    Ok(())
 }
 
 fn create_remove_publisher_from_task_topic_event(
+    namespace: String,
     topic_name: String,
     dts: RemovePublisherFromTaskTopic,
 ) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
         let event = TopicEvent::RemovePublisherTaskTopic(
-            EventRemovePublisherFromTaskTopicData::new(topic_name, dts),
+            EventRemovePublisherFromTaskTopicData::new(namespace, topic_name, dts),
         );
         queue.enqueue(event);
     } else {
@@ -163,54 +243,125 @@ pub async fn publish_to_task_topic(
     topic_name: String,
     publisher_identifier: String,
     dts: PublishToTaskTopic<TopicType>,
-) {
-    if let Ok(mut topics) = STATE.task_topics.lock() {
-        if let Some(topic) = topics.iter_mut().find(|topic| topic.name == topic_name) {
-            if topic.is_publisher(publisher_identifier) {
-                topic.publish(dts.data.clone());
-
-                create_publish_to_task_topic_event(topic_name.clone(), dts.clone())
-            } else {
-                warn!("TopicService::publish_to_task_topic tried to publish to a task_topic that the publisher is not a publisher of");
-            }
-        } else {
-            warn!("TopicService::publish_to_task_topic tried to publish to a task_topic that does not exist");
-        }
-    } else {
-        warn!("TopicService::publish_to_task_topic tried to lock a poisoned mutex");
+) -> ServiceResult<PublishedTask> {
+    let namespace = current_namespace();
+    authorize_identifier(&publisher_identifier)?;
+    authorize(Action::Publish, &topic_name)?;
+    let mut topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    if !topic.is_publisher(publisher_identifier.clone()) {
+        return Err(ServiceError::NotAPublisher(publisher_identifier));
     }
+    let bytes = dts.data.len() as u64;
+    quota_service::admit_publish(
+        DestinationType::TaskTopic,
+        &namespace,
+        &topic_name,
+        &publisher_identifier,
+        bytes,
+    )?;
+    let id = topic.publish(dts.data.clone());
+    quota_service::store(DestinationType::TaskTopic, &namespace, &topic_name, bytes);
+    metrics::record_publish(DestinationType::TaskTopic, &namespace, &topic_name, bytes);
+
+    create_publish_to_task_topic_event(namespace.clone(), topic_name.clone(), dts);
+    notification_service::notify_new_data(DestinationType::TaskTopic, &namespace, &topic_name);
+
+    Ok(PublishedTask::new(id))
+}
+
+/// Publishes a task routed by an exchange binding or a forwarding rule, authorized like
+/// `message_topic_service::route_to_message_topic` when the binding or rule was created.
+pub fn route_to_task_topic(
+    topic_name: String,
+    publisher_identifier: &str,
+    dts: PublishToTaskTopic<TopicType>,
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    let mut topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    let bytes = dts.data.len() as u64;
+    quota_service::admit_publish(
+        DestinationType::TaskTopic,
+        &namespace,
+        &topic_name,
+        publisher_identifier,
+        bytes,
+    )?;
+    topic.publish(dts.data.clone());
+    quota_service::store(DestinationType::TaskTopic, &namespace, &topic_name, bytes);
+    metrics::record_publish(DestinationType::TaskTopic, &namespace, &topic_name, bytes);
+
+    create_publish_to_task_topic_event(namespace.clone(), topic_name.clone(), dts);
+    notification_service::notify_new_data(DestinationType::TaskTopic, &namespace, &topic_name);
+    Ok(())
 }
 
-fn create_publish_to_task_topic_event(topic_name: String, dts: PublishToTaskTopic<TopicType>) {
+fn create_publish_to_task_topic_event(
+    namespace: String,
+    topic_name: String,
+    dts: PublishToTaskTopic<TopicType>,
+) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
-        let event = TopicEvent::PublishTaskTopic(EventPublishToTaskTopicData::new(topic_name, dts));
+        let event = TopicEvent::PublishTaskTopic(EventPublishToTaskTopicData::new(
+            namespace, topic_name, dts,
+        ));
         queue.enqueue(event);
     } else {
         warn!("TopicService::create_publish_to_task_topic_event tried to lock a poisoned mutex");
     }
 }
 
-pub async fn add_subscriber_to_task_topic(topic_name: String, dts: AddSubscriberToTaskTopic) {
-    if let Ok(mut topics) = STATE.task_topics.lock() {
-        if let Some(topic) = topics.iter_mut().find(|topic| topic.name == topic_name) {
-            if !topic.is_subscriber(dts.subscriber.clone()) {
-                topic.add_subscriber(dts.subscriber.clone());
-                create_subscribe_to_task_topic_event(topic_name.clone(), dts.clone())
-            } else {
-                warn!("TopicService::subscribe_to_task_topic tried to subscribe to a task_topic that the subscriber is already subscribed to");
-            }
-        } else {
-            warn!("TopicService::subscribe_to_task_topic tried to subscribe to a task_topic that does not exist");
-        }
-    } else {
-        warn!("TopicService::subscribe_to_task_topic tried to lock a poisoned mutex");
+pub async fn add_subscriber_to_task_topic(
+    topic_name: String,
+    dts: AddSubscriberToTaskTopic,
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    authorize_registration(Action::Subscribe, &topic_name, &dts.subscriber)?;
+    let mut topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    if topic.is_subscriber(dts.subscriber.clone()) {
+        return Err(ServiceError::AlreadyASubscriber(dts.subscriber));
     }
+    topic.add_subscriber(dts.subscriber.clone());
+
+    audit_service::record(
+        AuditAction::SubscriberAdded,
+        DestinationType::TaskTopic.as_str(),
+        &topic_name,
+        None,
+        details(&dts),
+    );
+    create_subscribe_to_task_topic_event(namespace.clone(), topic_name, dts);
+    Ok(())
 }
 
-fn create_subscribe_to_task_topic_event(topic_name: String, dts: AddSubscriberToTaskTopic) {
+fn create_subscribe_to_task_topic_event(
+    namespace: String,
+    topic_name: String,
+    dts: AddSubscriberToTaskTopic,
+) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
         let event = TopicEvent::AddSubscriberTaskTopic(EventAddSubscriberToTaskTopicData::new(
-            topic_name, dts,
+            namespace, topic_name, dts,
         ));
         queue.enqueue(event);
     } else {
@@ -221,31 +372,41 @@ fn create_subscribe_to_task_topic_event(topic_name: String, dts: AddSubscriberTo
 pub async fn remove_subscriber_from_task_topic(
     topic_name: String,
     dts: RemoveSubscriberFromTaskTopic,
-) {
-    if let Ok(mut topics) = STATE.task_topics.lock() {
-        if let Some(topic) = topics.iter_mut().find(|topic| topic.name == topic_name) {
-            if topic.is_subscriber(dts.subscriber.clone()) {
-                topic.remove_subscriber(dts.subscriber.clone());
-
-                create_unsubscribe_from_task_topic_event(topic_name.clone(), dts.clone())
-            } else {
-                warn!("TopicService::unsubscribe_from_task_topic tried to unsubscribe from a task_topic that the subscriber is not subscribed to");
-            }
-        } else {
-            warn!("TopicService::unsubscribe_from_task_topic tried to unsubscribe from a task_topic that does not exist");
-        }
-    } else {
-        warn!("TopicService::unsubscribe_from_task_topic tried to lock a poisoned mutex");
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    authorize_registration(Action::Subscribe, &topic_name, &dts.subscriber)?;
+    let mut topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    if !topic.is_subscriber(dts.subscriber.clone()) {
+        return Err(ServiceError::NotASubscriber(dts.subscriber));
     }
+    topic.remove_subscriber(dts.subscriber.clone());
+
+    audit_service::record(
+        AuditAction::SubscriberRemoved,
+        DestinationType::TaskTopic.as_str(),
+        &topic_name,
+        details(&dts),
+        None,
+    );
+    create_unsubscribe_from_task_topic_event(namespace.clone(), topic_name, dts);
+    Ok(())
 }
 
 fn create_unsubscribe_from_task_topic_event(
+    namespace: String,
     topic_name: String,
     dts: RemoveSubscriberFromTaskTopic,
 ) {
     if let Ok(mut queue) = STATE.event_queue.lock() {
         let event = TopicEvent::RemoveSubscriberTaskTopic(
-            EventRemoveSubscriberFromTaskTopicData::new(topic_name, dts),
+            EventRemoveSubscriberFromTaskTopicData::new(namespace, topic_name, dts),
         );
         queue.enqueue(event);
     } else {
@@ -255,43 +416,198 @@ fn create_unsubscribe_from_task_topic_event(
     }
 }
 
-pub async fn is_there_a_task_for_subscriber(topic_name: String, subscriber: String) -> NewTasks {
-    if let Ok(topics) = STATE.task_topics.lock() {
-        if let Some(topic) = topics.iter().find(|topic| topic.name == topic_name) {
-            if topic.is_subscriber(subscriber.clone()) {
-                NewTasks::new(topic.has_open_tasks())
-            } else {
-                warn!("TopicService::is_there_a_task_for_subscriber tried to check a task_topic that the subscriber is not subscribed to");
-                NewTasks::new(false)
-            }
-        } else {
-            warn!("TopicService::is_there_a_task_for_subscriber tried to check a task_topic that does not exist");
-            NewTasks::new(false)
-        }
-    } else {
-        warn!("TopicService::is_there_a_task_for_subscriber tried to lock a poisoned mutex");
-        NewTasks::new(false)
+pub async fn is_there_a_task_for_subscriber(
+    topic_name: String,
+    subscriber: String,
+) -> ServiceResult<NewTasks> {
+    let namespace = current_namespace();
+    authorize_identifier(&subscriber)?;
+    authorize(Action::Subscribe, &topic_name)?;
+    let topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or(ServiceError::TopicNotFound(topic_name))?;
+    if !topic.is_subscriber(subscriber.clone()) {
+        return Err(ServiceError::NotASubscriber(subscriber));
     }
+    Ok(NewTasks::new(topic.has_open_tasks()))
 }
 
+/// Leases the next open task to the subscriber, `None` when there is no open task.
 pub async fn get_new_task_for_subscriber(
     topic_name: String,
     subscriber: String,
-) -> Option<TopicType> {
-    if let Ok(mut topics) = STATE.task_topics.lock() {
-        if let Some(topic) = topics.iter_mut().find(|topic| topic.name == topic_name) {
-            if topic.is_subscriber(subscriber.clone()) {
-                topic.fetch_data(subscriber)
-            } else {
-                warn!("TopicService::get_new_task_for_subscriber tried to get a task from a task_topic that the subscriber is not subscribed to");
-                None
-            }
-        } else {
-            warn!("TopicService::get_new_task_for_subscriber tried to get a task from a task_topic that does not exist");
-            None
-        }
-    } else {
-        warn!("TopicService::get_new_task_for_subscriber tried to lock a poisoned mutex");
-        None
+) -> ServiceResult<Option<AssignedTask<TopicType>>> {
+    let namespace = current_namespace();
+    authorize_identifier(&subscriber)?;
+    authorize(Action::Subscribe, &topic_name)?;
+    let mut topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    if !topic.is_subscriber(subscriber.clone()) {
+        return Err(ServiceError::NotASubscriber(subscriber));
     }
+    let lease = task_lease_timeout();
+    topic.remove_finished_tasks(task_result_retention());
+    metrics::record_fetch(DestinationType::TaskTopic, &namespace, &topic_name);
+    Ok(topic
+        .fetch_data(subscriber, lease)
+        .map(|(id, data)| AssignedTask::new(id, data, lease.as_secs())))
+}
+
+pub async fn heartbeat_task(
+    topic_name: String,
+    subscriber: String,
+    task_id: Uuid,
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    authorize_identifier(&subscriber)?;
+    authorize(Action::Subscribe, &topic_name)?;
+    let mut topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    topic
+        .heartbeat(task_id, &subscriber, task_lease_timeout())
+        .then_some(())
+        .ok_or(ServiceError::TaskNotLeased(task_id))
+}
+
+pub async fn report_task_progress(
+    topic_name: String,
+    subscriber: String,
+    task_id: Uuid,
+    dts: ReportTaskProgress,
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    authorize_identifier(&subscriber)?;
+    authorize(Action::Subscribe, &topic_name)?;
+    let mut topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    topic
+        .report_progress(task_id, &subscriber, dts.progress, task_lease_timeout())
+        .then_some(())
+        .ok_or(ServiceError::TaskNotLeased(task_id))
+}
+
+pub async fn complete_task(
+    topic_name: String,
+    subscriber: String,
+    task_id: Uuid,
+    dts: CompleteTask<TopicType>,
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    authorize_identifier(&subscriber)?;
+    authorize(Action::Subscribe, &topic_name)?;
+    let mut topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    let data = topic
+        .complete(task_id, &subscriber, dts.result)
+        .ok_or(ServiceError::TaskNotLeased(task_id))?;
+    release_finished_task(&namespace, &topic_name, &data);
+    Ok(())
+}
+
+pub async fn fail_task(
+    topic_name: String,
+    subscriber: String,
+    task_id: Uuid,
+    dts: FailTask,
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    authorize_identifier(&subscriber)?;
+    authorize(Action::Subscribe, &topic_name)?;
+    let mut topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    let data = topic
+        .fail(task_id, &subscriber, dts.error)
+        .ok_or(ServiceError::TaskNotLeased(task_id))?;
+    release_finished_task(&namespace, &topic_name, &data);
+    Ok(())
+}
+
+// a finished task no longer counts towards the stored bytes, only its status and result are
+// kept for the retention
+fn release_finished_task(namespace: &str, topic_name: &str, data: &TopicType) {
+    quota_service::release(
+        DestinationType::TaskTopic,
+        namespace,
+        topic_name,
+        data.len() as u64,
+    );
+}
+
+/// Gives up the lease of a task, so that it is handed out again.
+pub async fn release_task(
+    topic_name: String,
+    subscriber: String,
+    task_id: Uuid,
+) -> ServiceResult<()> {
+    let namespace = current_namespace();
+    authorize_identifier(&subscriber)?;
+    authorize(Action::Subscribe, &topic_name)?;
+    let mut topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter_mut()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or_else(|| ServiceError::TopicNotFound(topic_name.clone()))?;
+    topic
+        .release(task_id, &subscriber)
+        .then_some(())
+        .ok_or(ServiceError::TaskNotLeased(task_id))?;
+    notification_service::notify_new_data(DestinationType::TaskTopic, &namespace, &topic_name);
+    Ok(())
+}
+
+pub async fn get_task_status(
+    topic_name: String,
+    task_id: Uuid,
+) -> ServiceResult<TaskStatusModel<TopicType>> {
+    let namespace = current_namespace();
+    authorize(Action::Read, &topic_name)?;
+    let topics = STATE
+        .task_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let topic = topics
+        .iter()
+        .find(|topic| topic.is_named(&namespace, &topic_name))
+        .ok_or(ServiceError::TopicNotFound(topic_name))?;
+    topic
+        .get_task_status(task_id)
+        .ok_or(ServiceError::TaskNotFound(task_id))
 }
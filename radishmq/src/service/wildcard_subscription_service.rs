@@ -0,0 +1,488 @@
+use crate::auth::permission::Action;
+use crate::event_queue::event::{
+    EventAddTopicToWildcardSubscriptionData, EventCreateWildcardSubscriptionData,
+    EventDeleteWildcardSubscriptionData, TopicEvent,
+};
+use crate::metrics;
+use crate::model::audit_model::AuditAction;
+use crate::model::destination_model::DestinationType;
+use crate::model::message_topic_model::{
+    AddSubscriberToMessageTopic, Message, NewDataMessageTopic, RemoveSubscriberFromMessageTopic,
+};
+use crate::model::wildcard_subscription_model::{
+    CreateWildcardSubscription, DataWildcardSubscription, TopicDataWildcardSubscription,
+    WildcardSubscriptionModel,
+};
+use crate::service::acl_service::is_authorized;
+use crate::service::audit_service::{self, details};
+use crate::service::credential_service::authorize_identifier;
+use crate::service::error::{ServiceError, ServiceResult};
+use crate::service::message_topic_service::{
+    create_add_subscriber_to_message_topic_event, create_fetch_data_from_topic_event,
+    create_remove_subscriber_from_message_topic_event,
+};
+use crate::topic::message_topic::MessageTopic;
+use crate::topic::wildcard_subscription::WildcardSubscription;
+use crate::utils::namespace::DEFAULT_NAMESPACE;
+use crate::utils::topic_pattern::TopicPattern;
+use crate::utils::types::TopicType;
+use crate::STATE;
+use log::warn;
+
+const WILDCARD_SUBSCRIPTION_RESOURCE: &str = "wildcard_subscription";
+
+pub async fn get_all_wildcard_subscriptions() -> ServiceResult<Vec<WildcardSubscriptionModel>> {
+    let topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let subscriptions = STATE
+        .wildcard_subscriptions
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    Ok(subscriptions
+        .iter()
+        .map(|subscription| subscription.to_model(get_subscribed_topics(&topics, subscription)))
+        .collect())
+}
+
+pub async fn get_wildcard_subscription(
+    subscriber: String,
+    pattern: String,
+) -> ServiceResult<WildcardSubscriptionModel> {
+    let topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let subscriptions = STATE
+        .wildcard_subscriptions
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    subscriptions
+        .iter()
+        .find(|subscription| subscription.is_keyed(&subscriber, &pattern))
+        .map(|subscription| subscription.to_model(get_subscribed_topics(&topics, subscription)))
+        .ok_or(ServiceError::WildcardSubscriptionNotFound(
+            subscriber,
+            Some(pattern),
+        ))
+}
+
+pub async fn create_wildcard_subscription(
+    dts: CreateWildcardSubscription,
+) -> ServiceResult<WildcardSubscriptionModel> {
+    authorize_identifier(&dts.subscriber)?;
+    if dts.filter.as_ref().is_some_and(|filter| !filter.is_valid()) {
+        return Err(ServiceError::InvalidFilter);
+    }
+    let pattern = TopicPattern::parse(&dts.pattern)
+        .ok_or_else(|| ServiceError::InvalidPattern(dts.pattern.clone()))?;
+
+    let mut topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let mut subscriptions = STATE
+        .wildcard_subscriptions
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    if subscriptions
+        .iter()
+        .any(|subscription| subscription.is_keyed(&dts.subscriber, &dts.pattern))
+    {
+        return Err(ServiceError::WildcardSubscriptionExists(
+            dts.subscriber,
+            dts.pattern,
+        ));
+    }
+
+    // the subscription owns the topic subscriptions it creates, and shares those another wildcard
+    // subscription of the subscriber created, but never a subscription made directly on a topic
+    let mut owned_topics = Vec::new();
+    for topic in topics
+        .iter_mut()
+        .filter(|topic| topic.namespace == DEFAULT_NAMESPACE && pattern.matches(&topic.name))
+    {
+        if !topic.is_subscriber(dts.subscriber.clone()) {
+            topic.add_subscriber(dts.subscriber.clone(), dts.filter.clone());
+
+            let added =
+                AddSubscriberToMessageTopic::new(dts.subscriber.clone(), dts.filter.clone());
+            audit_service::record(
+                AuditAction::SubscriberAdded,
+                DestinationType::MessageTopic.as_str(),
+                &topic.name,
+                None,
+                details(&added),
+            );
+            create_add_subscriber_to_message_topic_event(
+                topic.namespace.clone(),
+                topic.name.clone(),
+                added,
+            );
+            owned_topics.push(topic.name.clone());
+        } else if subscriptions
+            .iter()
+            .any(|other| other.subscriber == dts.subscriber && other.owns(&topic.name))
+        {
+            owned_topics.push(topic.name.clone());
+        }
+    }
+
+    let subscription = WildcardSubscription::new(
+        dts.subscriber.clone(),
+        pattern,
+        dts.filter.clone(),
+        owned_topics.clone(),
+    );
+    subscriptions.push(subscription.clone());
+
+    create_create_wildcard_subscription_event(dts, owned_topics);
+
+    let model = subscription.to_model(get_subscribed_topics(&topics, &subscription));
+    audit_service::record(
+        AuditAction::WildcardSubscriptionCreated,
+        WILDCARD_SUBSCRIPTION_RESOURCE,
+        &model.subscriber,
+        None,
+        details(&model),
+    );
+    Ok(model)
+}
+
+fn create_create_wildcard_subscription_event(dts: CreateWildcardSubscription, topics: Vec<String>) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::CreateWildcardSubscription(
+            EventCreateWildcardSubscriptionData::new(dts, topics),
+        );
+        queue.enqueue(event);
+    } else {
+        warn!("WildcardSubscriptionService::create_create_wildcard_subscription_event tried to lock a poisoned mutex");
+    }
+}
+
+pub async fn delete_wildcard_subscription(
+    subscriber: String,
+    pattern: String,
+) -> ServiceResult<WildcardSubscriptionModel> {
+    authorize_identifier(&subscriber)?;
+    let mut topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let mut subscriptions = STATE
+        .wildcard_subscriptions
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let index = subscriptions
+        .iter()
+        .position(|subscription| subscription.is_keyed(&subscriber, &pattern))
+        .ok_or_else(|| {
+            ServiceError::WildcardSubscriptionNotFound(subscriber.clone(), Some(pattern.clone()))
+        })?;
+    let subscription = subscriptions.remove(index);
+    let subscribed_topics = get_subscribed_topics(&topics, &subscription);
+
+    create_delete_wildcard_subscription_event(subscriber.clone(), pattern);
+
+    // a topic subscription stays while another wildcard subscription of the subscriber owns it
+    for topic in topics.iter_mut().filter(|topic| {
+        topic.namespace == DEFAULT_NAMESPACE
+            && subscription.owns(&topic.name)
+            && topic.is_subscriber(subscriber.clone())
+            && !subscriptions
+                .iter()
+                .any(|other| other.subscriber == subscriber && other.owns(&topic.name))
+    }) {
+        topic.remove_subscriber(subscriber.clone());
+
+        let removed = RemoveSubscriberFromMessageTopic::new(subscriber.clone());
+        audit_service::record(
+            AuditAction::SubscriberRemoved,
+            DestinationType::MessageTopic.as_str(),
+            &topic.name,
+            details(&removed),
+            None,
+        );
+        create_remove_subscriber_from_message_topic_event(
+            topic.namespace.clone(),
+            topic.name.clone(),
+            removed,
+        );
+    }
+
+    let model = subscription.to_model(subscribed_topics);
+    audit_service::record(
+        AuditAction::WildcardSubscriptionDeleted,
+        WILDCARD_SUBSCRIPTION_RESOURCE,
+        &subscriber,
+        details(&model),
+        None,
+    );
+    Ok(model)
+}
+
+fn create_delete_wildcard_subscription_event(subscriber: String, pattern: String) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::DeleteWildcardSubscription(
+            EventDeleteWildcardSubscriptionData::new(subscriber, pattern),
+        );
+        queue.enqueue(event);
+    } else {
+        warn!("WildcardSubscriptionService::create_delete_wildcard_subscription_event tried to lock a poisoned mutex");
+    }
+}
+
+pub async fn is_there_new_data_for_wildcard_subscriber(
+    subscriber: String,
+) -> ServiceResult<NewDataMessageTopic> {
+    authorize_identifier(&subscriber)?;
+    let patterns = get_wildcard_subscription_patterns(&subscriber)?;
+
+    let topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let new_data = topics
+        .iter()
+        .filter(|topic| topic.namespace == DEFAULT_NAMESPACE)
+        .filter(|topic| patterns.iter().any(|pattern| pattern.matches(&topic.name)))
+        .filter(|topic| topic.is_subscriber(subscriber.clone()))
+        .filter(|topic| is_authorized(Action::Subscribe, &topic.name))
+        .any(|topic| topic.new_data_to_fetch_for_subscriber(subscriber.clone()));
+
+    Ok(NewDataMessageTopic::new(new_data))
+}
+
+pub async fn get_new_data_for_wildcard_subscriber(
+    subscriber: String,
+) -> ServiceResult<DataWildcardSubscription<Message<TopicType>>> {
+    authorize_identifier(&subscriber)?;
+    let patterns = get_wildcard_subscription_patterns(&subscriber)?;
+
+    let mut topics = STATE
+        .message_topics
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let mut data = Vec::new();
+
+    for topic in topics
+        .iter_mut()
+        .filter(|topic| topic.namespace == DEFAULT_NAMESPACE)
+        .filter(|topic| patterns.iter().any(|pattern| pattern.matches(&topic.name)))
+        .filter(|topic| topic.is_subscriber(subscriber.clone()))
+        .filter(|topic| is_authorized(Action::Subscribe, &topic.name))
+    {
+        let topic_data = topic
+            .get_data_for_subscriber(subscriber.clone())
+            .unwrap_or_default();
+        metrics::record_fetch(DestinationType::MessageTopic, &topic.namespace, &topic.name);
+
+        create_fetch_data_from_topic_event(
+            topic.namespace.clone(),
+            topic.name.clone(),
+            subscriber.clone(),
+            topic.get_subscriber_index(subscriber.clone()),
+        );
+
+        if !topic_data.is_empty() {
+            data.push(TopicDataWildcardSubscription::new(
+                topic.name.clone(),
+                topic_data,
+            ));
+        }
+    }
+
+    Ok(DataWildcardSubscription::new(data))
+}
+
+/// Returns the subscribers a new message topic gets from the wildcard subscriptions matching it,
+/// every matching subscription owns the created topic subscription. The subscriptions are recorded
+/// in the audit log on behalf of the caller creating the topic.
+pub fn claim_new_message_topic(
+    namespace: &str,
+    topic_name: &str,
+) -> Vec<AddSubscriberToMessageTopic> {
+    if namespace != DEFAULT_NAMESPACE {
+        return Vec::new();
+    }
+
+    let Ok(mut subscriptions) = STATE.wildcard_subscriptions.lock() else {
+        warn!(
+            "WildcardSubscriptionService::claim_new_message_topic tried to lock a poisoned mutex"
+        );
+        return Vec::new();
+    };
+    let mut subscribers: Vec<AddSubscriberToMessageTopic> = Vec::new();
+    for subscription in subscriptions
+        .iter_mut()
+        .filter(|subscription| subscription.matches(topic_name))
+    {
+        subscription.add_topic(topic_name.to_string());
+        create_add_topic_to_wildcard_subscription_event(
+            subscription.subscriber.clone(),
+            subscription.pattern.as_str().to_string(),
+            topic_name.to_string(),
+        );
+
+        // a subscriber with several matching patterns subscribes once, with the filter of the first
+        if !subscribers
+            .iter()
+            .any(|subscriber| subscriber.subscriber == subscription.subscriber)
+        {
+            let added = AddSubscriberToMessageTopic::new(
+                subscription.subscriber.clone(),
+                subscription.filter.clone(),
+            );
+            audit_service::record(
+                AuditAction::SubscriberAdded,
+                DestinationType::MessageTopic.as_str(),
+                topic_name,
+                None,
+                details(&added),
+            );
+            subscribers.push(added);
+        }
+    }
+    subscribers
+}
+
+fn create_add_topic_to_wildcard_subscription_event(
+    subscriber: String,
+    pattern: String,
+    topic_name: String,
+) {
+    if let Ok(mut queue) = STATE.event_queue.lock() {
+        let event = TopicEvent::AddTopicToWildcardSubscription(
+            EventAddTopicToWildcardSubscriptionData::new(subscriber, pattern, topic_name),
+        );
+        queue.enqueue(event);
+    } else {
+        warn!("WildcardSubscriptionService::create_add_topic_to_wildcard_subscription_event tried to lock a poisoned mutex");
+    }
+}
+
+fn get_wildcard_subscription_patterns(subscriber: &str) -> ServiceResult<Vec<TopicPattern>> {
+    let subscriptions = STATE
+        .wildcard_subscriptions
+        .lock()
+        .map_err(|_| ServiceError::PoisonedLock)?;
+    let patterns: Vec<TopicPattern> = subscriptions
+        .iter()
+        .filter(|subscription| subscription.subscriber == subscriber)
+        .map(|subscription| subscription.pattern.clone())
+        .collect();
+    if patterns.is_empty() {
+        return Err(ServiceError::WildcardSubscriptionNotFound(
+            subscriber.to_string(),
+            None,
+        ));
+    }
+    Ok(patterns)
+}
+
+// wildcard subscriptions only span the topics of the default namespace
+fn get_subscribed_topics(
+    topics: &[MessageTopic<Message<TopicType>>],
+    subscription: &WildcardSubscription,
+) -> Vec<String> {
+    topics
+        .iter()
+        .filter(|topic| topic.namespace == DEFAULT_NAMESPACE && subscription.matches(&topic.name))
+        .filter(|topic| topic.is_subscriber(subscription.subscriber.clone()))
+        .map(|topic| topic.name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::message_topic_model::CreateMessageTopic;
+    use crate::service::message_topic_service::create_message_topic;
+
+    fn add_topic(name: &str, subscriber: Option<&str>) {
+        let mut topic = MessageTopic::new(DEFAULT_NAMESPACE.to_string(), name.to_string());
+        if let Some(subscriber) = subscriber {
+            topic.add_subscriber(subscriber.to_string(), None);
+        }
+        STATE.message_topics.lock().unwrap().push(topic);
+    }
+
+    fn is_subscribed(topic_name: &str, subscriber: &str) -> bool {
+        STATE
+            .message_topics
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|topic| topic.name == topic_name && topic.is_subscriber(subscriber.to_string()))
+    }
+
+    async fn subscribe(
+        subscriber: &str,
+        pattern: &str,
+    ) -> ServiceResult<WildcardSubscriptionModel> {
+        create_wildcard_subscription(CreateWildcardSubscription::new(
+            subscriber.to_string(),
+            pattern.to_string(),
+            None,
+        ))
+        .await
+    }
+
+    async fn unsubscribe(
+        subscriber: &str,
+        pattern: &str,
+    ) -> ServiceResult<WildcardSubscriptionModel> {
+        delete_wildcard_subscription(subscriber.to_string(), pattern.to_string()).await
+    }
+
+    #[tokio::test]
+    async fn delete_keeps_subscriptions_the_pattern_did_not_create() {
+        add_topic("ownership.direct", Some("owner"));
+        add_topic("ownership.created", None);
+
+        subscribe("owner", "ownership.*").await.unwrap();
+        assert!(is_subscribed("ownership.created", "owner"));
+        unsubscribe("owner", "ownership.*").await.unwrap();
+
+        assert!(is_subscribed("ownership.direct", "owner"));
+        assert!(!is_subscribed("ownership.created", "owner"));
+    }
+
+    #[tokio::test]
+    async fn subscriptions_are_keyed_by_subscriber_and_pattern() {
+        add_topic("shared.orders.eu", None);
+
+        subscribe("sharer", "shared.#").await.unwrap();
+        subscribe("sharer", "shared.orders.*").await.unwrap();
+        assert!(matches!(
+            subscribe("sharer", "shared.#").await,
+            Err(ServiceError::WildcardSubscriptionExists(_, _))
+        ));
+
+        // the topic subscription stays until the last pattern that created it is deleted
+        unsubscribe("sharer", "shared.#").await.unwrap();
+        assert!(is_subscribed("shared.orders.eu", "sharer"));
+        unsubscribe("sharer", "shared.orders.*").await.unwrap();
+        assert!(!is_subscribed("shared.orders.eu", "sharer"));
+        assert!(matches!(
+            unsubscribe("sharer", "shared.orders.*").await,
+            Err(ServiceError::WildcardSubscriptionNotFound(_, Some(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn new_topics_are_subscribed_by_matching_patterns() {
+        subscribe("claimer", "claimed.*").await.unwrap();
+        create_message_topic(CreateMessageTopic::new("claimed.orders".to_string()))
+            .await
+            .unwrap();
+
+        assert!(is_subscribed("claimed.orders", "claimer"));
+        let subscription =
+            get_wildcard_subscription("claimer".to_string(), "claimed.*".to_string())
+                .await
+                .unwrap();
+        assert_eq!(subscription.topics, vec!["claimed.orders".to_string()]);
+    }
+}
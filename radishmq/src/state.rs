@@ -1,14 +1,37 @@
+use crate::auth::credential::Credential;
+use crate::auth::grant::Grant;
+use crate::encryption::data_key::{DataKey, DataKeyId};
 use crate::event_queue::worker::EventQueue;
+use crate::model::destination_model::TopicDestination;
+use crate::model::message_topic_model::Message;
+use crate::topic::exchange::Exchange;
+use crate::topic::forwarding_rule::ForwardingRule;
 use crate::topic::message_topic::MessageTopic;
+use crate::topic::quota::QuotaUsage;
 use crate::topic::task_topic::TaskTopic;
+use crate::topic::wildcard_subscription::WildcardSubscription;
 use crate::utils::types::TopicType;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+const NOTIFICATION_CAPACITY: usize = 1024;
 
 #[derive(Clone)]
 pub struct State {
-    pub message_topics: Arc<Mutex<Vec<MessageTopic<TopicType>>>>,
+    pub message_topics: Arc<Mutex<Vec<MessageTopic<Message<TopicType>>>>>,
     pub task_topics: Arc<Mutex<Vec<TaskTopic<TopicType>>>>,
+    pub wildcard_subscriptions: Arc<Mutex<Vec<WildcardSubscription>>>,
+    pub exchanges: Arc<Mutex<Vec<Exchange>>>,
+    pub forwarding_rules: Arc<Mutex<Vec<ForwardingRule>>>,
+    pub retained_messages: Arc<Mutex<HashMap<String, Message<TopicType>>>>,
+    pub credentials: Arc<Mutex<Vec<Credential>>>,
+    pub acl_grants: Arc<Mutex<Vec<Grant>>>,
+    pub quota_usage: Arc<Mutex<QuotaUsage>>,
+    pub data_keys: Arc<Mutex<HashMap<DataKeyId, DataKey>>>,
+    pub topic_name_key: Arc<Mutex<Option<DataKey>>>,
     pub event_queue: Arc<Mutex<EventQueue>>,
+    pub notifications: broadcast::Sender<TopicDestination>,
 }
 
 impl State {
@@ -16,7 +39,17 @@ impl State {
         Self {
             message_topics: Arc::new(Mutex::new(Vec::new())),
             task_topics: Arc::new(Mutex::new(Vec::new())),
+            wildcard_subscriptions: Arc::new(Mutex::new(Vec::new())),
+            exchanges: Arc::new(Mutex::new(Vec::new())),
+            forwarding_rules: Arc::new(Mutex::new(Vec::new())),
+            retained_messages: Arc::new(Mutex::new(HashMap::new())),
+            credentials: Arc::new(Mutex::new(Vec::new())),
+            acl_grants: Arc::new(Mutex::new(Vec::new())),
+            quota_usage: Arc::new(Mutex::new(QuotaUsage::default())),
+            data_keys: Arc::new(Mutex::new(HashMap::new())),
+            topic_name_key: Arc::new(Mutex::new(None)),
             event_queue: Arc::new(Mutex::new(EventQueue::new())),
+            notifications: broadcast::channel(NOTIFICATION_CAPACITY).0,
         }
     }
 }
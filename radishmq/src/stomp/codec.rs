@@ -0,0 +1,130 @@
+use crate::stomp::frame::{unescape_header_part, StompFrame, StompItem, CONNECT, CONNECTED};
+use bytes::{Buf, Bytes, BytesMut};
+use std::io;
+use tokio_util::codec::{Decoder, Encoder};
+
+const MAX_FRAME_LENGTH: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Default)]
+pub struct StompCodec;
+
+impl Decoder for StompCodec {
+    type Item = StompItem;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        // heart-beats are end of lines between frames
+        if src.starts_with(b"\r\n") {
+            src.advance(2);
+            return Ok(Some(StompItem::Heartbeat));
+        }
+        if src.starts_with(b"\n") {
+            src.advance(1);
+            return Ok(Some(StompItem::Heartbeat));
+        }
+        if src.len() == 1 && src[0] == b'\r' {
+            return Ok(None);
+        }
+
+        let Some(header_end) = find_header_end(src) else {
+            return check_length(src).map(|_| None);
+        };
+        let (head, body_start) = header_end;
+        let head = std::str::from_utf8(&src[..head])
+            .map_err(|_| invalid("frame headers are not valid UTF-8"))?;
+
+        let mut lines = head
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line));
+        let command = lines.next().unwrap_or_default().to_string();
+        let escape = command != CONNECT && command != CONNECTED;
+        let mut headers = Vec::new();
+        for line in lines {
+            let (name, value) = line
+                .split_once(':')
+                .ok_or_else(|| invalid("header without a colon"))?;
+            headers.push((
+                unescape_header_part(name, escape)
+                    .ok_or_else(|| invalid("invalid header escape"))?,
+                unescape_header_part(value, escape)
+                    .ok_or_else(|| invalid("invalid header escape"))?,
+            ));
+        }
+
+        let content_length = headers
+            .iter()
+            .find(|(name, _)| name == "content-length")
+            .map(|(_, value)| value.parse::<usize>())
+            .transpose()
+            .map_err(|_| invalid("content-length is not a number"))?;
+
+        let body_length = match content_length {
+            Some(length) => {
+                if body_start + length >= src.len() {
+                    return check_length(src).map(|_| None);
+                }
+                if src[body_start + length] != 0 {
+                    return Err(invalid("body is not terminated by a NULL octet"));
+                }
+                length
+            }
+            None => match src[body_start..].iter().position(|byte| *byte == 0) {
+                Some(length) => length,
+                None => return check_length(src).map(|_| None),
+            },
+        };
+
+        src.advance(body_start);
+        let body: Bytes = src.split_to(body_length).freeze();
+        src.advance(1);
+
+        Ok(Some(StompItem::Frame(StompFrame {
+            command,
+            headers,
+            body,
+        })))
+    }
+}
+
+impl Encoder<StompItem> for StompCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: StompItem, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        match item {
+            StompItem::Frame(frame) => frame.encode(dst),
+            StompItem::Heartbeat => dst.extend_from_slice(b"\n"),
+        }
+        Ok(())
+    }
+}
+
+// returns the length of the command and header lines and the offset of the body
+fn find_header_end(src: &[u8]) -> Option<(usize, usize)> {
+    let mut index = 0;
+    while index + 1 < src.len() {
+        if src[index] == b'\n' {
+            if src[index + 1] == b'\n' {
+                return Some((index, index + 2));
+            }
+            if src[index + 1..].starts_with(b"\r\n") {
+                return Some((index, index + 3));
+            }
+        }
+        index += 1;
+    }
+    None
+}
+
+fn check_length(src: &BytesMut) -> io::Result<()> {
+    if src.len() > MAX_FRAME_LENGTH {
+        return Err(invalid("frame exceeds the size limit"));
+    }
+    Ok(())
+}
+
+fn invalid(reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("malformed STOMP frame: {}", reason),
+    )
+}
@@ -0,0 +1,122 @@
+use bytes::{BufMut, Bytes, BytesMut};
+
+pub const PROTOCOL_VERSION: &str = "1.2";
+
+pub const CONNECT: &str = "CONNECT";
+pub const STOMP: &str = "STOMP";
+pub const CONNECTED: &str = "CONNECTED";
+pub const SEND: &str = "SEND";
+pub const SUBSCRIBE: &str = "SUBSCRIBE";
+pub const UNSUBSCRIBE: &str = "UNSUBSCRIBE";
+pub const ACK: &str = "ACK";
+pub const NACK: &str = "NACK";
+pub const BEGIN: &str = "BEGIN";
+pub const COMMIT: &str = "COMMIT";
+pub const ABORT: &str = "ABORT";
+pub const DISCONNECT: &str = "DISCONNECT";
+pub const MESSAGE: &str = "MESSAGE";
+pub const RECEIPT: &str = "RECEIPT";
+pub const ERROR: &str = "ERROR";
+
+/// Either a frame or a heart-beat, which is a single end of line between frames.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StompItem {
+    Frame(StompFrame),
+    Heartbeat,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StompFrame {
+    pub command: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Bytes,
+}
+
+impl StompFrame {
+    pub fn new(command: &str) -> Self {
+        Self {
+            command: command.to_string(),
+            headers: Vec::new(),
+            body: Bytes::new(),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: Bytes) -> Self {
+        self.body = body;
+        self
+    }
+
+    /// Returns the first occurrence of a header, repeated headers keep their first value.
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn encode(&self, dst: &mut BytesMut) {
+        // CONNECT and CONNECTED frames do not escape their headers for 1.0 compatibility
+        let escape = self.command != CONNECT && self.command != CONNECTED;
+
+        dst.put_slice(self.command.as_bytes());
+        dst.put_u8(b'\n');
+        for (name, value) in &self.headers {
+            put_header_part(dst, name, escape);
+            dst.put_u8(b':');
+            put_header_part(dst, value, escape);
+            dst.put_u8(b'\n');
+        }
+        if !self.body.is_empty() {
+            dst.put_slice(format!("content-length:{}\n", self.body.len()).as_bytes());
+        }
+        dst.put_u8(b'\n');
+        dst.put_slice(&self.body);
+        dst.put_u8(0);
+    }
+}
+
+fn put_header_part(dst: &mut BytesMut, value: &str, escape: bool) {
+    if !escape {
+        dst.put_slice(value.as_bytes());
+        return;
+    }
+    for character in value.chars() {
+        match character {
+            '\\' => dst.put_slice(b"\\\\"),
+            '\n' => dst.put_slice(b"\\n"),
+            '\r' => dst.put_slice(b"\\r"),
+            ':' => dst.put_slice(b"\\c"),
+            _ => {
+                let mut buffer = [0; 4];
+                dst.put_slice(character.encode_utf8(&mut buffer).as_bytes());
+            }
+        }
+    }
+}
+
+pub fn unescape_header_part(value: &str, escape: bool) -> Option<String> {
+    if !escape {
+        return Some(value.to_string());
+    }
+    let mut unescaped = String::with_capacity(value.len());
+    let mut characters = value.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            unescaped.push(character);
+            continue;
+        }
+        match characters.next()? {
+            '\\' => unescaped.push('\\'),
+            'n' => unescaped.push('\n'),
+            'r' => unescaped.push('\r'),
+            'c' => unescaped.push(':'),
+            _ => return None,
+        }
+    }
+    Some(unescaped)
+}
@@ -0,0 +1,755 @@
+use crate::auth::authenticator::Authenticator;
+use crate::auth::identity::{self, Identity};
+use crate::model::destination_model::{DestinationType, TopicDestination};
+use crate::model::message_topic_model::{
+    AddSubscriberToMessageTopic, PublishToMessageTopic, RemoveSubscriberFromMessageTopic,
+};
+use crate::model::task_topic_model::{
+    AddSubscriberToTaskTopic, CompleteTask, PublishToTaskTopic, RemoveSubscriberFromTaskTopic,
+};
+use crate::service::error::ServiceError;
+use crate::service::{message_topic_service, notification_service, task_topic_service};
+use crate::stomp::codec::StompCodec;
+use crate::stomp::frame::{
+    StompFrame, StompItem, ABORT, ACK, BEGIN, COMMIT, CONNECT, CONNECTED, DISCONNECT, ERROR,
+    MESSAGE, NACK, PROTOCOL_VERSION, RECEIPT, SEND, STOMP, SUBSCRIBE, UNSUBSCRIBE,
+};
+use crate::utils::namespace::DEFAULT_NAMESPACE;
+use crate::utils::peer_address;
+use crate::utils::types::Headers;
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use log::warn;
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{interval_at, sleep_until, timeout, Instant};
+use tokio_util::codec::Framed;
+use uuid::Uuid;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const SERVER_HEARTBEAT: Duration = Duration::from_secs(10);
+const TOPIC_PREFIX: &str = "/topic/";
+const QUEUE_PREFIX: &str = "/queue/";
+
+// frame headers that control the protocol and are not stored with messages
+const CONTROL_HEADERS: [&str; 4] = ["destination", "content-length", "receipt", "transaction"];
+
+pub async fn start_stomp_server() -> io::Result<()> {
+    let port = match env::var("STOMP_PORT") {
+        Ok(port) => port.parse::<u16>().expect("STOMP_PORT must be a number"),
+        Err(_) => {
+            println!("Skipping STOMP listener...");
+            return Ok(());
+        }
+    };
+    let address = env::var("STOMP_ADDRESS")
+        .or_else(|_| env::var("HTTP_ADDRESS"))
+        .expect("STOMP_ADDRESS or HTTP_ADDRESS must be set");
+
+    let listener = TcpListener::bind((address.as_str(), port)).await?;
+    println!("STOMP listener started on {}:{}", address, port);
+
+    let authenticator = Authenticator::from_env().map(Arc::new);
+    tokio::spawn(accept_connections(listener, authenticator));
+    Ok(())
+}
+
+async fn accept_connections(listener: TcpListener, authenticator: Option<Arc<Authenticator>>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, address)) => {
+                let authenticator = authenticator.clone();
+                tokio::spawn(peer_address::scope(address.ip().to_string(), async move {
+                    if let Err(e) = handle_connection(stream, authenticator).await {
+                        warn!("StompServer::handle_connection closed a connection: {}", e);
+                    }
+                }));
+            }
+            Err(e) => warn!(
+                "StompServer::accept_connections could not accept a connection: {}",
+                e
+            ),
+        }
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    authenticator: Option<Arc<Authenticator>>,
+) -> io::Result<()> {
+    let mut framed = Framed::new(stream, StompCodec);
+
+    let connect = match timeout(CONNECT_TIMEOUT, next_frame(&mut framed)).await {
+        Ok(Some(frame)) => frame?,
+        Ok(None) | Err(_) => return Ok(()),
+    };
+    if connect.command != CONNECT && connect.command != STOMP {
+        return send_error(&mut framed, "first frame must be CONNECT", None).await;
+    }
+    let supports_version = connect
+        .get_header("accept-version")
+        .is_some_and(|versions| versions.split(',').any(|v| v.trim() == PROTOCOL_VERSION));
+    if !supports_version {
+        let frame = StompFrame::new(ERROR)
+            .header("version", PROTOCOL_VERSION)
+            .header("message", "supported protocol versions are 1.2");
+        return framed.send(StompItem::Frame(frame)).await;
+    }
+
+    let (client_sends, client_receives) = parse_heartbeat(connect.get_header("heart-beat"));
+    let session_id = Uuid::new_v4().simple().to_string();
+    let (identity, login) = match authenticator {
+        Some(authenticator) => match authenticate(&authenticator, &connect) {
+            Some((identity, login)) => (Some(identity), Some(login)),
+            None => return send_error(&mut framed, "access refused", None).await,
+        },
+        None => (None, connect.get_header("login").map(str::to_string)),
+    };
+
+    let connected = StompFrame::new(CONNECTED)
+        .header("version", PROTOCOL_VERSION)
+        .header("session", &session_id)
+        .header("server", concat!("RadishMQ/", env!("CARGO_PKG_VERSION")))
+        .header(
+            "heart-beat",
+            &format!(
+                "{},{}",
+                SERVER_HEARTBEAT.as_millis(),
+                SERVER_HEARTBEAT.as_millis()
+            ),
+        );
+    framed.send(StompItem::Frame(connected)).await?;
+
+    let mut connection = Connection {
+        framed,
+        anonymous: login.is_none(),
+        identity: login.unwrap_or(session_id),
+        subscriptions: HashMap::new(),
+        transactions: HashMap::new(),
+    };
+    identity::on_behalf_of(identity, async {
+        let result = connection.run(client_sends, client_receives).await;
+        connection.close().await;
+        result
+    })
+    .await
+}
+
+/// The `passcode` of CONNECT carries the API key or JWT. The `login` is the identifier the client
+/// publishes and subscribes as, without one the first principal of the credential is used.
+fn authenticate(authenticator: &Authenticator, connect: &StompFrame) -> Option<(Identity, String)> {
+    let identity = authenticator
+        .authenticate(connect.get_header("passcode")?)
+        .map_err(|e| warn!("StompServer::authenticate rejected a connection: {}", e))
+        .ok()?;
+    let login = connect
+        .get_header("login")
+        .map(str::to_string)
+        .or_else(|| identity.principals.first().cloned())?;
+
+    // deliveries are pushed by notifications, which only exist in the default namespace
+    (identity.namespace == DEFAULT_NAMESPACE && identity.acts_as(&login))
+        .then_some((identity, login))
+}
+
+async fn next_frame(framed: &mut Framed<TcpStream, StompCodec>) -> Option<io::Result<StompFrame>> {
+    loop {
+        match framed.next().await? {
+            Ok(StompItem::Heartbeat) => continue,
+            Ok(StompItem::Frame(frame)) => return Some(Ok(frame)),
+            Err(e) => return Some(Err(e)),
+        }
+    }
+}
+
+async fn send_error(
+    framed: &mut Framed<TcpStream, StompCodec>,
+    message: &str,
+    receipt: Option<&str>,
+) -> io::Result<()> {
+    let mut frame = StompFrame::new(ERROR).header("message", message);
+    if let Some(receipt) = receipt {
+        frame = frame.header("receipt-id", receipt);
+    }
+    framed.send(StompItem::Frame(frame)).await
+}
+
+// a heart-beat header `cx,cy` means the client sends every cx and wants to receive every cy ms
+fn parse_heartbeat(header: Option<&str>) -> (Duration, Duration) {
+    let (sends, receives) = header
+        .and_then(|header| header.split_once(','))
+        .map(|(sends, receives)| {
+            (
+                sends.trim().parse::<u64>().unwrap_or(0),
+                receives.trim().parse::<u64>().unwrap_or(0),
+            )
+        })
+        .unwrap_or((0, 0));
+    (
+        Duration::from_millis(sends),
+        Duration::from_millis(receives),
+    )
+}
+
+fn negotiate_heartbeat(client: Duration) -> Option<Duration> {
+    (!client.is_zero()).then(|| client.max(SERVER_HEARTBEAT))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Destination {
+    Topic(String),
+    Queue(String),
+}
+
+impl Destination {
+    fn parse(destination: &str) -> Option<Self> {
+        if let Some(topic) = destination.strip_prefix(TOPIC_PREFIX) {
+            Some(Self::Topic(topic.to_string()))
+        } else {
+            destination
+                .strip_prefix(QUEUE_PREFIX)
+                .map(|queue| Self::Queue(queue.to_string()))
+        }
+    }
+
+    fn to_header(&self) -> String {
+        match self {
+            Self::Topic(topic) => format!("{}{}", TOPIC_PREFIX, topic),
+            Self::Queue(queue) => format!("{}{}", QUEUE_PREFIX, queue),
+        }
+    }
+
+    fn is_notified_by(&self, destination: &TopicDestination) -> bool {
+        match self {
+            Self::Topic(topic) => {
+                destination.destination_type == DestinationType::MessageTopic
+                    && destination.destination == *topic
+            }
+            Self::Queue(queue) => {
+                destination.destination_type == DestinationType::TaskTopic
+                    && destination.destination == *queue
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AckMode {
+    Auto,
+    Client,
+    ClientIndividual,
+}
+
+struct Subscription {
+    destination: Destination,
+    ack: AckMode,
+    prefetch: usize,
+    // leased tasks that were sent but not acknowledged yet, in delivery order
+    pending: VecDeque<Uuid>,
+}
+
+struct Connection {
+    framed: Framed<TcpStream, StompCodec>,
+    identity: String,
+    anonymous: bool,
+    subscriptions: HashMap<String, Subscription>,
+    transactions: HashMap<String, Vec<StompFrame>>,
+}
+
+impl Connection {
+    async fn run(&mut self, client_sends: Duration, client_receives: Duration) -> io::Result<()> {
+        // missing heart-beats are tolerated for twice the negotiated interval
+        let receive_timeout = negotiate_heartbeat(client_sends).map(|interval| interval * 2);
+        let send_interval = negotiate_heartbeat(client_receives);
+        let mut deadline = receive_timeout.map(|timeout| Instant::now() + timeout);
+        let mut heartbeats = interval_at(
+            Instant::now() + send_interval.unwrap_or(SERVER_HEARTBEAT),
+            send_interval.unwrap_or(SERVER_HEARTBEAT),
+        );
+        let mut notifications = notification_service::subscribe_to_notifications();
+
+        loop {
+            tokio::select! {
+                item = self.framed.next() => {
+                    let Some(item) = item else {
+                        return Ok(());
+                    };
+                    deadline = receive_timeout.map(|timeout| Instant::now() + timeout);
+                    self.renew_leases().await;
+                    if let StompItem::Frame(frame) = item? {
+                        if !self.handle_frame(frame).await? {
+                            return Ok(());
+                        }
+                    }
+                }
+                _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                    return Ok(());
+                }
+                _ = heartbeats.tick(), if send_interval.is_some() => {
+                    self.framed.send(StompItem::Heartbeat).await?;
+                }
+                notification = notifications.recv() => {
+                    let destination = match notification {
+                        Ok(destination) => Some(destination),
+                        // missed notifications are recovered by checking every subscription
+                        Err(RecvError::Lagged(_)) => None,
+                        Err(RecvError::Closed) => return Ok(()),
+                    };
+                    let ids: Vec<String> = self
+                        .subscriptions
+                        .iter()
+                        .filter(|(_, subscription)| {
+                            destination.as_ref().is_none_or(|destination| {
+                                subscription.destination.is_notified_by(destination)
+                            })
+                        })
+                        .map(|(id, _)| id.clone())
+                        .collect();
+                    for id in ids {
+                        self.deliver(&id).await?;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns false when the connection has to be closed.
+    async fn handle_frame(&mut self, frame: StompFrame) -> io::Result<bool> {
+        let receipt = frame.get_header("receipt").map(str::to_string);
+
+        if let Some(transaction) = frame.get_header("transaction") {
+            if [SEND, ACK, NACK].contains(&frame.command.as_str()) {
+                let Some(frames) = self.transactions.get_mut(transaction) else {
+                    return self
+                        .error("transaction does not exist", receipt.as_deref())
+                        .await;
+                };
+                frames.push(frame);
+                return self.receipt(receipt.as_deref()).await;
+            }
+        }
+
+        let result = match frame.command.as_str() {
+            SEND => self.send(&frame, receipt.is_some()).await,
+            SUBSCRIBE => self.subscribe(&frame).await,
+            UNSUBSCRIBE => self.unsubscribe(&frame).await,
+            ACK => self.acknowledge(&frame, true).await,
+            NACK => self.acknowledge(&frame, false).await,
+            BEGIN => self.begin(&frame),
+            COMMIT => self.commit(&frame).await,
+            ABORT => match frame.get_header("transaction") {
+                Some(transaction) => self
+                    .transactions
+                    .remove(transaction)
+                    .map(|_| ())
+                    .ok_or("transaction does not exist"),
+                None => Err("ABORT requires a transaction header"),
+            },
+            DISCONNECT => {
+                self.receipt(receipt.as_deref()).await?;
+                return Ok(false);
+            }
+            _ => Err("unknown command"),
+        };
+
+        match result {
+            Ok(()) => {
+                self.receipt(receipt.as_deref()).await?;
+                match frame.command.as_str() {
+                    SUBSCRIBE => {
+                        if let Some(id) = frame.get_header("id") {
+                            self.deliver(id).await?;
+                        }
+                    }
+                    // acknowledged tasks make room for the next ones
+                    ACK | NACK | COMMIT => {
+                        let ids: Vec<String> = self.subscriptions.keys().cloned().collect();
+                        for id in ids {
+                            self.deliver(&id).await?;
+                        }
+                    }
+                    _ => {}
+                }
+                Ok(true)
+            }
+            Err(message) => self.error(message, receipt.as_deref()).await,
+        }
+    }
+
+    async fn send(&mut self, frame: &StompFrame, persist: bool) -> Result<(), &'static str> {
+        let destination = frame
+            .get_header("destination")
+            .and_then(Destination::parse)
+            .ok_or("SEND requires a /topic/ or /queue/ destination")?;
+        let body = String::from_utf8(frame.body.to_vec()).map_err(|_| "body must be UTF-8")?;
+
+        let published = match destination {
+            Destination::Topic(topic) => {
+                let mut headers = Headers::new();
+                for (name, value) in &frame.headers {
+                    if !CONTROL_HEADERS.contains(&name.as_str()) {
+                        headers.entry(name.clone()).or_insert(value.clone());
+                    }
+                }
+                let dts = PublishToMessageTopic::new(body, headers);
+
+                // a requested receipt is only sent once the message is persisted
+                if persist {
+                    match message_topic_service::publish_to_message_topic_with_ack(
+                        topic,
+                        self.identity.clone(),
+                        dts,
+                    )
+                    .await
+                    {
+                        Ok(persisted) => persisted.await.unwrap_or(false),
+                        Err(_) => false,
+                    }
+                } else {
+                    message_topic_service::publish_to_message_topic(
+                        topic,
+                        self.identity.clone(),
+                        dts,
+                    )
+                    .await
+                    .is_ok()
+                }
+            }
+            Destination::Queue(queue) => task_topic_service::publish_to_task_topic(
+                queue,
+                self.identity.clone(),
+                PublishToTaskTopic::new(body),
+            )
+            .await
+            .is_ok(),
+        };
+
+        published
+            .then_some(())
+            .ok_or("message could not be published to the destination")
+    }
+
+    async fn subscribe(&mut self, frame: &StompFrame) -> Result<(), &'static str> {
+        let id = frame
+            .get_header("id")
+            .ok_or("SUBSCRIBE requires an id header")?;
+        if self.subscriptions.contains_key(id) {
+            return Err("subscription id is already in use");
+        }
+        let destination = frame
+            .get_header("destination")
+            .and_then(Destination::parse)
+            .ok_or("SUBSCRIBE requires a /topic/ or /queue/ destination")?;
+        let ack = match frame.get_header("ack").unwrap_or("auto") {
+            "auto" => AckMode::Auto,
+            "client" => AckMode::Client,
+            "client-individual" => AckMode::ClientIndividual,
+            _ => return Err("unknown ack mode"),
+        };
+        let prefetch = match frame.get_header("prefetch-count") {
+            Some(prefetch) => prefetch
+                .parse::<usize>()
+                .ok()
+                .filter(|prefetch| *prefetch > 0)
+                .ok_or("prefetch-count must be a positive number")?,
+            None => 1,
+        };
+
+        let subscribed = match &destination {
+            Destination::Topic(topic) => {
+                message_topic_service::add_subscriber_to_message_topic(
+                    topic.clone(),
+                    AddSubscriberToMessageTopic::new(self.identity.clone(), None),
+                )
+                .await
+            }
+            Destination::Queue(queue) => {
+                task_topic_service::add_subscriber_to_task_topic(
+                    queue.clone(),
+                    AddSubscriberToTaskTopic::new(self.identity.clone()),
+                )
+                .await
+            }
+        };
+        match subscribed {
+            Ok(()) | Err(ServiceError::AlreadyASubscriber(_)) => {}
+            Err(_) => return Err("destination does not exist"),
+        }
+
+        self.subscriptions.insert(
+            id.to_string(),
+            Subscription {
+                destination,
+                ack,
+                prefetch,
+                pending: VecDeque::new(),
+            },
+        );
+        Ok(())
+    }
+
+    async fn unsubscribe(&mut self, frame: &StompFrame) -> Result<(), &'static str> {
+        let id = frame
+            .get_header("id")
+            .ok_or("UNSUBSCRIBE requires an id header")?;
+        let subscription = self
+            .subscriptions
+            .remove(id)
+            .ok_or("subscription does not exist")?;
+        self.remove_subscriber(&subscription.destination).await;
+        Ok(())
+    }
+
+    async fn acknowledge(&mut self, frame: &StompFrame, ack: bool) -> Result<(), &'static str> {
+        let id = frame
+            .get_header("id")
+            .ok_or("ACK and NACK require an id header")?;
+
+        // message topic deliveries are consumed when they are sent, so only tasks are acknowledged
+        let Ok(task_id) = Uuid::parse_str(id) else {
+            return Ok(());
+        };
+        let Some(subscription) = self
+            .subscriptions
+            .values_mut()
+            .find(|subscription| subscription.pending.contains(&task_id))
+        else {
+            return Ok(());
+        };
+        let Destination::Queue(queue) = subscription.destination.clone() else {
+            return Ok(());
+        };
+
+        // in client mode an acknowledgement covers every task delivered before it
+        let position = subscription
+            .pending
+            .iter()
+            .position(|pending| *pending == task_id)
+            .unwrap_or_default();
+        let acknowledged: Vec<Uuid> = match subscription.ack {
+            AckMode::Client => subscription.pending.drain(..=position).collect(),
+            _ => subscription.pending.remove(position).into_iter().collect(),
+        };
+
+        for task_id in acknowledged {
+            let updated = if ack {
+                task_topic_service::complete_task(
+                    queue.clone(),
+                    self.identity.clone(),
+                    task_id,
+                    CompleteTask::new(String::new()),
+                )
+                .await
+            } else {
+                task_topic_service::release_task(queue.clone(), self.identity.clone(), task_id)
+                    .await
+            };
+            if let Err(e) = updated {
+                warn!("StompServer::acknowledge could not settle a task: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    fn begin(&mut self, frame: &StompFrame) -> Result<(), &'static str> {
+        let transaction = frame
+            .get_header("transaction")
+            .ok_or("BEGIN requires a transaction header")?;
+        if self.transactions.contains_key(transaction) {
+            return Err("transaction is already in progress");
+        }
+        self.transactions
+            .insert(transaction.to_string(), Vec::new());
+        Ok(())
+    }
+
+    async fn commit(&mut self, frame: &StompFrame) -> Result<(), &'static str> {
+        let frames = frame
+            .get_header("transaction")
+            .and_then(|transaction| self.transactions.remove(transaction))
+            .ok_or("transaction does not exist")?;
+
+        for frame in frames {
+            match frame.command.as_str() {
+                SEND => self.send(&frame, false).await?,
+                ACK => self.acknowledge(&frame, true).await?,
+                NACK => self.acknowledge(&frame, false).await?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    async fn deliver(&mut self, id: &str) -> io::Result<()> {
+        let Some(subscription) = self.subscriptions.get(id) else {
+            return Ok(());
+        };
+        match subscription.destination.clone() {
+            Destination::Topic(topic) => self.deliver_messages(id, topic).await,
+            Destination::Queue(queue) => self.deliver_tasks(id, queue).await,
+        }
+    }
+
+    async fn deliver_messages(&mut self, id: &str, topic: String) -> io::Result<()> {
+        let Ok(data) = message_topic_service::get_new_data_for_subscriber(
+            topic.clone(),
+            self.identity.clone(),
+        )
+        .await
+        else {
+            return Ok(());
+        };
+        let acknowledged = self
+            .subscriptions
+            .get(id)
+            .is_some_and(|subscription| subscription.ack != AckMode::Auto);
+        let destination = Destination::Topic(topic).to_header();
+
+        for message in data.data {
+            let message_id = Uuid::new_v4().to_string();
+            let mut frame = StompFrame::new(MESSAGE)
+                .header("subscription", id)
+                .header("message-id", &message_id)
+                .header("destination", &destination);
+            if acknowledged {
+                frame = frame.header("ack", &message_id);
+            }
+            for (name, value) in &message.headers {
+                frame = frame.header(name, value);
+            }
+            let frame = frame.body(Bytes::from(message.data));
+            self.framed.send(StompItem::Frame(frame)).await?;
+        }
+        Ok(())
+    }
+
+    async fn deliver_tasks(&mut self, id: &str, queue: String) -> io::Result<()> {
+        let destination = Destination::Queue(queue.clone()).to_header();
+
+        loop {
+            let Some(subscription) = self.subscriptions.get(id) else {
+                return Ok(());
+            };
+            let ack = subscription.ack;
+            if ack != AckMode::Auto && subscription.pending.len() >= subscription.prefetch {
+                return Ok(());
+            }
+            let Ok(Some(task)) = task_topic_service::get_new_task_for_subscriber(
+                queue.clone(),
+                self.identity.clone(),
+            )
+            .await
+            else {
+                return Ok(());
+            };
+
+            let task_id = task.id.to_string();
+            let mut frame = StompFrame::new(MESSAGE)
+                .header("subscription", id)
+                .header("message-id", &task_id)
+                .header("destination", &destination)
+                .header("lease-timeout", &task.lease_timeout.to_string());
+            if ack != AckMode::Auto {
+                frame = frame.header("ack", &task_id);
+            }
+            let frame = frame.body(Bytes::from(task.data));
+            self.framed.send(StompItem::Frame(frame)).await?;
+
+            // with automatic acknowledgement a task is done once it has been sent
+            if ack == AckMode::Auto {
+                task_topic_service::complete_task(
+                    queue.clone(),
+                    self.identity.clone(),
+                    task.id,
+                    CompleteTask::new(String::new()),
+                )
+                .await
+                .unwrap_or_else(|e| {
+                    warn!(
+                        "StompServer::deliver_tasks could not complete a task: {}",
+                        e
+                    )
+                });
+            } else if let Some(subscription) = self.subscriptions.get_mut(id) {
+                subscription.pending.push_back(task.id);
+            }
+        }
+    }
+
+    // a client that is still sending frames or heart-beats keeps the leases of its tasks
+    async fn renew_leases(&self) {
+        for subscription in self.subscriptions.values() {
+            let Destination::Queue(queue) = &subscription.destination else {
+                continue;
+            };
+            for task_id in &subscription.pending {
+                let renewed = task_topic_service::heartbeat_task(
+                    queue.clone(),
+                    self.identity.clone(),
+                    *task_id,
+                )
+                .await;
+                if let Err(e) = renewed {
+                    warn!("StompServer::renew_leases could not renew a lease: {}", e);
+                }
+            }
+        }
+    }
+
+    async fn receipt(&mut self, receipt: Option<&str>) -> io::Result<bool> {
+        if let Some(receipt) = receipt {
+            let frame = StompFrame::new(RECEIPT).header("receipt-id", receipt);
+            self.framed.send(StompItem::Frame(frame)).await?;
+        }
+        Ok(true)
+    }
+
+    async fn error(&mut self, message: &str, receipt: Option<&str>) -> io::Result<bool> {
+        send_error(&mut self.framed, message, receipt).await?;
+        Ok(false)
+    }
+
+    async fn remove_subscriber(&self, destination: &Destination) {
+        let removed = match destination {
+            Destination::Topic(topic) => {
+                message_topic_service::remove_subscriber_from_message_topic(
+                    topic.clone(),
+                    RemoveSubscriberFromMessageTopic::new(self.identity.clone()),
+                )
+                .await
+            }
+            Destination::Queue(queue) => {
+                task_topic_service::remove_subscriber_from_task_topic(
+                    queue.clone(),
+                    RemoveSubscriberFromTaskTopic::new(self.identity.clone()),
+                )
+                .await
+            }
+        };
+        if let Err(e) = removed {
+            warn!(
+                "StompServer::remove_subscriber could not remove a subscriber: {}",
+                e
+            );
+        }
+    }
+
+    // subscriptions of a login outlive the connection, anonymous sessions cannot come back
+    async fn close(&mut self) {
+        if self.anonymous {
+            let subscriptions: Vec<Subscription> = self
+                .subscriptions
+                .drain()
+                .map(|(_, subscription)| subscription)
+                .collect();
+            for subscription in subscriptions {
+                self.remove_subscriber(&subscription.destination).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::tcp::frame::Frame;
+use bytes::{Buf, BufMut, BytesMut};
+use std::io;
+use tokio_util::codec::{Decoder, Encoder};
+
+// version (1) + op code (1) + correlation id (4) + body length (4)
+const HEADER_LENGTH: usize = 10;
+const MAX_BODY_LENGTH: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Default)]
+pub struct FrameCodec;
+
+impl Decoder for FrameCodec {
+    type Item = Frame;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.len() < HEADER_LENGTH {
+            return Ok(None);
+        }
+
+        let body_length = u32::from_be_bytes([src[6], src[7], src[8], src[9]]) as usize;
+        if body_length > MAX_BODY_LENGTH {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("frame body of {} bytes exceeds the limit", body_length),
+            ));
+        }
+        if src.len() < HEADER_LENGTH + body_length {
+            src.reserve(HEADER_LENGTH + body_length - src.len());
+            return Ok(None);
+        }
+
+        let version = src.get_u8();
+        let op_code = src.get_u8();
+        let correlation_id = src.get_u32();
+        src.advance(4);
+        let body = src.split_to(body_length).freeze();
+
+        Ok(Some(Frame {
+            version,
+            op_code,
+            correlation_id,
+            body,
+        }))
+    }
+}
+
+impl Encoder<Frame> for FrameCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, frame: Frame, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        dst.reserve(HEADER_LENGTH + frame.body.len());
+        dst.put_u8(frame.version);
+        dst.put_u8(frame.op_code);
+        dst.put_u32(frame.correlation_id);
+        dst.put_u32(frame.body.len() as u32);
+        dst.put_slice(&frame.body);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tcp::frame::{OpCode, PROTOCOL_VERSION};
+    use bytes::Bytes;
+
+    fn encode(frame: Frame) -> BytesMut {
+        let mut dst = BytesMut::new();
+        FrameCodec.encode(frame, &mut dst).unwrap();
+        dst
+    }
+
+    #[test]
+    fn frames_survive_a_round_trip() {
+        let frame = Frame::new(
+            OpCode::CreateMessageTopic as u8,
+            0xDEAD_BEEF,
+            Bytes::from_static(b"{\"name\":\"orders\"}"),
+        );
+        let mut src = encode(frame.clone());
+        assert_eq!(src.len(), HEADER_LENGTH + frame.body.len());
+        assert_eq!(&src[..6], &[PROTOCOL_VERSION, 0x03, 0xDE, 0xAD, 0xBE, 0xEF]);
+
+        assert_eq!(FrameCodec.decode(&mut src).unwrap(), Some(frame));
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn frames_split_across_reads_wait_for_the_rest() {
+        let frame = Frame::new(OpCode::Ping as u8, 7, Bytes::from_static(b"body"));
+        let encoded = encode(frame.clone());
+
+        // a partial header and a partial body both wait for more bytes without consuming any
+        let mut src = BytesMut::from(&encoded[..4]);
+        assert_eq!(FrameCodec.decode(&mut src).unwrap(), None);
+        src.extend_from_slice(&encoded[4..12]);
+        assert_eq!(FrameCodec.decode(&mut src).unwrap(), None);
+        assert_eq!(src.len(), 12);
+
+        src.extend_from_slice(&encoded[12..]);
+        assert_eq!(FrameCodec.decode(&mut src).unwrap(), Some(frame));
+    }
+
+    #[test]
+    fn oversized_bodies_are_rejected_before_they_are_read() {
+        let mut src = BytesMut::new();
+        src.put_u8(PROTOCOL_VERSION);
+        src.put_u8(OpCode::PublishToMessageTopic as u8);
+        src.put_u32(1);
+        src.put_u32(MAX_BODY_LENGTH as u32 + 1);
+
+        let error = FrameCodec.decode(&mut src).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+        // a body of exactly the limit is only waited for
+        let mut src = BytesMut::new();
+        src.put_u8(PROTOCOL_VERSION);
+        src.put_u8(OpCode::PublishToMessageTopic as u8);
+        src.put_u32(1);
+        src.put_u32(MAX_BODY_LENGTH as u32);
+        assert_eq!(FrameCodec.decode(&mut src).unwrap(), None);
+    }
+
+    #[test]
+    fn pipelined_frames_are_decoded_in_order() {
+        let frames: Vec<Frame> = (1..=3)
+            .map(|correlation_id| {
+                Frame::new(
+                    OpCode::GetMessageTopic as u8,
+                    correlation_id,
+                    Bytes::from(format!("topic-{}", correlation_id)),
+                )
+            })
+            .collect();
+        let mut src = BytesMut::new();
+        for frame in &frames {
+            src.extend_from_slice(&encode(frame.clone()));
+        }
+
+        for frame in frames {
+            assert_eq!(FrameCodec.decode(&mut src).unwrap(), Some(frame));
+        }
+        assert_eq!(FrameCodec.decode(&mut src).unwrap(), None);
+    }
+}
@@ -0,0 +1,301 @@
+use crate::model::message_topic_model::Message;
+use crate::utils::types::{Headers, TopicType};
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use uuid::Uuid;
+
+pub const PROTOCOL_VERSION: u8 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub version: u8,
+    pub op_code: u8,
+    pub correlation_id: u32,
+    pub body: Bytes,
+}
+
+impl Frame {
+    pub fn new(op_code: u8, correlation_id: u32, body: Bytes) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            op_code,
+            correlation_id,
+            body,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum OpCode {
+    Ping = 0x00,
+
+    GetAllMessageTopics = 0x01,
+    GetMessageTopic = 0x02,
+    CreateMessageTopic = 0x03,
+    DeleteMessageTopic = 0x04,
+    AddPublisherToMessageTopic = 0x05,
+    RemovePublisherFromMessageTopic = 0x06,
+    PublishToMessageTopic = 0x07,
+    AddSubscriberToMessageTopic = 0x08,
+    RemoveSubscriberFromMessageTopic = 0x09,
+    IsThereNewDataForSubscriber = 0x0A,
+    GetNewDataForSubscriber = 0x0B,
+    SubscribeToMessageTopic = 0x0C,
+
+    GetAllTaskTopics = 0x20,
+    GetTaskTopic = 0x21,
+    CreateTaskTopic = 0x22,
+    DeleteTaskTopic = 0x23,
+    AddPublisherToTaskTopic = 0x24,
+    RemovePublisherFromTaskTopic = 0x25,
+    PublishToTaskTopic = 0x26,
+    AddSubscriberToTaskTopic = 0x27,
+    RemoveSubscriberFromTaskTopic = 0x28,
+    IsThereATaskForSubscriber = 0x29,
+    GetNewTaskForSubscriber = 0x2A,
+    HeartbeatTask = 0x2B,
+    ReportTaskProgress = 0x2C,
+    CompleteTask = 0x2D,
+    FailTask = 0x2E,
+    GetTaskStatus = 0x2F,
+    SubscribeToTaskTopic = 0x30,
+
+    GetAllWildcardSubscriptions = 0x40,
+    GetWildcardSubscription = 0x41,
+    CreateWildcardSubscription = 0x42,
+    DeleteWildcardSubscription = 0x43,
+    IsThereNewDataForWildcardSubscriber = 0x44,
+    GetNewDataForWildcardSubscriber = 0x45,
+
+    GetAllExchanges = 0x50,
+    GetExchange = 0x51,
+    CreateExchange = 0x52,
+    DeleteExchange = 0x53,
+    AddBindingToExchange = 0x54,
+    RemoveBindingFromExchange = 0x55,
+    AddPublisherToExchange = 0x56,
+    RemovePublisherFromExchange = 0x57,
+    PublishToExchange = 0x58,
+
+    GetAllForwardingRules = 0x60,
+    GetForwardingRule = 0x61,
+    CreateForwardingRule = 0x62,
+    DeleteForwardingRule = 0x63,
+
+    Unsubscribe = 0x70,
+    Authenticate = 0x71,
+
+    Push = 0xF0,
+}
+
+impl TryFrom<u8> for OpCode {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        let op_code = match value {
+            0x00 => Self::Ping,
+            0x01 => Self::GetAllMessageTopics,
+            0x02 => Self::GetMessageTopic,
+            0x03 => Self::CreateMessageTopic,
+            0x04 => Self::DeleteMessageTopic,
+            0x05 => Self::AddPublisherToMessageTopic,
+            0x06 => Self::RemovePublisherFromMessageTopic,
+            0x07 => Self::PublishToMessageTopic,
+            0x08 => Self::AddSubscriberToMessageTopic,
+            0x09 => Self::RemoveSubscriberFromMessageTopic,
+            0x0A => Self::IsThereNewDataForSubscriber,
+            0x0B => Self::GetNewDataForSubscriber,
+            0x0C => Self::SubscribeToMessageTopic,
+            0x20 => Self::GetAllTaskTopics,
+            0x21 => Self::GetTaskTopic,
+            0x22 => Self::CreateTaskTopic,
+            0x23 => Self::DeleteTaskTopic,
+            0x24 => Self::AddPublisherToTaskTopic,
+            0x25 => Self::RemovePublisherFromTaskTopic,
+            0x26 => Self::PublishToTaskTopic,
+            0x27 => Self::AddSubscriberToTaskTopic,
+            0x28 => Self::RemoveSubscriberFromTaskTopic,
+            0x29 => Self::IsThereATaskForSubscriber,
+            0x2A => Self::GetNewTaskForSubscriber,
+            0x2B => Self::HeartbeatTask,
+            0x2C => Self::ReportTaskProgress,
+            0x2D => Self::CompleteTask,
+            0x2E => Self::FailTask,
+            0x2F => Self::GetTaskStatus,
+            0x30 => Self::SubscribeToTaskTopic,
+            0x40 => Self::GetAllWildcardSubscriptions,
+            0x41 => Self::GetWildcardSubscription,
+            0x42 => Self::CreateWildcardSubscription,
+            0x43 => Self::DeleteWildcardSubscription,
+            0x44 => Self::IsThereNewDataForWildcardSubscriber,
+            0x45 => Self::GetNewDataForWildcardSubscriber,
+            0x50 => Self::GetAllExchanges,
+            0x51 => Self::GetExchange,
+            0x52 => Self::CreateExchange,
+            0x53 => Self::DeleteExchange,
+            0x54 => Self::AddBindingToExchange,
+            0x55 => Self::RemoveBindingFromExchange,
+            0x56 => Self::AddPublisherToExchange,
+            0x57 => Self::RemovePublisherFromExchange,
+            0x58 => Self::PublishToExchange,
+            0x60 => Self::GetAllForwardingRules,
+            0x61 => Self::GetForwardingRule,
+            0x62 => Self::CreateForwardingRule,
+            0x63 => Self::DeleteForwardingRule,
+            0x70 => Self::Unsubscribe,
+            0x71 => Self::Authenticate,
+            0xF0 => Self::Push,
+            _ => return Err(()),
+        };
+        Ok(op_code)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Status {
+    Ok = 0x00,
+    NotFound = 0x01,
+    Conflict = 0x02,
+    BadRequest = 0x03,
+    UnknownOperation = 0x04,
+    UnsupportedVersion = 0x05,
+    Forbidden = 0x06,
+    InternalError = 0x07,
+    QuotaExceeded = 0x08,
+    Unauthenticated = 0x09,
+}
+
+/// Reads the fields of a frame body: strings are prefixed with a `u16` length, blobs and JSON
+/// documents with a `u32` length, all integers are big endian.
+pub struct BodyReader {
+    body: Bytes,
+}
+
+impl BodyReader {
+    pub fn new(body: Bytes) -> Self {
+        Self { body }
+    }
+
+    pub fn read_u8(&mut self) -> Option<u8> {
+        (self.body.remaining() >= 1).then(|| self.body.get_u8())
+    }
+
+    pub fn read_u32(&mut self) -> Option<u32> {
+        (self.body.remaining() >= 4).then(|| self.body.get_u32())
+    }
+
+    pub fn read_string(&mut self) -> Option<String> {
+        if self.body.remaining() < 2 {
+            return None;
+        }
+        let length = self.body.get_u16() as usize;
+        self.read_utf8(length)
+    }
+
+    pub fn read_blob(&mut self) -> Option<TopicType> {
+        let length = self.read_u32()? as usize;
+        self.read_utf8(length)
+    }
+
+    pub fn read_uuid(&mut self) -> Option<Uuid> {
+        if self.body.remaining() < 16 {
+            return None;
+        }
+        Uuid::from_slice(&self.body.split_to(16)).ok()
+    }
+
+    pub fn read_headers(&mut self) -> Option<Headers> {
+        if self.body.remaining() < 2 {
+            return None;
+        }
+        let count = self.body.get_u16();
+        let mut headers = Headers::new();
+        for _ in 0..count {
+            headers.insert(self.read_string()?, self.read_string()?);
+        }
+        Some(headers)
+    }
+
+    pub fn read_json<T: DeserializeOwned>(&mut self) -> Option<T> {
+        let length = self.read_u32()? as usize;
+        if self.body.remaining() < length {
+            return None;
+        }
+        serde_json::from_slice(&self.body.split_to(length)).ok()
+    }
+
+    fn read_utf8(&mut self, length: usize) -> Option<String> {
+        if self.body.remaining() < length {
+            return None;
+        }
+        String::from_utf8(self.body.split_to(length).to_vec()).ok()
+    }
+}
+
+pub struct BodyWriter {
+    body: BytesMut,
+}
+
+impl BodyWriter {
+    pub fn new(status: Status) -> Self {
+        let mut body = BytesMut::new();
+        body.put_u8(status as u8);
+        Self { body }
+    }
+
+    pub fn write_u8(mut self, value: u8) -> Self {
+        self.body.put_u8(value);
+        self
+    }
+
+    pub fn write_u64(mut self, value: u64) -> Self {
+        self.body.put_u64(value);
+        self
+    }
+
+    pub fn write_string(mut self, value: &str) -> Self {
+        self.body.put_u16(value.len() as u16);
+        self.body.put_slice(value.as_bytes());
+        self
+    }
+
+    pub fn write_blob(mut self, value: &str) -> Self {
+        self.body.put_u32(value.len() as u32);
+        self.body.put_slice(value.as_bytes());
+        self
+    }
+
+    pub fn write_uuid(mut self, value: Uuid) -> Self {
+        self.body.put_slice(value.as_bytes());
+        self
+    }
+
+    pub fn write_headers(mut self, headers: &Headers) -> Self {
+        self.body.put_u16(headers.len() as u16);
+        for (name, value) in headers {
+            self = self.write_string(name).write_string(value);
+        }
+        self
+    }
+
+    pub fn write_messages(mut self, messages: &[Message<TopicType>]) -> Self {
+        self.body.put_u32(messages.len() as u32);
+        for message in messages {
+            self = self
+                .write_headers(&message.headers)
+                .write_blob(&message.data);
+        }
+        self
+    }
+
+    pub fn write_json<T: Serialize>(self, value: &T) -> Self {
+        self.write_blob(&serde_json::to_string(value).unwrap())
+    }
+
+    pub fn finish(self) -> Bytes {
+        self.body.freeze()
+    }
+}
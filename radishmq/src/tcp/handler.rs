@@ -0,0 +1,499 @@
+use crate::model::exchange_model::{
+    AddPublisherToExchange, PublishToExchange, RemovePublisherFromExchange,
+};
+use crate::model::message_topic_model::{
+    AddPublisherToMessageTopic, PublishToMessageTopic, RemovePublisherFromMessageTopic,
+    RemoveSubscriberFromMessageTopic,
+};
+use crate::model::task_topic_model::{
+    AddPublisherToTaskTopic, AddSubscriberToTaskTopic, CompleteTask, FailTask, PublishToTaskTopic,
+    RemovePublisherFromTaskTopic, RemoveSubscriberFromTaskTopic, ReportTaskProgress,
+};
+use crate::service::error::{ServiceError, ServiceResult};
+use crate::service::{
+    exchange_service, forwarding_rule_service, message_topic_service, task_topic_service,
+    wildcard_subscription_service,
+};
+use crate::tcp::frame::{BodyReader, BodyWriter, OpCode, Status};
+use bytes::Bytes;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Subscription {
+    MessageTopic { topic: String, subscriber: String },
+    TaskTopic { topic: String, subscriber: String },
+}
+
+pub type Subscriptions = HashMap<u32, Subscription>;
+
+pub async fn handle_request(
+    op_code: OpCode,
+    correlation_id: u32,
+    body: Bytes,
+    subscriptions: &mut Subscriptions,
+) -> Bytes {
+    let mut reader = BodyReader::new(body);
+    handle_operation(op_code, correlation_id, &mut reader, subscriptions)
+        .await
+        .unwrap_or_else(|| status(Status::BadRequest))
+}
+
+// returns None when the body does not contain the arguments the operation needs
+async fn handle_operation(
+    op_code: OpCode,
+    correlation_id: u32,
+    reader: &mut BodyReader,
+    subscriptions: &mut Subscriptions,
+) -> Option<Bytes> {
+    let body = match op_code {
+        OpCode::Ping => status(Status::Ok),
+
+        OpCode::GetAllMessageTopics => {
+            respond(message_topic_service::get_all_message_topics().await)
+        }
+        OpCode::GetMessageTopic => {
+            respond(message_topic_service::get_message_topic(reader.read_string()?).await)
+        }
+        OpCode::CreateMessageTopic => {
+            respond(message_topic_service::create_message_topic(reader.read_json()?).await)
+        }
+        OpCode::DeleteMessageTopic => {
+            done(message_topic_service::delete_message_topic(reader.read_string()?).await)
+        }
+        OpCode::AddPublisherToMessageTopic => done(
+            message_topic_service::add_publisher_to_message_topic(
+                reader.read_string()?,
+                AddPublisherToMessageTopic::new(reader.read_string()?),
+            )
+            .await,
+        ),
+        OpCode::RemovePublisherFromMessageTopic => done(
+            message_topic_service::remove_publisher_from_message_topic(
+                reader.read_string()?,
+                RemovePublisherFromMessageTopic::new(reader.read_string()?),
+            )
+            .await,
+        ),
+        OpCode::PublishToMessageTopic => {
+            let topic_name = reader.read_string()?;
+            let publisher = reader.read_string()?;
+            let headers = reader.read_headers()?;
+            let data = reader.read_blob()?;
+            done(
+                message_topic_service::publish_to_message_topic(
+                    topic_name,
+                    publisher,
+                    PublishToMessageTopic::new(data, headers),
+                )
+                .await,
+            )
+        }
+        OpCode::AddSubscriberToMessageTopic => done(
+            message_topic_service::add_subscriber_to_message_topic(
+                reader.read_string()?,
+                reader.read_json()?,
+            )
+            .await,
+        ),
+        OpCode::RemoveSubscriberFromMessageTopic => done(
+            message_topic_service::remove_subscriber_from_message_topic(
+                reader.read_string()?,
+                RemoveSubscriberFromMessageTopic::new(reader.read_string()?),
+            )
+            .await,
+        ),
+        OpCode::IsThereNewDataForSubscriber => {
+            match message_topic_service::is_there_new_data_for_subscriber(
+                reader.read_string()?,
+                reader.read_string()?,
+            )
+            .await
+            {
+                Ok(data) => BodyWriter::new(Status::Ok)
+                    .write_u8(data.new_data as u8)
+                    .finish(),
+                Err(e) => failed(e),
+            }
+        }
+        OpCode::GetNewDataForSubscriber => {
+            match message_topic_service::get_new_data_for_subscriber(
+                reader.read_string()?,
+                reader.read_string()?,
+            )
+            .await
+            {
+                Ok(data) => BodyWriter::new(Status::Ok)
+                    .write_messages(&data.data)
+                    .finish(),
+                Err(e) => failed(e),
+            }
+        }
+        OpCode::SubscribeToMessageTopic => {
+            let topic = reader.read_string()?;
+            let subscriber = reader.read_string()?;
+            match message_topic_service::is_there_new_data_for_subscriber(
+                topic.clone(),
+                subscriber.clone(),
+            )
+            .await
+            {
+                Ok(_) => {
+                    subscriptions.insert(
+                        correlation_id,
+                        Subscription::MessageTopic { topic, subscriber },
+                    );
+                    status(Status::Ok)
+                }
+                Err(e) => failed(e),
+            }
+        }
+
+        OpCode::GetAllTaskTopics => respond(task_topic_service::get_all_task_topics().await),
+        OpCode::GetTaskTopic => {
+            respond(task_topic_service::get_task_topic(reader.read_string()?).await)
+        }
+        OpCode::CreateTaskTopic => {
+            respond(task_topic_service::create_task_topic(reader.read_json()?).await)
+        }
+        OpCode::DeleteTaskTopic => {
+            done(task_topic_service::delete_task_topic(reader.read_string()?).await)
+        }
+        OpCode::AddPublisherToTaskTopic => done(
+            task_topic_service::add_publisher_to_task_topic(
+                reader.read_string()?,
+                AddPublisherToTaskTopic::new(reader.read_string()?),
+            )
+            .await,
+        ),
+        OpCode::RemovePublisherFromTaskTopic => done(
+            task_topic_service::remove_publisher_from_task_topic(
+                reader.read_string()?,
+                RemovePublisherFromTaskTopic::new(reader.read_string()?),
+            )
+            .await,
+        ),
+        OpCode::PublishToTaskTopic => {
+            match task_topic_service::publish_to_task_topic(
+                reader.read_string()?,
+                reader.read_string()?,
+                PublishToTaskTopic::new(reader.read_blob()?),
+            )
+            .await
+            {
+                Ok(task) => BodyWriter::new(Status::Ok).write_uuid(task.id).finish(),
+                Err(e) => failed(e),
+            }
+        }
+        OpCode::AddSubscriberToTaskTopic => done(
+            task_topic_service::add_subscriber_to_task_topic(
+                reader.read_string()?,
+                AddSubscriberToTaskTopic::new(reader.read_string()?),
+            )
+            .await,
+        ),
+        OpCode::RemoveSubscriberFromTaskTopic => done(
+            task_topic_service::remove_subscriber_from_task_topic(
+                reader.read_string()?,
+                RemoveSubscriberFromTaskTopic::new(reader.read_string()?),
+            )
+            .await,
+        ),
+        OpCode::IsThereATaskForSubscriber => {
+            match task_topic_service::is_there_a_task_for_subscriber(
+                reader.read_string()?,
+                reader.read_string()?,
+            )
+            .await
+            {
+                Ok(tasks) => BodyWriter::new(Status::Ok)
+                    .write_u8(tasks.new_tasks as u8)
+                    .finish(),
+                Err(e) => failed(e),
+            }
+        }
+        OpCode::GetNewTaskForSubscriber => {
+            match task_topic_service::get_new_task_for_subscriber(
+                reader.read_string()?,
+                reader.read_string()?,
+            )
+            .await
+            {
+                Ok(Some(task)) => {
+                    assigned_task(Status::Ok, task.id, task.lease_timeout, &task.data)
+                }
+                Ok(None) => status(Status::NotFound),
+                Err(e) => failed(e),
+            }
+        }
+        OpCode::HeartbeatTask => done(
+            task_topic_service::heartbeat_task(
+                reader.read_string()?,
+                reader.read_string()?,
+                reader.read_uuid()?,
+            )
+            .await,
+        ),
+        OpCode::ReportTaskProgress => done(
+            task_topic_service::report_task_progress(
+                reader.read_string()?,
+                reader.read_string()?,
+                reader.read_uuid()?,
+                ReportTaskProgress::new(reader.read_u8()?),
+            )
+            .await,
+        ),
+        OpCode::CompleteTask => done(
+            task_topic_service::complete_task(
+                reader.read_string()?,
+                reader.read_string()?,
+                reader.read_uuid()?,
+                CompleteTask::new(reader.read_blob()?),
+            )
+            .await,
+        ),
+        OpCode::FailTask => done(
+            task_topic_service::fail_task(
+                reader.read_string()?,
+                reader.read_string()?,
+                reader.read_uuid()?,
+                FailTask::new(reader.read_string()?),
+            )
+            .await,
+        ),
+        OpCode::GetTaskStatus => respond(
+            task_topic_service::get_task_status(reader.read_string()?, reader.read_uuid()?).await,
+        ),
+        OpCode::SubscribeToTaskTopic => {
+            let topic = reader.read_string()?;
+            let subscriber = reader.read_string()?;
+            match task_topic_service::is_there_a_task_for_subscriber(
+                topic.clone(),
+                subscriber.clone(),
+            )
+            .await
+            {
+                Ok(_) => {
+                    subscriptions.insert(
+                        correlation_id,
+                        Subscription::TaskTopic { topic, subscriber },
+                    );
+                    status(Status::Ok)
+                }
+                Err(e) => failed(e),
+            }
+        }
+
+        OpCode::GetAllWildcardSubscriptions => {
+            respond(wildcard_subscription_service::get_all_wildcard_subscriptions().await)
+        }
+        OpCode::GetWildcardSubscription => respond(
+            wildcard_subscription_service::get_wildcard_subscription(
+                reader.read_string()?,
+                reader.read_string()?,
+            )
+            .await,
+        ),
+        OpCode::CreateWildcardSubscription => respond(
+            wildcard_subscription_service::create_wildcard_subscription(reader.read_json()?).await,
+        ),
+        OpCode::DeleteWildcardSubscription => done(
+            wildcard_subscription_service::delete_wildcard_subscription(
+                reader.read_string()?,
+                reader.read_string()?,
+            )
+            .await,
+        ),
+        OpCode::IsThereNewDataForWildcardSubscriber => {
+            match wildcard_subscription_service::is_there_new_data_for_wildcard_subscriber(
+                reader.read_string()?,
+            )
+            .await
+            {
+                Ok(data) => BodyWriter::new(Status::Ok)
+                    .write_u8(data.new_data as u8)
+                    .finish(),
+                Err(e) => failed(e),
+            }
+        }
+        OpCode::GetNewDataForWildcardSubscriber => respond(
+            wildcard_subscription_service::get_new_data_for_wildcard_subscriber(
+                reader.read_string()?,
+            )
+            .await,
+        ),
+
+        OpCode::GetAllExchanges => respond(exchange_service::get_all_exchanges().await),
+        OpCode::GetExchange => respond(exchange_service::get_exchange(reader.read_string()?).await),
+        OpCode::CreateExchange => {
+            respond(exchange_service::create_exchange(reader.read_json()?).await)
+        }
+        OpCode::DeleteExchange => {
+            done(exchange_service::delete_exchange(reader.read_string()?).await)
+        }
+        OpCode::AddBindingToExchange => respond(
+            exchange_service::add_binding_to_exchange(reader.read_string()?, reader.read_json()?)
+                .await,
+        ),
+        OpCode::RemoveBindingFromExchange => done(
+            exchange_service::remove_binding_from_exchange(
+                reader.read_string()?,
+                reader.read_uuid()?,
+            )
+            .await,
+        ),
+        OpCode::AddPublisherToExchange => done(
+            exchange_service::add_publisher_to_exchange(
+                reader.read_string()?,
+                AddPublisherToExchange::new(reader.read_string()?),
+            )
+            .await,
+        ),
+        OpCode::RemovePublisherFromExchange => done(
+            exchange_service::remove_publisher_from_exchange(
+                reader.read_string()?,
+                RemovePublisherFromExchange::new(reader.read_string()?),
+            )
+            .await,
+        ),
+        OpCode::PublishToExchange => {
+            let exchange_name = reader.read_string()?;
+            let publisher = reader.read_string()?;
+            let routing_key = reader.read_string()?;
+            let headers = reader.read_headers()?;
+            let data = reader.read_blob()?;
+            respond(
+                exchange_service::publish_to_exchange(
+                    exchange_name,
+                    publisher,
+                    PublishToExchange::new(data, routing_key, headers),
+                )
+                .await,
+            )
+        }
+
+        OpCode::GetAllForwardingRules => {
+            respond(forwarding_rule_service::get_all_forwarding_rules().await)
+        }
+        OpCode::GetForwardingRule => {
+            respond(forwarding_rule_service::get_forwarding_rule(reader.read_string()?).await)
+        }
+        OpCode::CreateForwardingRule => {
+            respond(forwarding_rule_service::create_forwarding_rule(reader.read_json()?).await)
+        }
+        OpCode::DeleteForwardingRule => {
+            done(forwarding_rule_service::delete_forwarding_rule(reader.read_string()?).await)
+        }
+
+        OpCode::Unsubscribe => flag(subscriptions.remove(&reader.read_u32()?).is_some()),
+
+        // answered by the connection, which keeps the identity
+        OpCode::Authenticate => status(Status::UnknownOperation),
+        OpCode::Push => status(Status::UnknownOperation),
+    };
+
+    Some(body)
+}
+
+pub async fn collect_pushes(subscription: &Subscription) -> Vec<Bytes> {
+    match subscription {
+        Subscription::MessageTopic { topic, subscriber } => {
+            match message_topic_service::get_new_data_for_subscriber(
+                topic.clone(),
+                subscriber.clone(),
+            )
+            .await
+            {
+                Ok(data) if !data.data.is_empty() => vec![BodyWriter::new(Status::Ok)
+                    .write_messages(&data.data)
+                    .finish()],
+                _ => Vec::new(),
+            }
+        }
+        Subscription::TaskTopic { topic, subscriber } => {
+            let mut pushes = Vec::new();
+            while let Ok(Some(task)) =
+                task_topic_service::get_new_task_for_subscriber(topic.clone(), subscriber.clone())
+                    .await
+            {
+                pushes.push(assigned_task(
+                    Status::Ok,
+                    task.id,
+                    task.lease_timeout,
+                    &task.data,
+                ));
+            }
+            pushes
+        }
+    }
+}
+
+pub fn status(status: Status) -> Bytes {
+    BodyWriter::new(status).finish()
+}
+
+fn json<T: Serialize>(value: &T) -> Bytes {
+    BodyWriter::new(Status::Ok).write_json(value).finish()
+}
+
+fn respond<T: Serialize>(value: ServiceResult<T>) -> Bytes {
+    match value {
+        Ok(value) => json(&value),
+        Err(e) => failed(e),
+    }
+}
+
+fn done<T>(value: ServiceResult<T>) -> Bytes {
+    match value {
+        Ok(_) => status(Status::Ok),
+        Err(e) => failed(e),
+    }
+}
+
+fn failed(error: ServiceError) -> Bytes {
+    status(match error {
+        ServiceError::TopicNotFound(_)
+        | ServiceError::TaskNotFound(_)
+        | ServiceError::CredentialNotFound(_)
+        | ServiceError::GrantNotFound(_)
+        | ServiceError::ExchangeNotFound(_)
+        | ServiceError::BindingNotFound(_)
+        | ServiceError::ForwardingRuleNotFound(_)
+        | ServiceError::WildcardSubscriptionNotFound(_, _) => Status::NotFound,
+        ServiceError::AlreadyExists(_)
+        | ServiceError::AlreadyAPublisher(_)
+        | ServiceError::AlreadyASubscriber(_)
+        | ServiceError::TaskNotLeased(_)
+        | ServiceError::CredentialExists(_)
+        | ServiceError::GrantExists(_)
+        | ServiceError::ExchangeExists(_)
+        | ServiceError::ForwardingRuleExists(_)
+        | ServiceError::WildcardSubscriptionExists(_, _) => Status::Conflict,
+        ServiceError::InvalidName(_)
+        | ServiceError::InvalidPattern(_)
+        | ServiceError::InvalidBinding(_)
+        | ServiceError::InvalidFilter => Status::BadRequest,
+        ServiceError::NotAPublisher(_)
+        | ServiceError::NotASubscriber(_)
+        | ServiceError::IdentityMismatch(_)
+        | ServiceError::PermissionDenied(_) => Status::Forbidden,
+        ServiceError::QuotaExceeded(_, _) => Status::QuotaExceeded,
+        ServiceError::DatabaseUnavailable(_) | ServiceError::PoisonedLock => Status::InternalError,
+    })
+}
+
+fn flag(success: bool) -> Bytes {
+    if success {
+        status(Status::Ok)
+    } else {
+        status(Status::NotFound)
+    }
+}
+
+fn assigned_task(status: Status, id: uuid::Uuid, lease_timeout: u64, data: &str) -> Bytes {
+    BodyWriter::new(status)
+        .write_uuid(id)
+        .write_u64(lease_timeout)
+        .write_blob(data)
+        .finish()
+}
@@ -0,0 +1,4 @@
+pub mod codec;
+pub mod frame;
+pub mod handler;
+pub mod server;
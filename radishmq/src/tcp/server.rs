@@ -0,0 +1,288 @@
+use crate::auth::authenticator::Authenticator;
+use crate::auth::identity::{self, Identity};
+use crate::model::destination_model::{DestinationType, TopicDestination};
+use crate::service::notification_service;
+use crate::tcp::codec::FrameCodec;
+use crate::tcp::frame::{BodyReader, Frame, OpCode, Status, PROTOCOL_VERSION};
+use crate::tcp::handler::{collect_pushes, handle_request, status, Subscription, Subscriptions};
+use crate::utils::peer_address;
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use log::warn;
+use std::env;
+use std::io;
+use std::sync::Arc;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::codec::Framed;
+
+pub async fn start_tcp_server() -> io::Result<()> {
+    let port = match env::var("TCP_PORT") {
+        Ok(port) => port.parse::<u16>().expect("TCP_PORT must be a number"),
+        Err(_) => {
+            println!("Skipping TCP listener...");
+            return Ok(());
+        }
+    };
+    let address = env::var("TCP_ADDRESS")
+        .or_else(|_| env::var("HTTP_ADDRESS"))
+        .expect("TCP_ADDRESS or HTTP_ADDRESS must be set");
+
+    let listener = TcpListener::bind((address.as_str(), port)).await?;
+    println!("TCP listener started on {}:{}", address, port);
+
+    let authenticator = Authenticator::from_env().map(Arc::new);
+    tokio::spawn(accept_connections(listener, authenticator));
+    Ok(())
+}
+
+async fn accept_connections(listener: TcpListener, authenticator: Option<Arc<Authenticator>>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, address)) => {
+                let authenticator = authenticator.clone();
+                tokio::spawn(peer_address::scope(address.ip().to_string(), async move {
+                    if let Err(e) = handle_connection(stream, authenticator).await {
+                        warn!("TcpServer::handle_connection closed a connection: {}", e);
+                    }
+                }));
+            }
+            Err(e) => warn!(
+                "TcpServer::accept_connections could not accept a connection: {}",
+                e
+            ),
+        }
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    authenticator: Option<Arc<Authenticator>>,
+) -> io::Result<()> {
+    let mut framed = Framed::new(stream, FrameCodec);
+    let mut notifications = notification_service::subscribe_to_notifications();
+    let mut subscriptions = Subscriptions::new();
+    // with authentication enabled, a connection runs on behalf of the identity it authenticated as
+    let mut connection = Connection {
+        authenticator,
+        identity: None,
+    };
+
+    loop {
+        tokio::select! {
+            frame = framed.next() => {
+                let Some(frame) = frame else {
+                    return Ok(());
+                };
+                let frame = frame?;
+                let correlation_id = frame.correlation_id;
+
+                framed.send(connection.respond(frame, &mut subscriptions).await).await?;
+
+                // a new subscription immediately receives what is already waiting for it
+                if let Some(subscription) = subscriptions.get(&correlation_id).cloned() {
+                    push(&mut framed, &connection, correlation_id, &subscription).await?;
+                }
+            }
+            notification = notifications.recv() => {
+                let destination = match notification {
+                    Ok(destination) => Some(destination),
+                    // missed notifications are recovered by checking every subscription
+                    Err(RecvError::Lagged(_)) => None,
+                    Err(RecvError::Closed) => return Ok(()),
+                };
+
+                let matching: Vec<(u32, Subscription)> = subscriptions
+                    .iter()
+                    .filter(|(_, subscription)| {
+                        destination
+                            .as_ref()
+                            .is_none_or(|destination| is_subscribed_to(subscription, destination))
+                    })
+                    .map(|(correlation_id, subscription)| (*correlation_id, subscription.clone()))
+                    .collect();
+
+                for (correlation_id, subscription) in matching {
+                    push(&mut framed, &connection, correlation_id, &subscription).await?;
+                }
+            }
+        }
+    }
+}
+
+struct Connection {
+    authenticator: Option<Arc<Authenticator>>,
+    identity: Option<Identity>,
+}
+
+impl Connection {
+    async fn respond(&mut self, frame: Frame, subscriptions: &mut Subscriptions) -> Frame {
+        let body = if frame.version != PROTOCOL_VERSION {
+            status(Status::UnsupportedVersion)
+        } else {
+            match OpCode::try_from(frame.op_code) {
+                Ok(OpCode::Authenticate) => self.authenticate(frame.body),
+                Ok(op_code) if op_code != OpCode::Ping && !self.is_authenticated() => {
+                    status(Status::Unauthenticated)
+                }
+                Ok(op_code) => {
+                    identity::on_behalf_of(
+                        self.identity.clone(),
+                        handle_request(op_code, frame.correlation_id, frame.body, subscriptions),
+                    )
+                    .await
+                }
+                Err(_) => status(Status::UnknownOperation),
+            }
+        };
+
+        Frame::new(frame.op_code, frame.correlation_id, body)
+    }
+
+    fn authenticate(&mut self, body: Bytes) -> Bytes {
+        let Some(credential) = BodyReader::new(body).read_string() else {
+            return status(Status::BadRequest);
+        };
+        let Some(authenticator) = &self.authenticator else {
+            return status(Status::Ok);
+        };
+
+        match authenticator.authenticate(&credential) {
+            Ok(identity) => {
+                self.identity = Some(identity);
+                status(Status::Ok)
+            }
+            Err(e) => {
+                warn!("TcpServer::authenticate rejected a connection: {}", e);
+                self.identity = None;
+                status(Status::Unauthenticated)
+            }
+        }
+    }
+
+    fn is_authenticated(&self) -> bool {
+        self.authenticator.is_none() || self.identity.is_some()
+    }
+}
+
+async fn push(
+    framed: &mut Framed<TcpStream, FrameCodec>,
+    connection: &Connection,
+    correlation_id: u32,
+    subscription: &Subscription,
+) -> io::Result<()> {
+    let pushes =
+        identity::on_behalf_of(connection.identity.clone(), collect_pushes(subscription)).await;
+    for body in pushes {
+        framed
+            .send(Frame::new(OpCode::Push as u8, correlation_id, body))
+            .await?;
+    }
+    Ok(())
+}
+
+fn is_subscribed_to(subscription: &Subscription, destination: &TopicDestination) -> bool {
+    match subscription {
+        Subscription::MessageTopic { topic, .. } => {
+            destination.destination_type == DestinationType::MessageTopic
+                && destination.destination == *topic
+        }
+        Subscription::TaskTopic { topic, .. } => {
+            destination.destination_type == DestinationType::TaskTopic
+                && destination.destination == *topic
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::{BufMut, BytesMut};
+    use std::time::Duration;
+    use tokio::io::AsyncWriteExt;
+    use tokio_util::codec::{Encoder, FramedRead};
+
+    const RECEIVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn request(version: u8, op_code: u8, correlation_id: u32, body: Bytes) -> Frame {
+        Frame {
+            version,
+            op_code,
+            correlation_id,
+            body,
+        }
+    }
+
+    fn topic_name(name: &str) -> Bytes {
+        let mut body = BytesMut::new();
+        body.put_u16(name.len() as u16);
+        body.put_slice(name.as_bytes());
+        body.freeze()
+    }
+
+    fn json(document: &str) -> Bytes {
+        let mut body = BytesMut::new();
+        body.put_u32(document.len() as u32);
+        body.put_slice(document.as_bytes());
+        body.freeze()
+    }
+
+    #[tokio::test]
+    async fn pipelined_requests_are_answered_in_order_with_their_correlation_ids() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(accept_connections(listener, None));
+
+        let requests = [
+            request(PROTOCOL_VERSION, OpCode::Ping as u8, 11, Bytes::new()),
+            request(PROTOCOL_VERSION + 1, OpCode::Ping as u8, 12, Bytes::new()),
+            request(PROTOCOL_VERSION, 0x7F, 13, Bytes::new()),
+            request(
+                PROTOCOL_VERSION,
+                OpCode::CreateMessageTopic as u8,
+                14,
+                json("{\"name\":\"tcp-pipelined\"}"),
+            ),
+            request(
+                PROTOCOL_VERSION,
+                OpCode::GetMessageTopic as u8,
+                15,
+                topic_name("tcp-pipelined"),
+            ),
+            request(
+                PROTOCOL_VERSION,
+                OpCode::GetMessageTopic as u8,
+                16,
+                Bytes::from_static(&[0x00]),
+            ),
+        ];
+        let mut pipelined = BytesMut::new();
+        for frame in requests {
+            FrameCodec.encode(frame, &mut pipelined).unwrap();
+        }
+
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream.write_all(&pipelined).await.unwrap();
+        let mut responses = FramedRead::new(stream, FrameCodec);
+
+        let expected = [
+            (OpCode::Ping as u8, 11, Status::Ok),
+            (OpCode::Ping as u8, 12, Status::UnsupportedVersion),
+            (0x7F, 13, Status::UnknownOperation),
+            (OpCode::CreateMessageTopic as u8, 14, Status::Ok),
+            (OpCode::GetMessageTopic as u8, 15, Status::Ok),
+            (OpCode::GetMessageTopic as u8, 16, Status::BadRequest),
+        ];
+        for (op_code, correlation_id, status) in expected {
+            let response = tokio::time::timeout(RECEIVE_TIMEOUT, responses.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            assert_eq!(response.version, PROTOCOL_VERSION);
+            assert_eq!(response.op_code, op_code);
+            assert_eq!(response.correlation_id, correlation_id);
+            assert_eq!(response.body[0], status as u8);
+        }
+    }
+}
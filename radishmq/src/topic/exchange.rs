@@ -0,0 +1,345 @@
+use crate::model::destination_model::TopicDestination;
+use crate::model::exchange_model::{BindingModel, ExchangeModel, ExchangeType, HeaderMatch};
+use crate::utils::topic_pattern::TopicPattern;
+use crate::utils::types::Headers;
+use log::{info, warn};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub id: Uuid,
+    pub destination: TopicDestination,
+    pub routing_key: Option<String>,
+    pub arguments: Headers,
+    pub header_match: HeaderMatch,
+    pattern: Option<TopicPattern>,
+}
+
+impl Binding {
+    pub fn new(
+        id: Uuid,
+        destination: TopicDestination,
+        routing_key: Option<String>,
+        arguments: Headers,
+        header_match: HeaderMatch,
+    ) -> Self {
+        let pattern = routing_key.as_deref().and_then(TopicPattern::parse);
+
+        Self {
+            id,
+            destination,
+            routing_key,
+            arguments,
+            header_match,
+            pattern,
+        }
+    }
+
+    fn matches(&self, exchange_type: ExchangeType, routing_key: &str, headers: &Headers) -> bool {
+        match exchange_type {
+            ExchangeType::Direct => self.routing_key.as_deref() == Some(routing_key),
+            ExchangeType::Fanout => true,
+            ExchangeType::Topic => self
+                .pattern
+                .as_ref()
+                .is_some_and(|pattern| pattern.matches(routing_key)),
+            ExchangeType::Headers => {
+                let mut arguments = self
+                    .arguments
+                    .iter()
+                    .map(|(key, value)| headers.get(key) == Some(value));
+
+                match self.header_match {
+                    HeaderMatch::All => arguments.all(|matched| matched),
+                    HeaderMatch::Any => arguments.any(|matched| matched),
+                }
+            }
+        }
+    }
+
+    pub fn to_model(&self) -> BindingModel {
+        BindingModel {
+            id: self.id,
+            destination: self.destination.clone(),
+            routing_key: self.routing_key.clone(),
+            arguments: self.arguments.clone(),
+            header_match: self.header_match,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Exchange {
+    pub name: String,
+    pub exchange_type: ExchangeType,
+    bindings: Arc<Mutex<Vec<Binding>>>,
+    publisher: Arc<Mutex<Vec<String>>>,
+}
+
+impl Exchange {
+    pub fn new(name: String, exchange_type: ExchangeType) -> Self {
+        Self {
+            name,
+            exchange_type,
+            bindings: Arc::new(Mutex::new(Vec::new())),
+            publisher: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Explains why a binding with the routing key and header arguments could never route.
+    pub fn validate_binding(
+        &self,
+        routing_key: Option<&str>,
+        arguments: &Headers,
+    ) -> Result<(), String> {
+        let is_valid = match self.exchange_type {
+            ExchangeType::Direct => routing_key.is_some(),
+            ExchangeType::Topic => routing_key.and_then(TopicPattern::parse).is_some(),
+            ExchangeType::Fanout => true,
+            ExchangeType::Headers => {
+                if arguments.is_empty() {
+                    return Err(
+                        "a binding of a headers exchange needs at least one header argument"
+                            .to_string(),
+                    );
+                }
+                true
+            }
+        };
+        is_valid.then_some(()).ok_or_else(|| {
+            format!(
+                "the routing key is not valid for a {} exchange",
+                self.exchange_type.as_str()
+            )
+        })
+    }
+
+    pub fn add_binding(&mut self, binding: Binding) {
+        if let Ok(mut bindings) = self.bindings.lock() {
+            info!(
+                "Added binding '{}' to exchange '{}'.",
+                binding.id, self.name
+            );
+            bindings.push(binding);
+        } else {
+            panic!("Exchange::add_binding() tried to lock a poisoned mutex");
+        }
+    }
+
+    pub fn remove_binding(&mut self, id: Uuid) -> Option<Binding> {
+        if let Ok(mut bindings) = self.bindings.lock() {
+            if let Some(index) = bindings.iter().position(|binding| binding.id == id) {
+                info!("Removed binding '{}' from exchange '{}'.", id, self.name);
+                Some(bindings.remove(index))
+            } else {
+                warn!("Exchange::remove_binding() tried to remove a non-existing binding");
+                None
+            }
+        } else {
+            panic!("Exchange::remove_binding() tried to lock a poisoned mutex");
+        }
+    }
+
+    pub fn route(&self, routing_key: &str, headers: &Headers) -> Vec<TopicDestination> {
+        if let Ok(bindings) = self.bindings.lock() {
+            let mut destinations: Vec<TopicDestination> = Vec::new();
+
+            for binding in bindings
+                .iter()
+                .filter(|binding| binding.matches(self.exchange_type, routing_key, headers))
+            {
+                if !destinations.contains(&binding.destination) {
+                    destinations.push(binding.destination.clone());
+                }
+            }
+
+            destinations
+        } else {
+            panic!("Exchange::route() tried to lock a poisoned mutex");
+        }
+    }
+
+    pub fn add_publisher(&mut self, identifier: String) {
+        if let Ok(mut publisher) = self.publisher.lock() {
+            if !publisher.contains(&identifier) {
+                publisher.push(identifier.clone());
+                info!(
+                    "Added publisher '{}' to exchange '{}'.",
+                    identifier, self.name
+                );
+            } else {
+                warn!("Exchange::add_publisher() tried to add an already existing publisher");
+            }
+        } else {
+            panic!("Exchange::add_publisher() tried to lock a poisoned mutex");
+        }
+    }
+
+    pub fn remove_publisher(&mut self, identifier: String) {
+        if let Ok(mut publisher) = self.publisher.lock() {
+            if publisher.contains(&identifier) {
+                publisher.retain(|x| x != &identifier);
+                info!(
+                    "Removed publisher '{}' from exchange '{}'.",
+                    identifier, self.name
+                );
+            } else {
+                warn!("Exchange::remove_publisher() tried to remove a non-existing publisher");
+            }
+        } else {
+            panic!("Exchange::remove_publisher() tried to lock a poisoned mutex");
+        }
+    }
+
+    pub fn is_publisher(&self, identifier: String) -> bool {
+        if let Ok(publisher) = self.publisher.lock() {
+            publisher.contains(&identifier)
+        } else {
+            panic!("Exchange::is_publisher() tried to lock a poisoned mutex");
+        }
+    }
+
+    pub fn to_model(&self) -> ExchangeModel {
+        let bindings = if let Ok(bindings) = self.bindings.lock() {
+            bindings.iter().map(|binding| binding.to_model()).collect()
+        } else {
+            panic!("Exchange::to_model() tried to lock a poisoned mutex");
+        };
+
+        let publisher = if let Ok(publisher) = self.publisher.lock() {
+            publisher.clone()
+        } else {
+            panic!("Exchange::to_model() tried to lock a poisoned mutex");
+        };
+
+        ExchangeModel {
+            name: self.name.clone(),
+            exchange_type: self.exchange_type,
+            bindings,
+            publisher,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::destination_model::DestinationType;
+
+    fn headers(pairs: &[(&str, &str)]) -> Headers {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn exchange(
+        exchange_type: ExchangeType,
+        bindings: &[(&str, Option<&str>, Headers, HeaderMatch)],
+    ) -> Exchange {
+        let mut exchange = Exchange::new("orders".to_string(), exchange_type);
+        for (destination, routing_key, arguments, header_match) in bindings {
+            exchange.add_binding(Binding::new(
+                Uuid::new_v4(),
+                TopicDestination::new(DestinationType::MessageTopic, destination.to_string()),
+                routing_key.map(str::to_string),
+                arguments.clone(),
+                *header_match,
+            ));
+        }
+        exchange
+    }
+
+    fn routed(exchange: &Exchange, routing_key: &str, headers: &Headers) -> Vec<String> {
+        exchange
+            .route(routing_key, headers)
+            .into_iter()
+            .map(|destination| destination.destination)
+            .collect()
+    }
+
+    #[test]
+    fn direct_and_topic_exchanges_route_by_routing_key() {
+        let direct = exchange(
+            ExchangeType::Direct,
+            &[
+                ("eu", Some("orders.eu"), Headers::new(), HeaderMatch::All),
+                ("us", Some("orders.us"), Headers::new(), HeaderMatch::All),
+            ],
+        );
+        assert_eq!(routed(&direct, "orders.eu", &Headers::new()), ["eu"]);
+        assert!(routed(&direct, "orders.*", &Headers::new()).is_empty());
+
+        let topic = exchange(
+            ExchangeType::Topic,
+            &[
+                ("all", Some("orders.#"), Headers::new(), HeaderMatch::All),
+                ("eu", Some("orders.eu.*"), Headers::new(), HeaderMatch::All),
+            ],
+        );
+        assert_eq!(
+            routed(&topic, "orders.eu.created", &Headers::new()),
+            ["all", "eu"]
+        );
+        assert_eq!(routed(&topic, "orders.us", &Headers::new()), ["all"]);
+    }
+
+    #[test]
+    fn fanout_exchanges_route_to_every_destination_once() {
+        let fanout = exchange(
+            ExchangeType::Fanout,
+            &[
+                ("audit", None, Headers::new(), HeaderMatch::All),
+                ("audit", None, Headers::new(), HeaderMatch::All),
+                ("billing", None, Headers::new(), HeaderMatch::All),
+            ],
+        );
+        assert_eq!(
+            routed(&fanout, "anything", &Headers::new()),
+            ["audit", "billing"]
+        );
+    }
+
+    #[test]
+    fn headers_exchanges_match_all_or_any_argument() {
+        let arguments = headers(&[("region", "eu"), ("priority", "high")]);
+        let exchange = exchange(
+            ExchangeType::Headers,
+            &[
+                ("all", None, arguments.clone(), HeaderMatch::All),
+                ("any", None, arguments, HeaderMatch::Any),
+            ],
+        );
+        let both = headers(&[("region", "eu"), ("priority", "high"), ("other", "x")]);
+        assert_eq!(routed(&exchange, "", &both), ["all", "any"]);
+        assert_eq!(
+            routed(&exchange, "", &headers(&[("region", "eu")])),
+            ["any"]
+        );
+        assert!(routed(&exchange, "", &headers(&[("region", "us")])).is_empty());
+    }
+
+    #[test]
+    fn validates_bindings_by_exchange_type() {
+        let topic = Exchange::new("orders".to_string(), ExchangeType::Topic);
+        assert!(topic
+            .validate_binding(Some("orders.#"), &Headers::new())
+            .is_ok());
+        assert!(topic
+            .validate_binding(Some("orders..eu"), &Headers::new())
+            .is_err());
+        assert!(topic.validate_binding(None, &Headers::new()).is_err());
+
+        let direct = Exchange::new("orders".to_string(), ExchangeType::Direct);
+        assert!(direct.validate_binding(None, &Headers::new()).is_err());
+
+        let headers_exchange = Exchange::new("orders".to_string(), ExchangeType::Headers);
+        assert!(headers_exchange
+            .validate_binding(None, &Headers::new())
+            .is_err());
+        assert!(headers_exchange
+            .validate_binding(None, &headers(&[("region", "eu")]))
+            .is_ok());
+    }
+}
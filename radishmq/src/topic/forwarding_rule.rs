@@ -0,0 +1,58 @@
+use crate::model::destination_model::TopicDestination;
+use crate::model::forwarding_rule_model::{ForwardingRuleModel, HeaderRewrite};
+use crate::model::message_topic_model::Message;
+use crate::utils::topic_pattern::TopicPattern;
+use crate::utils::types::TopicType;
+
+#[derive(Debug, Clone)]
+pub struct ForwardingRule {
+    pub namespace: String,
+    pub name: String,
+    pub source: TopicPattern,
+    pub destination: TopicDestination,
+    pub header_rewrite: HeaderRewrite,
+}
+
+impl ForwardingRule {
+    pub fn new(
+        namespace: String,
+        name: String,
+        source: TopicPattern,
+        destination: TopicDestination,
+        header_rewrite: HeaderRewrite,
+    ) -> Self {
+        Self {
+            namespace,
+            name,
+            source,
+            destination,
+            header_rewrite,
+        }
+    }
+
+    pub fn is_named(&self, namespace: &str, name: &str) -> bool {
+        self.namespace == namespace && self.name == name
+    }
+
+    /// Rules only forward the messages of the topics in their own namespace.
+    pub fn matches(&self, namespace: &str, topic_name: &str) -> bool {
+        self.namespace == namespace && self.source.matches(topic_name)
+    }
+
+    pub fn rewrite(&self, mut message: Message<TopicType>) -> Message<TopicType> {
+        for header in &self.header_rewrite.remove {
+            message.headers.remove(header);
+        }
+        message.headers.extend(self.header_rewrite.set.clone());
+        message
+    }
+
+    pub fn to_model(&self) -> ForwardingRuleModel {
+        ForwardingRuleModel {
+            name: self.name.clone(),
+            source: self.source.as_str().to_string(),
+            destination: self.destination.clone(),
+            header_rewrite: self.header_rewrite.clone(),
+        }
+    }
+}
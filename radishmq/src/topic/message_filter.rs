@@ -0,0 +1,274 @@
+use crate::model::message_topic_model::Message;
+use crate::utils::types::TopicType;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cell::OnceCell;
+use std::cmp::Ordering;
+use utoipa::ToSchema;
+
+const HEADER_FIELD_PREFIX: &str = "headers.";
+const DATA_FIELD: &str = "data";
+const DATA_FIELD_PREFIX: &str = "data.";
+
+pub trait Filterable {
+    fn header(&self, name: &str) -> Option<&str>;
+
+    fn payload(&self) -> Value;
+}
+
+impl Filterable for Message<TopicType> {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(|value| value.as_str())
+    }
+
+    fn payload(&self) -> Value {
+        serde_json::from_str(&self.data).unwrap_or_else(|_| Value::String(self.data.clone()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FieldComparison {
+    pub field: String,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FieldMembership {
+    pub field: String,
+    pub values: Vec<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FieldReference {
+    pub field: String,
+}
+
+/// Filter expression evaluated against the headers (`headers.<name>`) and the JSON payload
+/// (`data` or `data.<path>`) of a message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageFilter {
+    Eq(FieldComparison),
+    Ne(FieldComparison),
+    Gt(FieldComparison),
+    Gte(FieldComparison),
+    Lt(FieldComparison),
+    Lte(FieldComparison),
+    In(FieldMembership),
+    Exists(FieldReference),
+    #[schema(no_recursion)]
+    And(Vec<MessageFilter>),
+    #[schema(no_recursion)]
+    Or(Vec<MessageFilter>),
+    #[schema(no_recursion)]
+    Not(Box<MessageFilter>),
+}
+
+impl MessageFilter {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Eq(comparison)
+            | Self::Ne(comparison)
+            | Self::Gt(comparison)
+            | Self::Gte(comparison)
+            | Self::Lt(comparison)
+            | Self::Lte(comparison) => is_valid_field(&comparison.field),
+            Self::In(membership) => is_valid_field(&membership.field),
+            Self::Exists(reference) => is_valid_field(&reference.field),
+            Self::And(filters) | Self::Or(filters) => filters.iter().all(|f| f.is_valid()),
+            Self::Not(filter) => filter.is_valid(),
+        }
+    }
+
+    pub fn matches<M: Filterable>(&self, message: &M) -> bool {
+        self.evaluate(&FilterContext::new(message))
+    }
+
+    fn evaluate<M: Filterable>(&self, context: &FilterContext<M>) -> bool {
+        match self {
+            Self::Eq(comparison) => context
+                .resolve(&comparison.field)
+                .is_some_and(|field| is_equal(&field, &comparison.value)),
+            Self::Ne(comparison) => !context
+                .resolve(&comparison.field)
+                .is_some_and(|field| is_equal(&field, &comparison.value)),
+            Self::Gt(comparison) => context.compare(comparison).is_some_and(|o| o.is_gt()),
+            Self::Gte(comparison) => context.compare(comparison).is_some_and(|o| o.is_ge()),
+            Self::Lt(comparison) => context.compare(comparison).is_some_and(|o| o.is_lt()),
+            Self::Lte(comparison) => context.compare(comparison).is_some_and(|o| o.is_le()),
+            Self::In(membership) => context.resolve(&membership.field).is_some_and(|field| {
+                membership
+                    .values
+                    .iter()
+                    .any(|value| is_equal(&field, value))
+            }),
+            Self::Exists(reference) => context.resolve(&reference.field).is_some(),
+            Self::And(filters) => filters.iter().all(|filter| filter.evaluate(context)),
+            Self::Or(filters) => filters.iter().any(|filter| filter.evaluate(context)),
+            Self::Not(filter) => !filter.evaluate(context),
+        }
+    }
+}
+
+struct FilterContext<'a, M: Filterable> {
+    message: &'a M,
+    payload: OnceCell<Value>,
+}
+
+impl<'a, M: Filterable> FilterContext<'a, M> {
+    fn new(message: &'a M) -> Self {
+        Self {
+            message,
+            payload: OnceCell::new(),
+        }
+    }
+
+    fn resolve(&self, field: &str) -> Option<Value> {
+        if let Some(header) = field.strip_prefix(HEADER_FIELD_PREFIX) {
+            return self
+                .message
+                .header(header)
+                .map(|value| Value::String(value.to_string()));
+        }
+
+        let payload = self.payload.get_or_init(|| self.message.payload());
+        if field == DATA_FIELD {
+            return Some(payload.clone());
+        }
+
+        let mut current = payload;
+        for segment in field.strip_prefix(DATA_FIELD_PREFIX)?.split('.') {
+            current = match current {
+                Value::Object(object) => object.get(segment)?,
+                Value::Array(array) => array.get(segment.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+        Some(current.clone())
+    }
+
+    fn compare(&self, comparison: &FieldComparison) -> Option<Ordering> {
+        compare(&self.resolve(&comparison.field)?, &comparison.value)
+    }
+}
+
+fn is_valid_field(field: &str) -> bool {
+    match field.strip_prefix(HEADER_FIELD_PREFIX) {
+        Some(header) => !header.is_empty(),
+        None => {
+            field == DATA_FIELD
+                || field
+                    .strip_prefix(DATA_FIELD_PREFIX)
+                    .is_some_and(|path| path.split('.').all(|segment| !segment.is_empty()))
+        }
+    }
+}
+
+fn is_equal(field: &Value, value: &Value) -> bool {
+    match (field, value) {
+        (Value::String(field), Value::Number(_)) | (Value::String(field), Value::Bool(_)) => {
+            serde_json::from_str::<Value>(field).is_ok_and(|parsed| is_equal(&parsed, value))
+        }
+        (Value::Number(field), Value::Number(value)) => field.as_f64() == value.as_f64(),
+        _ => field == value,
+    }
+}
+
+fn compare(field: &Value, value: &Value) -> Option<Ordering> {
+    match (field, value) {
+        (Value::Number(field), Value::Number(value)) => {
+            field.as_f64()?.partial_cmp(&value.as_f64()?)
+        }
+        (Value::String(field), Value::String(value)) => Some(field.cmp(value)),
+        (Value::String(field), Value::Number(value)) => {
+            field.parse::<f64>().ok()?.partial_cmp(&value.as_f64()?)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn filter(filter: Value) -> MessageFilter {
+        serde_json::from_value(filter).unwrap()
+    }
+
+    fn order() -> Message<TopicType> {
+        Message::new(
+            json!({"total": 42.5, "items": [{"sku": "a-1"}], "express": true}).to_string(),
+            [
+                ("region".to_string(), "eu".to_string()),
+                ("retries".to_string(), "3".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+        )
+    }
+
+    #[test]
+    fn compares_headers_and_payload_fields() {
+        let order = order();
+        assert!(filter(json!({"eq": {"field": "headers.region", "value": "eu"}})).matches(&order));
+        assert!(filter(json!({"eq": {"field": "headers.retries", "value": 3}})).matches(&order));
+        assert!(filter(json!({"gt": {"field": "data.total", "value": 40}})).matches(&order));
+        assert!(filter(json!({"lte": {"field": "headers.retries", "value": 3}})).matches(&order));
+        assert!(
+            filter(json!({"eq": {"field": "data.items.0.sku", "value": "a-1"}})).matches(&order)
+        );
+        assert!(!filter(json!({"lt": {"field": "data.total", "value": 40}})).matches(&order));
+        assert!(!filter(json!({"gt": {"field": "data.express", "value": 0}})).matches(&order));
+    }
+
+    #[test]
+    fn missing_fields_only_match_negations() {
+        let order = order();
+        assert!(!filter(json!({"eq": {"field": "headers.missing", "value": "x"}})).matches(&order));
+        assert!(filter(json!({"ne": {"field": "headers.missing", "value": "x"}})).matches(&order));
+        assert!(!filter(json!({"exists": {"field": "data.items.1"}})).matches(&order));
+        assert!(filter(json!({"exists": {"field": "data.items.0"}})).matches(&order));
+    }
+
+    #[test]
+    fn combines_filters() {
+        let order = order();
+        let filter = filter(json!({"and": [
+            {"in": {"field": "headers.region", "values": ["eu", "uk"]}},
+            {"or": [
+                {"eq": {"field": "data.express", "value": false}},
+                {"gte": {"field": "data.total", "value": 42.5}}
+            ]},
+            {"not": {"exists": {"field": "headers.cancelled"}}}
+        ]}));
+        assert!(filter.is_valid());
+        assert!(filter.matches(&order));
+        assert!(!filter.matches(&Message::new("plain text".to_string(), Default::default())));
+    }
+
+    #[test]
+    fn plain_text_payloads_are_compared_as_strings() {
+        let message = Message::new("hello".to_string(), Default::default());
+        assert!(filter(json!({"eq": {"field": "data", "value": "hello"}})).matches(&message));
+        assert!(!filter(json!({"exists": {"field": "data.length"}})).matches(&message));
+    }
+
+    #[test]
+    fn rejects_fields_outside_headers_and_data() {
+        for field in [
+            "region",
+            "headers.",
+            "data.",
+            "data..total",
+            "payload.total",
+        ] {
+            assert!(
+                !filter(json!({"exists": {"field": field}})).is_valid(),
+                "{}",
+                field
+            );
+        }
+        assert!(!filter(json!({"not": {"eq": {"field": "total", "value": 1}}})).is_valid());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::model::message_topic_model::MessageTopicModel;
+use crate::topic::message_filter::{Filterable, MessageFilter};
 use log::{error, info, warn};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
@@ -7,24 +8,43 @@ use std::sync::{Arc, Mutex};
 
 #[derive(Clone)]
 pub struct MessageTopic<T: Send + Clone + Debug> {
+    pub namespace: String,
     pub name: String,
     index: Arc<Mutex<usize>>,
+    // the index of the first message held in `data`, messages before it were lost on a restart
+    first_index: Arc<Mutex<usize>>,
     data: Arc<Mutex<Vec<T>>>,
     subscriber: Arc<Mutex<HashMap<String, usize>>>,
+    subscriber_filter: Arc<Mutex<HashMap<String, MessageFilter>>>,
     publisher: Arc<Mutex<Vec<String>>>,
 }
 
 impl<T: Send + Clone + Debug> MessageTopic<T> {
-    pub fn new(name: String) -> Self {
+    pub fn new(namespace: String, name: String) -> Self {
         Self {
+            namespace,
             name,
             index: Arc::new(Mutex::new(0)),
+            first_index: Arc::new(Mutex::new(0)),
             data: Arc::new(Mutex::new(Vec::new())),
             subscriber: Arc::new(Mutex::new(HashMap::new())),
+            subscriber_filter: Arc::new(Mutex::new(HashMap::new())),
             publisher: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
+    /// A topic restored with the index it had, messages are not persisted so it holds none.
+    pub fn restore(namespace: String, name: String, index: usize) -> Self {
+        let topic = Self::new(namespace, name);
+        *topic.index.lock().unwrap() = index;
+        *topic.first_index.lock().unwrap() = index;
+        topic
+    }
+
+    pub fn is_named(&self, namespace: &str, name: &str) -> bool {
+        self.namespace == namespace && self.name == name
+    }
+
     pub fn publish(&mut self, data_to_add: T) {
         if let Ok(mut data) = self.data.lock() {
             data.push(data_to_add);
@@ -70,6 +90,9 @@ impl<T: Send + Clone + Debug> MessageTopic<T> {
                 *offset -= biggest_subscriber_offset;
             }
             *index -= biggest_subscriber_offset;
+            if let Ok(mut first_index) = self.first_index.lock() {
+                *first_index = first_index.saturating_sub(biggest_subscriber_offset);
+            }
         } else {
             panic!("MessageTopic::update_index() tried to lock a poisoned mutex");
         }
@@ -115,10 +138,13 @@ impl<T: Send + Clone + Debug> MessageTopic<T> {
         }
     }
 
-    pub fn add_subscriber(&mut self, identifier: String) {
+    pub fn add_subscriber(&mut self, identifier: String, filter: Option<MessageFilter>) {
         if let Ok(mut subscriber) = self.subscriber.lock() {
             if !subscriber.contains_key(&identifier) {
                 subscriber.insert(identifier.clone(), self.get_index());
+                if let Some(filter) = filter {
+                    self.set_subscriber_filter(identifier.clone(), filter);
+                }
                 info!(
                     "Added subscriber '{}' to message_topic '{}'.",
                     identifier, self.name
@@ -131,10 +157,22 @@ impl<T: Send + Clone + Debug> MessageTopic<T> {
         }
     }
 
+    /// Adds a subscriber at the offset it had fetched up to before a restart.
+    pub fn restore_subscriber(
+        &mut self,
+        identifier: String,
+        filter: Option<MessageFilter>,
+        offset: usize,
+    ) {
+        self.add_subscriber(identifier.clone(), filter);
+        self.update_subscriber_last_fetch(identifier, offset.min(self.get_index()));
+    }
+
     pub fn remove_subscriber(&mut self, identifier: String) {
         if let Ok(mut subscriber) = self.subscriber.lock() {
             if subscriber.contains_key(&identifier) {
                 subscriber.remove(&identifier);
+                self.remove_subscriber_filter(&identifier);
                 info!(
                     "Removed subscriber '{}' from message_topic '{}'.",
                     identifier, self.name
@@ -149,6 +187,14 @@ impl<T: Send + Clone + Debug> MessageTopic<T> {
         }
     }
 
+    pub fn is_subscriber(&self, identifier: String) -> bool {
+        if let Ok(subscriber) = self.subscriber.lock() {
+            subscriber.contains_key(&identifier)
+        } else {
+            panic!("MessageTopic::is_subscriber() tried to lock a poisoned mutex");
+        }
+    }
+
     pub fn get_subscriber_index(&self, identifier: String) -> usize {
         if let Ok(subscriber) = self.subscriber.lock() {
             if subscriber.contains_key(&identifier) {
@@ -161,22 +207,28 @@ impl<T: Send + Clone + Debug> MessageTopic<T> {
         }
     }
 
-    pub fn new_data_to_fetch_for_subscriber(&self, identifier: String) -> bool {
-        self.get_subscriber_offset(identifier.clone()).unwrap() < self.get_index()
+    fn set_subscriber_filter(&self, identifier: String, filter: MessageFilter) {
+        if let Ok(mut subscriber_filter) = self.subscriber_filter.lock() {
+            subscriber_filter.insert(identifier, filter);
+        } else {
+            panic!("MessageTopic::set_subscriber_filter() tried to lock a poisoned mutex");
+        }
     }
 
-    pub fn get_data_for_subscriber(&mut self, identifier: String) -> Option<Vec<T>> {
-        let current_length: usize;
-        let ret_val = if let Ok(data) = self.data.lock() {
-            current_length = data.len();
-            let subscriber_last_fetch = self.get_subscriber_last_fetch(identifier.clone()).unwrap();
+    fn remove_subscriber_filter(&self, identifier: &str) {
+        if let Ok(mut subscriber_filter) = self.subscriber_filter.lock() {
+            subscriber_filter.remove(identifier);
+        } else {
+            panic!("MessageTopic::remove_subscriber_filter() tried to lock a poisoned mutex");
+        }
+    }
 
-            Some(data[subscriber_last_fetch..current_length].to_vec())
+    fn get_subscriber_filter(&self, identifier: &str) -> Option<MessageFilter> {
+        if let Ok(subscriber_filter) = self.subscriber_filter.lock() {
+            subscriber_filter.get(identifier).cloned()
         } else {
-            panic!("MessageTopic::get_data_for_subscriber() tried to lock a poisoned mutex");
-        };
-        self.update_subscriber_last_fetch(identifier.clone(), current_length);
-        ret_val
+            panic!("MessageTopic::get_subscriber_filter() tried to lock a poisoned mutex");
+        }
     }
 
     fn get_subscriber_last_fetch(&self, identifier: String) -> Option<usize> {
@@ -211,15 +263,48 @@ impl<T: Send + Clone + Debug> MessageTopic<T> {
         }
     }
 
-    fn get_subscriber_offset(&self, identifier: String) -> Option<usize> {
+    fn get_first_index(&self) -> usize {
+        if let Ok(first_index) = self.first_index.lock() {
+            *first_index
+        } else {
+            panic!("MessageTopic::get_first_index() tried to lock a poisoned mutex");
+        }
+    }
+
+    // the position in `data` of the first message after an offset
+    fn get_data_position(&self, offset: usize, data_length: usize) -> usize {
+        offset
+            .saturating_sub(self.get_first_index())
+            .min(data_length)
+    }
+
+    /// The messages the topic holds.
+    pub fn depth(&self) -> usize {
+        if let Ok(data) = self.data.lock() {
+            data.len()
+        } else {
+            panic!("MessageTopic::depth() tried to lock a poisoned mutex");
+        }
+    }
+
+    pub fn publisher_count(&self) -> usize {
+        if let Ok(publisher) = self.publisher.lock() {
+            publisher.len()
+        } else {
+            panic!("MessageTopic::publisher_count() tried to lock a poisoned mutex");
+        }
+    }
+
+    /// The number of messages every subscriber is behind the index of the topic.
+    pub fn subscriber_lags(&self) -> Vec<(String, usize)> {
+        let index = self.get_index();
         if let Ok(subscriber) = self.subscriber.lock() {
-            if subscriber.contains_key(&identifier) {
-                subscriber.get(&*identifier).cloned()
-            } else {
-                None
-            }
+            subscriber
+                .iter()
+                .map(|(identifier, offset)| (identifier.clone(), index.saturating_sub(*offset)))
+                .collect()
         } else {
-            panic!("MessageTopic::get_subscriber_offset() tried to lock a poisoned mutex");
+            panic!("MessageTopic::subscriber_lags() tried to lock a poisoned mutex");
         }
     }
 
@@ -272,6 +357,64 @@ impl<T: Send + Clone + Debug> MessageTopic<T> {
     }
 }
 
+impl<T: Send + Clone + Debug + Filterable> MessageTopic<T> {
+    pub fn new_data_to_fetch_for_subscriber(&self, identifier: String) -> bool {
+        match self.get_subscriber_filter(&identifier) {
+            Some(filter) => {
+                let subscriber_last_fetch =
+                    self.get_subscriber_last_fetch(identifier.clone()).unwrap();
+
+                if let Ok(data) = self.data.lock() {
+                    data[self.get_data_position(subscriber_last_fetch, data.len())..]
+                        .iter()
+                        .any(|message| filter.matches(message))
+                } else {
+                    panic!("MessageTopic::new_data_to_fetch_for_subscriber() tried to lock a poisoned mutex");
+                }
+            }
+            None => {
+                let subscriber_last_fetch =
+                    self.get_subscriber_last_fetch(identifier.clone()).unwrap();
+
+                if let Ok(data) = self.data.lock() {
+                    self.get_data_position(subscriber_last_fetch, data.len()) < data.len()
+                } else {
+                    panic!("MessageTopic::new_data_to_fetch_for_subscriber() tried to lock a poisoned mutex");
+                }
+            }
+        }
+    }
+
+    pub fn get_data_for_subscriber(&mut self, identifier: String) -> Option<Vec<T>> {
+        let filter = self.get_subscriber_filter(&identifier);
+        let current_length: usize;
+        let ret_val = if let Ok(data) = self.data.lock() {
+            current_length = data.len();
+            let subscriber_last_fetch = self.get_subscriber_last_fetch(identifier.clone()).unwrap();
+
+            // messages that do not match the filter are skipped, but the offset still moves past them
+            let new_data = &data[self.get_data_position(subscriber_last_fetch, current_length)..];
+            match filter {
+                Some(filter) => Some(
+                    new_data
+                        .iter()
+                        .filter(|message| filter.matches(*message))
+                        .cloned()
+                        .collect(),
+                ),
+                None => Some(new_data.to_vec()),
+            }
+        } else {
+            panic!("MessageTopic::get_data_for_subscriber() tried to lock a poisoned mutex");
+        };
+        self.update_subscriber_last_fetch(
+            identifier.clone(),
+            self.get_first_index() + current_length,
+        );
+        ret_val
+    }
+}
+
 impl<T: Send + Clone + Debug> Debug for MessageTopic<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MessageTopic")
@@ -282,3 +425,46 @@ impl<T: Send + Clone + Debug> Debug for MessageTopic<T> {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::message_topic_model::{Message, PublishToMessageTopic};
+    use crate::utils::types::Headers;
+
+    fn message(data: &str) -> Message<String> {
+        Message::from(PublishToMessageTopic::new(data.to_string(), Headers::new()))
+    }
+
+    #[test]
+    fn restored_subscribers_keep_their_offset() {
+        let mut topic = MessageTopic::restore("default".to_string(), "orders".to_string(), 10);
+        topic.restore_subscriber("behind".to_string(), None, 7);
+        topic.restore_subscriber("current".to_string(), None, 10);
+        topic.restore_subscriber("ahead".to_string(), None, 12);
+
+        assert_eq!(topic.get_subscriber_index("behind".to_string()), 7);
+        assert_eq!(topic.get_subscriber_index("ahead".to_string()), 10);
+        assert!(!topic.new_data_to_fetch_for_subscriber("behind".to_string()));
+        assert!(!topic.new_data_to_fetch_for_subscriber("current".to_string()));
+
+        topic.publish(message("first"));
+        topic.publish(message("second"));
+
+        let data = topic.get_data_for_subscriber("behind".to_string()).unwrap();
+        assert_eq!(data.len(), 2);
+        assert_eq!(data[0].data, "first");
+        assert_eq!(topic.get_subscriber_index("behind".to_string()), 12);
+
+        let mut lags = topic.subscriber_lags();
+        lags.sort();
+        assert_eq!(
+            lags,
+            vec![
+                ("ahead".to_string(), 2),
+                ("behind".to_string(), 0),
+                ("current".to_string(), 2)
+            ]
+        );
+    }
+}
@@ -1,2 +1,7 @@
+pub mod exchange;
+pub mod forwarding_rule;
+pub mod message_filter;
 pub mod message_topic;
+pub mod quota;
 pub mod task_topic;
+pub mod wildcard_subscription;
@@ -0,0 +1,232 @@
+use crate::model::destination_model::DestinationType;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// What a quota is counted for, every publisher, topic and namespace has its own usage.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum QuotaScope {
+    Publisher {
+        namespace: String,
+        identifier: String,
+    },
+    Topic {
+        namespace: String,
+        destination_type: DestinationType,
+        name: String,
+    },
+    Namespace(String),
+}
+
+impl QuotaScope {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Publisher { .. } => "publisher",
+            Self::Topic { .. } => "topic",
+            Self::Namespace(_) => "namespace",
+        }
+    }
+
+    pub fn namespace(&self) -> &str {
+        match self {
+            Self::Publisher { namespace, .. } | Self::Topic { namespace, .. } => namespace,
+            Self::Namespace(namespace) => namespace,
+        }
+    }
+}
+
+impl fmt::Display for QuotaScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Publisher { identifier, .. } => write!(f, "publisher '{}'", identifier),
+            Self::Topic {
+                destination_type,
+                name,
+                ..
+            } => write!(f, "{} '{}'", destination_type.as_str(), name),
+            Self::Namespace(namespace) => write!(f, "namespace '{}'", namespace),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Limit {
+    MessagesPerSecond,
+    BytesPerSecond,
+    Topics,
+    StoredBytes,
+}
+
+impl Limit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MessagesPerSecond => "messages-per-second",
+            Self::BytesPerSecond => "bytes-per-second",
+            Self::Topics => "topics",
+            Self::StoredBytes => "stored-bytes",
+        }
+    }
+}
+
+/// Refills `rate` tokens per second and holds at most one second worth of them.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(rate: u64) -> Self {
+        Self {
+            rate: rate as f64,
+            tokens: rate as f64,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    /// The time until `amount` tokens can be taken, `None` if they can be taken now. An amount
+    /// larger than the bucket only waits for a full bucket and leaves it in debt.
+    pub fn wait_time(&mut self, amount: u64) -> Option<Duration> {
+        self.refill();
+        let needed = (amount as f64).min(self.rate);
+        (self.tokens < needed).then(|| Duration::from_secs_f64((needed - self.tokens) / self.rate))
+    }
+
+    pub fn take(&mut self, amount: u64) {
+        self.refill();
+        self.tokens -= amount as f64;
+    }
+
+    /// A full bucket admits the same as a new one, so it can be dropped until it is used again.
+    pub fn is_full(&mut self) -> bool {
+        self.refill();
+        self.tokens >= self.rate
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.refilled_at = now;
+    }
+}
+
+// full buckets are looked for at most this often, instead of on every publish
+const BUCKET_SWEEP_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The token buckets of the rate limits and the bytes currently stored per topic and namespace.
+#[derive(Debug, Default)]
+pub struct QuotaUsage {
+    pub buckets: HashMap<(QuotaScope, Limit), TokenBucket>,
+    pub stored_bytes: HashMap<QuotaScope, u64>,
+    swept_at: Option<Instant>,
+}
+
+impl QuotaUsage {
+    /// Drops the buckets of publishers and topics that have not published for a while, which
+    /// would otherwise pile up for every identifier that ever published.
+    pub fn evict_full_buckets(&mut self) {
+        if self
+            .swept_at
+            .is_some_and(|swept_at| swept_at.elapsed() < BUCKET_SWEEP_INTERVAL)
+        {
+            return;
+        }
+        self.buckets.retain(|_, bucket| !bucket.is_full());
+        self.swept_at = Some(Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_waits(bucket: &mut TokenBucket, amount: u64, expected: f64) {
+        let wait = bucket
+            .wait_time(amount)
+            .expect("the bucket holds too few tokens")
+            .as_secs_f64();
+        // the bucket refills while the test runs
+        assert!(
+            wait <= expected && wait > expected - 0.05,
+            "waits {}s instead of {}s",
+            wait,
+            expected
+        );
+    }
+
+    #[test]
+    fn starts_full_and_waits_for_missing_tokens() {
+        let mut bucket = TokenBucket::new(10);
+        assert_eq!(bucket.wait_time(10), None);
+
+        bucket.take(8);
+        assert_eq!(bucket.wait_time(2), None);
+        assert_waits(&mut bucket, 5, 0.3);
+    }
+
+    #[test]
+    fn refills_with_its_rate_up_to_one_second_worth() {
+        let mut bucket = TokenBucket::new(10);
+        bucket.take(10);
+        bucket.refilled_at -= Duration::from_millis(500);
+        assert_eq!(bucket.wait_time(5), None);
+        assert_waits(&mut bucket, 6, 0.1);
+
+        bucket.refilled_at -= Duration::from_secs(60);
+        assert_eq!(bucket.wait_time(10), None);
+        bucket.take(10);
+        assert_waits(&mut bucket, 1, 0.1);
+    }
+
+    #[test]
+    fn amounts_larger_than_the_bucket_leave_it_in_debt() {
+        let mut bucket = TokenBucket::new(10);
+        // a message larger than the limit passes a full bucket instead of waiting forever
+        assert_eq!(bucket.wait_time(25), None);
+        bucket.take(25);
+        assert_waits(&mut bucket, 1, 1.6);
+        assert_waits(&mut bucket, 25, 2.5);
+    }
+
+    #[test]
+    fn full_buckets_are_evicted_once_per_sweep_interval() {
+        let scope = |identifier: &str| QuotaScope::Publisher {
+            namespace: "default".to_string(),
+            identifier: identifier.to_string(),
+        };
+        let mut usage = QuotaUsage::default();
+        let mut idle = TokenBucket::new(10);
+        idle.take(10);
+        idle.refilled_at -= Duration::from_secs(1);
+        let mut busy = TokenBucket::new(10);
+        busy.take(10);
+        usage
+            .buckets
+            .insert((scope("idle"), Limit::MessagesPerSecond), idle);
+        usage
+            .buckets
+            .insert((scope("busy"), Limit::MessagesPerSecond), busy);
+
+        usage.evict_full_buckets();
+        assert_eq!(
+            usage.buckets.keys().collect::<Vec<_>>(),
+            [&(scope("busy"), Limit::MessagesPerSecond)]
+        );
+
+        // the next sweep waits for the interval even though the bucket refills meanwhile
+        usage
+            .buckets
+            .values_mut()
+            .for_each(|bucket| bucket.refilled_at -= Duration::from_secs(1));
+        usage.evict_full_buckets();
+        assert_eq!(usage.buckets.len(), 1);
+
+        usage.swept_at = usage
+            .swept_at
+            .map(|swept_at| swept_at - BUCKET_SWEEP_INTERVAL);
+        usage.evict_full_buckets();
+        assert!(usage.buckets.is_empty());
+    }
+}
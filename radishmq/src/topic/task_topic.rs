@@ -1,48 +1,109 @@
-use crate::model::task_topic_model::TaskTopicModel;
+use crate::model::task_topic_model::{TaskStatus, TaskStatusModel, TaskTopicModel};
 use log::warn;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Debug;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+struct TrackedTask<T: Send + Clone + Debug> {
+    id: Uuid,
+    data: T,
+    status: TaskStatus,
+    progress: u8,
+    subscriber: Option<String>,
+    attempts: u32,
+    lease_expires_at: Option<Instant>,
+    finished_at: Option<Instant>,
+    result: Option<T>,
+    error: Option<String>,
+}
+
+impl<T: Send + Clone + Debug> TrackedTask<T> {
+    fn new(id: Uuid, data: T) -> Self {
+        Self {
+            id,
+            data,
+            status: TaskStatus::Queued,
+            progress: 0,
+            subscriber: None,
+            attempts: 0,
+            lease_expires_at: None,
+            finished_at: None,
+            result: None,
+            error: None,
+        }
+    }
+
+    fn is_leased_by(&self, identifier: &str) -> bool {
+        self.status == TaskStatus::InProgress && self.subscriber.as_deref() == Some(identifier)
+    }
+
+    fn requeue(&mut self) {
+        self.status = TaskStatus::Queued;
+        self.progress = 0;
+        self.subscriber = None;
+        self.lease_expires_at = None;
+    }
+
+    fn to_model(&self) -> TaskStatusModel<T> {
+        TaskStatusModel {
+            id: self.id,
+            status: self.status,
+            progress: self.progress,
+            subscriber: self.subscriber.clone(),
+            attempts: self.attempts,
+            result: self.result.clone(),
+            error: self.error.clone(),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct TaskTopic<T: Send + Clone + Debug> {
+    pub namespace: String,
     pub name: String,
-    data: Arc<Mutex<VecDeque<T>>>,
+    data: Arc<Mutex<VecDeque<Uuid>>>,
+    tasks: Arc<Mutex<HashMap<Uuid, TrackedTask<T>>>>,
     pub subscriber: Arc<Mutex<Vec<String>>>,
     pub publisher: Arc<Mutex<Vec<String>>>,
 }
 
 impl<T: Send + Clone + Debug> TaskTopic<T> {
-    pub fn new(name: String) -> Self {
+    pub fn new(namespace: String, name: String) -> Self {
         Self {
+            namespace,
             name,
             data: Arc::new(Mutex::new(VecDeque::new())),
+            tasks: Arc::new(Mutex::new(HashMap::new())),
             subscriber: Arc::new(Mutex::new(Vec::new())),
             publisher: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
-    pub fn publish(&mut self, data_to_add: T) {
-        if let Ok(mut data) = self.data.lock() {
-            data.push_back(data_to_add);
+    pub fn is_named(&self, namespace: &str, name: &str) -> bool {
+        self.namespace == namespace && self.name == name
+    }
+
+    pub fn publish(&mut self, data_to_add: T) -> Uuid {
+        let id = Uuid::new_v4();
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.insert(id, TrackedTask::new(id, data_to_add));
         } else {
             panic!("TaskTopic::publish() tried to lock a poisoned mutex");
         }
-    }
-
-    pub fn publish_multiple(&mut self, data_to_add: &mut Vec<T>) {
         if let Ok(mut data) = self.data.lock() {
-            for item in data_to_add.drain(..) {
-                data.push_back(item);
-            }
+            data.push_back(id);
         } else {
             panic!("TaskTopic::publish() tried to lock a poisoned mutex");
         }
+        id
     }
 
     pub fn add_publisher(&mut self, identifier: String) {
         if let Ok(mut publisher) = self.publisher.lock() {
-            if !publisher.iter().any(|x| *x == identifier) {
+            if !publisher.contains(&identifier) {
                 publisher.push(identifier);
             } else {
                 warn!("TaskTopic::add_publisher() tried to add a publisher that is already a publisher");
@@ -54,7 +115,7 @@ impl<T: Send + Clone + Debug> TaskTopic<T> {
 
     pub fn remove_publisher(&mut self, identifier: String) {
         if let Ok(mut publisher) = self.publisher.lock() {
-            if publisher.iter().any(|x| *x == identifier) {
+            if publisher.contains(&identifier) {
                 publisher.retain(|x| *x != identifier);
             } else {
                 warn!("TaskTopic::remove_publisher() tried to remove a publisher that is not a publisher");
@@ -66,7 +127,7 @@ impl<T: Send + Clone + Debug> TaskTopic<T> {
 
     pub fn is_publisher(&self, identifier: String) -> bool {
         if let Ok(publisher) = self.publisher.lock() {
-            publisher.iter().any(|x| *x == identifier)
+            publisher.contains(&identifier)
         } else {
             panic!("TaskTopic::is_publisher() tried to lock a poisoned mutex");
         }
@@ -74,7 +135,7 @@ impl<T: Send + Clone + Debug> TaskTopic<T> {
 
     pub fn add_subscriber(&mut self, identifier: String) {
         if let Ok(mut subscriber) = self.subscriber.lock() {
-            if !subscriber.iter().any(|x| *x == identifier) {
+            if !subscriber.contains(&identifier) {
                 subscriber.push(identifier);
             } else {
                 warn!("TaskTopic::subscribe() tried to subscribe a subscriber that is already subscribed");
@@ -86,7 +147,7 @@ impl<T: Send + Clone + Debug> TaskTopic<T> {
 
     pub fn remove_subscriber(&mut self, identifier: String) {
         if let Ok(mut subscriber) = self.subscriber.lock() {
-            if subscriber.iter().any(|x| *x == identifier) {
+            if subscriber.contains(&identifier) {
                 subscriber.retain(|x| *x != identifier);
             } else {
                 warn!("TaskTopic::unsubscribe() tried to unsubscribe a subscriber that is not subscribed");
@@ -97,6 +158,8 @@ impl<T: Send + Clone + Debug> TaskTopic<T> {
     }
 
     pub fn has_open_tasks(&self) -> bool {
+        self.requeue_expired_tasks();
+
         if let Ok(data) = self.data.lock() {
             !data.is_empty()
         } else {
@@ -104,25 +167,188 @@ impl<T: Send + Clone + Debug> TaskTopic<T> {
         }
     }
 
-    pub fn fetch_data(&mut self, identifier: String) -> Option<T> {
+    /// The tasks waiting for a subscriber.
+    pub fn depth(&self) -> usize {
+        self.requeue_expired_tasks();
+
+        if let Ok(data) = self.data.lock() {
+            data.len()
+        } else {
+            panic!("TaskTopic::depth() tried to lock a poisoned mutex");
+        }
+    }
+
+    pub fn fetch_data(&mut self, identifier: String, lease: Duration) -> Option<(Uuid, T)> {
         if let Ok(subscriber) = self.subscriber.lock() {
-            if !subscriber.iter().any(|x| *x == identifier) {
+            if !subscriber.contains(&identifier) {
                 panic!("TaskTopic::fetch_data() a subscriber tried to fetch data from a topic it is not subscribed to");
             }
         } else {
             panic!("TaskTopic::fetch_data() tried to lock a poisoned mutex");
         }
 
-        if let Ok(mut data) = self.data.lock() {
-            data.pop_front()
+        self.requeue_expired_tasks();
+
+        if let Ok(mut tasks) = self.tasks.lock() {
+            let id = if let Ok(mut data) = self.data.lock() {
+                data.pop_front()?
+            } else {
+                panic!("TaskTopic::fetch_data() tried to lock a poisoned mutex");
+            };
+
+            let task = tasks.get_mut(&id)?;
+            task.status = TaskStatus::InProgress;
+            task.subscriber = Some(identifier);
+            task.attempts += 1;
+            task.lease_expires_at = Some(Instant::now() + lease);
+
+            Some((id, task.data.clone()))
         } else {
             panic!("TaskTopic::fetch_data() tried to lock a poisoned mutex");
         }
     }
 
+    pub fn heartbeat(&mut self, id: Uuid, identifier: &str, lease: Duration) -> bool {
+        self.update_leased_task(id, identifier, |task| {
+            task.lease_expires_at = Some(Instant::now() + lease);
+        })
+        .is_some()
+    }
+
+    pub fn report_progress(
+        &mut self,
+        id: Uuid,
+        identifier: &str,
+        progress: u8,
+        lease: Duration,
+    ) -> bool {
+        self.update_leased_task(id, identifier, |task| {
+            task.progress = progress.min(100);
+            task.lease_expires_at = Some(Instant::now() + lease);
+        })
+        .is_some()
+    }
+
+    /// Finishes a leased task and returns its data, `None` if the subscriber holds no lease on it.
+    pub fn complete(&mut self, id: Uuid, identifier: &str, result: T) -> Option<T> {
+        self.update_leased_task(id, identifier, |task| {
+            task.status = TaskStatus::Succeeded;
+            task.progress = 100;
+            task.lease_expires_at = None;
+            task.finished_at = Some(Instant::now());
+            task.result = Some(result);
+            task.data.clone()
+        })
+    }
+
+    /// Finishes a leased task as failed and returns its data, like `complete`.
+    pub fn fail(&mut self, id: Uuid, identifier: &str, error: String) -> Option<T> {
+        self.update_leased_task(id, identifier, |task| {
+            task.status = TaskStatus::Failed;
+            task.lease_expires_at = None;
+            task.finished_at = Some(Instant::now());
+            task.error = Some(error);
+            task.data.clone()
+        })
+    }
+
+    /// Gives up the lease of a task, it goes back to the front of the queue.
+    pub fn release(&mut self, id: Uuid, identifier: &str) -> bool {
+        self.requeue_expired_tasks();
+
+        if let Ok(mut tasks) = self.tasks.lock() {
+            match tasks.get_mut(&id) {
+                Some(task) if task.is_leased_by(identifier) => {
+                    if let Ok(mut data) = self.data.lock() {
+                        task.requeue();
+                        data.push_front(task.id);
+                        true
+                    } else {
+                        panic!("TaskTopic::release() tried to lock a poisoned mutex");
+                    }
+                }
+                _ => {
+                    warn!("TaskTopic::release() tried to release a task that is not leased by the subscriber");
+                    false
+                }
+            }
+        } else {
+            panic!("TaskTopic::release() tried to lock a poisoned mutex");
+        }
+    }
+
+    pub fn get_task_status(&self, id: Uuid) -> Option<TaskStatusModel<T>> {
+        self.requeue_expired_tasks();
+
+        if let Ok(tasks) = self.tasks.lock() {
+            tasks.get(&id).map(|task| task.to_model())
+        } else {
+            panic!("TaskTopic::get_task_status() tried to lock a poisoned mutex");
+        }
+    }
+
+    /// Removes the tasks whose result is no longer retained.
+    pub fn remove_finished_tasks(&mut self, retention: Duration) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.retain(|_, task| {
+                task.finished_at
+                    .is_none_or(|finished_at| finished_at.elapsed() < retention)
+            });
+        } else {
+            panic!("TaskTopic::remove_finished_tasks() tried to lock a poisoned mutex");
+        }
+    }
+
+    fn update_leased_task<R>(
+        &mut self,
+        id: Uuid,
+        identifier: &str,
+        update: impl FnOnce(&mut TrackedTask<T>) -> R,
+    ) -> Option<R> {
+        self.requeue_expired_tasks();
+
+        if let Ok(mut tasks) = self.tasks.lock() {
+            match tasks.get_mut(&id) {
+                Some(task) if task.is_leased_by(identifier) => Some(update(task)),
+                _ => {
+                    warn!("TaskTopic::update_leased_task() tried to update a task that is not leased by the subscriber");
+                    None
+                }
+            }
+        } else {
+            panic!("TaskTopic::update_leased_task() tried to lock a poisoned mutex");
+        }
+    }
+
+    // tasks whose lease ran out go back to the front of the queue for the next subscriber
+    fn requeue_expired_tasks(&self) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            let now = Instant::now();
+            let mut expired: Vec<&mut TrackedTask<T>> = tasks
+                .values_mut()
+                .filter(|task| {
+                    task.status == TaskStatus::InProgress
+                        && task.lease_expires_at.is_some_and(|expires| expires <= now)
+                })
+                .collect();
+            expired.sort_by_key(|task| std::cmp::Reverse(task.lease_expires_at));
+
+            if let Ok(mut data) = self.data.lock() {
+                for task in expired {
+                    task.requeue();
+                    data.push_front(task.id);
+                }
+            } else {
+                panic!("TaskTopic::requeue_expired_tasks() tried to lock a poisoned mutex");
+            }
+        } else {
+            panic!("TaskTopic::requeue_expired_tasks() tried to lock a poisoned mutex");
+        }
+    }
+
     pub fn is_subscriber(&self, identifier: String) -> bool {
         if let Ok(subscriber) = self.subscriber.lock() {
-            subscriber.iter().any(|x| *x == identifier)
+            subscriber.contains(&identifier)
         } else {
             panic!("TaskTopic::is_subscriber() tried to lock a poisoned mutex");
         }
@@ -139,3 +365,123 @@ impl<T: Send + Clone + Debug> TaskTopic<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEASE: Duration = Duration::from_secs(60);
+
+    fn topic_with_tasks(tasks: &[&str]) -> (TaskTopic<String>, Vec<Uuid>) {
+        let mut topic = TaskTopic::new("default".to_string(), "jobs".to_string());
+        topic.add_subscriber("worker-1".to_string());
+        topic.add_subscriber("worker-2".to_string());
+        let ids = tasks
+            .iter()
+            .map(|task| topic.publish(task.to_string()))
+            .collect();
+        (topic, ids)
+    }
+
+    #[test]
+    fn leased_tasks_are_only_handed_out_once() {
+        let (mut topic, ids) = topic_with_tasks(&["first", "second"]);
+
+        assert_eq!(
+            topic.fetch_data("worker-1".to_string(), LEASE),
+            Some((ids[0], "first".to_string()))
+        );
+        assert_eq!(
+            topic.fetch_data("worker-2".to_string(), LEASE),
+            Some((ids[1], "second".to_string()))
+        );
+        assert_eq!(topic.fetch_data("worker-1".to_string(), LEASE), None);
+        assert_eq!(topic.depth(), 0);
+
+        let status = topic.get_task_status(ids[0]).unwrap();
+        assert_eq!(status.status, TaskStatus::InProgress);
+        assert_eq!(status.subscriber.as_deref(), Some("worker-1"));
+        assert_eq!(status.attempts, 1);
+    }
+
+    #[test]
+    fn expired_leases_are_requeued_at_the_front() {
+        let (mut topic, ids) = topic_with_tasks(&["first", "second"]);
+        topic.fetch_data("worker-1".to_string(), Duration::ZERO);
+
+        assert_eq!(topic.depth(), 2);
+        assert_eq!(
+            topic.get_task_status(ids[0]).unwrap().status,
+            TaskStatus::Queued
+        );
+        assert!(topic
+            .complete(ids[0], "worker-1", "done".to_string())
+            .is_none());
+
+        assert_eq!(
+            topic.fetch_data("worker-2".to_string(), LEASE),
+            Some((ids[0], "first".to_string()))
+        );
+        let status = topic.get_task_status(ids[0]).unwrap();
+        assert_eq!(status.subscriber.as_deref(), Some("worker-2"));
+        assert_eq!(status.attempts, 2);
+    }
+
+    #[test]
+    fn only_the_leaseholder_finishes_a_task() {
+        let (mut topic, ids) = topic_with_tasks(&["first"]);
+        topic.fetch_data("worker-1".to_string(), Duration::ZERO);
+
+        // a heartbeat after the lease ran out is too late
+        assert!(!topic.heartbeat(ids[0], "worker-1", LEASE));
+        topic.fetch_data("worker-1".to_string(), LEASE);
+
+        assert!(topic.heartbeat(ids[0], "worker-1", LEASE));
+        assert!(topic.report_progress(ids[0], "worker-1", 150, LEASE));
+        assert_eq!(topic.get_task_status(ids[0]).unwrap().progress, 100);
+        assert!(topic
+            .complete(ids[0], "worker-2", "stolen".to_string())
+            .is_none());
+        assert_eq!(
+            topic.complete(ids[0], "worker-1", "done".to_string()),
+            Some("first".to_string())
+        );
+        assert!(topic
+            .fail(ids[0], "worker-1", "again".to_string())
+            .is_none());
+
+        let status = topic.get_task_status(ids[0]).unwrap();
+        assert_eq!(status.status, TaskStatus::Succeeded);
+        assert_eq!(status.result.as_deref(), Some("done"));
+    }
+
+    #[test]
+    fn released_tasks_are_requeued_at_the_front() {
+        let (mut topic, ids) = topic_with_tasks(&["first", "second"]);
+        topic.fetch_data("worker-1".to_string(), LEASE);
+
+        assert!(!topic.release(ids[0], "worker-2"));
+        assert!(topic.release(ids[0], "worker-1"));
+        assert!(topic
+            .complete(ids[0], "worker-1", "done".to_string())
+            .is_none());
+        assert_eq!(
+            topic.fetch_data("worker-2".to_string(), LEASE),
+            Some((ids[0], "first".to_string()))
+        );
+        assert_eq!(topic.get_task_status(ids[0]).unwrap().attempts, 2);
+    }
+
+    #[test]
+    fn finished_tasks_are_removed_after_their_retention() {
+        let (mut topic, ids) = topic_with_tasks(&["first", "second"]);
+        topic.fetch_data("worker-1".to_string(), LEASE);
+        topic.fail(ids[0], "worker-1", "broken".to_string());
+
+        topic.remove_finished_tasks(LEASE);
+        assert!(topic.get_task_status(ids[0]).is_some());
+        topic.remove_finished_tasks(Duration::ZERO);
+        assert!(topic.get_task_status(ids[0]).is_none());
+        assert!(topic.get_task_status(ids[1]).is_some());
+    }
+}
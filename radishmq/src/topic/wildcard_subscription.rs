@@ -0,0 +1,56 @@
+use crate::model::wildcard_subscription_model::WildcardSubscriptionModel;
+use crate::topic::message_filter::MessageFilter;
+use crate::utils::topic_pattern::TopicPattern;
+
+/// A subscription of a subscriber to every message topic matching a pattern, keyed by both. It
+/// remembers the topics whose subscription it created, only those are removed with it.
+#[derive(Debug, Clone)]
+pub struct WildcardSubscription {
+    pub subscriber: String,
+    pub pattern: TopicPattern,
+    pub filter: Option<MessageFilter>,
+    pub topics: Vec<String>,
+}
+
+impl WildcardSubscription {
+    pub fn new(
+        subscriber: String,
+        pattern: TopicPattern,
+        filter: Option<MessageFilter>,
+        topics: Vec<String>,
+    ) -> Self {
+        Self {
+            subscriber,
+            pattern,
+            filter,
+            topics,
+        }
+    }
+
+    pub fn is_keyed(&self, subscriber: &str, pattern: &str) -> bool {
+        self.subscriber == subscriber && self.pattern.as_str() == pattern
+    }
+
+    pub fn matches(&self, topic_name: &str) -> bool {
+        self.pattern.matches(topic_name)
+    }
+
+    pub fn owns(&self, topic_name: &str) -> bool {
+        self.topics.iter().any(|topic| topic == topic_name)
+    }
+
+    pub fn add_topic(&mut self, topic_name: String) {
+        if !self.owns(&topic_name) {
+            self.topics.push(topic_name);
+        }
+    }
+
+    pub fn to_model(&self, topics: Vec<String>) -> WildcardSubscriptionModel {
+        WildcardSubscriptionModel {
+            subscriber: self.subscriber.clone(),
+            pattern: self.pattern.as_str().to_string(),
+            filter: self.filter.clone(),
+            topics,
+        }
+    }
+}
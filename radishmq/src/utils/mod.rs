@@ -1,2 +1,5 @@
+pub mod namespace;
+pub mod peer_address;
 pub mod queue;
+pub mod topic_pattern;
 pub mod types;
@@ -0,0 +1,31 @@
+use std::future::Future;
+
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+const MAX_NAMESPACE_LENGTH: usize = 64;
+
+tokio::task_local! {
+    static NAMESPACE: String;
+}
+
+/// Namespaces are part of the route, so they are limited to lowercase letters, digits, `-` and
+/// `_`.
+pub fn is_valid_namespace(namespace: &str) -> bool {
+    !namespace.is_empty()
+        && namespace.len() <= MAX_NAMESPACE_LENGTH
+        && namespace
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// Runs `f` inside `namespace`, the services see it through `current_namespace`.
+pub async fn scope<F: Future>(namespace: String, f: F) -> F::Output {
+    NAMESPACE.scope(namespace, f).await
+}
+
+/// The namespace of the running request, the default namespace outside of a namespaced route.
+pub fn current_namespace() -> String {
+    NAMESPACE
+        .try_with(|namespace| namespace.clone())
+        .unwrap_or(DEFAULT_NAMESPACE.to_string())
+}
@@ -0,0 +1,16 @@
+use std::future::Future;
+
+tokio::task_local! {
+    static PEER_ADDRESS: String;
+}
+
+/// Runs `f` for a client connected from `address`, the services see it through
+/// `current_peer_address`.
+pub async fn scope<F: Future>(address: String, f: F) -> F::Output {
+    PEER_ADDRESS.scope(address, f).await
+}
+
+/// The address of the client of the running request, `None` if the listener does not know it.
+pub fn current_peer_address() -> Option<String> {
+    PEER_ADDRESS.try_with(|address| address.clone()).ok()
+}
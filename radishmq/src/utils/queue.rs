@@ -29,6 +29,14 @@ impl<T: Send + Clone> Queue<T> {
         }
     }
 
+    pub fn len(&self) -> usize {
+        if let Ok(queue) = self.queue.lock() {
+            queue.len()
+        } else {
+            panic!("Queue::len() tried to lock a poisoned mutex")
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         if let Ok(queue) = self.queue.lock() {
             queue.is_empty()
@@ -0,0 +1,190 @@
+pub const TOPIC_NAME_SEPARATOR: char = '.';
+pub const SINGLE_SEGMENT_WILDCARD: &str = "*";
+pub const MULTI_SEGMENT_WILDCARD: &str = "#";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatternSegment {
+    Literal(String),
+    SingleWildcard,
+    MultiWildcard,
+}
+
+/// A dot-separated topic pattern like `orders.*.created` or `metrics.#`.
+///
+/// `*` matches exactly one segment, `#` matches zero or more segments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicPattern {
+    pattern: String,
+    segments: Vec<PatternSegment>,
+}
+
+impl TopicPattern {
+    pub fn parse(pattern: &str) -> Option<Self> {
+        if pattern.is_empty() {
+            return None;
+        }
+
+        let mut segments = Vec::new();
+        for segment in pattern.split(TOPIC_NAME_SEPARATOR) {
+            match segment {
+                SINGLE_SEGMENT_WILDCARD => segments.push(PatternSegment::SingleWildcard),
+                // `#.#` matches what a single `#` matches
+                MULTI_SEGMENT_WILDCARD
+                    if segments.last() == Some(&PatternSegment::MultiWildcard) => {}
+                MULTI_SEGMENT_WILDCARD => segments.push(PatternSegment::MultiWildcard),
+                _ if is_valid_segment(segment) => {
+                    segments.push(PatternSegment::Literal(segment.to_string()))
+                }
+                _ => return None,
+            }
+        }
+
+        Some(Self {
+            pattern: pattern.to_string(),
+            segments,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn matches(&self, topic_name: &str) -> bool {
+        let names: Vec<&str> = topic_name.split(TOPIC_NAME_SEPARATOR).collect();
+        matches_segments(&self.segments, &names)
+    }
+
+    /// Checks that every topic name matched by `other` is also matched by this pattern.
+    pub fn covers(&self, other: &TopicPattern) -> bool {
+        covers_segments(&self.segments, &other.segments)
+    }
+}
+
+fn matches_segments(segments: &[PatternSegment], names: &[&str]) -> bool {
+    matches_sequence(segments, names, |segment, name| match segment {
+        PatternSegment::Literal(literal) => literal == name,
+        _ => true,
+    })
+}
+
+fn covers_segments(segments: &[PatternSegment], others: &[PatternSegment]) -> bool {
+    matches_sequence(segments, others, |segment, other| match segment {
+        PatternSegment::Literal(literal) => {
+            matches!(other, PatternSegment::Literal(other) if literal == other)
+        }
+        // a `*` only covers what matches exactly one segment, never a `#`
+        _ => *other != PatternSegment::MultiWildcard,
+    })
+}
+
+// matches the pattern against the items prefix by prefix, `matched[j]` tells whether the
+// segments seen so far match the first `j` items, so the work is bounded by the product of the
+// lengths instead of growing exponentially with the number of `#`
+fn matches_sequence<T>(
+    segments: &[PatternSegment],
+    items: &[T],
+    matches_one: impl Fn(&PatternSegment, &T) -> bool,
+) -> bool {
+    let mut matched = vec![false; items.len() + 1];
+    matched[0] = true;
+
+    for segment in segments {
+        if *segment == PatternSegment::MultiWildcard {
+            // `#` extends every matched prefix by any number of items
+            for j in 1..matched.len() {
+                matched[j] |= matched[j - 1];
+            }
+        } else {
+            for j in (1..matched.len()).rev() {
+                matched[j] = matched[j - 1] && matches_one(segment, &items[j - 1]);
+            }
+            matched[0] = false;
+        }
+    }
+    matched[items.len()]
+}
+
+fn is_valid_segment(segment: &str) -> bool {
+    !segment.is_empty()
+        && !segment.contains(SINGLE_SEGMENT_WILDCARD)
+        && !segment.contains(MULTI_SEGMENT_WILDCARD)
+}
+
+/// Checks that a topic name is a dot-separated list of non-empty segments without wildcards.
+pub fn is_valid_topic_name(topic_name: &str) -> bool {
+    topic_name.split(TOPIC_NAME_SEPARATOR).all(is_valid_segment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(pattern: &str) -> TopicPattern {
+        TopicPattern::parse(pattern).unwrap()
+    }
+
+    #[test]
+    fn single_wildcard_matches_exactly_one_segment() {
+        let pattern = pattern("orders.*.created");
+        assert!(pattern.matches("orders.eu.created"));
+        assert!(!pattern.matches("orders.created"));
+        assert!(!pattern.matches("orders.eu.de.created"));
+        assert!(!pattern.matches("orders.eu.deleted"));
+    }
+
+    #[test]
+    fn multi_wildcard_matches_any_number_of_segments() {
+        let pattern = pattern("metrics.#");
+        assert!(pattern.matches("metrics"));
+        assert!(pattern.matches("metrics.cpu"));
+        assert!(pattern.matches("metrics.cpu.load.1m"));
+        assert!(!pattern.matches("logs.cpu"));
+
+        assert!(TopicPattern::parse("#.created")
+            .unwrap()
+            .matches("orders.eu.created"));
+    }
+
+    #[test]
+    fn rejects_empty_segments_and_partial_wildcards() {
+        for invalid in ["", "orders..created", "orders.", "orders.e*", "orders.#eu"] {
+            assert!(TopicPattern::parse(invalid).is_none(), "{}", invalid);
+        }
+        assert!(is_valid_topic_name("orders.eu.created"));
+        assert!(!is_valid_topic_name("orders.*"));
+        assert!(!is_valid_topic_name("orders..created"));
+    }
+
+    #[test]
+    fn consecutive_multi_wildcards_are_collapsed() {
+        assert_eq!(
+            pattern("orders.#.#.#").segments,
+            pattern("orders.#").segments
+        );
+        assert_eq!(pattern("orders.#.#.#").as_str(), "orders.#.#.#");
+        assert!(pattern("#.#").matches("orders"));
+        assert!(pattern("orders.#.#.created").matches("orders.created"));
+    }
+
+    #[test]
+    fn many_multi_wildcards_match_in_polynomial_time() {
+        let pattern = pattern(&format!("{}.a", ["#"; 40].join(".a.")));
+        let names = vec!["a"; 200].join(".");
+        assert!(!pattern.matches(&format!("{}.b", names)));
+        assert!(pattern.matches(&names));
+        assert!(pattern.covers(&pattern));
+        assert!(!pattern.covers(&TopicPattern::parse(&format!("{}.*", names)).unwrap()));
+    }
+
+    #[test]
+    fn covers_only_patterns_it_matches_completely() {
+        assert!(pattern("orders.#").covers(&pattern("orders.*.created")));
+        assert!(pattern("orders.*").covers(&pattern("orders.eu")));
+        assert!(pattern("#").covers(&pattern("orders.#")));
+        assert!(!pattern("orders.*").covers(&pattern("orders.#")));
+        assert!(!pattern("orders.eu").covers(&pattern("orders.*")));
+        assert!(pattern("orders.*.#").covers(&pattern("orders.eu.#")));
+        assert!(!pattern("orders.*.#").covers(&pattern("orders.#")));
+        assert!(pattern("#.created").covers(&pattern("#.*.created")));
+    }
+}
@@ -1,4 +1,8 @@
+use std::collections::HashMap;
+
 #[allow(dead_code)]
 pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
 
 pub type TopicType = String;
+
+pub type Headers = HashMap<String, String>;
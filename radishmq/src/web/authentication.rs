@@ -0,0 +1,98 @@
+use crate::auth::credential::hash_secret;
+use crate::auth::identity::{self, Identity};
+use crate::auth::jwt::JwtSettings;
+use crate::service::credential_service;
+use crate::utils::namespace::current_namespace;
+use crate::web::problem::Problem;
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::http::StatusCode;
+use actix_web::middleware::Next;
+use actix_web::{web, Error, ResponseError};
+use std::env;
+
+const API_KEY_HEADER: &str = "x-api-key";
+
+// the API description has to be readable to find out how to authenticate, probes carry no key
+const PUBLIC_PATHS: [&str; 4] = [
+    "/v1/openapi.json",
+    "/v1/docs",
+    "/health/live",
+    "/health/ready",
+];
+
+const METRICS_PATH: &str = "/metrics";
+
+/// Authenticates requests with an API key, passed as `Authorization: Bearer <key>` or
+/// `X-API-Key: <key>`, or with a JWT bearer token if the app has `JwtSettings`. The request runs
+/// on behalf of the identity of the key or token.
+pub async fn authentication(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    if PUBLIC_PATHS.contains(&req.path()) || is_metrics_scrape(&req) {
+        return next.call(req).await.map(|res| res.map_into_left_body());
+    }
+
+    match authenticate(&req) {
+        Ok(identity) if !identity.may_enter(&current_namespace()) => {
+            let response = Problem::new(
+                StatusCode::FORBIDDEN,
+                "namespace-mismatch",
+                format!(
+                    "the caller belongs to the namespace '{}'",
+                    identity.namespace
+                ),
+            )
+            .error_response();
+            Ok(req.into_response(response).map_into_right_body())
+        }
+        Ok(identity) => identity::scope(identity, next.call(req))
+            .await
+            .map(|res| res.map_into_left_body()),
+        Err(detail) => {
+            let mut response =
+                Problem::new(StatusCode::UNAUTHORIZED, "unauthenticated", detail).error_response();
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            Ok(req.into_response(response).map_into_right_body())
+        }
+    }
+}
+
+fn authenticate(req: &ServiceRequest) -> Result<Identity, String> {
+    if let Some(jwt_settings) = req.app_data::<web::Data<JwtSettings>>() {
+        let token = bearer_token(req).ok_or("a bearer token is required".to_string())?;
+        return jwt_settings
+            .verify(token)
+            .map_err(|e| format!("the bearer token is not valid: {}", e));
+    }
+
+    let api_key = req
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+    bearer_token(req)
+        .or(api_key)
+        .and_then(|secret| credential_service::authenticate(secret.trim()))
+        .ok_or("a valid API key is required".to_string())
+}
+
+// `METRICS_SCRAPE_KEY` only reads the metrics, so a scrape job does not need the admin key
+fn is_metrics_scrape(req: &ServiceRequest) -> bool {
+    req.path() == METRICS_PATH
+        && env::var("METRICS_SCRAPE_KEY").is_ok_and(|scrape_key| {
+            !scrape_key.is_empty()
+                && bearer_token(req)
+                    .is_some_and(|token| hash_secret(token.trim()) == hash_secret(&scrape_key))
+        })
+}
+
+fn bearer_token(req: &ServiceRequest) -> Option<&str> {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
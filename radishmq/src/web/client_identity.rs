@@ -0,0 +1,29 @@
+use crate::web::problem::Problem;
+use crate::web::tls::ClientCertificate;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::StatusCode;
+use actix_web::middleware::Next;
+use actix_web::Error;
+
+/// Requires the `{identifier}` of the path to be the common name of the client certificate.
+///
+/// Only TLS connections carry a `ClientCertificate` and only when `HTTP_TLS_CLIENT_IDENTITY` is
+/// enabled, requests over plain HTTP or the Unix socket are not checked.
+pub async fn client_identity(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    if let Some(certificate) = req.conn_data::<ClientCertificate>() {
+        let identifier = req.match_info().get("identifier");
+        if identifier.is_none() || certificate.common_name.as_deref() != identifier {
+            return Err(Problem::new(
+                StatusCode::FORBIDDEN,
+                "identity-mismatch",
+                "the identifier does not match the client certificate".to_string(),
+            )
+            .into());
+        }
+    }
+    next.call(req).await
+}
@@ -0,0 +1,147 @@
+use crate::model::cloud_event_model::{
+    validate_attribute_headers, CloudEvent, ATTRIBUTE_HEADER_PREFIX,
+};
+use crate::model::message_topic_model::{Message, PublishToMessageTopic};
+use crate::utils::types::{Headers, TopicType};
+use crate::web::negotiation::{content_type_essence, payload_problem, Body, MediaType};
+use crate::web::problem::Problem;
+use actix_web::dev::Payload;
+use actix_web::http::header::{self, Header};
+use actix_web::http::StatusCode;
+use actix_web::{web, FromRequest, HttpRequest, HttpResponse};
+use futures::future::{ready, LocalBoxFuture, Ready};
+
+pub const STRUCTURED: &str = "application/cloudevents+json";
+pub const BATCH: &str = "application/cloudevents-batch+json";
+
+/// The body of a publish, a plain `PublishToMessageTopic` or a CloudEvent in structured or binary
+/// mode whose attributes are kept in `ce-` headers.
+pub struct Publication(PublishToMessageTopic<TopicType>);
+
+impl Publication {
+    pub fn into_inner(self) -> PublishToMessageTopic<TopicType> {
+        self.0
+    }
+}
+
+impl FromRequest for Publication {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        if content_type_essence(req).as_deref() == Some(STRUCTURED) {
+            let bytes = web::Bytes::from_request(req, payload);
+
+            return Box::pin(async move {
+                let bytes = bytes.await.map_err(payload_problem)?;
+                let event: CloudEvent = serde_json::from_slice(&bytes).map_err(invalid_event)?;
+                let dts = PublishToMessageTopic::try_from(event).map_err(invalid_event)?;
+                Ok(Publication(dts))
+            });
+        }
+
+        if req.headers().contains_key("ce-specversion") {
+            let headers = binary_mode_headers(req);
+            let bytes = web::Bytes::from_request(req, payload);
+
+            return Box::pin(async move {
+                validate_attribute_headers(&headers).map_err(invalid_event)?;
+                let data = String::from_utf8(bytes.await.map_err(payload_problem)?.to_vec())
+                    .map_err(invalid_event)?;
+                Ok(Publication(PublishToMessageTopic::new(data, headers)))
+            });
+        }
+
+        let body = Body::<PublishToMessageTopic<TopicType>>::from_request(req, payload);
+        Box::pin(async move { Ok(Publication(body.await?.into_inner())) })
+    }
+}
+
+/// Whether the client asked for the new messages as a CloudEvents batch.
+pub struct CloudEventsBatch(pub bool);
+
+impl FromRequest for CloudEventsBatch {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let batch = header::Accept::parse(req)
+            .ok()
+            .and_then(|accept| {
+                accept
+                    .ranked()
+                    .iter()
+                    .find_map(|mime| match mime.essence_str() {
+                        BATCH => Some(true),
+                        "*/*" | "application/*" => Some(false),
+                        essence => MediaType::from_essence(essence).map(|_| false),
+                    })
+            })
+            .unwrap_or_default();
+
+        ready(Ok(CloudEventsBatch(batch)))
+    }
+}
+
+pub fn batch_response(source: &str, messages: Vec<Message<TopicType>>) -> HttpResponse {
+    let events: Vec<CloudEvent> = messages
+        .into_iter()
+        .map(|message| CloudEvent::from_message(message, source))
+        .collect();
+
+    HttpResponse::Ok()
+        .content_type(BATCH)
+        .insert_header((header::VARY, "Accept"))
+        .json(events)
+}
+
+/// Takes the attributes of a binary mode event from the `ce-` headers, the `Content-Type`
+/// becomes `datacontenttype`.
+fn binary_mode_headers(req: &HttpRequest) -> Headers {
+    let mut headers: Headers = req
+        .headers()
+        .iter()
+        .filter(|(name, _)| name.as_str().starts_with(ATTRIBUTE_HEADER_PREFIX))
+        .map(|(name, value)| {
+            let value = percent_decode(value.to_str().unwrap_or_default());
+            (name.as_str().to_string(), value)
+        })
+        .collect();
+
+    if let Some(content_type) = req.headers().get(header::CONTENT_TYPE) {
+        headers.insert(
+            format!("{}datacontenttype", ATTRIBUTE_HEADER_PREFIX),
+            content_type.to_str().unwrap_or_default().to_string(),
+        );
+    }
+    headers
+}
+
+/// The HTTP binding percent-encodes header values outside of printable ASCII.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn invalid_event(e: impl ToString) -> Problem {
+    Problem::new(StatusCode::BAD_REQUEST, "invalid-event", e.to_string())
+}
@@ -0,0 +1,96 @@
+use crate::model::acl_model::{CreateGrant, GrantModel};
+use crate::service::acl_service;
+use crate::service::error::ServiceError;
+use crate::web::negotiation::{Body, EncodedResponse, MediaType};
+use crate::web::problem::Problem;
+use actix_web::{delete, get, post, web, HttpResponse};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+pub fn acl_controller_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_all_grants)
+        .service(get_grant)
+        .service(create_grant)
+        .service(delete_grant);
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+struct GrantQuery {
+    /// Only list the grants of this principal
+    principal: Option<String>,
+}
+
+/// Lists the grants of the ACL.
+#[utoipa::path(
+    tag = "acl",
+    params(GrantQuery),
+    responses(
+        (status = 200, description = "The grants", body = Vec<GrantModel>),
+        (status = 403, description = "The caller may not manage the ACL", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[get("/acl")]
+async fn get_all_grants(
+    query: web::Query<GrantQuery>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let grants = acl_service::get_all_grants(query.into_inner().principal).await?;
+    Ok(HttpResponse::Ok().encoded(accept, grants))
+}
+
+/// Returns a grant of the ACL.
+#[utoipa::path(
+    tag = "acl",
+    params(
+        ("id" = Uuid, Path, description = "Id of the grant")
+    ),
+    responses(
+        (status = 200, description = "The grant", body = GrantModel),
+        (status = 403, description = "The caller may not manage the ACL", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The grant does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[get("/acl/{id}")]
+async fn get_grant(id: web::Path<Uuid>, accept: MediaType) -> Result<HttpResponse, ServiceError> {
+    let grant = acl_service::get_grant(id.into_inner()).await?;
+    Ok(HttpResponse::Ok().encoded(accept, grant))
+}
+
+/// Grants a role on the topics matching a pattern to a principal.
+#[utoipa::path(
+    tag = "acl",
+    request_body = CreateGrant,
+    responses(
+        (status = 201, description = "The grant was created", body = GrantModel),
+        (status = 400, description = "The principal, the pattern or the body is not valid", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "The caller may not manage the ACL", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "An equal grant already exists", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[post("/acl")]
+async fn create_grant(
+    body: Body<CreateGrant>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let grant = acl_service::create_grant(body.into_inner()).await?;
+    Ok(HttpResponse::Created().encoded(accept, grant))
+}
+
+/// Revokes a grant of the ACL.
+#[utoipa::path(
+    tag = "acl",
+    params(
+        ("id" = Uuid, Path, description = "Id of the grant")
+    ),
+    responses(
+        (status = 204, description = "The grant was revoked"),
+        (status = 403, description = "The caller may not manage the ACL", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The grant does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[delete("/acl/{id}")]
+async fn delete_grant(id: web::Path<Uuid>) -> Result<HttpResponse, ServiceError> {
+    acl_service::delete_grant(id.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,118 @@
+use crate::model::audit_model::{AuditAction, AuditEntryModel, AuditFilter};
+use crate::service::audit_service;
+use crate::service::error::ServiceError;
+use crate::web::negotiation::{EncodedResponse, MediaType};
+use crate::web::problem::Problem;
+use actix_web::{get, web, HttpResponse};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use serde::Deserialize;
+use sqlx::{Pool, Postgres};
+use utoipa::IntoParams;
+
+const NDJSON: &str = "application/x-ndjson";
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1000;
+
+pub fn audit_controller_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_audit_entries).service(export_audit_entries);
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+struct AuditQuery {
+    /// Only list the changes of this caller
+    actor: Option<String>,
+    /// Only list changes of this kind
+    action: Option<AuditAction>,
+    /// Only list changes of this kind of resource, e.g. `message_topic`
+    resource_type: Option<String>,
+    /// Only list changes of the resource with this name
+    resource: Option<String>,
+    /// Only list changes at or after this time
+    since: Option<DateTime<Utc>>,
+    /// Only list changes before this time
+    until: Option<DateTime<Utc>>,
+}
+
+impl AuditQuery {
+    fn into_filter(self) -> AuditFilter {
+        AuditFilter {
+            actor: self.actor,
+            action: self.action,
+            resource_type: self.resource_type,
+            resource: self.resource,
+            since: self.since,
+            until: self.until,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+struct LimitQuery {
+    /// The maximum number of entries, 100 by default and at most 1000
+    limit: Option<i64>,
+}
+
+/// Lists the changes of the namespace in the order they happened.
+#[utoipa::path(
+    tag = "audit",
+    params(AuditQuery, LimitQuery),
+    responses(
+        (status = 200, description = "The entries of the audit log", body = Vec<AuditEntryModel>),
+        (status = 400, description = "A filter is not valid", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "The caller is not the admin", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[get("/audit")]
+async fn get_audit_entries(
+    query: web::Query<AuditQuery>,
+    limit: web::Query<LimitQuery>,
+    db_connection_pool: web::Data<Pool<Postgres>>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let limit = limit.limit.unwrap_or(DEFAULT_LIMIT).clamp(0, MAX_LIMIT);
+    let entries = audit_service::get_entries(
+        db_connection_pool.get_ref(),
+        query.into_inner().into_filter(),
+        Some(limit),
+    )
+    .await?;
+    Ok(HttpResponse::Ok().encoded(accept, entries))
+}
+
+/// Exports every matching change of the namespace as newline delimited JSON, streamed page by page.
+#[utoipa::path(
+    tag = "audit",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "One entry of the audit log per line", body = AuditEntryModel, content_type = "application/x-ndjson"),
+        (status = 400, description = "A filter is not valid", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "The caller is not the admin", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[get("/audit/export")]
+async fn export_audit_entries(
+    query: web::Query<AuditQuery>,
+    db_connection_pool: web::Data<Pool<Postgres>>,
+) -> Result<HttpResponse, ServiceError> {
+    let pages = audit_service::export_entries(
+        db_connection_pool.get_ref().clone(),
+        query.into_inner().into_filter(),
+    )?;
+
+    let body = pages.map(|page| {
+        page.map(|entries| {
+            let mut lines = String::new();
+            for entry in entries {
+                if let Ok(line) = serde_json::to_string(&entry) {
+                    lines.push_str(&line);
+                    lines.push('\n');
+                }
+            }
+            Bytes::from(lines)
+        })
+    });
+    Ok(HttpResponse::Ok().content_type(NDJSON).streaming(body))
+}
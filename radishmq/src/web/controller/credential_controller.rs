@@ -0,0 +1,86 @@
+use crate::model::credential_model::{CreateCredential, CreatedCredential, CredentialModel};
+use crate::service::credential_service;
+use crate::service::error::ServiceError;
+use crate::web::negotiation::{Body, EncodedResponse, MediaType};
+use crate::web::problem::Problem;
+use actix_web::{delete, get, post, web, HttpResponse};
+
+pub fn credential_controller_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_all_credentials)
+        .service(get_credential)
+        .service(create_credential)
+        .service(delete_credential);
+}
+
+/// Lists all credentials without their secrets.
+#[utoipa::path(
+    tag = "credentials",
+    responses(
+        (status = 200, description = "All credentials", body = Vec<CredentialModel>),
+        (status = 403, description = "The caller is not the admin", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[get("/credentials")]
+async fn get_all_credentials(accept: MediaType) -> Result<HttpResponse, ServiceError> {
+    let credentials = credential_service::get_all_credentials().await?;
+    Ok(HttpResponse::Ok().encoded(accept, credentials))
+}
+
+/// Returns a credential without its secret.
+#[utoipa::path(
+    tag = "credentials",
+    params(
+        ("name" = String, Path, description = "Name of the credential")
+    ),
+    responses(
+        (status = 200, description = "The credential", body = CredentialModel),
+        (status = 403, description = "The caller is not the admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The credential does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[get("/credentials/{name}")]
+async fn get_credential(
+    name: web::Path<String>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let credential = credential_service::get_credential(name.into_inner()).await?;
+    Ok(HttpResponse::Ok().encoded(accept, credential))
+}
+
+/// Creates an API key bound to principals, its secret is only returned once.
+#[utoipa::path(
+    tag = "credentials",
+    request_body = CreateCredential,
+    responses(
+        (status = 201, description = "The credential was created", body = CreatedCredential),
+        (status = 400, description = "A name or the body is not valid", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "The caller is not the admin", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "A credential with the name already exists", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[post("/credentials")]
+async fn create_credential(
+    body: Body<CreateCredential>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let credential = credential_service::create_credential(body.into_inner()).await?;
+    Ok(HttpResponse::Created().encoded(accept, credential))
+}
+
+/// Deletes a credential, its secret is rejected from then on.
+#[utoipa::path(
+    tag = "credentials",
+    params(
+        ("name" = String, Path, description = "Name of the credential")
+    ),
+    responses(
+        (status = 204, description = "The credential was deleted"),
+        (status = 403, description = "The caller is not the admin", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The credential does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[delete("/credentials/{name}")]
+async fn delete_credential(name: web::Path<String>) -> Result<HttpResponse, ServiceError> {
+    credential_service::delete_credential(name.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
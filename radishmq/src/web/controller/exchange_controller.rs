@@ -0,0 +1,223 @@
+use crate::model::exchange_model::{
+    AddBindingToExchange, AddPublisherToExchange, BindingModel, CreateExchange, ExchangeModel,
+    PublishToExchange, RemovePublisherFromExchange, RoutedMessageExchange,
+};
+use crate::service::error::ServiceError;
+use crate::service::exchange_service;
+use crate::utils::types::TopicType;
+use crate::web::client_identity::client_identity;
+use crate::web::negotiation::{Body, EncodedResponse, MediaType};
+use crate::web::problem::Problem;
+use actix_web::middleware::from_fn;
+use actix_web::{delete, get, post, web, HttpResponse};
+use uuid::Uuid;
+
+pub fn exchange_controller_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_all_exchanges)
+        .service(get_exchange)
+        .service(create_exchange)
+        .service(delete_exchange)
+        .service(add_binding_to_exchange)
+        .service(remove_binding_from_exchange)
+        .service(add_publisher_to_exchange)
+        .service(remove_publisher_from_exchange)
+        .service(publish_to_exchange);
+}
+
+/// Lists all exchanges.
+#[utoipa::path(
+    tag = "exchanges",
+    responses(
+        (status = 200, description = "All exchanges", body = Vec<ExchangeModel>)
+    )
+)]
+#[get("/exchanges")]
+async fn get_all_exchanges(accept: MediaType) -> Result<HttpResponse, ServiceError> {
+    let exchanges = exchange_service::get_all_exchanges().await?;
+    Ok(HttpResponse::Ok().encoded(accept, exchanges))
+}
+
+/// Returns an exchange with its bindings.
+#[utoipa::path(
+    tag = "exchanges",
+    params(
+        ("exchange_name" = String, Path, description = "Name of the exchange")
+    ),
+    responses(
+        (status = 200, description = "The exchange", body = ExchangeModel),
+        (status = 404, description = "The exchange does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[get("/exchanges/{exchange_name}")]
+async fn get_exchange(
+    exchange_name: web::Path<String>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let exchange = exchange_service::get_exchange(exchange_name.into_inner()).await?;
+    Ok(HttpResponse::Ok().encoded(accept, exchange))
+}
+
+/// Creates an exchange.
+#[utoipa::path(
+    tag = "exchanges",
+    request_body = CreateExchange,
+    responses(
+        (status = 201, description = "The exchange was created", body = ExchangeModel),
+        (status = 400, description = "The exchange name or the body is not valid", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "An exchange with the name already exists", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[post("/exchanges")]
+async fn create_exchange(
+    body: Body<CreateExchange>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let exchange = exchange_service::create_exchange(body.into_inner()).await?;
+    Ok(HttpResponse::Created().encoded(accept, exchange))
+}
+
+/// Deletes an exchange with its bindings.
+#[utoipa::path(
+    tag = "exchanges",
+    params(
+        ("exchange_name" = String, Path, description = "Name of the exchange")
+    ),
+    responses(
+        (status = 204, description = "The exchange was deleted"),
+        (status = 404, description = "The exchange does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[delete("/exchanges/{exchange_name}")]
+async fn delete_exchange(exchange_name: web::Path<String>) -> Result<HttpResponse, ServiceError> {
+    exchange_service::delete_exchange(exchange_name.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Binds a topic to an exchange.
+#[utoipa::path(
+    tag = "exchanges",
+    params(
+        ("exchange_name" = String, Path, description = "Name of the exchange")
+    ),
+    request_body = AddBindingToExchange,
+    responses(
+        (status = 201, description = "The binding was created", body = BindingModel),
+        (status = 400, description = "The routing key or the arguments do not fit the exchange type", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "The caller may not publish to the destination", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The exchange or the destination does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[post("/exchanges/{exchange_name}/bindings")]
+async fn add_binding_to_exchange(
+    exchange_name: web::Path<String>,
+    body: Body<AddBindingToExchange>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let binding =
+        exchange_service::add_binding_to_exchange(exchange_name.into_inner(), body.into_inner())
+            .await?;
+    Ok(HttpResponse::Created().encoded(accept, binding))
+}
+
+/// Removes a binding of an exchange.
+#[utoipa::path(
+    tag = "exchanges",
+    params(
+        ("exchange_name" = String, Path, description = "Name of the exchange"),
+        ("binding_id" = Uuid, Path, description = "Id of the binding")
+    ),
+    responses(
+        (status = 204, description = "The binding was removed"),
+        (status = 404, description = "The exchange or the binding does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[delete("/exchanges/{exchange_name}/bindings/{binding_id}")]
+async fn remove_binding_from_exchange(
+    path: web::Path<(String, Uuid)>,
+) -> Result<HttpResponse, ServiceError> {
+    let (exchange_name, binding_id) = path.into_inner();
+
+    exchange_service::remove_binding_from_exchange(exchange_name, binding_id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Registers a publisher of an exchange.
+#[utoipa::path(
+    tag = "exchanges",
+    params(
+        ("exchange_name" = String, Path, description = "Name of the exchange")
+    ),
+    request_body = AddPublisherToExchange,
+    responses(
+        (status = 204, description = "The publisher was registered"),
+        (status = 404, description = "The exchange does not exist", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "The identifier is already a publisher of the exchange", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[post("/exchanges/{exchange_name}/publisher")]
+async fn add_publisher_to_exchange(
+    exchange_name: web::Path<String>,
+    body: Body<AddPublisherToExchange>,
+) -> Result<HttpResponse, ServiceError> {
+    exchange_service::add_publisher_to_exchange(exchange_name.into_inner(), body.into_inner())
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Removes a publisher of an exchange.
+#[utoipa::path(
+    tag = "exchanges",
+    params(
+        ("exchange_name" = String, Path, description = "Name of the exchange"),
+        ("identifier" = String, Path, description = "Identifier of the publisher")
+    ),
+    responses(
+        (status = 204, description = "The publisher was removed"),
+        (status = 403, description = "The identifier is not a publisher of the exchange", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The exchange does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[delete("/exchanges/{exchange_name}/publisher/{identifier}")]
+async fn remove_publisher_from_exchange(
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, ServiceError> {
+    let (exchange_name, identifier) = path.into_inner();
+
+    exchange_service::remove_publisher_from_exchange(
+        exchange_name,
+        RemovePublisherFromExchange::new(identifier),
+    )
+    .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Publishes a message to the topics the exchange routes it to.
+#[utoipa::path(
+    tag = "exchanges",
+    params(
+        ("exchange_name" = String, Path, description = "Name of the exchange"),
+        ("identifier" = String, Path, description = "Identifier of the publisher")
+    ),
+    request_body = PublishToExchange<String>,
+    responses(
+        (status = 200, description = "The destinations the message was routed to", body = RoutedMessageExchange),
+        (status = 400, description = "The body is not valid", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "The identifier is not a publisher of the exchange or does not match the client certificate", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The exchange does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[post(
+    "/exchanges/{exchange_name}/publisher/{identifier}/publish",
+    wrap = "from_fn(client_identity)"
+)]
+async fn publish_to_exchange(
+    path: web::Path<(String, String)>,
+    body: Body<PublishToExchange<TopicType>>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let (exchange_name, identifier) = path.into_inner();
+
+    let routed =
+        exchange_service::publish_to_exchange(exchange_name, identifier, body.into_inner()).await?;
+    Ok(HttpResponse::Ok().encoded(accept, routed))
+}
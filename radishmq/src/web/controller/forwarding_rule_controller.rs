@@ -0,0 +1,83 @@
+use crate::model::forwarding_rule_model::{CreateForwardingRule, ForwardingRuleModel};
+use crate::service::error::ServiceError;
+use crate::service::forwarding_rule_service;
+use crate::web::negotiation::{Body, EncodedResponse, MediaType};
+use crate::web::problem::Problem;
+use actix_web::{delete, get, post, web, HttpResponse};
+
+pub fn forwarding_rule_controller_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_all_forwarding_rules)
+        .service(get_forwarding_rule)
+        .service(create_forwarding_rule)
+        .service(delete_forwarding_rule);
+}
+
+/// Lists all forwarding rules of the namespace.
+#[utoipa::path(
+    tag = "forwarding_rules",
+    responses(
+        (status = 200, description = "All forwarding rules", body = Vec<ForwardingRuleModel>)
+    )
+)]
+#[get("/forwarding_rules")]
+async fn get_all_forwarding_rules(accept: MediaType) -> Result<HttpResponse, ServiceError> {
+    let rules = forwarding_rule_service::get_all_forwarding_rules().await?;
+    Ok(HttpResponse::Ok().encoded(accept, rules))
+}
+
+/// Returns a forwarding rule.
+#[utoipa::path(
+    tag = "forwarding_rules",
+    params(
+        ("name" = String, Path, description = "Name of the forwarding rule")
+    ),
+    responses(
+        (status = 200, description = "The forwarding rule", body = ForwardingRuleModel),
+        (status = 404, description = "The forwarding rule does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[get("/forwarding_rules/{name}")]
+async fn get_forwarding_rule(
+    name: web::Path<String>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let rule = forwarding_rule_service::get_forwarding_rule(name.into_inner()).await?;
+    Ok(HttpResponse::Ok().encoded(accept, rule))
+}
+
+/// Creates a forwarding rule republishing the messages of the source topics to the destination.
+#[utoipa::path(
+    tag = "forwarding_rules",
+    request_body = CreateForwardingRule,
+    responses(
+        (status = 201, description = "The forwarding rule was created", body = ForwardingRuleModel),
+        (status = 400, description = "The name, the source pattern or the destination is not valid", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "The caller may not subscribe to the source or publish to the destination", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "A forwarding rule with the name already exists", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[post("/forwarding_rules")]
+async fn create_forwarding_rule(
+    body: Body<CreateForwardingRule>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let rule = forwarding_rule_service::create_forwarding_rule(body.into_inner()).await?;
+    Ok(HttpResponse::Created().encoded(accept, rule))
+}
+
+/// Deletes a forwarding rule.
+#[utoipa::path(
+    tag = "forwarding_rules",
+    params(
+        ("name" = String, Path, description = "Name of the forwarding rule")
+    ),
+    responses(
+        (status = 204, description = "The forwarding rule was deleted"),
+        (status = 404, description = "The forwarding rule does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[delete("/forwarding_rules/{name}")]
+async fn delete_forwarding_rule(name: web::Path<String>) -> Result<HttpResponse, ServiceError> {
+    forwarding_rule_service::delete_forwarding_rule(name.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
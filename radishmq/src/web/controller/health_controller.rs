@@ -0,0 +1,25 @@
+use crate::model::health_model::{HealthModel, HealthStatus};
+use crate::service::health_service;
+use actix_web::{get, web, HttpResponse};
+use sqlx::{Pool, Postgres};
+
+pub fn health_controller_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_liveness).service(get_readiness);
+}
+
+fn health_response(health: HealthModel) -> HttpResponse {
+    match health.status {
+        HealthStatus::Up => HttpResponse::Ok().json(health),
+        HealthStatus::Down => HttpResponse::ServiceUnavailable().json(health),
+    }
+}
+
+#[get("/health/live")]
+async fn get_liveness() -> HttpResponse {
+    health_response(health_service::liveness())
+}
+
+#[get("/health/ready")]
+async fn get_readiness(db_connection_pool: web::Data<Pool<Postgres>>) -> HttpResponse {
+    health_response(health_service::readiness(db_connection_pool.get_ref()).await)
+}
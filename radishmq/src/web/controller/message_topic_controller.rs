@@ -1,16 +1,25 @@
+use crate::model::cloud_event_model::CloudEvent;
 use crate::model::message_topic_model::{
-    AddPublisherToMessageTopic, AddSubscriberToMessageTopic, CreateMessageTopic,
-    PublishToMessageTopic, RemovePublisherFromMessageTopic, RemoveSubscriberFromMessageTopic,
+    AddPublisherToMessageTopic, AddSubscriberToMessageTopic, CreateMessageTopic, DataMessageTopic,
+    DataQuery, MessageTopicModel, NewDataMessageTopic, PublishToMessageTopic,
+    RemovePublisherFromMessageTopic, RemoveSubscriberFromMessageTopic,
 };
+use crate::service::error::ServiceError;
 use crate::service::message_topic_service;
-use crate::utils::types::TopicType;
-use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use crate::web::client_identity::client_identity;
+use crate::web::cloud_event::{batch_response, CloudEventsBatch, Publication};
+use crate::web::negotiation::{Body, EncodedResponse, MediaType};
+use crate::web::problem::Problem;
+use actix_web::middleware::from_fn;
+use actix_web::{delete, get, post, web, HttpResponse};
 
 pub fn message_topic_controller_config(cfg: &mut web::ServiceConfig) {
     cfg.service(get_all_message_topics)
         .service(get_message_topics)
         .service(create_message_topics)
         .service(delete_message_topics)
+        .service(add_publisher_to_message_topic)
+        .service(remove_publisher_from_message_topic)
         .service(publish_to_message_topics)
         .service(add_subscriber_to_message_topic)
         .service(remove_subscriber_from_message_topic)
@@ -18,144 +27,296 @@ pub fn message_topic_controller_config(cfg: &mut web::ServiceConfig) {
         .service(get_new_data_for_subscriber);
 }
 
+/// Lists all message topics.
+#[utoipa::path(
+    tag = "message_topics",
+    responses(
+        (status = 200, description = "All message topics", body = Vec<MessageTopicModel>)
+    )
+)]
 #[get("/message_topics")]
-async fn get_all_message_topics() -> impl Responder {
-    let topics = message_topic_service::get_all_message_topics().await;
-    HttpResponse::Ok().json(topics)
+async fn get_all_message_topics(accept: MediaType) -> Result<HttpResponse, ServiceError> {
+    let topics = message_topic_service::get_all_message_topics().await?;
+    Ok(HttpResponse::Ok().encoded(accept, topics))
 }
 
+/// Returns a message topic.
+#[utoipa::path(
+    tag = "message_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic")
+    ),
+    responses(
+        (status = 200, description = "The message topic", body = MessageTopicModel),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
 #[get("/message_topics/{topic_name}")]
-async fn get_message_topics(topic_name: web::Path<String>) -> impl Responder {
-    let topic = message_topic_service::get_message_topic(topic_name.into_inner()).await;
-
-    match topic {
-        Some(topic) => HttpResponse::Ok().json(topic),
-        None => HttpResponse::NotFound().body(""),
-    }
+async fn get_message_topics(
+    topic_name: web::Path<String>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let topic = message_topic_service::get_message_topic(topic_name.into_inner()).await?;
+    Ok(HttpResponse::Ok().encoded(accept, topic))
 }
 
+/// Creates a message topic.
+#[utoipa::path(
+    tag = "message_topics",
+    request_body = CreateMessageTopic,
+    responses(
+        (status = 201, description = "The message topic was created", body = MessageTopicModel),
+        (status = 400, description = "The topic name or the body is not valid", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "A topic with the name already exists", body = Problem, content_type = "application/problem+json")
+    )
+)]
 #[post("/message_topics")]
-async fn create_message_topics(body: web::Json<CreateMessageTopic>) -> impl Responder {
-    let topic = message_topic_service::create_message_topic(body.into_inner()).await;
-
-    match topic {
-        Some(topic) => HttpResponse::Created().json(topic),
-        None => HttpResponse::Conflict().body(""),
-    }
+async fn create_message_topics(
+    body: Body<CreateMessageTopic>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let topic = message_topic_service::create_message_topic(body.into_inner()).await?;
+    Ok(HttpResponse::Created().encoded(accept, topic))
 }
 
+/// Deletes a message topic.
+#[utoipa::path(
+    tag = "message_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic")
+    ),
+    responses(
+        (status = 204, description = "The message topic was deleted"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
 #[delete("/message_topics/{topic_name}")]
-async fn delete_message_topics(topic_name: web::Path<String>) -> impl Responder {
-    let topic = message_topic_service::delete_message_topic(topic_name.into_inner()).await;
-
-    match topic {
-        Some(_) => HttpResponse::NoContent().body(""),
-        None => HttpResponse::NotFound().body(""),
-    }
+async fn delete_message_topics(
+    topic_name: web::Path<String>,
+) -> Result<HttpResponse, ServiceError> {
+    message_topic_service::delete_message_topic(topic_name.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
 }
 
+/// Registers a publisher of a message topic.
+#[utoipa::path(
+    tag = "message_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic")
+    ),
+    request_body = AddPublisherToMessageTopic,
+    responses(
+        (status = 204, description = "The publisher was registered"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "The identifier is already a publisher of the topic", body = Problem, content_type = "application/problem+json")
+    )
+)]
 #[post("/message_topics/{topic_name}/publisher")]
 async fn add_publisher_to_message_topic(
     topic_name: web::Path<String>,
-    body: web::Json<AddPublisherToMessageTopic>,
-) -> impl Responder {
+    body: Body<AddPublisherToMessageTopic>,
+) -> Result<HttpResponse, ServiceError> {
     message_topic_service::add_publisher_to_message_topic(
         topic_name.into_inner(),
         body.into_inner(),
     )
-    .await;
+    .await?;
 
-    HttpResponse::NoContent().body("")
+    Ok(HttpResponse::NoContent().finish())
 }
 
+/// Removes a publisher of a message topic.
+#[utoipa::path(
+    tag = "message_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("identifier" = String, Path, description = "Identifier of the publisher")
+    ),
+    responses(
+        (status = 204, description = "The publisher was removed"),
+        (status = 403, description = "The identifier is not a publisher of the topic", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
 #[delete("/message_topics/{topic_name}/publisher/{identifier}")]
 async fn remove_publisher_from_message_topic(
-    topic_name: web::Path<String>,
-    identifier: web::Path<String>,
-) -> impl Responder {
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, identifier) = path.into_inner();
+
     message_topic_service::remove_publisher_from_message_topic(
-        topic_name.into_inner(),
-        RemovePublisherFromMessageTopic::new(identifier.into_inner()),
+        topic_name,
+        RemovePublisherFromMessageTopic::new(identifier),
     )
-    .await;
+    .await?;
 
-    HttpResponse::NoContent().body("")
+    Ok(HttpResponse::NoContent().finish())
 }
 
-#[post("/message_topics/{topic_name}/publisher/{identifier}/publish")]
-async fn publish_to_message_topics(
-    topic_name: web::Path<String>,
-    identifier: web::Path<String>,
-    body: web::Json<PublishToMessageTopic<TopicType>>,
-) -> impl Responder {
-    message_topic_service::publish_to_message_topic(
-        topic_name.into_inner(),
-        identifier.into_inner(),
-        body.into_inner(),
+/// Publishes a message to a message topic.
+#[utoipa::path(
+    tag = "message_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("identifier" = String, Path, description = "Identifier of the publisher")
+    ),
+    request_body(
+        description = "The message, or a CloudEvent in structured mode or in binary mode with `ce-` headers",
+        content(
+            (PublishToMessageTopic<String> = "application/json"),
+            (CloudEvent = "application/cloudevents+json")
+        )
+    ),
+    responses(
+        (status = 204, description = "The message was published"),
+        (status = 400, description = "The body or the CloudEvent is not valid", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "The identifier is not a publisher of the topic or does not match the client certificate", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json")
     )
-    .await;
+)]
+#[post(
+    "/message_topics/{topic_name}/publisher/{identifier}/publish",
+    wrap = "from_fn(client_identity)"
+)]
+async fn publish_to_message_topics(
+    path: web::Path<(String, String)>,
+    body: Publication,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, identifier) = path.into_inner();
+
+    message_topic_service::publish_to_message_topic(topic_name, identifier, body.into_inner())
+        .await?;
 
-    HttpResponse::NoContent().body("")
+    Ok(HttpResponse::NoContent().finish())
 }
 
+/// Registers a subscriber of a message topic, optionally with a message filter.
+#[utoipa::path(
+    tag = "message_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic")
+    ),
+    request_body = AddSubscriberToMessageTopic,
+    responses(
+        (status = 204, description = "The subscriber was registered"),
+        (status = 400, description = "The message filter or the body is not valid", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "The identifier is already a subscriber of the topic", body = Problem, content_type = "application/problem+json")
+    )
+)]
 #[post("/message_topics/{topic_name}/subscribers")]
 async fn add_subscriber_to_message_topic(
     topic_name: web::Path<String>,
-    body: web::Json<AddSubscriberToMessageTopic>,
-) -> impl Responder {
+    body: Body<AddSubscriberToMessageTopic>,
+) -> Result<HttpResponse, ServiceError> {
     message_topic_service::add_subscriber_to_message_topic(
         topic_name.into_inner(),
         body.into_inner(),
     )
-    .await;
+    .await?;
 
-    HttpResponse::NoContent().body("")
+    Ok(HttpResponse::NoContent().finish())
 }
 
+/// Removes a subscriber of a message topic.
+#[utoipa::path(
+    tag = "message_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("identifier" = String, Path, description = "Identifier of the subscriber")
+    ),
+    responses(
+        (status = 204, description = "The subscriber was removed"),
+        (status = 403, description = "The identifier is not a subscriber of the topic", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
 #[delete("/message_topics/{topic_name}/subscribers/{identifier}")]
 async fn remove_subscriber_from_message_topic(
-    topic_name: web::Path<String>,
-    identifier: web::Path<String>,
-) -> impl Responder {
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, identifier) = path.into_inner();
+
     message_topic_service::remove_subscriber_from_message_topic(
-        topic_name.into_inner(),
-        RemoveSubscriberFromMessageTopic::new(identifier.into_inner()),
+        topic_name,
+        RemoveSubscriberFromMessageTopic::new(identifier),
     )
-    .await;
+    .await?;
 
-    HttpResponse::NoContent().body("")
+    Ok(HttpResponse::NoContent().finish())
 }
 
-#[get("/message_topics/{topic_name}/subscribers/{identifier}/is_new_data")]
-async fn is_there_new_data_for_subscriber(
-    topic_name: web::Path<String>,
-    identifier: web::Path<String>,
-) -> impl Responder {
-    let data = message_topic_service::is_there_new_data_for_subscriber(
-        topic_name.into_inner(),
-        identifier.into_inner(),
+/// Tells whether there are messages the subscriber has not fetched yet.
+#[utoipa::path(
+    tag = "message_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("identifier" = String, Path, description = "Identifier of the subscriber")
+    ),
+    responses(
+        (status = 200, description = "Whether there is new data", body = NewDataMessageTopic),
+        (status = 403, description = "The identifier is not a subscriber of the topic or does not match the client certificate", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json")
     )
-    .await;
+)]
+#[get(
+    "/message_topics/{topic_name}/subscribers/{identifier}/is_new_data",
+    wrap = "from_fn(client_identity)"
+)]
+async fn is_there_new_data_for_subscriber(
+    path: web::Path<(String, String)>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, identifier) = path.into_inner();
 
-    match data {
-        Some(data) => HttpResponse::Ok().json(data),
-        None => HttpResponse::NotFound().body(""),
-    }
+    let data =
+        message_topic_service::is_there_new_data_for_subscriber(topic_name, identifier).await?;
+
+    Ok(HttpResponse::Ok().encoded(accept, data))
 }
 
-#[get("/message_topics/{topic_name}/subscribers/{identifier}/get_data")]
-async fn get_new_data_for_subscriber(
-    topic_name: web::Path<String>,
-    identifier: web::Path<String>,
-) -> impl Responder {
-    let data = message_topic_service::get_new_data_for_subscriber(
-        topic_name.into_inner(),
-        identifier.into_inner(),
+/// Fetches the messages the subscriber has not fetched yet.
+#[utoipa::path(
+    tag = "message_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("identifier" = String, Path, description = "Identifier of the subscriber"),
+        DataQuery
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The new messages, with their headers if asked for, as CloudEvents if a CloudEvents batch is accepted",
+            content(
+                (DataMessageTopic<String> = "application/json"),
+                (Vec<CloudEvent> = "application/cloudevents-batch+json")
+            )
+        ),
+        (status = 403, description = "The identifier is not a subscriber of the topic or does not match the client certificate", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json")
     )
-    .await;
+)]
+#[get(
+    "/message_topics/{topic_name}/subscribers/{identifier}/get_data",
+    wrap = "from_fn(client_identity)"
+)]
+async fn get_new_data_for_subscriber(
+    path: web::Path<(String, String)>,
+    query: web::Query<DataQuery>,
+    accept: MediaType,
+    batch: CloudEventsBatch,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, identifier) = path.into_inner();
 
-    match data {
-        Some(data) => HttpResponse::Ok().json(data),
-        None => HttpResponse::NotFound().body(""),
+    let data =
+        message_topic_service::get_new_data_for_subscriber(topic_name.clone(), identifier).await?;
+
+    if batch.0 {
+        let source = format!("/message_topics/{}", topic_name);
+        return Ok(batch_response(&source, data.data));
+    }
+    if query.headers {
+        return Ok(HttpResponse::Ok().encoded(accept, data));
     }
+    Ok(HttpResponse::Ok().encoded(accept, data.without_headers()))
 }
@@ -0,0 +1,15 @@
+use crate::service::error::ServiceError;
+use crate::service::metrics_service;
+use actix_web::{get, web, HttpResponse};
+
+const PROMETHEUS_TEXT: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+pub fn metrics_controller_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_metrics);
+}
+
+#[get("/metrics")]
+async fn get_metrics() -> Result<HttpResponse, ServiceError> {
+    let body = metrics_service::render()?;
+    Ok(HttpResponse::Ok().content_type(PROMETHEUS_TEXT).body(body))
+}
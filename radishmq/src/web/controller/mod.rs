@@ -1,2 +1,10 @@
+pub mod acl_controller;
+pub mod audit_controller;
+pub mod credential_controller;
+pub mod exchange_controller;
+pub mod forwarding_rule_controller;
+pub mod health_controller;
 pub mod message_topic_controller;
+pub mod metrics_controller;
 pub mod task_topic_controller;
+pub mod wildcard_subscription_controller;
@@ -1,149 +1,441 @@
 use crate::model::task_topic_model::{
-    AddPublisherToTaskTopic, AddSubscriberToTaskTopic, CreateTaskTopic, PublishToTaskTopic,
-    RemovePublisherFromTaskTopic, RemoveSubscriberFromTaskTopic,
+    AddPublisherToTaskTopic, AddSubscriberToTaskTopic, AssignedTask, CompleteTask, CreateTaskTopic,
+    FailTask, NewTasks, PublishToTaskTopic, PublishedTask, RemovePublisherFromTaskTopic,
+    RemoveSubscriberFromTaskTopic, ReportTaskProgress, TaskStatusModel, TaskTopicModel,
 };
+use crate::service::error::ServiceError;
 use crate::service::task_topic_service;
 use crate::utils::types::TopicType;
-use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use crate::web::client_identity::client_identity;
+use crate::web::negotiation::{Body, EncodedResponse, MediaType};
+use crate::web::problem::Problem;
+use actix_web::middleware::from_fn;
+use actix_web::{delete, get, post, web, HttpResponse};
+use uuid::Uuid;
 
 pub fn task_topic_controller_config(cfg: &mut web::ServiceConfig) {
     cfg.service(get_all_task_topics)
         .service(get_task_topics)
         .service(create_task_topics)
         .service(delete_task_topics)
+        .service(add_publisher_to_task_topic)
+        .service(remove_publisher_from_task_topic)
         .service(publish_to_task_topics)
         .service(add_subscriber_to_task_topic)
         .service(remove_subscriber_from_task_topic)
         .service(is_there_a_task_for_subscriber)
-        .service(get_new_task_for_subscriber);
+        .service(get_new_task_for_subscriber)
+        .service(heartbeat_task)
+        .service(report_task_progress)
+        .service(complete_task)
+        .service(fail_task)
+        .service(get_task_status);
 }
 
+/// Lists all task topics.
+#[utoipa::path(
+    tag = "task_topics",
+    responses(
+        (status = 200, description = "All task topics", body = Vec<TaskTopicModel>)
+    )
+)]
 #[get("/task_topics")]
-async fn get_all_task_topics() -> impl Responder {
-    let topics = task_topic_service::get_all_task_topics().await;
-    HttpResponse::Ok().json(topics)
+async fn get_all_task_topics(accept: MediaType) -> Result<HttpResponse, ServiceError> {
+    let topics = task_topic_service::get_all_task_topics().await?;
+    Ok(HttpResponse::Ok().encoded(accept, topics))
 }
 
+/// Returns a task topic.
+#[utoipa::path(
+    tag = "task_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic")
+    ),
+    responses(
+        (status = 200, description = "The task topic", body = TaskTopicModel),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
 #[get("/task_topics/{topic_name}")]
-async fn get_task_topics(topic_name: web::Path<String>) -> impl Responder {
-    let topic = task_topic_service::get_task_topic(topic_name.into_inner()).await;
-
-    match topic {
-        Some(topic) => HttpResponse::Ok().json(topic),
-        None => HttpResponse::NotFound().body(""),
-    }
+async fn get_task_topics(
+    topic_name: web::Path<String>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let topic = task_topic_service::get_task_topic(topic_name.into_inner()).await?;
+    Ok(HttpResponse::Ok().encoded(accept, topic))
 }
 
+/// Creates a task topic.
+#[utoipa::path(
+    tag = "task_topics",
+    request_body = CreateTaskTopic,
+    responses(
+        (status = 201, description = "The task topic was created", body = TaskTopicModel),
+        (status = 400, description = "The topic name or the body is not valid", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "A topic with the name already exists", body = Problem, content_type = "application/problem+json")
+    )
+)]
 #[post("/task_topics")]
-async fn create_task_topics(body: web::Json<CreateTaskTopic>) -> impl Responder {
-    let topic = task_topic_service::create_task_topic(body.into_inner()).await;
-
-    match topic {
-        Some(topic) => HttpResponse::Created().json(topic),
-        None => HttpResponse::Conflict().body(""),
-    }
+async fn create_task_topics(
+    body: Body<CreateTaskTopic>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let topic = task_topic_service::create_task_topic(body.into_inner()).await?;
+    Ok(HttpResponse::Created().encoded(accept, topic))
 }
 
+/// Deletes a task topic.
+#[utoipa::path(
+    tag = "task_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic")
+    ),
+    responses(
+        (status = 204, description = "The task topic was deleted"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
 #[delete("/task_topics/{topic_name}")]
-async fn delete_task_topics(topic_name: web::Path<String>) -> impl Responder {
-    let topic = task_topic_service::delete_task_topic(topic_name.into_inner()).await;
-
-    match topic {
-        Some(_) => HttpResponse::NoContent().body(""),
-        None => HttpResponse::NotFound().body(""),
-    }
+async fn delete_task_topics(topic_name: web::Path<String>) -> Result<HttpResponse, ServiceError> {
+    task_topic_service::delete_task_topic(topic_name.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
 }
 
+/// Registers a publisher of a task topic.
+#[utoipa::path(
+    tag = "task_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic")
+    ),
+    request_body = AddPublisherToTaskTopic,
+    responses(
+        (status = 204, description = "The publisher was registered"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "The identifier is already a publisher of the topic", body = Problem, content_type = "application/problem+json")
+    )
+)]
 #[post("/task_topics/{topic_name}/publisher")]
 async fn add_publisher_to_task_topic(
     topic_name: web::Path<String>,
-    body: web::Json<AddPublisherToTaskTopic>,
-) -> impl Responder {
+    body: Body<AddPublisherToTaskTopic>,
+) -> Result<HttpResponse, ServiceError> {
     task_topic_service::add_publisher_to_task_topic(topic_name.into_inner(), body.into_inner())
-        .await;
+        .await?;
 
-    HttpResponse::NoContent().body("")
+    Ok(HttpResponse::NoContent().finish())
 }
 
+/// Removes a publisher of a task topic.
+#[utoipa::path(
+    tag = "task_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("identifier" = String, Path, description = "Identifier of the publisher")
+    ),
+    responses(
+        (status = 204, description = "The publisher was removed"),
+        (status = 403, description = "The identifier is not a publisher of the topic", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
 #[delete("/task_topics/{topic_name}/publisher/{identifier}")]
 async fn remove_publisher_from_task_topic(
-    topic_name: web::Path<String>,
-    identifier: web::Path<String>,
-) -> impl Responder {
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, identifier) = path.into_inner();
+
     task_topic_service::remove_publisher_from_task_topic(
-        topic_name.into_inner(),
-        RemovePublisherFromTaskTopic::new(identifier.into_inner()),
+        topic_name,
+        RemovePublisherFromTaskTopic::new(identifier),
     )
-    .await;
+    .await?;
 
-    HttpResponse::NoContent().body("")
+    Ok(HttpResponse::NoContent().finish())
 }
 
-#[post("/task_topics/{topic_name}/publisher/{identifier}/publish")]
-async fn publish_to_task_topics(
-    topic_name: web::Path<String>,
-    identifier: web::Path<String>,
-    body: web::Json<PublishToTaskTopic<TopicType>>,
-) -> impl Responder {
-    task_topic_service::publish_to_task_topic(
-        topic_name.into_inner(),
-        identifier.into_inner(),
-        body.into_inner(),
+/// Publishes a task to a task topic.
+#[utoipa::path(
+    tag = "task_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("identifier" = String, Path, description = "Identifier of the publisher")
+    ),
+    request_body = PublishToTaskTopic<String>,
+    responses(
+        (status = 201, description = "The task was queued", body = PublishedTask),
+        (status = 403, description = "The identifier is not a publisher of the topic or does not match the client certificate", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json")
     )
-    .await;
+)]
+#[post(
+    "/task_topics/{topic_name}/publisher/{identifier}/publish",
+    wrap = "from_fn(client_identity)"
+)]
+async fn publish_to_task_topics(
+    path: web::Path<(String, String)>,
+    body: Body<PublishToTaskTopic<TopicType>>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, identifier) = path.into_inner();
 
-    HttpResponse::NoContent().body("")
+    let task = task_topic_service::publish_to_task_topic(topic_name, identifier, body.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Created().encoded(accept, task))
 }
 
+/// Registers a subscriber of a task topic.
+#[utoipa::path(
+    tag = "task_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic")
+    ),
+    request_body = AddSubscriberToTaskTopic,
+    responses(
+        (status = 204, description = "The subscriber was registered"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "The identifier is already a subscriber of the topic", body = Problem, content_type = "application/problem+json")
+    )
+)]
 #[post("/task_topics/{topic_name}/subscriber")]
 async fn add_subscriber_to_task_topic(
     topic_name: web::Path<String>,
-    body: web::Json<AddSubscriberToTaskTopic>,
-) -> impl Responder {
+    body: Body<AddSubscriberToTaskTopic>,
+) -> Result<HttpResponse, ServiceError> {
     task_topic_service::add_subscriber_to_task_topic(topic_name.into_inner(), body.into_inner())
-        .await;
+        .await?;
 
-    HttpResponse::NoContent().body("")
+    Ok(HttpResponse::NoContent().finish())
 }
 
+/// Removes a subscriber of a task topic.
+#[utoipa::path(
+    tag = "task_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("identifier" = String, Path, description = "Identifier of the subscriber")
+    ),
+    responses(
+        (status = 204, description = "The subscriber was removed"),
+        (status = 403, description = "The identifier is not a subscriber of the topic", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
 #[delete("/task_topics/{topic_name}/subscriber/{identifier}")]
 async fn remove_subscriber_from_task_topic(
-    topic_name: web::Path<String>,
-    body: web::Json<RemoveSubscriberFromTaskTopic>,
-) -> impl Responder {
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, identifier) = path.into_inner();
+
     task_topic_service::remove_subscriber_from_task_topic(
-        topic_name.into_inner(),
-        body.into_inner(),
+        topic_name,
+        RemoveSubscriberFromTaskTopic::new(identifier),
     )
-    .await;
+    .await?;
 
-    HttpResponse::NoContent().body("")
+    Ok(HttpResponse::NoContent().finish())
 }
 
-#[get("/task_topics/{topic_name}/subscribers/{identifier}/is_there_a_task")]
-async fn is_there_a_task_for_subscriber(
-    topic_name: web::Path<String>,
-    identifier: web::Path<String>,
-) -> impl Responder {
-    let new_data = task_topic_service::is_there_a_task_for_subscriber(
-        topic_name.into_inner(),
-        identifier.into_inner(),
+/// Tells whether there is a queued task for the subscriber.
+#[utoipa::path(
+    tag = "task_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("identifier" = String, Path, description = "Identifier of the subscriber")
+    ),
+    responses(
+        (status = 200, description = "Whether there is a task", body = NewTasks),
+        (status = 403, description = "The identifier is not a subscriber of the topic or does not match the client certificate", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json")
     )
-    .await;
+)]
+#[get(
+    "/task_topics/{topic_name}/subscribers/{identifier}/is_there_a_task",
+    wrap = "from_fn(client_identity)"
+)]
+async fn is_there_a_task_for_subscriber(
+    path: web::Path<(String, String)>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, identifier) = path.into_inner();
 
-    HttpResponse::Ok().json(new_data)
+    let new_data =
+        task_topic_service::is_there_a_task_for_subscriber(topic_name, identifier).await?;
+
+    Ok(HttpResponse::Ok().encoded(accept, new_data))
 }
 
-#[get("/task_topics/{topic_name}/subscribers/{identifier}/get_new_task")]
+/// Leases the next queued task to the subscriber.
+#[utoipa::path(
+    tag = "task_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("identifier" = String, Path, description = "Identifier of the subscriber")
+    ),
+    responses(
+        (status = 200, description = "The leased task or `null` if no task is queued", body = AssignedTask<String>),
+        (status = 403, description = "The identifier is not a subscriber of the topic or does not match the client certificate", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[get(
+    "/task_topics/{topic_name}/subscribers/{identifier}/get_new_task",
+    wrap = "from_fn(client_identity)"
+)]
 async fn get_new_task_for_subscriber(
-    topic_name: web::Path<String>,
-    identifier: web::Path<String>,
-) -> impl Responder {
-    let task = task_topic_service::get_new_task_for_subscriber(
-        topic_name.into_inner(),
-        identifier.into_inner(),
+    path: web::Path<(String, String)>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, identifier) = path.into_inner();
+
+    let task = task_topic_service::get_new_task_for_subscriber(topic_name, identifier).await?;
+
+    Ok(HttpResponse::Ok().encoded(accept, task))
+}
+
+/// Extends the lease of a task.
+#[utoipa::path(
+    tag = "task_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("identifier" = String, Path, description = "Identifier of the subscriber"),
+        ("task_id" = Uuid, Path, description = "Id of the task")
+    ),
+    responses(
+        (status = 204, description = "The lease was extended"),
+        (status = 403, description = "The identifier is not a subscriber of the topic or does not match the client certificate", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "The task is not leased by the subscriber", body = Problem, content_type = "application/problem+json")
     )
-    .await;
+)]
+#[post(
+    "/task_topics/{topic_name}/subscribers/{identifier}/tasks/{task_id}/heartbeat",
+    wrap = "from_fn(client_identity)"
+)]
+async fn heartbeat_task(
+    path: web::Path<(String, String, Uuid)>,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, identifier, task_id) = path.into_inner();
+
+    task_topic_service::heartbeat_task(topic_name, identifier, task_id).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Reports the progress of a leased task in percent.
+#[utoipa::path(
+    tag = "task_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("identifier" = String, Path, description = "Identifier of the subscriber"),
+        ("task_id" = Uuid, Path, description = "Id of the task")
+    ),
+    request_body = ReportTaskProgress,
+    responses(
+        (status = 204, description = "The progress was recorded"),
+        (status = 403, description = "The identifier is not a subscriber of the topic or does not match the client certificate", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "The task is not leased by the subscriber", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[post(
+    "/task_topics/{topic_name}/subscribers/{identifier}/tasks/{task_id}/progress",
+    wrap = "from_fn(client_identity)"
+)]
+async fn report_task_progress(
+    path: web::Path<(String, String, Uuid)>,
+    body: Body<ReportTaskProgress>,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, identifier, task_id) = path.into_inner();
+
+    task_topic_service::report_task_progress(topic_name, identifier, task_id, body.into_inner())
+        .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Completes a leased task with its result.
+#[utoipa::path(
+    tag = "task_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("identifier" = String, Path, description = "Identifier of the subscriber"),
+        ("task_id" = Uuid, Path, description = "Id of the task")
+    ),
+    request_body = CompleteTask<String>,
+    responses(
+        (status = 204, description = "The task succeeded"),
+        (status = 403, description = "The identifier is not a subscriber of the topic or does not match the client certificate", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "The task is not leased by the subscriber", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[post(
+    "/task_topics/{topic_name}/subscribers/{identifier}/tasks/{task_id}/complete",
+    wrap = "from_fn(client_identity)"
+)]
+async fn complete_task(
+    path: web::Path<(String, String, Uuid)>,
+    body: Body<CompleteTask<TopicType>>,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, identifier, task_id) = path.into_inner();
+
+    task_topic_service::complete_task(topic_name, identifier, task_id, body.into_inner()).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Fails a leased task, it is queued again until its attempts are used up.
+#[utoipa::path(
+    tag = "task_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("identifier" = String, Path, description = "Identifier of the subscriber"),
+        ("task_id" = Uuid, Path, description = "Id of the task")
+    ),
+    request_body = FailTask,
+    responses(
+        (status = 204, description = "The failure was recorded"),
+        (status = 403, description = "The identifier is not a subscriber of the topic or does not match the client certificate", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The topic does not exist", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "The task is not leased by the subscriber", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[post(
+    "/task_topics/{topic_name}/subscribers/{identifier}/tasks/{task_id}/fail",
+    wrap = "from_fn(client_identity)"
+)]
+async fn fail_task(
+    path: web::Path<(String, String, Uuid)>,
+    body: Body<FailTask>,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, identifier, task_id) = path.into_inner();
+
+    task_topic_service::fail_task(topic_name, identifier, task_id, body.into_inner()).await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Returns the status of a task.
+#[utoipa::path(
+    tag = "task_topics",
+    params(
+        ("topic_name" = String, Path, description = "Name of the topic"),
+        ("task_id" = Uuid, Path, description = "Id of the task")
+    ),
+    responses(
+        (status = 200, description = "The status of the task", body = TaskStatusModel<String>),
+        (status = 404, description = "The topic or the task does not exist", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[get("/task_topics/{topic_name}/tasks/{task_id}")]
+async fn get_task_status(
+    path: web::Path<(String, Uuid)>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let (topic_name, task_id) = path.into_inner();
+
+    let status = task_topic_service::get_task_status(topic_name, task_id).await?;
 
-    HttpResponse::Ok().json(task)
+    Ok(HttpResponse::Ok().encoded(accept, status))
 }
@@ -0,0 +1,176 @@
+use crate::model::message_topic_model::{DataQuery, NewDataMessageTopic};
+use crate::model::wildcard_subscription_model::{
+    CreateWildcardSubscription, DataWildcardSubscription, WildcardSubscriptionModel,
+};
+use crate::service::error::ServiceError;
+use crate::service::wildcard_subscription_service;
+use crate::web::client_identity::client_identity;
+use crate::web::negotiation::{Body, EncodedResponse, MediaType};
+use crate::web::problem::Problem;
+use actix_web::middleware::from_fn;
+use actix_web::{delete, get, post, web, HttpResponse};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+pub fn wildcard_subscription_controller_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_all_wildcard_subscriptions)
+        .service(get_wildcard_subscription)
+        .service(create_wildcard_subscription)
+        .service(delete_wildcard_subscription)
+        .service(is_there_new_data_for_wildcard_subscriber)
+        .service(get_new_data_for_wildcard_subscriber);
+}
+
+// a subscriber may hold a wildcard subscription per pattern
+#[derive(Debug, Deserialize, IntoParams)]
+struct PatternQuery {
+    /// The pattern of the wildcard subscription
+    pattern: String,
+}
+
+/// Lists all wildcard subscriptions.
+#[utoipa::path(
+    tag = "wildcard_subscriptions",
+    responses(
+        (status = 200, description = "All wildcard subscriptions", body = Vec<WildcardSubscriptionModel>)
+    )
+)]
+#[get("/wildcard_subscriptions")]
+async fn get_all_wildcard_subscriptions(accept: MediaType) -> Result<HttpResponse, ServiceError> {
+    let subscriptions = wildcard_subscription_service::get_all_wildcard_subscriptions().await?;
+    Ok(HttpResponse::Ok().encoded(accept, subscriptions))
+}
+
+/// Returns a wildcard subscription with the topics it currently spans.
+#[utoipa::path(
+    tag = "wildcard_subscriptions",
+    params(
+        ("identifier" = String, Path, description = "Identifier of the subscriber"),
+        PatternQuery
+    ),
+    responses(
+        (status = 200, description = "The wildcard subscription", body = WildcardSubscriptionModel),
+        (status = 404, description = "The subscriber has no wildcard subscription with the pattern", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[get("/wildcard_subscriptions/{identifier}")]
+async fn get_wildcard_subscription(
+    identifier: web::Path<String>,
+    query: web::Query<PatternQuery>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let subscription = wildcard_subscription_service::get_wildcard_subscription(
+        identifier.into_inner(),
+        query.into_inner().pattern,
+    )
+    .await?;
+    Ok(HttpResponse::Ok().encoded(accept, subscription))
+}
+
+/// Subscribes to every message topic of the default namespace matching a pattern, also to those
+/// created later.
+#[utoipa::path(
+    tag = "wildcard_subscriptions",
+    request_body = CreateWildcardSubscription,
+    responses(
+        (status = 201, description = "The wildcard subscription was created", body = WildcardSubscriptionModel),
+        (status = 400, description = "The pattern, the filter or the body is not valid", body = Problem, content_type = "application/problem+json"),
+        (status = 403, description = "The identifier does not match the caller", body = Problem, content_type = "application/problem+json"),
+        (status = 409, description = "The subscriber already has a wildcard subscription with the pattern", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[post("/wildcard_subscriptions")]
+async fn create_wildcard_subscription(
+    body: Body<CreateWildcardSubscription>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let subscription =
+        wildcard_subscription_service::create_wildcard_subscription(body.into_inner()).await?;
+    Ok(HttpResponse::Created().encoded(accept, subscription))
+}
+
+/// Deletes a wildcard subscription with the topic subscriptions it created.
+#[utoipa::path(
+    tag = "wildcard_subscriptions",
+    params(
+        ("identifier" = String, Path, description = "Identifier of the subscriber"),
+        PatternQuery
+    ),
+    responses(
+        (status = 204, description = "The wildcard subscription was deleted"),
+        (status = 404, description = "The subscriber has no wildcard subscription with the pattern", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[delete("/wildcard_subscriptions/{identifier}")]
+async fn delete_wildcard_subscription(
+    identifier: web::Path<String>,
+    query: web::Query<PatternQuery>,
+) -> Result<HttpResponse, ServiceError> {
+    wildcard_subscription_service::delete_wildcard_subscription(
+        identifier.into_inner(),
+        query.into_inner().pattern,
+    )
+    .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Tells whether a topic of the subscriber's wildcard subscriptions has messages it has not fetched
+/// yet.
+#[utoipa::path(
+    tag = "wildcard_subscriptions",
+    params(
+        ("identifier" = String, Path, description = "Identifier of the subscriber")
+    ),
+    responses(
+        (status = 200, description = "Whether there are new messages", body = NewDataMessageTopic),
+        (status = 403, description = "The identifier does not match the client certificate", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The subscriber has no wildcard subscription", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[get(
+    "/wildcard_subscriptions/{identifier}/is_new_data",
+    wrap = "from_fn(client_identity)"
+)]
+async fn is_there_new_data_for_wildcard_subscriber(
+    identifier: web::Path<String>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let data = wildcard_subscription_service::is_there_new_data_for_wildcard_subscriber(
+        identifier.into_inner(),
+    )
+    .await?;
+    Ok(HttpResponse::Ok().encoded(accept, data))
+}
+
+/// Fetches the messages of every topic of the subscriber's wildcard subscriptions it has not
+/// fetched yet.
+#[utoipa::path(
+    tag = "wildcard_subscriptions",
+    params(
+        ("identifier" = String, Path, description = "Identifier of the subscriber"),
+        DataQuery
+    ),
+    responses(
+        (status = 200, description = "The new messages per topic, with their headers if asked for", body = DataWildcardSubscription<String>),
+        (status = 403, description = "The identifier does not match the client certificate", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "The subscriber has no wildcard subscription", body = Problem, content_type = "application/problem+json")
+    )
+)]
+#[get(
+    "/wildcard_subscriptions/{identifier}/get_data",
+    wrap = "from_fn(client_identity)"
+)]
+async fn get_new_data_for_wildcard_subscriber(
+    identifier: web::Path<String>,
+    query: web::Query<DataQuery>,
+    accept: MediaType,
+) -> Result<HttpResponse, ServiceError> {
+    let data = wildcard_subscription_service::get_new_data_for_wildcard_subscriber(
+        identifier.into_inner(),
+    )
+    .await?;
+    if query.headers {
+        return Ok(HttpResponse::Ok().encoded(accept, data));
+    }
+    Ok(HttpResponse::Ok().encoded(accept, data.without_headers()))
+}
@@ -1,2 +1,13 @@
+mod authentication;
+mod client_identity;
+mod cloud_event;
 mod controller;
+mod namespace;
+mod negotiation;
+mod openapi;
+mod peer_address;
+mod problem;
+mod request_metrics;
 pub mod server;
+mod startup;
+mod tls;
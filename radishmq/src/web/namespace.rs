@@ -0,0 +1,79 @@
+use crate::utils::namespace::{self, is_valid_namespace, DEFAULT_NAMESPACE};
+use crate::web::problem::Problem;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::uri::PathAndQuery;
+use actix_web::http::{StatusCode, Uri};
+use actix_web::middleware::Next;
+use actix_web::Error;
+
+const NAMESPACE_PREFIX: &str = "/ns/";
+
+const NAMESPACED_COLLECTIONS: [&str; 6] = [
+    "message_topics",
+    "task_topics",
+    "forwarding_rules",
+    "credentials",
+    "acl",
+    "audit",
+];
+
+// only exist in the default namespace, other namespaces answer them with `not-namespaced`
+const DEFAULT_NAMESPACE_COLLECTIONS: [&str; 2] = ["exchanges", "wildcard_subscriptions"];
+
+/// Serves `/ns/{namespace}/...` and `/v1/ns/{namespace}/...` with the regular routes, the request
+/// runs inside the namespace of its path. Every other request runs in the default namespace.
+pub async fn namespace(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let Some((version, namespace, path)) = split_namespace(req.uri().path()) else {
+        return next.call(req).await;
+    };
+    let collection = path.split('/').next().unwrap_or_default();
+    if DEFAULT_NAMESPACE_COLLECTIONS.contains(&collection) && namespace != DEFAULT_NAMESPACE {
+        return Err(Problem::new(
+            StatusCode::NOT_FOUND,
+            "not-namespaced",
+            format!(
+                "{} only exist in the namespace '{}'",
+                collection.replace('_', " "),
+                DEFAULT_NAMESPACE
+            ),
+        )
+        .into());
+    }
+    if !NAMESPACED_COLLECTIONS.contains(&collection)
+        && !DEFAULT_NAMESPACE_COLLECTIONS.contains(&collection)
+    {
+        return next.call(req).await;
+    }
+    let Some(uri) = strip_namespace(req.uri(), version, path) else {
+        return next.call(req).await;
+    };
+
+    let namespace = namespace.to_string();
+    req.match_info_mut().get_mut().update(&uri);
+    req.head_mut().uri = uri;
+    namespace::scope(namespace, next.call(req)).await
+}
+
+// the API version prefix, the namespace and the path after it
+fn split_namespace(path: &str) -> Option<(&str, &str, &str)> {
+    let (version, path) = match path.strip_prefix("/v1") {
+        Some(rest) if rest.starts_with(NAMESPACE_PREFIX) => ("/v1", rest),
+        _ => ("", path),
+    };
+    let (namespace, path) = path.strip_prefix(NAMESPACE_PREFIX)?.split_once('/')?;
+    is_valid_namespace(namespace).then_some((version, namespace, path))
+}
+
+fn strip_namespace(uri: &Uri, version: &str, path: &str) -> Option<Uri> {
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{}/{}?{}", version, path, query),
+        None => format!("{}/{}", version, path),
+    };
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = Some(PathAndQuery::try_from(path_and_query).ok()?);
+    Uri::from_parts(parts).ok()
+}
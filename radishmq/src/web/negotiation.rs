@@ -0,0 +1,171 @@
+use crate::web::problem::Problem;
+use actix_web::dev::Payload;
+use actix_web::http::header::{self, Header};
+use actix_web::http::StatusCode;
+use actix_web::{web, FromRequest, HttpRequest, HttpResponse, HttpResponseBuilder, ResponseError};
+use futures::future::{ready, LocalBoxFuture, Ready};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub const JSON: &str = "application/json";
+pub const MESSAGE_PACK: &str = "application/msgpack";
+pub const CBOR: &str = "application/cbor";
+
+/// The encodings of request and response bodies, JSON is used unless the client asks for
+/// another one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MediaType {
+    #[default]
+    Json,
+    MessagePack,
+    Cbor,
+}
+
+impl MediaType {
+    pub fn from_essence(essence: &str) -> Option<Self> {
+        match essence {
+            JSON => Some(Self::Json),
+            MESSAGE_PACK | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Some(Self::MessagePack)
+            }
+            CBOR => Some(Self::Cbor),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => JSON,
+            Self::MessagePack => MESSAGE_PACK,
+            Self::Cbor => CBOR,
+        }
+    }
+
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            // named fields keep the documents shaped like their JSON counterparts
+            Self::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+            Self::Cbor => {
+                let mut buffer = Vec::new();
+                ciborium::into_writer(value, &mut buffer).map_err(|e| e.to_string())?;
+                Ok(buffer)
+            }
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, String> {
+        match self {
+            Self::Json => serde_json::from_slice(bytes).map_err(|e| e.to_string()),
+            Self::MessagePack => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+            Self::Cbor => ciborium::from_reader(bytes).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// Extracts the encoding of the response body from the `Accept` header, the media type with the
+/// highest quality that is supported wins.
+impl FromRequest for MediaType {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let media_type = header::Accept::parse(req)
+            .ok()
+            .and_then(|accept| {
+                accept
+                    .ranked()
+                    .iter()
+                    .find_map(|mime| match mime.essence_str() {
+                        "*/*" | "application/*" => Some(MediaType::Json),
+                        essence => MediaType::from_essence(essence),
+                    })
+            })
+            .unwrap_or_default();
+
+        ready(Ok(media_type))
+    }
+}
+
+/// A request body decoded according to its `Content-Type`, JSON if the header is missing.
+pub struct Body<T>(pub T);
+
+impl<T> Body<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: DeserializeOwned + 'static> FromRequest for Body<T> {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let media_type = request_media_type(req);
+        let bytes = web::Bytes::from_request(req, payload);
+
+        Box::pin(async move {
+            let media_type = media_type?;
+            let bytes = bytes.await.map_err(payload_problem)?;
+
+            media_type
+                .decode(&bytes)
+                .map(Body)
+                .map_err(|e| Problem::new(StatusCode::BAD_REQUEST, "invalid-body", e).into())
+        })
+    }
+}
+
+/// Answers a body that cannot be read, e.g. because it exceeds the size limit.
+pub fn payload_problem(e: actix_web::Error) -> Problem {
+    let status = e.as_response_error().status_code();
+    Problem::new(status, "invalid-body", e.to_string())
+}
+
+/// The media type of the request body without its parameters, `None` if the header is missing.
+pub fn content_type_essence(req: &HttpRequest) -> Option<String> {
+    let content_type = req.headers().get(header::CONTENT_TYPE)?;
+
+    Some(
+        content_type
+            .to_str()
+            .unwrap_or_default()
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase(),
+    )
+}
+
+fn request_media_type(req: &HttpRequest) -> Result<MediaType, Problem> {
+    let Some(essence) = content_type_essence(req) else {
+        return Ok(MediaType::Json);
+    };
+
+    MediaType::from_essence(&essence).ok_or_else(|| {
+        Problem::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "unsupported-media-type",
+            format!("'{}' is not a supported content type", essence),
+        )
+    })
+}
+
+pub trait EncodedResponse {
+    /// Sets the body encoded in the negotiated media type, like `json` does for JSON.
+    fn encoded(&mut self, media_type: MediaType, body: impl Serialize) -> HttpResponse;
+}
+
+impl EncodedResponse for HttpResponseBuilder {
+    fn encoded(&mut self, media_type: MediaType, body: impl Serialize) -> HttpResponse {
+        match media_type.encode(&body) {
+            Ok(bytes) => self
+                .content_type(media_type.content_type())
+                .insert_header((header::VARY, "Accept"))
+                .body(bytes),
+            Err(e) => Problem::new(StatusCode::INTERNAL_SERVER_ERROR, "internal-error", e)
+                .error_response(),
+        }
+    }
+}
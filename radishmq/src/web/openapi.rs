@@ -0,0 +1,169 @@
+use crate::auth::{auth_mode_from_env, AuthMode};
+use crate::web::controller::{
+    acl_controller, audit_controller, credential_controller, exchange_controller,
+    forwarding_rule_controller, message_topic_controller, task_topic_controller,
+    wildcard_subscription_controller,
+};
+use crate::web::negotiation::{CBOR, JSON, MESSAGE_PACK};
+use crate::web::problem::Problem;
+use actix_web::{get, web, HttpResponse};
+use utoipa::openapi::security::{
+    ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme,
+};
+use utoipa::openapi::{Content, RefOr};
+use utoipa::{Modify, OpenApi};
+use utoipa_redoc::{Redoc, Servable};
+
+/// The OpenAPI document of the REST routes, generated from the handlers and the model types.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "RadishMQ"),
+    servers((url = "/v1")),
+    paths(
+        message_topic_controller::get_all_message_topics,
+        message_topic_controller::get_message_topics,
+        message_topic_controller::create_message_topics,
+        message_topic_controller::delete_message_topics,
+        message_topic_controller::add_publisher_to_message_topic,
+        message_topic_controller::remove_publisher_from_message_topic,
+        message_topic_controller::publish_to_message_topics,
+        message_topic_controller::add_subscriber_to_message_topic,
+        message_topic_controller::remove_subscriber_from_message_topic,
+        message_topic_controller::is_there_new_data_for_subscriber,
+        message_topic_controller::get_new_data_for_subscriber,
+        task_topic_controller::get_all_task_topics,
+        task_topic_controller::get_task_topics,
+        task_topic_controller::create_task_topics,
+        task_topic_controller::delete_task_topics,
+        task_topic_controller::add_publisher_to_task_topic,
+        task_topic_controller::remove_publisher_from_task_topic,
+        task_topic_controller::publish_to_task_topics,
+        task_topic_controller::add_subscriber_to_task_topic,
+        task_topic_controller::remove_subscriber_from_task_topic,
+        task_topic_controller::is_there_a_task_for_subscriber,
+        task_topic_controller::get_new_task_for_subscriber,
+        task_topic_controller::heartbeat_task,
+        task_topic_controller::report_task_progress,
+        task_topic_controller::complete_task,
+        task_topic_controller::fail_task,
+        task_topic_controller::get_task_status,
+        wildcard_subscription_controller::get_all_wildcard_subscriptions,
+        wildcard_subscription_controller::get_wildcard_subscription,
+        wildcard_subscription_controller::create_wildcard_subscription,
+        wildcard_subscription_controller::delete_wildcard_subscription,
+        wildcard_subscription_controller::is_there_new_data_for_wildcard_subscriber,
+        wildcard_subscription_controller::get_new_data_for_wildcard_subscriber,
+        exchange_controller::get_all_exchanges,
+        exchange_controller::get_exchange,
+        exchange_controller::create_exchange,
+        exchange_controller::delete_exchange,
+        exchange_controller::add_binding_to_exchange,
+        exchange_controller::remove_binding_from_exchange,
+        exchange_controller::add_publisher_to_exchange,
+        exchange_controller::remove_publisher_from_exchange,
+        exchange_controller::publish_to_exchange,
+        forwarding_rule_controller::get_all_forwarding_rules,
+        forwarding_rule_controller::get_forwarding_rule,
+        forwarding_rule_controller::create_forwarding_rule,
+        forwarding_rule_controller::delete_forwarding_rule,
+        credential_controller::get_all_credentials,
+        credential_controller::get_credential,
+        credential_controller::create_credential,
+        credential_controller::delete_credential,
+        acl_controller::get_all_grants,
+        acl_controller::get_grant,
+        acl_controller::create_grant,
+        acl_controller::delete_grant,
+        audit_controller::get_audit_entries,
+        audit_controller::export_audit_entries,
+    ),
+    components(schemas(Problem)),
+    modifiers(&BinaryMediaTypes, &Authentication),
+    tags(
+        (name = "message_topics", description = "Topics delivering every message to all subscribers"),
+        (name = "task_topics", description = "Topics leasing every task to one subscriber"),
+        (name = "wildcard_subscriptions", description = "Subscriptions to every message topic matching a pattern"),
+        (name = "exchanges", description = "Routing of published messages to bound topics"),
+        (name = "forwarding_rules", description = "Republishing of the messages of matching topics to another topic"),
+        (name = "credentials", description = "API keys of the authenticated principals"),
+        (name = "acl", description = "Roles of principals on topics"),
+        (name = "audit", description = "Changes of topics, memberships, credentials and the ACL"),
+    )
+)]
+struct ApiDoc;
+
+/// Offers every JSON request and response body in the other negotiable media types as well.
+struct BinaryMediaTypes;
+
+impl Modify for BinaryMediaTypes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [&mut item.get, &mut item.post, &mut item.delete];
+
+            for operation in operations.into_iter().flatten() {
+                if let Some(body) = operation.request_body.as_mut() {
+                    let binary = binary_media_types(body.content.get(JSON));
+                    body.content.extend(binary);
+                }
+
+                for response in operation.responses.responses.values_mut() {
+                    if let RefOr::T(response) = response {
+                        let binary = binary_media_types(response.content.get(JSON));
+                        response.content.extend(binary);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn binary_media_types(json: Option<&Content>) -> Vec<(String, Content)> {
+    json.map(|json| {
+        vec![
+            (MESSAGE_PACK.to_string(), json.clone()),
+            (CBOR.to_string(), json.clone()),
+        ]
+    })
+    .unwrap_or_default()
+}
+
+/// Declares how requests authenticate in `AUTH_MODE`, with an API key in either header or with a
+/// JWT bearer token.
+struct Authentication;
+
+impl Modify for Authentication {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let Some(auth_mode) = auth_mode_from_env() else {
+            return;
+        };
+
+        let components = openapi.components.get_or_insert_with(Default::default);
+        let mut security = Vec::new();
+        if auth_mode == AuthMode::ApiKey {
+            components.add_security_scheme(
+                "api_key",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-API-Key"))),
+            );
+            security.push(SecurityRequirement::new("api_key", Vec::<String>::new()));
+        }
+
+        let mut bearer = HttpBuilder::new().scheme(HttpAuthScheme::Bearer);
+        if auth_mode == AuthMode::Jwt {
+            bearer = bearer.bearer_format("JWT");
+        }
+        components.add_security_scheme("bearer", SecurityScheme::Http(bearer.build()));
+        security.push(SecurityRequirement::new("bearer", Vec::<String>::new()));
+
+        openapi.security = Some(security);
+    }
+}
+
+pub fn openapi_config(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_openapi_document)
+        .service(Redoc::with_url("/docs", ApiDoc::openapi()));
+}
+
+#[get("/openapi.json")]
+async fn get_openapi_document() -> HttpResponse {
+    HttpResponse::Ok().json(ApiDoc::openapi())
+}
@@ -0,0 +1,17 @@
+use crate::utils::peer_address;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::Error;
+
+/// Runs the request with the address of the connected client, forwarded headers are ignored
+/// since any client can set them.
+pub async fn peer_address(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    match req.peer_addr() {
+        Some(address) => peer_address::scope(address.ip().to_string(), next.call(req)).await,
+        None => next.call(req).await,
+    }
+}
@@ -0,0 +1,206 @@
+use crate::service::error::ServiceError;
+use actix_web::error::{PathError, QueryPayloadError};
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, HttpResponse, ResponseError};
+use serde::Serialize;
+use std::fmt;
+use utoipa::ToSchema;
+
+const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+/// An RFC 9457 problem document, the body of every error response of the `/v1` API.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+}
+
+impl Problem {
+    pub fn new(status: StatusCode, kind: &str, detail: String) -> Self {
+        Self {
+            problem_type: format!("urn:radish-mq:problem:{}", kind),
+            title: status.canonical_reason().unwrap_or_default().to_string(),
+            status: status.as_u16(),
+            detail,
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.detail)
+    }
+}
+
+impl ResponseError for Problem {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .content_type(PROBLEM_CONTENT_TYPE)
+            .json(self)
+    }
+}
+
+impl ResponseError for ServiceError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ServiceError::TopicNotFound(_)
+            | ServiceError::TaskNotFound(_)
+            | ServiceError::CredentialNotFound(_)
+            | ServiceError::GrantNotFound(_)
+            | ServiceError::ExchangeNotFound(_)
+            | ServiceError::BindingNotFound(_)
+            | ServiceError::ForwardingRuleNotFound(_)
+            | ServiceError::WildcardSubscriptionNotFound(_, _) => StatusCode::NOT_FOUND,
+            ServiceError::AlreadyExists(_)
+            | ServiceError::AlreadyAPublisher(_)
+            | ServiceError::AlreadyASubscriber(_)
+            | ServiceError::TaskNotLeased(_)
+            | ServiceError::CredentialExists(_)
+            | ServiceError::GrantExists(_)
+            | ServiceError::ExchangeExists(_)
+            | ServiceError::ForwardingRuleExists(_)
+            | ServiceError::WildcardSubscriptionExists(_, _) => StatusCode::CONFLICT,
+            ServiceError::InvalidName(_)
+            | ServiceError::InvalidPattern(_)
+            | ServiceError::InvalidBinding(_)
+            | ServiceError::InvalidFilter => StatusCode::BAD_REQUEST,
+            ServiceError::NotAPublisher(_)
+            | ServiceError::NotASubscriber(_)
+            | ServiceError::IdentityMismatch(_)
+            | ServiceError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+            ServiceError::QuotaExceeded(_, Some(_)) => StatusCode::TOO_MANY_REQUESTS,
+            ServiceError::QuotaExceeded(_, None) => StatusCode::INSUFFICIENT_STORAGE,
+            ServiceError::DatabaseUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ServiceError::PoisonedLock => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response =
+            Problem::new(self.status_code(), self.kind(), self.to_string()).error_response();
+        if let ServiceError::QuotaExceeded(_, Some(retry_after)) = self {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(*retry_after));
+        }
+        response
+    }
+}
+
+/// Answers path segments that cannot be parsed, like a task id that is not a uuid.
+pub fn path_error_handler(err: PathError, _: &HttpRequest) -> actix_web::Error {
+    Problem::new(StatusCode::NOT_FOUND, "invalid-path", err.to_string()).into()
+}
+
+/// Answers query parameters that cannot be parsed, like a timestamp that is not RFC 3339.
+pub fn query_error_handler(err: QueryPayloadError, _: &HttpRequest) -> actix_web::Error {
+    Problem::new(StatusCode::BAD_REQUEST, "invalid-query", err.to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::to_bytes;
+    use serde_json::{json, Value};
+
+    async fn problem(error: impl ResponseError) -> (StatusCode, HttpResponse<()>, Value) {
+        let response = error.error_response();
+        let status = response.status();
+        let (response, body) = response.into_parts();
+        let body = to_bytes(body).await.unwrap();
+        (status, response, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn content_type(response: &HttpResponse<()>) -> &str {
+        response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .unwrap()
+            .to_str()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn service_errors_are_problem_documents() {
+        let cases = [
+            (
+                ServiceError::PermissionDenied("publish:orders".to_string()),
+                StatusCode::FORBIDDEN,
+                "permission-denied",
+            ),
+            (
+                ServiceError::TopicNotFound("orders".to_string()),
+                StatusCode::NOT_FOUND,
+                "topic-not-found",
+            ),
+            (
+                ServiceError::ExchangeExists("orders".to_string()),
+                StatusCode::CONFLICT,
+                "exchange-exists",
+            ),
+            (
+                ServiceError::QuotaExceeded("messages-per-second".to_string(), Some(1)),
+                StatusCode::TOO_MANY_REQUESTS,
+                "quota-exceeded",
+            ),
+            (
+                ServiceError::QuotaExceeded("stored-bytes".to_string(), None),
+                StatusCode::INSUFFICIENT_STORAGE,
+                "quota-exceeded",
+            ),
+        ];
+
+        for (error, status, kind) in cases {
+            let detail = error.to_string();
+            let (response_status, response, body) = problem(error).await;
+            assert_eq!(response_status, status);
+            assert_eq!(content_type(&response), PROBLEM_CONTENT_TYPE);
+            assert_eq!(
+                body,
+                json!({
+                    "type": format!("urn:radish-mq:problem:{}", kind),
+                    "title": status.canonical_reason().unwrap(),
+                    "status": status.as_u16(),
+                    "detail": detail,
+                })
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn exceeded_rate_limits_tell_when_to_retry() {
+        let error = ServiceError::QuotaExceeded("messages-per-second".to_string(), Some(2));
+        let (_, response, _) = problem(error).await;
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "2");
+
+        let error = ServiceError::QuotaExceeded("topics".to_string(), None);
+        let (_, response, _) = problem(error).await;
+        assert!(response.headers().get(header::RETRY_AFTER).is_none());
+    }
+
+    #[tokio::test]
+    async fn web_problems_keep_their_kind() {
+        let error = Problem::new(
+            StatusCode::FORBIDDEN,
+            "namespace-mismatch",
+            "the caller belongs to the namespace 'team-a'".to_string(),
+        );
+        let (status, response, body) = problem(error).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(content_type(&response), PROBLEM_CONTENT_TYPE);
+        assert_eq!(body["type"], "urn:radish-mq:problem:namespace-mismatch");
+        assert_eq!(body["title"], "Forbidden");
+        assert_eq!(
+            body["detail"],
+            "the caller belongs to the namespace 'team-a'"
+        );
+    }
+}
@@ -1,23 +1,120 @@
+use crate::auth::jwt::jwt_settings_from_env;
+use crate::auth::{auth_mode_from_env, AuthMode};
+use crate::web::authentication::authentication;
+use crate::web::controller::acl_controller::acl_controller_config;
+use crate::web::controller::audit_controller::audit_controller_config;
+use crate::web::controller::credential_controller::credential_controller_config;
+use crate::web::controller::exchange_controller::exchange_controller_config;
+use crate::web::controller::forwarding_rule_controller::forwarding_rule_controller_config;
+use crate::web::controller::health_controller::health_controller_config;
 use crate::web::controller::message_topic_controller::message_topic_controller_config;
+use crate::web::controller::metrics_controller::metrics_controller_config;
 use crate::web::controller::task_topic_controller::task_topic_controller_config;
-use actix_web::{middleware, App, HttpServer};
+use crate::web::controller::wildcard_subscription_controller::wildcard_subscription_controller_config;
+use crate::web::namespace::namespace;
+use crate::web::openapi::openapi_config;
+use crate::web::peer_address::peer_address;
+use crate::web::problem::{path_error_handler, query_error_handler};
+use crate::web::request_metrics::request_metrics;
+use crate::web::startup::startup;
+use crate::web::tls::{create_server_config, store_client_certificate, tls_settings_from_env};
+use actix_web::dev::Server;
+use actix_web::middleware::{from_fn, Condition};
+use actix_web::{middleware, web, App, HttpServer};
+use sqlx::{Pool, Postgres};
 use std::env;
+#[cfg(unix)]
+use std::os::unix::fs::FileTypeExt;
 
-pub async fn start_webserver() -> std::io::Result<()> {
+// the limit bodies had when they were always read as JSON
+const MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+fn api_config(cfg: &mut web::ServiceConfig) {
+    cfg.configure(message_topic_controller_config)
+        .configure(task_topic_controller_config)
+        .configure(wildcard_subscription_controller_config)
+        .configure(exchange_controller_config)
+        .configure(forwarding_rule_controller_config)
+        .configure(credential_controller_config)
+        .configure(acl_controller_config)
+        .configure(audit_controller_config);
+}
+
+/// Binds the listeners, the returned server has to be awaited to serve requests.
+pub fn start_webserver(db_connection_pool: Pool<Postgres>) -> std::io::Result<Server> {
     let port = env::var("HTTP_PORT")
         .expect("HTTP_PORT must be set")
         .parse::<u16>()
         .expect("HTTP_PORT must be a number");
 
     let address = env::var("HTTP_ADDRESS").expect("HTTP_ADDRESS must be set");
+    let tls_settings = tls_settings_from_env();
+    let client_identity = tls_settings
+        .as_ref()
+        .is_some_and(|settings| settings.client_identity);
+
+    let auth_mode = auth_mode_from_env();
+    let authenticate = auth_mode.is_some();
+    let jwt_settings =
+        (auth_mode == Some(AuthMode::Jwt)).then(|| web::Data::new(jwt_settings_from_env()));
+    let db_connection_pool = web::Data::new(db_connection_pool);
 
-    HttpServer::new(move || {
-        App::new()
+    let server = HttpServer::new(move || {
+        let mut app = App::new();
+        if let Some(jwt_settings) = &jwt_settings {
+            app = app.app_data(jwt_settings.clone());
+        }
+
+        // namespaced paths are rewritten before the request is authenticated
+        app.wrap(Condition::new(authenticate, from_fn(authentication)))
+            .wrap(from_fn(startup))
+            .wrap(from_fn(namespace))
+            .wrap(from_fn(peer_address))
             .wrap(middleware::Logger::default())
-            .configure(message_topic_controller_config)
-            .configure(task_topic_controller_config)
+            .wrap(from_fn(request_metrics))
+            .app_data(db_connection_pool.clone())
+            .app_data(web::PayloadConfig::new(MAX_BODY_SIZE))
+            .app_data(web::PathConfig::default().error_handler(path_error_handler))
+            .app_data(web::QueryConfig::default().error_handler(query_error_handler))
+            .service(
+                web::scope("/v1")
+                    .configure(openapi_config)
+                    .configure(api_config),
+            )
+            // the unversioned routes are kept for clients written before /v1
+            .configure(api_config)
+            .configure(metrics_controller_config)
+            .configure(health_controller_config)
     })
-    .bind((address, port))?
-    .run()
-    .await
+    .on_connect(move |connection, data| {
+        if client_identity {
+            store_client_certificate(connection, data);
+        }
+    });
+
+    // plain HTTP also accepts HTTP/2 with prior knowledge, TLS negotiates it with ALPN
+    let mut server = match tls_settings {
+        Some(settings) => {
+            server.bind_rustls_0_23((address, port), create_server_config(&settings)?)?
+        }
+        None => server.bind_auto_h2c((address, port))?,
+    };
+
+    #[cfg(unix)]
+    if let Ok(path) = env::var("HTTP_UNIX_SOCKET") {
+        // a socket file left behind by a previous run would make the bind fail, any other file at
+        // the path is kept
+        if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+            if !metadata.file_type().is_socket() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AlreadyExists,
+                    format!("HTTP_UNIX_SOCKET '{}' exists and is no socket", path),
+                ));
+            }
+            std::fs::remove_file(&path)?;
+        }
+        server = server.bind_uds(&path)?;
+    }
+
+    Ok(server.run())
 }
@@ -0,0 +1,32 @@
+use crate::service::health_service;
+use crate::web::problem::Problem;
+use actix_web::body::{EitherBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::http::StatusCode;
+use actix_web::middleware::Next;
+use actix_web::{Error, ResponseError};
+
+const HEALTH_PATH_PREFIX: &str = "/health/";
+
+/// Answers every request but the health probes with `503` until the state is loaded, requests
+/// would otherwise see and change a partially restored state.
+pub async fn startup(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    if health_service::is_started() || req.path().starts_with(HEALTH_PATH_PREFIX) {
+        return next.call(req).await.map(|res| res.map_into_left_body());
+    }
+
+    let mut response = Problem::new(
+        StatusCode::SERVICE_UNAVAILABLE,
+        "starting",
+        "the broker is still starting".to_string(),
+    )
+    .error_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from_static("1"));
+    Ok(req.into_response(response).map_into_right_body())
+}
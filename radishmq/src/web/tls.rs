@@ -0,0 +1,213 @@
+use actix_tls::accept::rustls_0_23::TlsStream;
+use actix_web::dev::Extensions;
+use actix_web::rt::net::TcpStream;
+use log::{info, warn};
+use rustls::crypto::ring::{default_provider, sign::any_supported_type};
+use rustls::pki_types::CertificateDer;
+use rustls::server::{ClientHello, ResolvesServerCert, WebPkiClientVerifier};
+use rustls::sign::CertifiedKey;
+use rustls::{RootCertStore, ServerConfig};
+use std::any::Any;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+const DEFAULT_RELOAD_INTERVAL: u64 = 60;
+
+pub struct TlsSettings {
+    cert_path: String,
+    key_path: String,
+    client_ca_path: Option<String>,
+    client_auth_optional: bool,
+    pub client_identity: bool,
+    reload_interval: Duration,
+}
+
+/// The common name of the client certificate of a TLS connection, stored as connection data when
+/// client certificates are used as identities.
+#[derive(Debug, Clone)]
+pub struct ClientCertificate {
+    pub common_name: Option<String>,
+}
+
+/// Reads the TLS settings, TLS is enabled when both `HTTP_TLS_CERT` and `HTTP_TLS_KEY` are set.
+pub fn tls_settings_from_env() -> Option<TlsSettings> {
+    let cert_path = env::var("HTTP_TLS_CERT").ok()?;
+    let key_path = env::var("HTTP_TLS_KEY").expect("HTTP_TLS_KEY must be set with HTTP_TLS_CERT");
+    let client_ca_path = env::var("HTTP_TLS_CLIENT_CA").ok();
+
+    let client_auth_optional = match env::var("HTTP_TLS_CLIENT_AUTH").as_deref() {
+        Ok("optional") => true,
+        Ok("required") | Err(_) => false,
+        Ok(_) => panic!("HTTP_TLS_CLIENT_AUTH must be 'required' or 'optional'"),
+    };
+    let client_identity = env::var("HTTP_TLS_CLIENT_IDENTITY")
+        .map(|value| {
+            value
+                .parse::<bool>()
+                .expect("HTTP_TLS_CLIENT_IDENTITY must be a bool")
+        })
+        .unwrap_or(false);
+    if client_identity && client_ca_path.is_none() {
+        panic!("HTTP_TLS_CLIENT_IDENTITY requires HTTP_TLS_CLIENT_CA to be set");
+    }
+    let reload_interval = env::var("HTTP_TLS_RELOAD_INTERVAL")
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .expect("HTTP_TLS_RELOAD_INTERVAL must be a number")
+        })
+        .unwrap_or(DEFAULT_RELOAD_INTERVAL);
+
+    Some(TlsSettings {
+        cert_path,
+        key_path,
+        client_ca_path,
+        client_auth_optional,
+        client_identity,
+        reload_interval: Duration::from_secs(reload_interval),
+    })
+}
+
+/// Builds the rustls config and starts watching the certificate and key files for changes.
+pub fn create_server_config(settings: &TlsSettings) -> io::Result<ServerConfig> {
+    let provider = Arc::new(default_provider());
+    let resolver = Arc::new(ReloadingCertResolver::new(
+        settings.cert_path.clone(),
+        settings.key_path.clone(),
+    )?);
+
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(io::Error::other)?;
+    let builder = match &settings.client_ca_path {
+        Some(client_ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(client_ca_path)? {
+                roots.add(cert).map_err(io::Error::other)?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+            let verifier = if settings.client_auth_optional {
+                verifier.allow_unauthenticated()
+            } else {
+                verifier
+            };
+            builder.with_client_cert_verifier(verifier.build().map_err(io::Error::other)?)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    if !settings.reload_interval.is_zero() {
+        tokio::spawn(watch_certificate(
+            resolver.clone(),
+            settings.reload_interval,
+        ));
+    }
+    Ok(builder.with_cert_resolver(resolver))
+}
+
+/// Stores the common name of the client certificate of TLS connections for `client_identity`.
+pub fn store_client_certificate(connection: &dyn Any, data: &mut Extensions) {
+    if let Some(stream) = connection.downcast_ref::<TlsStream<TcpStream>>() {
+        let common_name = stream
+            .get_ref()
+            .1
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .and_then(common_name);
+        data.insert(ClientCertificate { common_name });
+    }
+}
+
+fn common_name(cert: &CertificateDer) -> Option<String> {
+    let (_, cert) = X509Certificate::from_der(cert.as_ref()).ok()?;
+    let common_name = cert.subject().iter_common_name().next()?;
+    common_name.as_str().ok().map(str::to_string)
+}
+
+fn load_certs(path: &str) -> io::Result<Vec<CertificateDer<'static>>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    rustls_pemfile::certs(&mut reader).collect()
+}
+
+fn load_certified_key(cert_path: &str, key_path: &str) -> io::Result<CertifiedKey> {
+    let certs = load_certs(cert_path)?;
+    if certs.is_empty() {
+        return Err(io::Error::other(format!("no certificate in {}", cert_path)));
+    }
+    let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(key_path)?))?
+        .ok_or_else(|| io::Error::other(format!("no private key in {}", key_path)))?;
+    let key = any_supported_type(&key).map_err(io::Error::other)?;
+    Ok(CertifiedKey::new(certs, key))
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Serves the current certificate, which is replaced when the files change on disk.
+#[derive(Debug)]
+struct ReloadingCertResolver {
+    cert_path: String,
+    key_path: String,
+    certified_key: RwLock<Arc<CertifiedKey>>,
+}
+
+impl ReloadingCertResolver {
+    fn new(cert_path: String, key_path: String) -> io::Result<Self> {
+        let certified_key = load_certified_key(&cert_path, &key_path)?;
+        Ok(Self {
+            cert_path,
+            key_path,
+            certified_key: RwLock::new(Arc::new(certified_key)),
+        })
+    }
+
+    fn reload(&self) {
+        match load_certified_key(&self.cert_path, &self.key_path) {
+            Ok(certified_key) => {
+                if let Ok(mut current) = self.certified_key.write() {
+                    *current = Arc::new(certified_key);
+                    info!("Reloaded the TLS certificate from '{}'.", self.cert_path);
+                } else {
+                    panic!("ReloadingCertResolver::reload() tried to lock a poisoned lock");
+                }
+            }
+            // a half written certificate is picked up with the next change
+            Err(e) => warn!(
+                "ReloadingCertResolver::reload could not load the TLS certificate: {}",
+                e
+            ),
+        }
+    }
+}
+
+impl ResolvesServerCert for ReloadingCertResolver {
+    fn resolve(&self, _: ClientHello) -> Option<Arc<CertifiedKey>> {
+        if let Ok(certified_key) = self.certified_key.read() {
+            Some(certified_key.clone())
+        } else {
+            panic!("ReloadingCertResolver::resolve() tried to lock a poisoned lock");
+        }
+    }
+}
+
+async fn watch_certificate(resolver: Arc<ReloadingCertResolver>, interval: Duration) {
+    let mut last_modified = (modified(&resolver.cert_path), modified(&resolver.key_path));
+    let mut interval = tokio::time::interval(interval);
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+        let current = (modified(&resolver.cert_path), modified(&resolver.key_path));
+        if current != last_modified {
+            last_modified = current;
+            resolver.reload();
+        }
+    }
+}